@@ -0,0 +1,100 @@
+//! # Async sampling
+//!
+//! Wrappers around the heavy sampling and calibration entry points for async services.
+//! The work runs on the blocking pool of the tokio runtime, so the executor threads
+//! are never blocked. A panic in the work is resumed in the awaiting task.
+//!
+//! The deterministic mode of [`crate::rng`] is thread local, seed inside the closure
+//! passed to [`blocking`] (or use `run_batch`) for reproducible results.
+
+use std::{future::Future, panic, sync::Arc};
+
+use ndarray::{Array1, Array2};
+use ndrustfft::Zero;
+use tokio::task;
+
+use crate::{
+  progress::Hooks,
+  quant::volatility::heston::HestonCalibrator,
+  stochastic::{batch::Checkpoint, Sampling},
+};
+
+/// Run a blocking closure on the blocking pool
+pub fn blocking<F, R>(f: F) -> impl Future<Output = R>
+where
+  F: FnOnce() -> R + Send + 'static,
+  R: Send + 'static,
+{
+  let handle = task::spawn_blocking(f);
+
+  async move {
+    match handle.await {
+      Ok(result) => result,
+      Err(err) if err.is_panic() => panic::resume_unwind(err.into_panic()),
+      Err(err) => panic!("Blocking task failed: {err}"),
+    }
+  }
+}
+
+/// Sample a single path
+pub fn sample<S, T>(sampler: Arc<S>) -> impl Future<Output = Array1<T>>
+where
+  S: Sampling<T> + 'static,
+  T: Clone + Send + Sync + Zero + 'static,
+{
+  blocking(move || sampler.sample())
+}
+
+/// Sample `m` paths in parallel
+pub fn sample_par<S, T>(sampler: Arc<S>) -> impl Future<Output = Array2<T>>
+where
+  S: Sampling<T> + 'static,
+  T: Clone + Send + Sync + Zero + 'static,
+{
+  blocking(move || sampler.sample_par())
+}
+
+/// Sample `m` paths in parallel with progress reports and cancellation,
+/// resolves to None if it was cancelled
+pub fn sample_par_with<S, T>(
+  sampler: Arc<S>,
+  hooks: Hooks,
+) -> impl Future<Output = Option<Array2<T>>>
+where
+  S: Sampling<T> + 'static,
+  T: Clone + Send + Sync + Zero + 'static,
+{
+  blocking(move || sampler.sample_par_with(&hooks))
+}
+
+/// Run (or resume) a checkpointed batch run, see `BatchRunner`
+pub fn run_batch<S>(
+  sampler: Arc<S>,
+  paths: usize,
+  batch: usize,
+  seed: u64,
+  checkpoint: Option<std::path::PathBuf>,
+  keep_paths: bool,
+  hooks: Hooks,
+) -> impl Future<Output = std::io::Result<Checkpoint>>
+where
+  S: Sampling<f64> + 'static,
+{
+  blocking(move || {
+    let mut runner = crate::stochastic::batch::BatchRunner::new(
+      &*sampler, paths, batch, seed, checkpoint, keep_paths,
+    );
+    runner.hooks = hooks;
+    runner.run()
+  })
+}
+
+/// Calibrate the Heston model, resolves to the calibrated calibrator
+pub fn calibrate_heston(
+  mut calibrator: HestonCalibrator,
+) -> impl Future<Output = HestonCalibrator> {
+  blocking(move || {
+    calibrator.calibrate();
+    calibrator
+  })
+}
@@ -0,0 +1,429 @@
+//! # Command line
+//!
+//! Configuration and runner of the `stochastic-rs` binary. A run reads a TOML or JSON
+//! configuration naming a model of the [`ModelRegistry`] with its parameters, samples m
+//! paths of n steps or prices a European option on their terminal values, and writes the
+//! result as CSV, JSON or Parquet: one row per path with the columns x_0, x_1, ... as in
+//! the datasets, or the columns price and std_error.
+//!
+//! ```toml
+//! model = "heston"
+//! n = 252
+//! m = 10000
+//! t = 1.0
+//! seed = 42
+//!
+//! [params]
+//! s0 = 100.0
+//! v0 = 0.04
+//! kappa = 2.0
+//! theta = 0.04
+//! sigma = 0.3
+//! rho = -0.7
+//!
+//! [pricing]
+//! strike = 100.0
+//! rate = 0.03
+//! payoff = "call"
+//!
+//! [output]
+//! path = "heston.csv"
+//! ```
+
+use std::{
+  fs::File,
+  io::{self, Write},
+  path::{Path, PathBuf},
+  str::FromStr,
+};
+
+use anyhow::{bail, Context, Result};
+use ndarray::{Array1, Array2};
+use polars::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+  rng::with_seed,
+  stochastic::registry::{ModelRegistry, Params},
+};
+
+/// Configuration of a run
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+  /// Name of the model in the registry
+  pub model: String,
+  /// Number of steps
+  pub n: usize,
+  /// Number of paths
+  #[serde(default = "one")]
+  pub m: usize,
+  /// Horizon (default 1)
+  pub t: Option<f64>,
+  /// Seed of the deterministic mode, the paths are not reproducible without it
+  pub seed: Option<u64>,
+  /// Model parameters other than n, m and t
+  #[serde(default)]
+  pub params: Params,
+  /// Price a European option instead of writing the paths
+  pub pricing: Option<Pricing>,
+  #[serde(default)]
+  pub output: Output,
+}
+
+fn one() -> usize {
+  1
+}
+
+/// European option on the terminal value of the paths, discounted at the rate over t
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pricing {
+  pub strike: f64,
+  #[serde(default)]
+  pub rate: f64,
+  #[serde(default)]
+  pub payoff: Payoff,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Payoff {
+  #[default]
+  Call,
+  Put,
+}
+
+/// Destination of the result, the standard output and CSV by default
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Output {
+  pub path: Option<PathBuf>,
+  /// Format, inferred from the extension of the path when it is not given
+  pub format: Option<Format>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+  Csv,
+  Json,
+  Parquet,
+}
+
+impl FromStr for Format {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    match s.to_ascii_lowercase().as_str() {
+      "csv" => Ok(Self::Csv),
+      "json" => Ok(Self::Json),
+      "parquet" | "pq" => Ok(Self::Parquet),
+      _ => bail!("Unknown format {}, expected csv, json or parquet", s),
+    }
+  }
+}
+
+impl Output {
+  fn format(&self) -> Format {
+    self.format.unwrap_or_else(|| {
+      self
+        .path
+        .as_ref()
+        .and_then(|path| path.extension())
+        .and_then(|extension| extension.to_str())
+        .and_then(|extension| extension.parse().ok())
+        .unwrap_or(Format::Csv)
+    })
+  }
+}
+
+/// Result of a run
+#[derive(Debug, Clone)]
+pub enum Report {
+  /// Paths, one row per path
+  Paths(Array2<f64>),
+  /// Monte Carlo price with its standard error
+  Price { price: f64, std_error: f64 },
+}
+
+impl Config {
+  /// Configuration from a TOML document, or from a JSON document if it starts with `{`
+  pub fn parse(document: &str) -> Result<Self> {
+    if document.trim_start().starts_with('{') {
+      serde_json::from_str(document).context("Invalid JSON configuration")
+    } else {
+      toml::from_str(document).context("Invalid TOML configuration")
+    }
+  }
+
+  /// Configuration from a file, or from the standard input for the path `-`
+  pub fn from_path(path: &Path) -> Result<Self> {
+    let document = if path == Path::new("-") {
+      io::read_to_string(io::stdin())?
+    } else {
+      std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?
+    };
+    Self::parse(&document)
+  }
+
+  /// Sample the model of the registry, and price the option if there is one
+  pub fn run(&self, registry: &ModelRegistry) -> Result<Report> {
+    for grid in ["n", "m", "t"] {
+      if self.params.contains_key(grid) {
+        bail!(
+          "Set {} at the top level of the configuration, not in params",
+          grid
+        );
+      }
+    }
+    if self.m == 0 {
+      bail!("The number of paths m must be positive");
+    }
+
+    let mut params = self.params.clone();
+    params.insert("n".into(), self.n as f64);
+    params.insert("m".into(), self.m as f64);
+    if let Some(t) = self.t {
+      params.insert("t".into(), t);
+    }
+    let model = registry.create(&self.model, &params)?;
+    let paths = match self.seed {
+      Some(seed) => with_seed(seed, || model.sample_par()),
+      None => model.sample_par(),
+    };
+
+    let Some(pricing) = self.pricing else {
+      return Ok(Report::Paths(paths));
+    };
+    let discount = (-pricing.rate * self.t.unwrap_or(1.0)).exp();
+    let payoffs: Array1<f64> = paths
+      .column(paths.ncols() - 1)
+      .mapv(|x| match pricing.payoff {
+        Payoff::Call => (x - pricing.strike).max(0.0),
+        Payoff::Put => (pricing.strike - x).max(0.0),
+      });
+    let std_error = if self.m > 1 {
+      discount * (payoffs.var(1.0) / self.m as f64).sqrt()
+    } else {
+      f64::NAN
+    };
+
+    Ok(Report::Price {
+      price: discount * payoffs.mean().unwrap(),
+      std_error,
+    })
+  }
+}
+
+impl Report {
+  /// Named columns of the result
+  fn columns(&self) -> Vec<(String, Vec<f64>)> {
+    match self {
+      Self::Paths(paths) => paths
+        .columns()
+        .into_iter()
+        .enumerate()
+        .map(|(i, values)| (format!("x_{}", i), values.to_vec()))
+        .collect(),
+      Self::Price { price, std_error } => vec![
+        ("price".into(), vec![*price]),
+        ("std_error".into(), vec![*std_error]),
+      ],
+    }
+  }
+
+  /// Write the result to the output
+  pub fn write(&self, output: &Output) -> Result<()> {
+    let columns = self.columns();
+    let format = output.format();
+
+    if format == Format::Parquet {
+      let Some(path) = &output.path else {
+        bail!("Parquet output needs a path");
+      };
+      let mut df = DataFrame::new(
+        columns
+          .iter()
+          .map(|(name, values)| Series::new(name.as_str().into(), values))
+          .collect(),
+      )?;
+      ParquetWriter::new(File::create(path)?).finish(&mut df)?;
+      return Ok(());
+    }
+
+    let mut writer: Box<dyn Write> = match &output.path {
+      Some(path) => Box::new(io::BufWriter::new(File::create(path)?)),
+      None => Box::new(io::stdout().lock()),
+    };
+    let rows = columns.first().map_or(0, |(_, values)| values.len());
+
+    if format == Format::Csv {
+      let header: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+      writeln!(writer, "{}", header.join(","))?;
+      for row in 0..rows {
+        let line: Vec<String> = columns
+          .iter()
+          .map(|(_, values)| values[row].to_string())
+          .collect();
+        writeln!(writer, "{}", line.join(","))?;
+      }
+    } else {
+      let records: Vec<serde_json::Map<String, serde_json::Value>> = (0..rows)
+        .map(|row| {
+          columns
+            .iter()
+            .map(|(name, values)| (name.clone(), serde_json::Value::from(values[row])))
+            .collect()
+        })
+        .collect();
+      serde_json::to_writer(&mut writer, &records)?;
+      writeln!(writer)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const GBM: &str = r#"
+model = "gbm"
+n = 9
+m = 4
+t = 0.5
+seed = 7
+
+[params]
+mu = 0.05
+sigma = 0.2
+x0 = 100.0
+"#;
+
+  fn temp(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cli-{}-{}", std::process::id(), name))
+  }
+
+  #[test]
+  fn toml_and_json_configurations_agree() {
+    let toml = Config::parse(GBM).unwrap();
+    let json = Config::parse(
+      r#"{"model": "gbm", "n": 9, "m": 4, "t": 0.5, "seed": 7,
+          "params": {"mu": 0.05, "sigma": 0.2, "x0": 100.0}}"#,
+    )
+    .unwrap();
+
+    assert_eq!((toml.model.as_str(), toml.n, toml.m), ("gbm", 9, 4));
+    assert_eq!(toml.params, json.params);
+    assert!(toml.pricing.is_none());
+    assert_eq!(Config::parse("model = \"gbm\"\nn = 3").unwrap().m, 1);
+  }
+
+  #[test]
+  fn invalid_configurations_are_rejected() {
+    assert!(Config::parse("model = \"gbm\"\nn = 3\nsteps = 4").is_err());
+    assert!(Config::parse("model = \"gbm\"").is_err());
+
+    let registry = ModelRegistry::default();
+    let grid_in_params = Config::parse("model = \"gbm\"\nn = 3\n[params]\nn = 4").unwrap();
+    assert!(grid_in_params.run(&registry).is_err());
+    let unknown_model = Config::parse("model = \"nope\"\nn = 3").unwrap();
+    assert!(unknown_model.run(&registry).is_err());
+  }
+
+  #[test]
+  fn seeded_runs_are_reproducible() {
+    let registry = ModelRegistry::default();
+    let config = Config::parse(GBM).unwrap();
+    let (Report::Paths(a), Report::Paths(b)) = (
+      config.run(&registry).unwrap(),
+      config.run(&registry).unwrap(),
+    ) else {
+      panic!("Expected paths");
+    };
+
+    assert_eq!(a.dim(), (4, 10));
+    assert_eq!(a, b);
+    assert!(a.column(0).iter().all(|&x| x == 100.0));
+  }
+
+  #[test]
+  fn pricing_reports_the_discounted_payoff() {
+    let registry = ModelRegistry::default();
+    let mut config = Config::parse(GBM).unwrap();
+    config.m = 2000;
+    config.pricing = Some(Pricing {
+      strike: 0.0,
+      rate: 0.05,
+      payoff: Payoff::Call,
+    });
+    let Report::Price { price, std_error } = config.run(&registry).unwrap() else {
+      panic!("Expected a price");
+    };
+
+    // a call struck at 0 is the discounted forward, the spot under mu = rate
+    assert!(
+      (price - 100.0).abs() < 4.0 * std_error + 0.5,
+      "{price} {std_error}"
+    );
+  }
+
+  #[test]
+  fn formats_are_inferred_from_the_extension() {
+    let output = |path: &str| Output {
+      path: Some(path.into()),
+      format: None,
+    };
+    assert_eq!(output("a.json").format(), Format::Json);
+    assert_eq!(output("a.PQ").format(), Format::Parquet);
+    assert_eq!(output("a.txt").format(), Format::Csv);
+    assert_eq!(Output::default().format(), Format::Csv);
+    assert!("xml".parse::<Format>().is_err());
+  }
+
+  #[test]
+  fn reports_are_written_as_csv_and_json() {
+    let report = Report::Paths(Array2::from_shape_fn((2, 3), |(i, j)| (i * 3 + j) as f64));
+
+    let csv = temp("paths.csv");
+    report
+      .write(&Output {
+        path: Some(csv.clone()),
+        format: None,
+      })
+      .unwrap();
+    assert_eq!(
+      std::fs::read_to_string(&csv).unwrap(),
+      "x_0,x_1,x_2\n0,1,2\n3,4,5\n"
+    );
+
+    let json = temp("price.json");
+    Report::Price {
+      price: 1.5,
+      std_error: 0.25,
+    }
+    .write(&Output {
+      path: Some(json.clone()),
+      format: None,
+    })
+    .unwrap();
+    let records: serde_json::Value =
+      serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+    assert_eq!(
+      records,
+      serde_json::json!([{"price": 1.5, "std_error": 0.25}])
+    );
+
+    let parquet = Output {
+      path: None,
+      format: Some(Format::Parquet),
+    };
+    assert!(report.write(&parquet).is_err());
+
+    std::fs::remove_file(csv).unwrap();
+    std::fs::remove_file(json).unwrap();
+  }
+}
@@ -0,0 +1,250 @@
+//! # Datasets
+//!
+//! Labeled simulation datasets for machine learning pipelines. Every sample is a
+//! simulated path together with the parameters it was generated with, the parameters
+//! are drawn uniformly from the given ranges by a seeded generator and every path is
+//! simulated on its own seed, so a dataset is reproducible from its seed.
+
+use std::{fs::File, path::Path};
+
+use anyhow::Result;
+use ndarray::{Array1, Array2, Axis};
+use ndarray_npy::NpzWriter;
+use polars::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::rng::{path_seed, with_seed};
+use crate::stochastic::{diffusion::fou::FOU, process::fbm::Fbm, Sampling};
+
+/// Range of a generating parameter
+#[derive(Debug, Clone)]
+pub struct ParameterRange {
+  pub name: String,
+  pub low: f64,
+  pub high: f64,
+}
+
+impl ParameterRange {
+  pub fn new(name: &str, low: f64, high: f64) -> Self {
+    assert!(low <= high, "Lower bound must not exceed the upper bound");
+
+    Self {
+      name: name.to_string(),
+      low,
+      high,
+    }
+  }
+}
+
+/// Simulated paths (rows) with their generating parameters (rows)
+#[derive(Debug, Clone)]
+pub struct Dataset {
+  /// Parameter names
+  pub names: Vec<String>,
+  /// Generating parameters, one row per sample
+  pub params: Array2<f64>,
+  /// Paths, one row per sample
+  pub paths: Array2<f64>,
+}
+
+impl Dataset {
+  /// Number of samples
+  pub fn len(&self) -> usize {
+    self.paths.nrows()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  fn select(&self, idx: &[usize]) -> Self {
+    Self {
+      names: self.names.clone(),
+      params: self.params.select(Axis(0), idx),
+      paths: self.paths.select(Axis(0), idx),
+    }
+  }
+
+  /// Deterministic train, validation and test split after shuffling with the seed
+  pub fn split(&self, train: f64, validation: f64, seed: u64) -> (Self, Self, Self) {
+    assert!(
+      train >= 0.0 && validation >= 0.0 && train + validation <= 1.0,
+      "Split fractions must be non-negative and sum up to at most one"
+    );
+
+    let mut idx = (0..self.len()).collect::<Vec<_>>();
+    idx.shuffle(&mut StdRng::seed_from_u64(seed));
+
+    let n_train = (train * self.len() as f64).round() as usize;
+    let n_validation =
+      ((validation * self.len() as f64).round() as usize).min(self.len() - n_train);
+
+    (
+      self.select(&idx[..n_train]),
+      self.select(&idx[n_train..n_train + n_validation]),
+      self.select(&idx[n_train + n_validation..]),
+    )
+  }
+
+  /// Write the dataset to a NumPy .npz archive with the arrays `paths` and `params`,
+  /// the parameter names are in the order of the columns of `params`
+  pub fn write_npz<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+    let mut npz = NpzWriter::new(File::create(path)?);
+    npz.add_array("paths", &self.paths)?;
+    npz.add_array("params", &self.params)?;
+    npz.finish()?;
+
+    Ok(())
+  }
+
+  /// Write the dataset to a Parquet file, one row per sample with the
+  /// parameter columns first followed by the path columns x_0, x_1, ...
+  pub fn write_parquet<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+    let mut columns = Vec::with_capacity(self.names.len() + self.paths.ncols());
+
+    for (name, values) in self.names.iter().zip(self.params.columns()) {
+      columns.push(Series::new(name.into(), values.to_vec()));
+    }
+
+    for (i, values) in self.paths.columns().into_iter().enumerate() {
+      columns.push(Series::new(format!("x_{}", i).into(), values.to_vec()));
+    }
+
+    let mut df = DataFrame::new(columns)?;
+    ParquetWriter::new(File::create(path)?).finish(&mut df)?;
+
+    Ok(())
+  }
+}
+
+/// Generator of labeled datasets from a simulation closure,
+/// `simulate` maps the parameters (in the order of `ranges`) to a path
+pub struct DatasetGenerator<F>
+where
+  F: Fn(&[f64]) -> Array1<f64> + Send + Sync,
+{
+  pub ranges: Vec<ParameterRange>,
+  pub simulate: F,
+  /// Number of samples
+  pub size: usize,
+  /// Seed of the parameter draws and of the paths
+  pub seed: u64,
+}
+
+impl<F> DatasetGenerator<F>
+where
+  F: Fn(&[f64]) -> Array1<f64> + Send + Sync,
+{
+  /// Draw the parameters and simulate the paths in parallel, path i in the deterministic
+  /// mode on `path_seed(seed, i)`, so the dataset only depends on the seed
+  pub fn generate(&self) -> Dataset {
+    let mut rng = StdRng::seed_from_u64(self.seed);
+    let params = Array2::from_shape_fn((self.size, self.ranges.len()), |(_, j)| {
+      let range = &self.ranges[j];
+
+      if range.low < range.high {
+        rng.gen_range(range.low..range.high)
+      } else {
+        range.low
+      }
+    });
+
+    let paths = params
+      .axis_iter(Axis(0))
+      .into_par_iter()
+      .enumerate()
+      .map(|(i, p)| {
+        with_seed(path_seed(self.seed, i as u64), || {
+          (self.simulate)(p.as_slice().unwrap())
+        })
+      })
+      .collect::<Vec<_>>();
+
+    let len = paths.first().map_or(0, |p| p.len());
+    let mut data = Array2::<f64>::zeros((self.size, len));
+
+    for (mut row, path) in data.axis_iter_mut(Axis(0)).zip(&paths) {
+      row.assign(path);
+    }
+
+    Dataset {
+      names: self.ranges.iter().map(|r| r.name.clone()).collect(),
+      params,
+      paths: data,
+    }
+  }
+}
+
+/// Fractional Brownian motion paths labeled with the Hurst parameter
+pub fn fbm_dataset(
+  n: usize,
+  hurst: (f64, f64),
+  size: usize,
+  seed: u64,
+) -> DatasetGenerator<impl Fn(&[f64]) -> Array1<f64> + Send + Sync> {
+  DatasetGenerator {
+    ranges: vec![ParameterRange::new("hurst", hurst.0, hurst.1)],
+    simulate: move |p: &[f64]| {
+      Fbm::new(&Fbm {
+        hurst: p[0],
+        n,
+        ..Default::default()
+      })
+      .sample()
+    },
+    size,
+    seed,
+  }
+}
+
+/// Fractional Ornstein-Uhlenbeck paths labeled with the Hurst parameter and the mean reversion speed
+pub fn fou_dataset(
+  n: usize,
+  hurst: (f64, f64),
+  theta: (f64, f64),
+  size: usize,
+  seed: u64,
+) -> DatasetGenerator<impl Fn(&[f64]) -> Array1<f64> + Send + Sync> {
+  DatasetGenerator {
+    ranges: vec![
+      ParameterRange::new("hurst", hurst.0, hurst.1),
+      ParameterRange::new("theta", theta.0, theta.1),
+    ],
+    simulate: move |p: &[f64]| {
+      FOU::new(&FOU {
+        hurst: p[0],
+        theta: p[1],
+        mu: 0.0,
+        sigma: 1.0,
+        n,
+        x0: Some(0.0),
+        t: Some(1.0),
+        ..Default::default()
+      })
+      .sample()
+    },
+    size,
+    seed,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn datasets_are_reproducible_from_the_seed() {
+    let generator = fou_dataset(64, (0.3, 0.8), (0.5, 2.0), 8, 11);
+    let (a, b) = (generator.generate(), generator.generate());
+
+    assert_eq!(a.params, b.params);
+    assert_eq!(a.paths, b.paths);
+    assert_ne!(a.paths.row(0), a.paths.row(1));
+
+    let (train, validation, test) = a.split(0.5, 0.25, 3);
+    let (train2, _, _) = b.split(0.5, 0.25, 3);
+    assert_eq!((train.len(), validation.len(), test.len()), (4, 2, 2));
+    assert_eq!(train.paths, train2.paths);
+  }
+}
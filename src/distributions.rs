@@ -0,0 +1,96 @@
+//! Distributions the models need beyond `rand_distr`, with their samplers, densities,
+//! distribution functions and quantiles.
+
+pub mod inverse_gaussian;
+pub mod mittag_leffler;
+pub mod noncentral_chi_squared;
+pub mod stable;
+
+/// Quantile of a continuous distribution function by bisection, the bracket [lo, hi] is
+/// widened until it contains the quantile
+pub(crate) fn invert_cdf<F>(cdf: F, p: f64, mut lo: f64, mut hi: f64) -> f64
+where
+  F: Fn(f64) -> f64,
+{
+  assert!(
+    (0.0..=1.0).contains(&p),
+    "The probability must be in [0, 1]"
+  );
+  if p == 0.0 {
+    return f64::NEG_INFINITY;
+  }
+  if p == 1.0 {
+    return f64::INFINITY;
+  }
+  let mut width = hi - lo;
+  while cdf(lo) > p {
+    lo -= width;
+    width *= 2.0;
+  }
+  let mut width = hi - lo;
+  while cdf(hi) < p {
+    hi += width;
+    width *= 2.0;
+  }
+
+  for _ in 0..200 {
+    let mid = 0.5 * (lo + hi);
+    if cdf(mid) < p {
+      lo = mid;
+    } else {
+      hi = mid;
+    }
+    if hi - lo <= 1e-14 * mid.abs().max(1e-300) {
+      break;
+    }
+  }
+  0.5 * (lo + hi)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use rand_distr::Distribution;
+
+  use super::*;
+  use crate::rng::{thread_rng, with_seed};
+
+  /// Kolmogorov-Smirnov statistic of n seeded samples against the distribution function
+  pub(crate) fn ks_statistic<D: Distribution<f64>>(
+    distribution: &D,
+    cdf: impl Fn(f64) -> f64,
+    n: usize,
+    seed: u64,
+  ) -> f64 {
+    let mut samples = with_seed(seed, || {
+      let mut rng = thread_rng();
+      (0..n)
+        .map(|_| distribution.sample(&mut rng))
+        .collect::<Vec<_>>()
+    });
+    samples.sort_by(f64::total_cmp);
+    samples
+      .iter()
+      .enumerate()
+      .map(|(i, &x)| {
+        let f = cdf(x);
+        ((i + 1) as f64 / n as f64 - f).max(f - i as f64 / n as f64)
+      })
+      .fold(0.0, f64::max)
+  }
+
+  /// Critical value of the statistic at the 1% level
+  pub(crate) fn ks_critical(n: usize) -> f64 {
+    1.63 / (n as f64).sqrt()
+  }
+
+  #[test]
+  fn inversion_widens_the_bracket() {
+    let logistic = |x: f64| 1.0 / (1.0 + (-x).exp());
+    for (p, lo, hi) in [(0.3, 10.0, 11.0), (0.999, -5.0, -4.0), (0.5, -1.0, 1.0)] {
+      let x = invert_cdf(logistic, p, lo, hi);
+      assert!((logistic(x) - p).abs() < 1e-13, "{p} {x}");
+    }
+    assert_eq!(invert_cdf(logistic, 0.0, 0.0, 1.0), f64::NEG_INFINITY);
+    assert_eq!(invert_cdf(logistic, 1.0, 0.0, 1.0), f64::INFINITY);
+  }
+}
@@ -0,0 +1,154 @@
+use std::f64::consts::PI;
+
+use rand::Rng;
+use rand_distr::{Distribution, StandardNormal};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use super::invert_cdf;
+use crate::stochastic::ProcessDistribution;
+
+/// Inverse Gaussian distribution with the mean mu and the shape lambda, the law of the first
+/// passage time of a Brownian motion with drift through a level
+#[derive(Debug, Clone, Copy)]
+pub struct InverseGaussian {
+  pub mu: f64,
+  /// Shape
+  pub lambda: f64,
+}
+
+impl Default for InverseGaussian {
+  fn default() -> Self {
+    Self {
+      mu: 1.0,
+      lambda: 1.0,
+    }
+  }
+}
+
+impl InverseGaussian {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.mu > 0.0 && params.lambda > 0.0,
+      "The mean and the shape must be positive"
+    );
+
+    *params
+  }
+
+  pub fn mean(&self) -> f64 {
+    self.mu
+  }
+
+  pub fn variance(&self) -> f64 {
+    self.mu.powi(3) / self.lambda
+  }
+
+  pub fn pdf(&self, x: f64) -> f64 {
+    if x <= 0.0 {
+      return 0.0;
+    }
+    (self.lambda / (2.0 * PI * x.powi(3))).sqrt()
+      * (-self.lambda * (x - self.mu).powi(2) / (2.0 * self.mu * self.mu * x)).exp()
+  }
+
+  /// Phi(sqrt(lambda / x) (x / mu - 1)) + e^(2 lambda / mu) Phi(-sqrt(lambda / x) (x / mu + 1)),
+  /// the second term in logs where e^(2 lambda / mu) overflows and by the Mills ratio where
+  /// Phi(-b) underflows
+  pub fn cdf(&self, x: f64) -> f64 {
+    if x <= 0.0 {
+      return 0.0;
+    }
+    let n = Normal::default();
+    let a = (self.lambda / x).sqrt();
+    let b = a * (x / self.mu + 1.0);
+    let second = if b < 30.0 {
+      (2.0 * self.lambda / self.mu + n.cdf(-b).ln()).exp()
+    } else {
+      let b2 = b * b;
+      (2.0 * self.lambda / self.mu - 0.5 * b2).exp() / (b * (2.0 * PI).sqrt())
+        * (1.0 - 1.0 / b2 + 3.0 / (b2 * b2))
+    };
+    (n.cdf(a * (x / self.mu - 1.0)) + second).min(1.0)
+  }
+
+  pub fn quantile(&self, p: f64) -> f64 {
+    if p <= 0.0 {
+      return 0.0;
+    }
+    invert_cdf(
+      |x| self.cdf(x),
+      p,
+      0.0,
+      self.mu + 4.0 * self.variance().sqrt(),
+    )
+  }
+}
+
+/// Michael, Schucany and Haas transformation with multiple roots
+/// https://doi.org/10.1080/00031305.1976.10479147 (Michael, Schucany, Haas 1976)
+impl Distribution<f64> for InverseGaussian {
+  fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+    let (mu, lambda) = (self.mu, self.lambda);
+    let z: f64 = rng.sample(StandardNormal);
+    let y = z * z;
+    let x = mu + mu * mu * y / (2.0 * lambda)
+      - mu / (2.0 * lambda) * (4.0 * mu * lambda * y + (mu * y).powi(2)).sqrt();
+    if rng.gen::<f64>() <= mu / (mu + x) {
+      x
+    } else {
+      mu * mu / x
+    }
+  }
+}
+
+impl ProcessDistribution for InverseGaussian {}
+
+#[cfg(test)]
+mod tests {
+  use approx::assert_relative_eq;
+  use quadrature::double_exponential;
+
+  use super::*;
+  use crate::rng::{thread_rng, with_seed};
+
+  #[test]
+  fn distribution_function_is_the_integral_of_the_density() {
+    // e^(2 lambda / mu) overflows at lambda = 400 and Phi(-b) underflows at the upper points
+    for (mu, lambda) in [(1.0, 1.0), (2.0, 0.5), (1.0, 60.0), (1.0, 400.0)] {
+      let ig = InverseGaussian::new(&InverseGaussian { mu, lambda });
+      for x in [0.3 * mu, mu, 2.0 * mu, 5.0 * mu] {
+        let mass = double_exponential::integrate(|y| ig.pdf(y), 0.0, x, 1e-12).integral;
+        assert_relative_eq!(ig.cdf(x), mass, epsilon = 1e-9);
+      }
+    }
+  }
+
+  #[test]
+  fn quantile_inverts_the_distribution_function() {
+    let ig = InverseGaussian::new(&InverseGaussian {
+      mu: 1.5,
+      lambda: 2.0,
+    });
+    for x in [0.1, 0.7, 1.5, 4.0, 12.0] {
+      assert_relative_eq!(ig.quantile(ig.cdf(x)), x, max_relative = 1e-9);
+    }
+  }
+
+  #[test]
+  fn sample_moments_match() {
+    let ig = InverseGaussian::new(&InverseGaussian {
+      mu: 1.5,
+      lambda: 2.0,
+    });
+    let n = 20_000;
+    let samples = with_seed(3, || {
+      let mut rng = thread_rng();
+      (0..n).map(|_| ig.sample(&mut rng)).collect::<Vec<f64>>()
+    });
+    let mean = samples.iter().sum::<f64>() / n as f64;
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+    assert!((mean - ig.mean()).abs() < 4.0 * (ig.variance() / n as f64).sqrt());
+    assert_relative_eq!(variance, ig.variance(), max_relative = 0.1);
+  }
+}
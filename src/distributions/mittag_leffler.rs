@@ -0,0 +1,178 @@
+use std::f64::consts::PI;
+
+use quadrature::double_exponential;
+use rand::Rng;
+use rand_distr::Distribution;
+
+use super::invert_cdf;
+use crate::stochastic::ProcessDistribution;
+
+/// Mittag-Leffler distribution with the index beta in (0, 1] and the scale, the survival
+/// function E_beta(-(t / scale)^beta) of the Mittag-Leffler function. The waiting times of
+/// the renewal process whose CTRW limit is time-fractional, heavy tailed without a mean for
+/// beta < 1 and exponential for beta = 1.
+#[derive(Debug, Clone, Copy)]
+pub struct MittagLeffler {
+  /// Index in (0, 1]
+  pub beta: f64,
+  pub scale: f64,
+}
+
+impl Default for MittagLeffler {
+  fn default() -> Self {
+    Self {
+      beta: 1.0,
+      scale: 1.0,
+    }
+  }
+}
+
+impl MittagLeffler {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.beta > 0.0 && params.beta <= 1.0 && params.scale > 0.0,
+      "The index must be in (0, 1] and the scale positive"
+    );
+
+    *params
+  }
+
+  /// int_0^inf r^(beta - 1 + power) e^(-r t) / (r^(2 beta) + 2 r^beta cos(beta pi) + 1) dr
+  /// sin(beta pi) / pi, the completely monotone representation of E_beta(-t^beta) for
+  /// power 0 and of its negative derivative for power 1
+  fn spectral(&self, t: f64, power: f64) -> f64 {
+    let beta = self.beta;
+    let (sin, cos) = (beta * PI).sin_cos();
+    // w = r^beta removes the singularity at 0 and w = u / (1 - u) maps [0, 1) onto [0, inf)
+    let integrand = |u: f64| {
+      if u <= 0.0 || u >= 1.0 {
+        return 0.0;
+      }
+      let w = u / (1.0 - u);
+      let r = w.powf(1.0 / beta);
+      let value =
+        r.powf(power) * (-r * t).exp() / (w * w + 2.0 * w * cos + 1.0) / (1.0 - u).powi(2);
+      if value.is_finite() {
+        value
+      } else {
+        0.0
+      }
+    };
+    sin / (PI * beta) * double_exponential::integrate(integrand, 0.0, 1.0, 1e-12).integral
+  }
+
+  /// Survival function E_beta(-(t / scale)^beta)
+  pub fn survival(&self, t: f64) -> f64 {
+    if t <= 0.0 {
+      return 1.0;
+    }
+    let t = t / self.scale;
+    if self.beta == 1.0 {
+      return (-t).exp();
+    }
+    self.spectral(t, 0.0).clamp(0.0, 1.0)
+  }
+
+  pub fn cdf(&self, t: f64) -> f64 {
+    1.0 - self.survival(t)
+  }
+
+  pub fn pdf(&self, t: f64) -> f64 {
+    if t <= 0.0 {
+      return 0.0;
+    }
+    let x = t / self.scale;
+    if self.beta == 1.0 {
+      return (-x).exp() / self.scale;
+    }
+    self.spectral(x, 1.0) / self.scale
+  }
+
+  pub fn quantile(&self, p: f64) -> f64 {
+    if p <= 0.0 {
+      return 0.0;
+    }
+    if self.beta == 1.0 {
+      return -self.scale * (1.0 - p).ln();
+    }
+    invert_cdf(|t| self.cdf(t), p, 0.0, self.scale)
+  }
+}
+
+/// -scale ln U (sin(beta pi) / tan(beta pi V) - cos(beta pi))^(1 / beta)
+/// https://doi.org/10.1016/S0895-7177(01)00106-6 (Kozubowski, Rachev 1999)
+impl Distribution<f64> for MittagLeffler {
+  fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+    let u = 1.0 - rng.gen::<f64>();
+    if self.beta == 1.0 {
+      return -self.scale * u.ln();
+    }
+    let v: f64 = rng.gen();
+    let beta = self.beta;
+    -self.scale
+      * u.ln()
+      * ((beta * PI).sin() / (beta * PI * v).tan() - (beta * PI).cos()).powf(1.0 / beta)
+  }
+}
+
+impl ProcessDistribution for MittagLeffler {}
+
+#[cfg(test)]
+mod tests {
+  use approx::assert_relative_eq;
+  use statrs::function::erf::erfc;
+
+  use super::*;
+  use crate::distributions::tests::{ks_critical, ks_statistic};
+
+  #[test]
+  fn exponential_at_index_one() {
+    let ml = MittagLeffler::new(&MittagLeffler {
+      beta: 1.0,
+      scale: 2.0,
+    });
+    for t in [0.1, 1.0, 5.0] {
+      assert_relative_eq!(ml.survival(t), (-t / 2.0).exp(), max_relative = 1e-14);
+      assert_relative_eq!(ml.pdf(t), (-t / 2.0).exp() / 2.0, max_relative = 1e-14);
+    }
+  }
+
+  #[test]
+  fn spectral_integral_at_index_one_half() {
+    // E_(1/2)(-sqrt(t)) = e^t erfc(sqrt(t)) and its negative derivative
+    let ml = MittagLeffler::new(&MittagLeffler {
+      beta: 0.5,
+      scale: 1.0,
+    });
+    for t in [0.05f64, 0.5, 1.0, 4.0] {
+      let survival = t.exp() * erfc(t.sqrt());
+      assert_relative_eq!(ml.survival(t), survival, max_relative = 1e-8);
+      assert_relative_eq!(
+        ml.pdf(t),
+        1.0 / (PI * t).sqrt() - survival,
+        max_relative = 1e-7
+      );
+    }
+  }
+
+  #[test]
+  fn quantile_inverts_the_distribution_function() {
+    for beta in [0.4, 0.8, 1.0] {
+      let ml = MittagLeffler::new(&MittagLeffler { beta, scale: 1.5 });
+      for t in [0.01, 0.5, 3.0, 12.0] {
+        assert_relative_eq!(ml.quantile(ml.cdf(t)), t, max_relative = 1e-9);
+      }
+    }
+  }
+
+  #[test]
+  fn samples_follow_the_distribution_function() {
+    let n = 2000;
+    for beta in [0.3, 0.7, 1.0] {
+      let ml = MittagLeffler::new(&MittagLeffler { beta, scale: 1.5 });
+      let d = ks_statistic(&ml, |t| ml.cdf(t), n, 9);
+      assert!(d < ks_critical(n), "beta {beta}: {d}");
+    }
+  }
+}
@@ -0,0 +1,202 @@
+use rand::Rng;
+use rand_distr::{ChiSquared, Distribution, Poisson, StandardNormal};
+use statrs::function::gamma::{gamma_lr, ln_gamma};
+
+use super::invert_cdf;
+use crate::{stats::likelihood::ln_bessel_i, stochastic::ProcessDistribution};
+
+/// Noncentral chi-squared distribution with k degrees of freedom and the noncentrality
+/// lambda, the law of the sum of k squared normals with the sum of squared means lambda and
+/// of the CIR transition
+#[derive(Debug, Clone, Copy)]
+pub struct NoncentralChiSquared {
+  /// Degrees of freedom
+  pub k: f64,
+  /// Noncentrality
+  pub lambda: f64,
+}
+
+impl Default for NoncentralChiSquared {
+  fn default() -> Self {
+    Self {
+      k: 1.0,
+      lambda: 0.0,
+    }
+  }
+}
+
+impl NoncentralChiSquared {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.k > 0.0 && params.lambda >= 0.0,
+      "The degrees of freedom must be positive and the noncentrality non-negative"
+    );
+
+    *params
+  }
+
+  pub fn mean(&self) -> f64 {
+    self.k + self.lambda
+  }
+
+  pub fn variance(&self) -> f64 {
+    2.0 * (self.k + 2.0 * self.lambda)
+  }
+
+  /// Density 1/2 e^(-(x + lambda) / 2) (x / lambda)^(k / 4 - 1 / 2) I_(k/2 - 1)(sqrt(lambda x))
+  pub fn pdf(&self, x: f64) -> f64 {
+    if x <= 0.0 {
+      return 0.0;
+    }
+    let half_k = 0.5 * self.k;
+    let ln_pdf = if self.lambda == 0.0 {
+      (half_k - 1.0) * x.ln() - 0.5 * x - half_k * 2f64.ln() - ln_gamma(half_k)
+    } else {
+      -std::f64::consts::LN_2 - 0.5 * (x + self.lambda)
+        + (0.25 * self.k - 0.5) * (x / self.lambda).ln()
+        + ln_bessel_i(half_k - 1.0, (self.lambda * x).sqrt())
+    };
+    ln_pdf.exp()
+  }
+
+  /// Distribution function, the Poisson mixture sum_j P(N = j) P(chi^2_(k + 2j) <= x) with
+  /// N Poisson of mean lambda / 2, summed outward from the mode of N
+  pub fn cdf(&self, x: f64) -> f64 {
+    if x <= 0.0 {
+      return 0.0;
+    }
+    let mean = 0.5 * self.lambda;
+    let term = |j: f64| {
+      let ln_weight = if mean > 0.0 {
+        j * mean.ln() - mean - ln_gamma(j + 1.0)
+      } else if j == 0.0 {
+        0.0
+      } else {
+        f64::NEG_INFINITY
+      };
+      (ln_weight.exp(), gamma_lr(0.5 * self.k + j, 0.5 * x))
+    };
+
+    let mode = mean.floor();
+    let mut sum = 0.0;
+    let mut j = mode;
+    loop {
+      let (weight, p) = term(j);
+      sum += weight * p;
+      if weight * p < 1e-17 * sum.max(1e-300) && j > mode {
+        break;
+      }
+      j += 1.0;
+    }
+    let mut j = mode - 1.0;
+    while j >= 0.0 {
+      let (weight, p) = term(j);
+      sum += weight * p;
+      if weight < 1e-17 * sum.max(1e-300) {
+        break;
+      }
+      j -= 1.0;
+    }
+    sum.min(1.0)
+  }
+
+  pub fn quantile(&self, p: f64) -> f64 {
+    if p <= 0.0 {
+      return 0.0;
+    }
+    let sd = self.variance().sqrt();
+    invert_cdf(|x| self.cdf(x), p, 0.0, self.mean() + 4.0 * sd)
+  }
+}
+
+/// (Z + sqrt(lambda))^2 + chi^2_(k-1) for k > 1, the Poisson mixture chi^2_(k + 2N) else
+impl Distribution<f64> for NoncentralChiSquared {
+  fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+    if self.k > 1.0 {
+      let z: f64 = rng.sample(StandardNormal);
+      let central = if self.k > 1.0 + 1e-12 {
+        ChiSquared::new(self.k - 1.0).unwrap().sample(rng)
+      } else {
+        0.0
+      };
+      (z + self.lambda.sqrt()).powi(2) + central
+    } else {
+      let n = if self.lambda > 0.0 {
+        Poisson::new(0.5 * self.lambda).unwrap().sample(rng)
+      } else {
+        0.0
+      };
+      ChiSquared::new(self.k + 2.0 * n).unwrap().sample(rng)
+    }
+  }
+}
+
+impl ProcessDistribution for NoncentralChiSquared {}
+
+#[cfg(test)]
+mod tests {
+  use approx::assert_relative_eq;
+  use quadrature::double_exponential;
+  use statrs::distribution::{ChiSquared as CentralChiSquared, Continuous, ContinuousCDF};
+
+  use super::*;
+  use crate::rng::{thread_rng, with_seed};
+
+  #[test]
+  fn central_at_zero_noncentrality() {
+    let ncx2 = NoncentralChiSquared::new(&NoncentralChiSquared {
+      k: 3.5,
+      lambda: 0.0,
+    });
+    let chi2 = CentralChiSquared::new(3.5).unwrap();
+    for x in [0.2, 1.0, 3.5, 10.0] {
+      assert_relative_eq!(ncx2.pdf(x), chi2.pdf(x), max_relative = 1e-12);
+      assert_relative_eq!(ncx2.cdf(x), chi2.cdf(x), max_relative = 1e-12);
+    }
+  }
+
+  #[test]
+  fn distribution_function_is_the_integral_of_the_density() {
+    for (k, lambda) in [(3.0, 2.0), (0.5, 4.0), (10.0, 50.0)] {
+      let ncx2 = NoncentralChiSquared::new(&NoncentralChiSquared { k, lambda });
+      let mean = ncx2.mean();
+      for (a, b) in [
+        (0.1 * mean, 0.5 * mean),
+        (0.5 * mean, mean),
+        (mean, 2.0 * mean),
+      ] {
+        let mass = double_exponential::integrate(|x| ncx2.pdf(x), a, b, 1e-12).integral;
+        assert_relative_eq!(ncx2.cdf(b) - ncx2.cdf(a), mass, epsilon = 1e-9);
+      }
+    }
+  }
+
+  #[test]
+  fn quantile_inverts_the_distribution_function() {
+    let ncx2 = NoncentralChiSquared::new(&NoncentralChiSquared {
+      k: 3.0,
+      lambda: 2.0,
+    });
+    for x in [0.3, 2.0, 5.0, 15.0] {
+      assert_relative_eq!(ncx2.quantile(ncx2.cdf(x)), x, max_relative = 1e-9);
+    }
+  }
+
+  #[test]
+  fn sample_moments_match() {
+    let n = 20_000;
+    // k > 1 draws the shifted normal, k <= 1 the Poisson mixture
+    for (k, lambda) in [(3.0, 2.0), (0.5, 4.0)] {
+      let ncx2 = NoncentralChiSquared::new(&NoncentralChiSquared { k, lambda });
+      let samples = with_seed(5, || {
+        let mut rng = thread_rng();
+        (0..n).map(|_| ncx2.sample(&mut rng)).collect::<Vec<f64>>()
+      });
+      let mean = samples.iter().sum::<f64>() / n as f64;
+      let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+      assert!((mean - ncx2.mean()).abs() < 4.0 * (ncx2.variance() / n as f64).sqrt());
+      assert_relative_eq!(variance, ncx2.variance(), max_relative = 0.1);
+    }
+  }
+}
@@ -0,0 +1,327 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use quadrature::double_exponential;
+use rand::Rng;
+use rand_distr::{Distribution, Exp1};
+use statrs::function::gamma::gamma;
+
+use super::invert_cdf;
+use crate::stochastic::ProcessDistribution;
+
+/// Alpha-stable distribution in the parameterization of Samorodnitsky and Taqqu, with the
+/// characteristic function exp(-|c u|^alpha (1 - i beta sign(u) tan(pi alpha / 2)) + i mu u)
+/// for alpha != 1 and exp(-|c u| (1 + i beta 2 / pi sign(u) ln|u|) + i mu u) for alpha = 1.
+/// Sampled by the Chambers-Mallows-Stuck method, the density and the distribution function
+/// are the single integrals of Nolan over a finite interval.
+/// https://doi.org/10.1080/15326349708807450 (Nolan 1997)
+#[derive(Debug, Clone, Copy)]
+pub struct Stable {
+  /// Index of stability in (0, 2]
+  pub alpha: f64,
+  /// Skewness in [-1, 1]
+  pub beta: f64,
+  /// Scale
+  pub c: f64,
+  /// Location
+  pub mu: f64,
+}
+
+impl Default for Stable {
+  fn default() -> Self {
+    Self {
+      alpha: 2.0,
+      beta: 0.0,
+      c: 1.0,
+      mu: 0.0,
+    }
+  }
+}
+
+/// Integral of f over [a, b] where f is 0 where it cannot be evaluated
+fn integrate(f: impl Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+  if b <= a {
+    return 0.0;
+  }
+  let f = |x: f64| {
+    let value = f(x);
+    if value.is_finite() {
+      value
+    } else {
+      0.0
+    }
+  };
+  double_exponential::integrate(f, a, b, 1e-12).integral
+}
+
+impl Stable {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.alpha > 0.0 && params.alpha <= 2.0,
+      "The index must be in (0, 2]"
+    );
+    assert!(
+      (-1.0..=1.0).contains(&params.beta),
+      "The skewness must be in [-1, 1]"
+    );
+    assert!(params.c > 0.0, "The scale must be positive");
+
+    *params
+  }
+
+  /// Mean mu for alpha > 1, undefined otherwise
+  pub fn mean(&self) -> f64 {
+    if self.alpha > 1.0 {
+      self.mu
+    } else {
+      f64::NAN
+    }
+  }
+
+  /// Variance 2 c^2 for alpha = 2, infinite otherwise
+  pub fn variance(&self) -> f64 {
+    if self.alpha == 2.0 {
+      2.0 * self.c * self.c
+    } else {
+      f64::INFINITY
+    }
+  }
+
+  /// The standardized variable (X - shift) / c in Nolan's S0 parameterization, which
+  /// is continuous in alpha
+  fn standardize(&self, x: f64) -> f64 {
+    let shift = if self.alpha == 1.0 {
+      self.mu + 2.0 / PI * self.beta * self.c * self.c.ln()
+    } else {
+      self.mu + self.beta * self.c * (PI * self.alpha / 2.0).tan()
+    };
+    (x - shift) / self.c
+  }
+
+  pub fn pdf(&self, x: f64) -> f64 {
+    standard_pdf(self.alpha, self.beta, self.standardize(x)) / self.c
+  }
+
+  pub fn cdf(&self, x: f64) -> f64 {
+    standard_cdf(self.alpha, self.beta, self.standardize(x)).clamp(0.0, 1.0)
+  }
+
+  pub fn quantile(&self, p: f64) -> f64 {
+    invert_cdf(|x| self.cdf(x), p, self.mu - self.c, self.mu + self.c)
+  }
+}
+
+/// Nolan's V function and the integration interval for alpha != 1, zeta = -beta tan(pi
+/// alpha / 2), theta0 = arctan(beta tan(pi alpha / 2)) / alpha
+fn nolan(alpha: f64, beta: f64) -> (f64, f64, impl Fn(f64) -> f64) {
+  let zeta = -beta * (PI * alpha / 2.0).tan();
+  let theta0 = (beta * (PI * alpha / 2.0).tan()).atan() / alpha;
+  let v = move |theta: f64| {
+    (alpha * theta0).cos().powf(1.0 / (alpha - 1.0))
+      * (theta.cos() / (alpha * (theta0 + theta)).sin()).powf(alpha / (alpha - 1.0))
+      * (alpha * theta0 + (alpha - 1.0) * theta).cos()
+      / theta.cos()
+  };
+  (zeta, theta0, v)
+}
+
+fn standard_pdf(alpha: f64, beta: f64, x: f64) -> f64 {
+  if alpha == 2.0 {
+    return (-x * x / 4.0).exp() / (2.0 * PI.sqrt());
+  }
+  if alpha == 1.0 {
+    if beta == 0.0 {
+      return 1.0 / (PI * (1.0 + x * x));
+    }
+    if beta < 0.0 {
+      return standard_pdf(alpha, -beta, -x);
+    }
+    let v = |theta: f64| {
+      2.0 / PI * (FRAC_PI_2 + beta * theta) / theta.cos()
+        * ((FRAC_PI_2 + beta * theta) * theta.tan() / beta).exp()
+    };
+    let g = (-PI * x / (2.0 * beta)).exp();
+    return g / (2.0 * beta)
+      * integrate(
+        |theta| {
+          let v = v(theta);
+          v * (-g * v).exp()
+        },
+        -FRAC_PI_2,
+        FRAC_PI_2,
+      );
+  }
+
+  let (zeta, theta0, v) = nolan(alpha, beta);
+  if (x - zeta).abs() < 1e-10 * (1.0 + zeta.abs()) {
+    return gamma(1.0 + 1.0 / alpha) * theta0.cos() / (PI * (1.0 + zeta * zeta).powf(0.5 / alpha));
+  }
+  if x < zeta {
+    return standard_pdf(alpha, -beta, -x);
+  }
+  let d = x - zeta;
+  let g = d.powf(alpha / (alpha - 1.0));
+  alpha * d.powf(1.0 / (alpha - 1.0)) / (PI * (alpha - 1.0).abs())
+    * integrate(
+      |theta| {
+        let v = v(theta);
+        v * (-g * v).exp()
+      },
+      -theta0,
+      FRAC_PI_2,
+    )
+}
+
+fn standard_cdf(alpha: f64, beta: f64, x: f64) -> f64 {
+  if alpha == 2.0 {
+    return 0.5 * statrs::function::erf::erfc(-x / 2.0);
+  }
+  if alpha == 1.0 {
+    if beta == 0.0 {
+      return 0.5 + x.atan() / PI;
+    }
+    if beta < 0.0 {
+      return 1.0 - standard_cdf(alpha, -beta, -x);
+    }
+    let v = |theta: f64| {
+      2.0 / PI * (FRAC_PI_2 + beta * theta) / theta.cos()
+        * ((FRAC_PI_2 + beta * theta) * theta.tan() / beta).exp()
+    };
+    let g = (-PI * x / (2.0 * beta)).exp();
+    return integrate(|theta| (-g * v(theta)).exp(), -FRAC_PI_2, FRAC_PI_2) / PI;
+  }
+
+  let (zeta, theta0, v) = nolan(alpha, beta);
+  if (x - zeta).abs() < 1e-10 * (1.0 + zeta.abs()) {
+    return (FRAC_PI_2 - theta0) / PI;
+  }
+  if x < zeta {
+    return 1.0 - standard_cdf(alpha, -beta, -x);
+  }
+  let g = (x - zeta).powf(alpha / (alpha - 1.0));
+  let c1 = if alpha < 1.0 {
+    (FRAC_PI_2 - theta0) / PI
+  } else {
+    1.0
+  };
+  c1 + (1.0 - alpha).signum() / PI * integrate(|theta| (-g * v(theta)).exp(), -theta0, FRAC_PI_2)
+}
+
+/// Chambers-Mallows-Stuck transformation of a uniform angle and a unit exponential
+/// https://doi.org/10.1080/01621459.1976.10480344 (Chambers, Mallows, Stuck 1976)
+impl Distribution<f64> for Stable {
+  fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+    let (alpha, beta) = (self.alpha, self.beta);
+    let v = PI * (rng.gen::<f64>() - 0.5);
+    let w: f64 = rng.sample(Exp1);
+
+    if alpha == 1.0 {
+      let z = 2.0 / PI
+        * ((FRAC_PI_2 + beta * v) * v.tan()
+          - beta * (FRAC_PI_2 * w * v.cos() / (FRAC_PI_2 + beta * v)).ln());
+      return self.c * z + 2.0 / PI * beta * self.c * self.c.ln() + self.mu;
+    }
+
+    let t = beta * (PI * alpha / 2.0).tan();
+    let b = t.atan() / alpha;
+    let s = (1.0 + t * t).powf(0.5 / alpha);
+    let z = s * (alpha * (v + b)).sin() / v.cos().powf(1.0 / alpha)
+      * ((v - alpha * (v + b)).cos() / w).powf((1.0 - alpha) / alpha);
+    self.c * z + self.mu
+  }
+}
+
+impl ProcessDistribution for Stable {}
+
+#[cfg(test)]
+mod tests {
+  use approx::assert_relative_eq;
+  use statrs::function::erf::erfc;
+
+  use super::*;
+  use crate::distributions::tests::{ks_critical, ks_statistic};
+
+  fn stable(alpha: f64, beta: f64, c: f64, mu: f64) -> Stable {
+    Stable::new(&Stable { alpha, beta, c, mu })
+  }
+
+  #[test]
+  fn gaussian_at_alpha_two() {
+    let s = stable(2.0, 0.0, 1.5, 0.3);
+    let sd = 1.5 * 2f64.sqrt();
+    for x in [-4.0, -1.0, 0.3, 2.0, 5.0] {
+      let z = (x - 0.3) / sd;
+      assert_relative_eq!(
+        s.pdf(x),
+        (-z * z / 2.0).exp() / (sd * (2.0 * PI).sqrt()),
+        max_relative = 1e-12
+      );
+      assert_relative_eq!(s.cdf(x), 0.5 * erfc(-z / 2f64.sqrt()), max_relative = 1e-12);
+    }
+  }
+
+  #[test]
+  fn cauchy_at_alpha_one() {
+    let s = stable(1.0, 0.0, 2.0, -1.0);
+    for x in [-10.0, -1.0, 0.5, 4.0] {
+      let z = (x + 1.0) / 2.0;
+      assert_relative_eq!(
+        s.pdf(x),
+        1.0 / (2.0 * PI * (1.0 + z * z)),
+        max_relative = 1e-12
+      );
+      assert_relative_eq!(s.cdf(x), 0.5 + z.atan() / PI, max_relative = 1e-12);
+    }
+  }
+
+  #[test]
+  fn levy_at_alpha_one_half() {
+    let c = 1.5;
+    let mu = 0.5;
+    let s = stable(0.5, 1.0, c, mu);
+    for x in [0.6, 1.0, 2.0, 5.0, 50.0] {
+      let d = x - mu;
+      assert_relative_eq!(
+        s.pdf(x),
+        (c / (2.0 * PI)).sqrt() * d.powf(-1.5) * (-c / (2.0 * d)).exp(),
+        max_relative = 1e-7
+      );
+      assert_relative_eq!(s.cdf(x), erfc((c / (2.0 * d)).sqrt()), max_relative = 1e-7);
+    }
+    assert_eq!(s.cdf(mu - 1.0), 0.0);
+  }
+
+  #[test]
+  fn density_integrates_to_the_distribution_function() {
+    for (alpha, beta) in [(1.5, 0.5), (0.7, -0.3), (1.0, 0.5), (1.2, -1.0)] {
+      let s = stable(alpha, beta, 1.3, 0.2);
+      for (a, b) in [(-2.0, -0.5), (-0.5, 1.0), (1.0, 3.0)] {
+        let mass = double_exponential::integrate(|x| s.pdf(x), a, b, 1e-10).integral;
+        assert_relative_eq!(mass, s.cdf(b) - s.cdf(a), epsilon = 1e-7);
+      }
+    }
+  }
+
+  #[test]
+  fn quantile_inverts_the_distribution_function() {
+    for (alpha, beta) in [(2.0, 0.0), (1.5, 0.5), (1.0, 0.5), (0.7, -0.3), (0.5, 1.0)] {
+      let s = stable(alpha, beta, 0.8, -0.4);
+      for x in [-1.5, -0.4, 0.3, 2.0] {
+        let p = s.cdf(x);
+        if p > 1e-10 && p < 1.0 - 1e-10 {
+          assert_relative_eq!(s.quantile(p), x, epsilon = 1e-8);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn samples_follow_the_distribution_function() {
+    let n = 2000;
+    for (alpha, beta) in [(2.0, 0.0), (1.5, 0.5), (1.0, 0.5), (0.7, -0.3), (0.5, 1.0)] {
+      let s = stable(alpha, beta, 2.0, 1.0);
+      let d = ks_statistic(&s, |x| s.cdf(x), n, 7);
+      assert!(d < ks_critical(n), "alpha {alpha} beta {beta}: {d}");
+    }
+  }
+}
@@ -32,7 +32,20 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 #[global_allocator]
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
+#[cfg(feature = "ai")]
 pub mod ai;
+#[cfg(feature = "async")]
+pub mod r#async;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "datasets")]
+pub mod datasets;
+pub mod distributions;
+pub mod prelude;
+pub mod progress;
 pub mod quant;
+pub mod rng;
+#[cfg(feature = "server")]
+pub mod server;
 pub mod stats;
 pub mod stochastic;
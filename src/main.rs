@@ -1,29 +1,73 @@
-use stochastic_rs::stochastic::{noise::fgn::FGN, Sampling};
+use std::path::PathBuf;
 
-fn main() {
-  let fbm = FGN::new(0.9, 10000, None, Some(10000));
+use anyhow::{bail, Context, Result};
+use stochastic_rs::{cli::Config, stochastic::registry::ModelRegistry};
 
-  let start = std::time::Instant::now();
-  for _ in 0..10000 {
-    let _ = fbm.sample();
-  }
+const USAGE: &str = "\
+usage: stochastic-rs <config.toml | config.json | -> [--output <path>] [--format csv|json|parquet]
+       stochastic-rs --models
+       stochastic-rs --serve <address>    (server feature)
+
+Samples the model of the configuration, or prices the option of its [pricing] table, and
+writes the result to the output of the configuration, the standard output by default.
+The configuration is read from the standard input for -. With --serve the models are
+served over HTTP instead: GET /models and POST /sample, /price and /calibrate with JSON
+bodies.";
 
-  let duration = start.elapsed();
-  println!(
-    "Time elapsed in expensive_function() is: {:?}",
-    duration.as_secs_f32()
-  );
+fn main() -> Result<()> {
+  let registry = ModelRegistry::default();
+  let mut args = std::env::args().skip(1);
+  let mut config = None;
+  let mut output = None;
+  let mut format = None;
 
-  let fbm = FGN::new(0.9, 10000, None, Some(10000));
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "-h" | "--help" => {
+        println!("{}", USAGE);
+        return Ok(());
+      }
+      "--models" => {
+        for name in registry.names() {
+          println!("{}", name);
+        }
+        return Ok(());
+      }
+      #[cfg(feature = "server")]
+      "--serve" => {
+        let addr = args
+          .next()
+          .context("--serve needs an address")?
+          .parse()
+          .context("Invalid address, expected host:port")?;
+        return tokio::runtime::Builder::new_multi_thread()
+          .enable_all()
+          .build()?
+          .block_on(stochastic_rs::server::serve(addr, registry));
+      }
+      "-o" | "--output" => {
+        output = Some(PathBuf::from(args.next().context("--output needs a path")?));
+      }
+      "-f" | "--format" => {
+        format = Some(args.next().context("--format needs a format")?.parse()?);
+      }
+      _ if config.is_none() && (arg == "-" || !arg.starts_with('-')) => {
+        config = Some(PathBuf::from(arg));
+      }
+      _ => bail!("Unexpected argument {}\n\n{}", arg, USAGE),
+    }
+  }
 
-  let start = std::time::Instant::now();
-  for _ in 0..10000 {
-    let _ = fbm.sample();
+  let Some(path) = config else {
+    bail!("No configuration given\n\n{}", USAGE);
+  };
+  let mut config = Config::from_path(&path)?;
+  if output.is_some() {
+    config.output.path = output;
+  }
+  if format.is_some() {
+    config.output.format = format;
   }
 
-  let duration = start.elapsed();
-  println!(
-    "Time elapsed in expensive_function() is: {:?}",
-    duration.as_secs_f32()
-  );
+  config.run(&registry)?.write(&config.output)
 }
@@ -0,0 +1,170 @@
+//! # Prelude
+//!
+//! The sampling traits are re-exported at the root, the models are grouped by family:
+//!
+//! ```ignore
+//! use stochastic_rs::prelude::*;
+//! use stochastic_rs::prelude::diffusion::*;
+//! ```
+
+pub use crate::stochastic::{
+  registry::{ModelRegistry, Params},
+  Distribution, ProcessDistribution, Sampling, Sampling2D, Sampling3D, StationaryDistribution,
+  TheoreticalMoments, TransitionDensity,
+};
+pub use crate::{
+  progress::{progress_channel, CancellationToken, Hooks, Progress},
+  rng::{seed, seed_with, with_generator, with_seed, with_stream, Generator},
+};
+
+/// Commodity spot and futures models
+pub mod commodity {
+  pub use crate::stochastic::commodity::{
+    electricity::{ElectricitySpot, SignedPareto},
+    schwartz::{Schwartz1F, Schwartz2F},
+  };
+}
+
+/// Diffusion processes
+pub mod diffusion {
+  pub use crate::stochastic::diffusion::{
+    cir::CIR,
+    fcir::FCIR,
+    fgbm::FGBM,
+    fjacobi::FJacobi,
+    fou::FOU,
+    gbm::GBM,
+    jacobi::Jacobi,
+    ou::OU,
+    regime_switching::{Coefficients, RegimeSwitching},
+  };
+}
+
+/// Short rate models
+pub mod interest {
+  pub use crate::stochastic::interest::{
+    duffie_kan::DuffieKan, fvasicek::FVasicek, ho_lee::HoLee, hull_white::HullWhite,
+    hull_white_2f::HullWhite2F, lmm::Lmm, vasicek::Vasicek,
+  };
+}
+
+/// Jump processes and jump diffusions
+pub mod jump {
+  pub use crate::stochastic::jump::{
+    bates::Bates1996, hawkes_jump_diffusion::HawkesJumpDiffusion, ig::IG, jump_fou::JumpFOU,
+    levy_diffusion::LevyDiffusion, merton::Merton, nig::NIG, vg::VG,
+  };
+  pub use crate::stochastic::process::{
+    ccustom::CompoundCustom, cpoisson::CompoundPoisson, customjt::CustomJt, hawkes::Hawkes,
+    poisson::Poisson,
+  };
+}
+
+/// Noise generators
+pub mod noise {
+  pub use crate::stochastic::noise::{
+    cfgns::CFGNS,
+    cgns::CGNS,
+    colored::ColoredNoise,
+    fgn::FGN,
+    power_law::{spectral_exponent, PowerLawNoise},
+    sine_wiener::SineWienerNoise,
+    spectral::SpectralNoise,
+    telegraph::TelegraphNoise,
+  };
+}
+
+/// Population dynamics
+pub mod population {
+  pub use crate::stochastic::population::{
+    logistic::{DemographicLogistic, Logistic},
+    sir::{SEIR, SIR},
+  };
+  pub use crate::stochastic::process::{
+    birth_death::BirthDeath,
+    csbp::CSBP,
+    extinction::{Extinction, ExtinctionStats},
+  };
+}
+
+/// Gaussian and general processes
+pub mod process {
+  pub use crate::stochastic::{
+    adaptive::{AdaptiveEstimate, AdaptiveMonteCarlo, ConvergenceDiagnostics},
+    batch::{BatchRunner, Checkpoint, PathStatistics},
+    malliavin::Malliavin,
+    process::{
+      bm::BM,
+      carma::Carma,
+      cbms::CBMS,
+      cfbms::Cfbms,
+      fbm::Fbm,
+      gaussian_process::{
+        fbm_kernel, matern_kernel, rbf_kernel, GaussianProcess, GaussianProcessMethod, Kernel,
+      },
+      gillespie::{Gillespie, Propensity, Trajectory},
+      karhunen_loeve::{KarhunenLoeve, KarhunenLoeveProcess},
+      markov_chain::MarkovChain,
+      mixed_fbm::MixedFbm,
+      path_construction::{
+        barrier_crossing_probability, BrownianBridge, PathConstruction, PrincipalComponents,
+      },
+      random_walk::{LevyFlight, RandomWalk},
+    },
+  };
+}
+
+/// Pricing, calibration and market microstructure
+pub mod quant {
+  #[cfg(feature = "dates")]
+  pub use crate::quant::bonds::hull_white::HullWhite as HullWhiteBond;
+  pub use crate::quant::{
+    bonds::{cir::CIR as CIRBond, vasicek::Vasicek as VasicekBond},
+    greeks::{
+      bump_and_revalue, path_estimator, Estimate, EuropeanGbm, GreekComparison, Ladder, RiskLadder,
+    },
+    microstructure::{
+      order_book::{OrderBook, OrderBookPath},
+      resampling::{calendar_bars, subordinate, transaction_counts, Bars},
+    },
+    options::{
+      bsm::{BSMCoc, BSM},
+      chain::{OptionChainGenerator, OptionQuote},
+    },
+    r#trait::Price,
+    shock::{Bump, Shock, Shockable},
+    volatility::{
+      double_heston::{DoubleHestonPricer, VarianceFactor},
+      heston::{HestonCalibrator, HestonPricer},
+    },
+    OptionType,
+  };
+}
+
+/// Statistics and estimators
+pub mod stats {
+  pub use crate::stats::{fd::FractalDim, mle::nmle_heston};
+}
+
+/// Stochastic volatility models
+pub mod volatility {
+  pub use crate::stochastic::volatility::{
+    bergomi::Bergomi,
+    diagnostics::{Diagnostics, HestonDiagnostics, HestonParams},
+    double_heston::DoubleHeston,
+    fheston::RoughHeston,
+    heston::Heston,
+    rbergomi::RoughBergomi,
+    sabr::Sabr,
+    wishart::{Wishart, WishartScheme},
+    HestonPow, HestonScheme,
+  };
+}
+
+/// Weather models and derivatives
+pub mod weather {
+  pub use crate::stochastic::weather::{
+    degree_days::{DegreeDayContract, DegreeDays},
+    temperature::CarTemperature,
+  };
+}
@@ -0,0 +1,117 @@
+//! # Progress and cancellation
+//!
+//! Long-running entry points (`Sampling::sample_par_with`, `BatchRunner` and the Heston
+//! calibration) accept [`Hooks`]: an optional progress callback and an optional
+//! cancellation token, which is checked cooperatively between paths or evaluations.
+
+use std::{
+  sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    mpsc::{self, Receiver},
+    Arc,
+  },
+  time::{Duration, Instant},
+};
+
+/// Progress of a long-running computation
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+  /// Completed units of work (paths or objective evaluations)
+  pub completed: usize,
+  /// Total units of work, if known
+  pub total: Option<usize>,
+  /// Time since the start
+  pub elapsed: Duration,
+  /// Estimated time until completion, if the total is known
+  pub eta: Option<Duration>,
+}
+
+pub type ProgressCallback = Arc<dyn Fn(Progress) + Send + Sync>;
+
+/// Progress callback that forwards the reports to a channel
+pub fn progress_channel() -> (ProgressCallback, Receiver<Progress>) {
+  let (tx, rx) = mpsc::channel();
+  let tx = std::sync::Mutex::new(tx);
+  let callback: ProgressCallback = Arc::new(move |p| {
+    // the receiver may have been dropped, progress is best effort
+    let _ = tx.lock().unwrap().send(p);
+  });
+
+  (callback, rx)
+}
+
+/// Cooperative cancellation token, clones share the same flag
+#[derive(Default, Debug, Clone)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn cancel(&self) {
+    self.0.store(true, Ordering::Relaxed);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.0.load(Ordering::Relaxed)
+  }
+}
+
+/// Progress and cancellation hooks of a computation
+#[derive(Default, Clone)]
+pub struct Hooks {
+  pub progress: Option<ProgressCallback>,
+  pub cancel: Option<CancellationToken>,
+}
+
+impl Hooks {
+  pub fn is_cancelled(&self) -> bool {
+    self.cancel.as_ref().is_some_and(|c| c.is_cancelled())
+  }
+}
+
+/// Counts the completed units of work and reports them to the hooks
+pub(crate) struct Tracker<'a> {
+  hooks: &'a Hooks,
+  total: Option<usize>,
+  initial: usize,
+  completed: AtomicUsize,
+  start: Instant,
+}
+
+impl<'a> Tracker<'a> {
+  pub(crate) fn new(hooks: &'a Hooks, total: Option<usize>, completed: usize) -> Self {
+    Self {
+      hooks,
+      total,
+      initial: completed,
+      completed: AtomicUsize::new(completed),
+      start: Instant::now(),
+    }
+  }
+
+  pub(crate) fn is_cancelled(&self) -> bool {
+    self.hooks.is_cancelled()
+  }
+
+  /// Add `units` completed units of work and report the progress
+  pub(crate) fn advance(&self, units: usize) {
+    let completed = self.completed.fetch_add(units, Ordering::Relaxed) + units;
+
+    if let Some(callback) = &self.hooks.progress {
+      let elapsed = self.start.elapsed();
+      let eta = self.total.map(|total| {
+        let rate = (completed - self.initial) as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+        Duration::from_secs_f64(total.saturating_sub(completed) as f64 / rate)
+      });
+
+      callback(Progress {
+        completed,
+        total: self.total,
+        elapsed,
+        eta,
+      });
+    }
+  }
+}
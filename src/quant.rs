@@ -1,7 +1,20 @@
+pub mod backtest;
 pub mod bonds;
+pub mod calibration;
+pub mod control;
+pub mod curve;
+pub mod fourier;
+pub mod greeks;
+pub mod insurance;
+pub mod microstructure;
 pub mod options;
+pub mod portfolio;
+pub mod scenario;
+pub mod shock;
 pub mod r#trait;
 pub mod volatility;
+pub mod xva;
+#[cfg(feature = "market-data")]
 pub mod yahoo;
 
 /// Option type.
@@ -0,0 +1,170 @@
+use ndarray::ArrayView1;
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+
+use crate::stats::comparison::LikelihoodRatio;
+
+/// x ln(p) with 0 ln(0) = 0
+fn x_ln(x: f64, p: f64) -> f64 {
+  if x == 0.0 {
+    0.0
+  } else {
+    x * p.ln()
+  }
+}
+
+fn chi_squared_test(statistic: f64, degrees_of_freedom: usize) -> LikelihoodRatio {
+  let statistic = statistic.max(0.0);
+  LikelihoodRatio {
+    statistic,
+    degrees_of_freedom,
+    p_value: 1.0
+      - ChiSquared::new(degrees_of_freedom as f64)
+        .unwrap()
+        .cdf(statistic),
+  }
+}
+
+/// Kupiec proportion of failures test of the unconditional coverage: the number of
+/// violations is Binomial(n, alpha) under a correct VaR at the tail probability alpha.
+/// https://doi.org/10.3905/jod.1995.407942 (Kupiec 1995)
+pub fn kupiec(violations: &[bool], alpha: f64) -> LikelihoodRatio {
+  let n = violations.len() as f64;
+  let x = violations.iter().filter(|&&v| v).count() as f64;
+  let rate = x / n;
+  let null = x_ln(n - x, 1.0 - alpha) + x_ln(x, alpha);
+  let alternative = x_ln(n - x, 1.0 - rate) + x_ln(x, rate);
+  chi_squared_test(-2.0 * (null - alternative), 1)
+}
+
+/// Christoffersen test of the independence of the violations against a first order Markov
+/// chain, violations cluster in time if the VaR reacts too slowly.
+/// https://doi.org/10.2307/2527341 (Christoffersen 1998)
+pub fn christoffersen_independence(violations: &[bool]) -> LikelihoodRatio {
+  let mut counts = [[0.0f64; 2]; 2];
+  for pair in violations.windows(2) {
+    counts[pair[0] as usize][pair[1] as usize] += 1.0;
+  }
+  let [[n00, n01], [n10, n11]] = counts;
+  let pi0 = n01 / (n00 + n01).max(1.0);
+  let pi1 = n11 / (n10 + n11).max(1.0);
+  let pi = (n01 + n11) / (n00 + n01 + n10 + n11);
+
+  let null = x_ln(n00 + n10, 1.0 - pi) + x_ln(n01 + n11, pi);
+  let alternative = x_ln(n00, 1.0 - pi0) + x_ln(n01, pi0) + x_ln(n10, 1.0 - pi1) + x_ln(n11, pi1);
+  chi_squared_test(-2.0 * (null - alternative), 1)
+}
+
+/// Result of a VaR backtest
+#[derive(Debug, Clone)]
+pub struct VarBacktest {
+  /// Whether the loss exceeded the VaR, one per period
+  pub violations: Vec<bool>,
+  /// Observed violation rate
+  pub rate: f64,
+  pub kupiec: LikelihoodRatio,
+  pub independence: LikelihoodRatio,
+  /// Christoffersen conditional coverage, the sum of the two, chi^2 with 2 degrees of
+  /// freedom
+  pub conditional_coverage: LikelihoodRatio,
+}
+
+/// Backtest of VaR forecasts against realized returns, e.g. daily returns from `Yahoo`.
+/// The VaR of a period is a positive loss at the tail probability alpha (0.01 for a 99% VaR),
+/// forecast before the period, and is violated if the return is below -VaR.
+pub fn var_backtest(returns: ArrayView1<f64>, var: ArrayView1<f64>, alpha: f64) -> VarBacktest {
+  assert_eq!(returns.len(), var.len(), "One VaR forecast per return");
+  assert!(alpha > 0.0 && alpha < 1.0, "alpha must be in (0, 1)");
+  let violations = returns
+    .iter()
+    .zip(var.iter())
+    .map(|(r, v)| *r < -v)
+    .collect::<Vec<_>>();
+  let rate = violations.iter().filter(|&&v| v).count() as f64 / violations.len() as f64;
+  let kupiec = kupiec(&violations, alpha);
+  let independence = christoffersen_independence(&violations);
+  let conditional_coverage = chi_squared_test(kupiec.statistic + independence.statistic, 2);
+
+  VarBacktest {
+    violations,
+    rate,
+    kupiec,
+    independence,
+    conditional_coverage,
+  }
+}
+
+/// Loss of a forecast
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Loss {
+  /// (forecast - realized)^2
+  #[default]
+  Squared,
+  /// |forecast - realized|
+  Absolute,
+  /// realized / forecast - ln(realized / forecast) - 1, robust to the noise of a variance
+  /// proxy (Patton 2011)
+  Qlike,
+}
+
+impl Loss {
+  pub fn loss(&self, forecast: f64, realized: f64) -> f64 {
+    match self {
+      Self::Squared => (forecast - realized).powi(2),
+      Self::Absolute => (forecast - realized).abs(),
+      Self::Qlike => realized / forecast - (realized / forecast).ln() - 1.0,
+    }
+  }
+}
+
+/// Result of a Diebold-Mariano test
+#[derive(Debug, Clone, Copy)]
+pub struct DieboldMariano {
+  /// Mean loss differential, negative if the first forecast is better
+  pub mean_differential: f64,
+  /// Asymptotically standard normal under equal accuracy
+  pub statistic: f64,
+  /// Two-sided p value
+  pub p_value: f64,
+}
+
+/// Diebold-Mariano test of the equal accuracy of two forecasts of the same series, with the
+/// long run variance of the loss differential from its autocovariances up to lag h - 1
+/// for h-step forecasts and the small sample correction of Harvey, Leybourne and Newbold.
+/// https://doi.org/10.1080/07350015.1995.10524599 (Diebold, Mariano 1995)
+pub fn diebold_mariano(
+  forecast1: ArrayView1<f64>,
+  forecast2: ArrayView1<f64>,
+  realized: ArrayView1<f64>,
+  loss: Loss,
+  horizon: usize,
+) -> DieboldMariano {
+  assert!(
+    forecast1.len() == realized.len() && forecast2.len() == realized.len(),
+    "One forecast of each per realized value"
+  );
+  assert!(horizon >= 1, "The horizon must be at least one step");
+  let d = realized
+    .iter()
+    .zip(forecast1.iter().zip(forecast2.iter()))
+    .map(|(&y, (&f1, &f2))| loss.loss(f1, y) - loss.loss(f2, y))
+    .collect::<Vec<_>>();
+  let n = d.len() as f64;
+  let mean = d.iter().sum::<f64>() / n;
+  let autocovariance = |lag: usize| {
+    d.iter()
+      .zip(d.iter().skip(lag))
+      .map(|(a, b)| (a - mean) * (b - mean))
+      .sum::<f64>()
+      / n
+  };
+  let long_run = autocovariance(0) + 2.0 * (1..horizon).map(autocovariance).sum::<f64>();
+  let h = horizon as f64;
+  let correction = ((n + 1.0 - 2.0 * h + h * (h - 1.0) / n) / n).sqrt();
+  let statistic = correction * mean / (long_run.max(f64::MIN_POSITIVE) / n).sqrt();
+
+  DieboldMariano {
+    mean_differential: mean,
+    statistic,
+    p_value: 2.0 * (1.0 - Normal::new(0.0, 1.0).unwrap().cdf(statistic.abs())),
+  }
+}
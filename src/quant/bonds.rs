@@ -1,3 +1,4 @@
 pub mod cir;
+#[cfg(feature = "dates")]
 pub mod hull_white;
 pub mod vasicek;
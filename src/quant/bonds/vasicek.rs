@@ -16,8 +16,10 @@ pub struct Vasicek {
   /// Maturity of the bond in days
   pub tau: f64,
   /// Evaluation date
+  #[cfg(feature = "dates")]
   pub eval: Option<chrono::NaiveDate>,
   /// Expiration date
+  #[cfg(feature = "dates")]
   pub expiration: Option<chrono::NaiveDate>,
 }
 
@@ -36,10 +38,12 @@ impl Price for Vasicek {
     Some(self.tau)
   }
 
+  #[cfg(feature = "dates")]
   fn eval(&self) -> Option<chrono::NaiveDate> {
     self.eval
   }
 
+  #[cfg(feature = "dates")]
   fn expiration(&self) -> Option<chrono::NaiveDate> {
     self.expiration
   }
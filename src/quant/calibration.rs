@@ -0,0 +1,5 @@
+pub mod global;
+pub mod objective;
+pub mod sensitivity;
+pub mod simplex;
+pub mod transform;
@@ -0,0 +1,208 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use rayon::prelude::*;
+
+use crate::progress::{Hooks, Tracker};
+
+/// Result of a minimization
+#[derive(Default, Debug, Clone)]
+pub struct Minimum {
+  /// Best parameters
+  pub params: Vec<f64>,
+  /// Objective at the best parameters
+  pub value: f64,
+  /// Number of objective evaluations
+  pub evaluations: usize,
+  /// Whether the stopping criterion was met before the budget ran out
+  pub converged: bool,
+}
+
+/// Differential evolution (DE/rand/1/bin) in a box, the objective of every generation
+/// is evaluated in parallel. The trial vectors are drawn from the ChaCha20 stream of
+/// [`crate::rng`] seeded with `seed`, so the result only depends on the seed, on every
+/// platform.
+/// https://doi.org/10.1023/A:1008202821328
+pub struct DifferentialEvolution {
+  /// Lower and upper bound of every parameter
+  pub bounds: Vec<(f64, f64)>,
+  /// Population size (default 15 times the dimension)
+  pub population: Option<usize>,
+  /// Maximum number of generations
+  pub generations: usize,
+  /// Differential weight (default 0.7)
+  pub weight: Option<f64>,
+  /// Crossover probability (default 0.9)
+  pub crossover: Option<f64>,
+  /// Stop when the spread of the population objective falls below this (default 1e-10)
+  pub tol: Option<f64>,
+  /// Seed of the trial vectors
+  pub seed: u64,
+  /// Progress and cancellation hooks, a unit of work is a generation
+  pub hooks: Hooks,
+}
+
+impl DifferentialEvolution {
+  #[must_use]
+  pub fn new(bounds: Vec<(f64, f64)>, generations: usize, seed: u64) -> Self {
+    assert!(!bounds.is_empty(), "At least one parameter is needed");
+    assert!(
+      bounds.iter().all(|(lo, hi)| lo < hi),
+      "Lower bounds must be below the upper bounds"
+    );
+
+    Self {
+      bounds,
+      population: None,
+      generations,
+      weight: None,
+      crossover: None,
+      tol: None,
+      seed,
+      hooks: Hooks::default(),
+    }
+  }
+
+  fn random_point(&self, rng: &mut ChaCha20Rng) -> Vec<f64> {
+    self
+      .bounds
+      .iter()
+      .map(|&(lo, hi)| rng.gen_range(lo..hi))
+      .collect()
+  }
+
+  pub fn minimize<F>(&self, f: F) -> Minimum
+  where
+    F: Fn(&[f64]) -> f64 + Sync,
+  {
+    let dim = self.bounds.len();
+    let size = self.population.unwrap_or(15 * dim).max(4);
+    let weight = self.weight.unwrap_or(0.7);
+    let crossover = self.crossover.unwrap_or(0.9);
+    let tol = self.tol.unwrap_or(1e-10);
+    let mut rng = ChaCha20Rng::seed_from_u64(self.seed);
+    let tracker = Tracker::new(&self.hooks, Some(self.generations), 0);
+
+    // non-finite objectives (invalid regions) never win a selection
+    let eval = |x: &Vec<f64>| {
+      let y = f(x);
+      if y.is_finite() {
+        y
+      } else {
+        f64::INFINITY
+      }
+    };
+
+    let mut population = (0..size)
+      .map(|_| self.random_point(&mut rng))
+      .collect::<Vec<_>>();
+    let mut values = population.par_iter().map(eval).collect::<Vec<_>>();
+    let mut evaluations = size;
+    let mut converged = false;
+
+    for _ in 0..self.generations {
+      if tracker.is_cancelled() {
+        break;
+      }
+
+      let trials = (0..size)
+        .map(|i| {
+          // three distinct members other than i (the population has at least 4)
+          let mut chosen = [i; 4];
+          for k in 1..4 {
+            chosen[k] = loop {
+              let j = rng.gen_range(0..size);
+              if !chosen[..k].contains(&j) {
+                break j;
+              }
+            };
+          }
+          let [_, a, b, c] = chosen;
+          let forced = rng.gen_range(0..dim);
+
+          (0..dim)
+            .map(|d| {
+              if d == forced || rng.gen::<f64>() < crossover {
+                let (lo, hi) = self.bounds[d];
+                let x = population[a][d] + weight * (population[b][d] - population[c][d]);
+                // reflect back into the box
+                if x < lo {
+                  (lo + (lo - x)).min(hi)
+                } else if x > hi {
+                  (hi - (x - hi)).max(lo)
+                } else {
+                  x
+                }
+              } else {
+                population[i][d]
+              }
+            })
+            .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+      let trial_values = trials.par_iter().map(eval).collect::<Vec<_>>();
+      evaluations += size;
+
+      for (i, (trial, value)) in trials.into_iter().zip(trial_values).enumerate() {
+        if value <= values[i] {
+          population[i] = trial;
+          values[i] = value;
+        }
+      }
+
+      tracker.advance(1);
+
+      let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+          (lo.min(v), hi.max(v))
+        });
+      if max - min <= tol * (1.0 + min.abs()) {
+        converged = true;
+        break;
+      }
+    }
+
+    let best = (0..size)
+      .min_by(|&i, &j| values[i].total_cmp(&values[j]))
+      .unwrap();
+
+    Minimum {
+      params: population[best].clone(),
+      value: values[best],
+      evaluations,
+      converged,
+    }
+  }
+}
+
+/// Multi-start local optimization: runs `local` from `starts` uniform random points of the
+/// box in parallel and returns the best local minimum. `local(x0)` returns the local minimum
+/// found from x0, e.g. by Levenberg-Marquardt.
+pub fn multi_start<L>(bounds: &[(f64, f64)], starts: usize, seed: u64, local: L) -> Minimum
+where
+  L: Fn(&[f64]) -> Minimum + Sync,
+{
+  assert!(starts > 0, "At least one start is needed");
+  let mut rng = ChaCha20Rng::seed_from_u64(seed);
+  let points = (0..starts)
+    .map(|_| {
+      bounds
+        .iter()
+        .map(|&(lo, hi)| rng.gen_range(lo..hi))
+        .collect::<Vec<_>>()
+    })
+    .collect::<Vec<_>>();
+
+  let minima = points.par_iter().map(|x0| local(x0)).collect::<Vec<_>>();
+  let evaluations = minima.iter().map(|m| m.evaluations).sum();
+
+  minima
+    .into_iter()
+    .min_by(|a, b| a.value.total_cmp(&b.value))
+    .map(|best| Minimum {
+      evaluations,
+      ..best
+    })
+    .unwrap()
+}
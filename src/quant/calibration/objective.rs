@@ -0,0 +1,175 @@
+use crate::quant::options::{
+  bsm::{BSMCoc, BSM},
+  chain::OptionQuote,
+};
+
+/// Error of a model price against a quote
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorMeasure {
+  /// Model price - mid
+  #[default]
+  Price,
+  /// (model price - mid) / mid
+  RelativePrice,
+  /// Black-Scholes implied volatility of the model price - implied volatility of the mid
+  ImpliedVolatility,
+}
+
+/// Weight of a quote in the objective
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Weighting {
+  #[default]
+  Uniform,
+  /// Black-Scholes vega of the quote, relative to the largest vega, so the liquid
+  /// near the money options dominate
+  Vega,
+  /// 1 / (ask - bid)^2, the quotes with a tight market are fitted more closely
+  InverseSpread,
+}
+
+/// Calibration objective sum_i w_i e_i^2 over option quotes, with the error e_i of
+/// the measure and the weight w_i of the weighting times the weight of the maturity
+/// bucket of the quote. Uniform least squares on prices overweights the expensive long
+/// dated and in the money options, which these choices correct.
+#[derive(Debug, Clone, Default)]
+pub struct Objective {
+  pub measure: ErrorMeasure,
+  pub weighting: Weighting,
+  /// Weight of the maturities up to each bound, (bound, weight) sorted by bound, the
+  /// quotes past the last bound have weight 1
+  pub maturity_buckets: Vec<(f64, f64)>,
+  /// Smallest spread of the inverse spread weights, so a zero width market does not take
+  /// all the weight (default 1e-4)
+  pub min_spread: Option<f64>,
+}
+
+impl Objective {
+  #[must_use]
+  pub fn new(measure: ErrorMeasure, weighting: Weighting) -> Self {
+    Self {
+      measure,
+      weighting,
+      maturity_buckets: Vec::new(),
+      min_spread: None,
+    }
+  }
+
+  #[must_use]
+  pub fn with_maturity_bucket(mut self, bound: f64, weight: f64) -> Self {
+    assert!(weight >= 0.0, "Weights must be non-negative");
+    self.maturity_buckets.push((bound, weight));
+    self.maturity_buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+    self
+  }
+
+  fn bucket_weight(&self, tau: f64) -> f64 {
+    self
+      .maturity_buckets
+      .iter()
+      .find(|(bound, _)| tau <= *bound)
+      .map_or(1.0, |(_, weight)| *weight)
+  }
+
+  /// Weight of every quote
+  pub fn weights(&self, quotes: &[OptionQuote]) -> Vec<f64> {
+    let max_vega = quotes
+      .iter()
+      .map(|quote| quote.vega)
+      .fold(0.0, f64::max)
+      .max(f64::MIN_POSITIVE);
+    let min_spread = self.min_spread.unwrap_or(1e-4);
+
+    quotes
+      .iter()
+      .map(|quote| {
+        let weight = match self.weighting {
+          Weighting::Uniform => 1.0,
+          Weighting::Vega => quote.vega / max_vega,
+          Weighting::InverseSpread => (quote.ask - quote.bid).max(min_spread).powi(-2),
+        };
+        weight * self.bucket_weight(quote.tau)
+      })
+      .collect()
+  }
+
+  /// Implied volatility of a price of the quoted option
+  fn implied_volatility(quote: &OptionQuote, price: f64, s0: f64, r: f64, q: f64) -> f64 {
+    BSM::new(&BSM {
+      s: s0,
+      v: 0.2,
+      k: quote.k,
+      r,
+      q: Some(q),
+      tau: Some(quote.tau),
+      option_type: quote.option_type,
+      b: BSMCoc::MERTON1973,
+      ..Default::default()
+    })
+    .implied_volatility(price)
+  }
+
+  /// Unweighted errors of the model prices, one per quote
+  pub fn errors(&self, quotes: &[OptionQuote], model: &[f64], s0: f64, r: f64, q: f64) -> Vec<f64> {
+    assert_eq!(quotes.len(), model.len(), "One model price per quote");
+    quotes
+      .iter()
+      .zip(model)
+      .map(|(quote, &price)| match self.measure {
+        ErrorMeasure::Price => price - quote.mid,
+        ErrorMeasure::RelativePrice => (price - quote.mid) / quote.mid,
+        ErrorMeasure::ImpliedVolatility => {
+          Self::implied_volatility(quote, price, s0, r, q)
+            - Self::implied_volatility(quote, quote.mid, s0, r, q)
+        }
+      })
+      .collect()
+  }
+
+  /// Weighted residuals sqrt(w_i) e_i, for a least squares solver
+  pub fn residuals(
+    &self,
+    quotes: &[OptionQuote],
+    model: &[f64],
+    s0: f64,
+    r: f64,
+    q: f64,
+  ) -> Vec<f64> {
+    self
+      .errors(quotes, model, s0, r, q)
+      .iter()
+      .zip(self.weights(quotes))
+      .map(|(e, w)| w.sqrt() * e)
+      .collect()
+  }
+
+  /// sum_i w_i e_i^2, a quote whose error is not finite (e.g. a model price outside the
+  /// no-arbitrage bounds of the implied volatility) makes the objective infinite
+  pub fn value(&self, quotes: &[OptionQuote], model: &[f64], s0: f64, r: f64, q: f64) -> f64 {
+    let value = self
+      .residuals(quotes, model, s0, r, q)
+      .iter()
+      .map(|e| e * e)
+      .sum::<f64>();
+    if value.is_finite() {
+      value
+    } else {
+      f64::INFINITY
+    }
+  }
+
+  /// Weights of the squared price errors equivalent to the objective to first order:
+  /// the relative error divides by the mid and the implied volatility error by the vega.
+  /// For price-based solvers such as the Levenberg-Marquardt calibrators.
+  pub fn price_weights(&self, quotes: &[OptionQuote]) -> Vec<f64> {
+    self
+      .weights(quotes)
+      .iter()
+      .zip(quotes)
+      .map(|(w, quote)| match self.measure {
+        ErrorMeasure::Price => *w,
+        ErrorMeasure::RelativePrice => w / quote.mid.max(f64::MIN_POSITIVE).powi(2),
+        ErrorMeasure::ImpliedVolatility => w / quote.vega.max(1e-8).powi(2),
+      })
+      .collect()
+  }
+}
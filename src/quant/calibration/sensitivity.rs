@@ -0,0 +1,156 @@
+use nalgebra::{DMatrix, DVector};
+
+/// Uncertainty of calibrated parameters from the Jacobian of the residuals at the optimum,
+/// with the Gauss-Newton approximation J^T J / s^2 of the Hessian of the least squares
+/// objective, s^2 = RSS / (n - p)
+#[derive(Debug, Clone)]
+pub struct ParameterUncertainty {
+  /// Calibrated parameters
+  pub params: Vec<f64>,
+  /// Jacobian of the residuals, one row per residual and one column per parameter
+  pub jacobian: DMatrix<f64>,
+  /// Covariance s^2 (J^T J)^-1 of the parameters
+  pub covariance: DMatrix<f64>,
+  /// Ratio of the largest to the smallest singular value of J, large for ill-identified
+  /// parameters
+  pub condition_number: f64,
+}
+
+impl ParameterUncertainty {
+  /// Central finite difference Jacobian of the residuals at the parameters, with the
+  /// relative step (default 1e-5)
+  pub fn from_residuals<F>(residuals: F, params: &[f64], step: Option<f64>) -> Self
+  where
+    F: Fn(&[f64]) -> Vec<f64>,
+  {
+    let step = step.unwrap_or(1e-5);
+    let r0 = residuals(params);
+    let n = r0.len();
+    let p = params.len();
+
+    let mut jacobian = DMatrix::<f64>::zeros(n, p);
+    for j in 0..p {
+      let h = step * params[j].abs().max(1.0);
+      let mut up = params.to_vec();
+      let mut down = params.to_vec();
+      up[j] += h;
+      down[j] -= h;
+      let (r_up, r_down) = (residuals(&up), residuals(&down));
+      for i in 0..n {
+        jacobian[(i, j)] = (r_up[i] - r_down[i]) / (2.0 * h);
+      }
+    }
+
+    let rss = r0.iter().map(|r| r * r).sum::<f64>();
+    Self::from_jacobian(jacobian, params, rss)
+  }
+
+  /// From a Jacobian, e.g. the analytic one of the optimizer, and the residual sum of
+  /// squares at the optimum
+  pub fn from_jacobian(jacobian: DMatrix<f64>, params: &[f64], rss: f64) -> Self {
+    let (n, p) = jacobian.shape();
+    assert_eq!(p, params.len(), "One Jacobian column per parameter");
+    assert!(n > p, "More residuals than parameters are needed");
+    let s2 = rss / (n - p) as f64;
+
+    let svd = jacobian.clone().svd(false, true);
+    let max = svd.singular_values.max();
+    let min = svd.singular_values.min();
+    let v_t = svd.v_t.as_ref().unwrap();
+    // (J^T J)^-1 = V S^-2 V^T over the singular values above the rounding level of J, the
+    // parameters that load on the null directions are left at infinite variance
+    let cutoff = max * f64::EPSILON * n.max(p) as f64;
+    let inverse_squares = DVector::from_iterator(
+      p,
+      svd
+        .singular_values
+        .iter()
+        .map(|&s| if s > cutoff { s.powi(-2) } else { 0.0 }),
+    );
+    let mut covariance = v_t.transpose() * DMatrix::from_diagonal(&inverse_squares) * v_t * s2;
+    for (k, &s) in svd.singular_values.iter().enumerate() {
+      if s <= cutoff {
+        for j in 0..p {
+          if v_t[(k, j)].abs() > f64::EPSILON.sqrt() {
+            covariance[(j, j)] = f64::INFINITY;
+          }
+        }
+      }
+    }
+
+    Self {
+      params: params.to_vec(),
+      jacobian,
+      covariance,
+      condition_number: max / min,
+    }
+  }
+
+  pub fn standard_errors(&self) -> Vec<f64> {
+    self
+      .covariance
+      .diagonal()
+      .iter()
+      .map(|v| v.sqrt())
+      .collect()
+  }
+
+  /// Correlation of the estimates, undefined (NaN) for the parameters of infinite variance
+  pub fn correlation(&self) -> DMatrix<f64> {
+    let se = self.standard_errors();
+    DMatrix::from_fn(se.len(), se.len(), |i, j| {
+      if se[i].is_finite() && se[j].is_finite() {
+        self.covariance[(i, j)] / (se[i] * se[j])
+      } else {
+        f64::NAN
+      }
+    })
+  }
+
+  /// Pairs of parameters whose estimates are correlated beyond the threshold in absolute
+  /// value (e.g. 0.9), i.e. the data only pins down a combination of the two
+  pub fn ill_identified(&self, threshold: f64) -> Vec<(usize, usize, f64)> {
+    let correlation = self.correlation();
+    let p = self.params.len();
+    let mut pairs = Vec::new();
+    for i in 0..p {
+      for j in i + 1..p {
+        let c = correlation[(i, j)];
+        if !c.is_finite() || c.abs() > threshold {
+          pairs.push((i, j, c));
+        }
+      }
+    }
+    pairs
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn collinear_parameters_have_infinite_variance_without_nans() {
+    // the residuals only depend on x0 + x1, x2 is identified
+    let jacobian = DMatrix::from_fn(10, 3, |i, j| match j {
+      0 | 1 => 1.0 + i as f64,
+      _ => (i as f64).sin(),
+    });
+    let uncertainty = ParameterUncertainty::from_jacobian(jacobian, &[1.0, 2.0, 3.0], 0.5);
+    let se = uncertainty.standard_errors();
+
+    assert!(se[0].is_infinite() && se[1].is_infinite());
+    assert!(se[2].is_finite() && se[2] > 0.0);
+    assert!(uncertainty.covariance.iter().all(|c| !c.is_nan()));
+    assert!(uncertainty
+      .ill_identified(0.9)
+      .iter()
+      .any(|&(i, j, _)| (i, j) == (0, 1)));
+  }
+
+  #[test]
+  #[should_panic(expected = "More residuals than parameters")]
+  fn square_jacobian_is_rejected() {
+    ParameterUncertainty::from_jacobian(DMatrix::identity(2, 2), &[1.0, 2.0], 0.0);
+  }
+}
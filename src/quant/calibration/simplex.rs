@@ -0,0 +1,117 @@
+use super::global::Minimum;
+
+/// Nelder-Mead simplex minimization without derivatives, started from the simplex of x0
+/// and x0 moved by `step` along every axis. Stops when the spread of the objective over the
+/// simplex falls below `tol` or after `max_iterations` iterations.
+/// https://doi.org/10.1093/comjnl/7.4.308 (Nelder, Mead)
+pub fn nelder_mead<F>(f: F, x0: &[f64], step: f64, max_iterations: usize, tol: f64) -> Minimum
+where
+  F: Fn(&[f64]) -> f64,
+{
+  nelder_mead_with_steps(f, x0, &vec![step; x0.len()], max_iterations, tol)
+}
+
+/// Nelder-Mead with a step per parameter, for parameters of different scales
+pub fn nelder_mead_with_steps<F>(
+  f: F,
+  x0: &[f64],
+  steps: &[f64],
+  max_iterations: usize,
+  tol: f64,
+) -> Minimum
+where
+  F: Fn(&[f64]) -> f64,
+{
+  let dim = x0.len();
+  assert!(dim > 0, "At least one parameter is needed");
+  assert_eq!(steps.len(), dim, "One step per parameter");
+  let (alpha, gamma, rho, sigma) = (1.0, 2.0, 0.5, 0.5);
+  let mut evaluations = 0;
+  let mut eval = |x: &[f64]| {
+    evaluations += 1;
+    let v = f(x);
+    if v.is_nan() {
+      f64::INFINITY
+    } else {
+      v
+    }
+  };
+
+  let mut simplex = (0..=dim)
+    .map(|i| {
+      let mut x = x0.to_vec();
+      if i > 0 {
+        x[i - 1] += steps[i - 1];
+      }
+      x
+    })
+    .collect::<Vec<_>>();
+  let mut values = simplex.iter().map(|x| eval(x)).collect::<Vec<_>>();
+  let mut converged = false;
+
+  let along = |from: &[f64], to: &[f64], t: f64| {
+    from
+      .iter()
+      .zip(to)
+      .map(|(a, b)| a + t * (b - a))
+      .collect::<Vec<_>>()
+  };
+
+  for _ in 0..max_iterations {
+    let mut order = (0..=dim).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+    simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+    values = order.iter().map(|&i| values[i]).collect();
+
+    if (values[dim] - values[0]).abs() <= tol {
+      converged = true;
+      break;
+    }
+
+    let centroid = (0..dim)
+      .map(|k| simplex[..dim].iter().map(|x| x[k]).sum::<f64>() / dim as f64)
+      .collect::<Vec<_>>();
+
+    let reflected = along(&centroid, &simplex[dim], -alpha);
+    let fr = eval(&reflected);
+
+    if fr < values[0] {
+      let expanded = along(&centroid, &simplex[dim], -gamma);
+      let fe = eval(&expanded);
+      (simplex[dim], values[dim]) = if fe < fr {
+        (expanded, fe)
+      } else {
+        (reflected, fr)
+      };
+    } else if fr < values[dim - 1] {
+      (simplex[dim], values[dim]) = (reflected, fr);
+    } else {
+      let contracted = if fr < values[dim] {
+        along(&centroid, &reflected, rho)
+      } else {
+        along(&centroid, &simplex[dim], rho)
+      };
+      let fc = eval(&contracted);
+
+      if fc < values[dim].min(fr) {
+        (simplex[dim], values[dim]) = (contracted, fc);
+      } else {
+        for i in 1..=dim {
+          simplex[i] = along(&simplex[0], &simplex[i], sigma);
+          values[i] = eval(&simplex[i]);
+        }
+      }
+    }
+  }
+
+  let best = (0..=dim)
+    .min_by(|&i, &j| values[i].total_cmp(&values[j]))
+    .unwrap();
+
+  Minimum {
+    params: simplex[best].clone(),
+    value: values[best],
+    evaluations,
+    converged,
+  }
+}
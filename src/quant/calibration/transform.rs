@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+/// Map of a constrained parameter to the whole real line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+  /// Unconstrained parameter
+  Identity,
+  /// Positive parameter, x = exp(u)
+  Positive,
+  /// Parameter in (lo, hi), x = lo + (hi - lo) / (1 + exp(-u))
+  Bounded(f64, f64),
+}
+
+impl Transform {
+  /// Constrained value of the unconstrained u
+  pub fn constrain(&self, u: f64) -> f64 {
+    match *self {
+      Self::Identity => u,
+      Self::Positive => u.exp(),
+      Self::Bounded(lo, hi) => lo + (hi - lo) / (1.0 + (-u).exp()),
+    }
+  }
+
+  /// Unconstrained value of x, values on the boundary are moved slightly inside
+  pub fn unconstrain(&self, x: f64) -> f64 {
+    match *self {
+      Self::Identity => x,
+      Self::Positive => x.max(f64::MIN_POSITIVE).ln(),
+      Self::Bounded(lo, hi) => {
+        let p = ((x - lo) / (hi - lo)).clamp(1e-12, 1.0 - 1e-12);
+        (p / (1.0 - p)).ln()
+      }
+    }
+  }
+
+  /// Derivative dx/du at the unconstrained u
+  pub fn derivative(&self, u: f64) -> f64 {
+    match *self {
+      Self::Identity => 1.0,
+      Self::Positive => u.exp(),
+      Self::Bounded(lo, hi) => {
+        let s = 1.0 / (1.0 + (-u).exp());
+        (hi - lo) * s * (1.0 - s)
+      }
+    }
+  }
+}
+
+/// Penalty of the constrained parameters, zero in the valid region
+pub type Penalty = Arc<dyn Fn(&[f64]) -> f64 + Send + Sync>;
+
+/// Quadratic penalty of the violation of the Feller condition 2 kappa theta >= sigma^2,
+/// the arguments are the indices of the parameters
+pub fn feller_penalty(kappa: usize, theta: usize, sigma: usize, weight: f64) -> Penalty {
+  Arc::new(move |x: &[f64]| {
+    let violation = x[sigma].powi(2) - 2.0 * x[kappa] * x[theta];
+    weight * violation.max(0.0).powi(2)
+  })
+}
+
+/// Barrier of a constraint, infinite outside the valid region. For the derivative-free
+/// optimizers, which reject such candidates.
+pub fn barrier(valid: impl Fn(&[f64]) -> bool + Send + Sync + 'static) -> Penalty {
+  Arc::new(move |x: &[f64]| if valid(x) { 0.0 } else { f64::INFINITY })
+}
+
+/// Parameter space of a calibration: a transform per parameter, so the optimizers work in
+/// unconstrained space, and penalties for the constraints that are not a box
+#[derive(Clone, Default)]
+pub struct ParameterSpace {
+  pub transforms: Vec<Transform>,
+  pub penalties: Vec<Penalty>,
+}
+
+impl ParameterSpace {
+  #[must_use]
+  pub fn new(transforms: Vec<Transform>) -> Self {
+    Self {
+      transforms,
+      penalties: Vec::new(),
+    }
+  }
+
+  #[must_use]
+  pub fn with_penalty(mut self, penalty: Penalty) -> Self {
+    self.penalties.push(penalty);
+    self
+  }
+
+  /// Heston parameters v0, theta, rho, kappa, sigma with a Feller penalty
+  #[must_use]
+  pub fn heston() -> Self {
+    Self::new(vec![
+      Transform::Positive,
+      Transform::Positive,
+      Transform::Bounded(-1.0, 1.0),
+      Transform::Positive,
+      Transform::Positive,
+    ])
+    .with_penalty(feller_penalty(3, 1, 4, 1e3))
+  }
+
+  pub fn len(&self) -> usize {
+    self.transforms.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.transforms.is_empty()
+  }
+
+  pub fn constrain(&self, u: &[f64]) -> Vec<f64> {
+    assert_eq!(u.len(), self.len(), "Wrong number of parameters");
+    self
+      .transforms
+      .iter()
+      .zip(u)
+      .map(|(t, &u)| t.constrain(u))
+      .collect()
+  }
+
+  pub fn unconstrain(&self, x: &[f64]) -> Vec<f64> {
+    assert_eq!(x.len(), self.len(), "Wrong number of parameters");
+    self
+      .transforms
+      .iter()
+      .zip(x)
+      .map(|(t, &x)| t.unconstrain(x))
+      .collect()
+  }
+
+  /// Diagonal of the Jacobian dx/du, for the chain rule of gradient based optimizers
+  pub fn derivatives(&self, u: &[f64]) -> Vec<f64> {
+    self
+      .transforms
+      .iter()
+      .zip(u)
+      .map(|(t, &u)| t.derivative(u))
+      .collect()
+  }
+
+  /// Total penalty of the constrained parameters
+  pub fn penalty(&self, x: &[f64]) -> f64 {
+    self.penalties.iter().map(|p| p(x)).sum()
+  }
+
+  /// Objective in unconstrained space: f of the constrained parameters plus the penalties
+  pub fn objective<'a, F>(&'a self, f: F) -> impl Fn(&[f64]) -> f64 + Sync + 'a
+  where
+    F: Fn(&[f64]) -> f64 + Sync + 'a,
+  {
+    move |u: &[f64]| {
+      let x = self.constrain(u);
+      f(&x) + self.penalty(&x)
+    }
+  }
+}
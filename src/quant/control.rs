@@ -0,0 +1,2 @@
+pub mod lqg;
+pub mod merton;
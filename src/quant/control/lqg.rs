@@ -0,0 +1,139 @@
+use nalgebra::{DMatrix, DVector};
+use ndarray::Array1;
+use rand_distr::{Distribution, StandardNormal};
+use rayon::prelude::*;
+
+use crate::{
+  quant::greeks::Estimate,
+  rng::{path_seed, thread_rng, with_seed},
+};
+
+/// Linear-quadratic-Gaussian regulator with full observation of the state
+/// dX = (A X + B u) dt + C dW,
+/// minimizing E[int_0^T (X'QX + u'Ru) dt + X(T)' G X(T)].
+/// The optimal control is the linear feedback u = -R^-1 B' P(t) X with the solution P of
+/// the Riccati equation -P' = A'P + PA - P B R^-1 B' P + Q, P(T) = G.
+#[derive(Default, Debug, Clone)]
+pub struct Lqg {
+  pub a: DMatrix<f64>,
+  pub b: DMatrix<f64>,
+  pub c: DMatrix<f64>,
+  /// State cost, positive semi-definite
+  pub q: DMatrix<f64>,
+  /// Control cost, positive definite
+  pub r: DMatrix<f64>,
+  /// Terminal cost, positive semi-definite
+  pub g: DMatrix<f64>,
+  pub x0: DVector<f64>,
+  /// Number of time steps
+  pub n: usize,
+  pub t: f64,
+  /// Riccati solution on the time grid
+  pub riccati: Vec<DMatrix<f64>>,
+}
+
+impl Lqg {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    let d = params.x0.len();
+    assert!(
+      params.a.shape() == (d, d)
+        && params.b.nrows() == d
+        && params.c.nrows() == d
+        && params.q.shape() == (d, d)
+        && params.g.shape() == (d, d),
+      "Matrices must have the dimension of x0"
+    );
+    assert_eq!(
+      params.r.shape(),
+      (params.b.ncols(), params.b.ncols()),
+      "R must have the dimension of the control"
+    );
+    assert!(params.n > 0, "At least one step is needed");
+
+    let mut lqg = params.clone();
+    lqg.riccati = lqg.solve_riccati();
+    lqg
+  }
+
+  /// Riccati solution backward in time by the classical Runge-Kutta scheme
+  fn solve_riccati(&self) -> Vec<DMatrix<f64>> {
+    let r_inv = self.r.clone().try_inverse().expect("R must be invertible");
+    let s = &self.b * r_inv * self.b.transpose();
+    let rhs = |p: &DMatrix<f64>| self.a.transpose() * p + p * &self.a - p * &s * p + &self.q;
+    let dt = self.t / self.n as f64;
+
+    let mut p = vec![DMatrix::zeros(self.x0.len(), self.x0.len()); self.n + 1];
+    p[self.n] = self.g.clone();
+    for k in (0..self.n).rev() {
+      let pk = &p[k + 1];
+      let k1 = rhs(pk);
+      let k2 = rhs(&(pk + &k1 * (0.5 * dt)));
+      let k3 = rhs(&(pk + &k2 * (0.5 * dt)));
+      let k4 = rhs(&(pk + &k3 * dt));
+      let next = pk + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * (dt / 6.0);
+      p[k] = (&next + next.transpose()) * 0.5;
+    }
+
+    p
+  }
+
+  /// Feedback gain K(t_k) of the optimal control u = -K X at the step k
+  pub fn gain(&self, k: usize) -> DMatrix<f64> {
+    self.r.clone().try_inverse().expect("R must be invertible")
+      * self.b.transpose()
+      * &self.riccati[k]
+  }
+
+  /// Optimal expected cost x0' P(0) x0 + int_0^T tr(C C' P(t)) dt
+  pub fn value(&self) -> f64 {
+    let dt = self.t / self.n as f64;
+    let cc = &self.c * self.c.transpose();
+    let noise = (0..self.n)
+      .map(|k| 0.5 * dt * ((&cc * &self.riccati[k]).trace() + (&cc * &self.riccati[k + 1]).trace()))
+      .sum::<f64>();
+
+    (self.x0.transpose() * &self.riccati[0] * &self.x0)[(0, 0)] + noise
+  }
+
+  /// Monte Carlo cost of the feedback policy `control(k, x)` at the step k,
+  /// from the Euler scheme of the controlled state
+  pub fn evaluate<F>(&self, control: F, paths: usize, seed: u64) -> Estimate
+  where
+    F: Fn(usize, &DVector<f64>) -> DVector<f64> + Sync,
+  {
+    let dt = self.t / self.n as f64;
+    let noises = self.c.ncols();
+
+    let costs = (0..paths)
+      .into_par_iter()
+      .map(|i| {
+        with_seed(path_seed(seed, i as u64), || {
+          let mut rng = thread_rng();
+          let mut x = self.x0.clone();
+          let mut cost = 0.0;
+          for k in 0..self.n {
+            let u = control(k, &x);
+            cost += ((x.transpose() * &self.q * &x)[(0, 0)]
+              + (u.transpose() * &self.r * &u)[(0, 0)])
+              * dt;
+            let dw = DVector::from_fn(noises, |_, _| {
+              let z: f64 = StandardNormal.sample(&mut rng);
+              z * dt.sqrt()
+            });
+            x = &x + (&self.a * &x + &self.b * &u) * dt + &self.c * dw;
+          }
+          cost + (x.transpose() * &self.g * &x)[(0, 0)]
+        })
+      })
+      .collect::<Vec<_>>();
+
+    Estimate::from_samples(Array1::from(costs).view())
+  }
+
+  /// Monte Carlo cost of the optimal feedback policy
+  pub fn evaluate_optimal(&self, paths: usize, seed: u64) -> Estimate {
+    let gains = (0..self.n).map(|k| self.gain(k)).collect::<Vec<_>>();
+    self.evaluate(|k, x| -(&gains[k] * x), paths, seed)
+  }
+}
@@ -0,0 +1,120 @@
+use ndarray::Array1;
+use rand_distr::{Distribution, StandardNormal};
+use rayon::prelude::*;
+
+use crate::{
+  quant::greeks::Estimate,
+  rng::{path_seed, thread_rng, with_seed},
+};
+
+/// Merton's portfolio problem: an investor with CRRA utility U(w) = w^(1 - gamma) / (1 - gamma)
+/// (log utility for gamma = 1) splits the wealth between cash earning r and a stock
+/// dS = mu S dt + sigma S dW, maximizing the expected utility of the terminal wealth.
+/// https://doi.org/10.2307/1926560 (Merton 1969)
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Merton {
+  pub mu: f64,
+  pub sigma: f64,
+  pub r: f64,
+  /// Relative risk aversion
+  pub gamma: f64,
+  /// Subjective discount rate of the consumption problem
+  pub rho: f64,
+  /// Investment horizon
+  pub t: f64,
+  pub w0: f64,
+}
+
+impl Merton {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.sigma > 0.0, "Volatility must be positive");
+    assert!(params.gamma > 0.0, "Risk aversion must be positive");
+    assert!(params.w0 > 0.0, "Initial wealth must be positive");
+
+    *params
+  }
+
+  /// Squared Sharpe ratio of the stock
+  fn sharpe2(&self) -> f64 {
+    ((self.mu - self.r) / self.sigma).powi(2)
+  }
+
+  /// Optimal constant fraction of wealth in the stock (mu - r) / (gamma sigma^2)
+  pub fn optimal_fraction(&self) -> f64 {
+    (self.mu - self.r) / (self.gamma * self.sigma.powi(2))
+  }
+
+  pub fn utility(&self, w: f64) -> f64 {
+    if (self.gamma - 1.0).abs() < 1e-12 {
+      w.ln()
+    } else {
+      w.powf(1.0 - self.gamma) / (1.0 - self.gamma)
+    }
+  }
+
+  /// Wealth with the same utility
+  pub fn certainty_equivalent(&self, utility: f64) -> f64 {
+    if (self.gamma - 1.0).abs() < 1e-12 {
+      utility.exp()
+    } else {
+      ((1.0 - self.gamma) * utility).powf(1.0 / (1.0 - self.gamma))
+    }
+  }
+
+  /// Value function V(t, w), the expected utility of the terminal wealth under the
+  /// optimal policy from the wealth w at the time t
+  pub fn value(&self, t: f64, w: f64) -> f64 {
+    let growth = self.r + 0.5 * self.sharpe2() / self.gamma;
+    let tau = self.t - t;
+
+    if (self.gamma - 1.0).abs() < 1e-12 {
+      w.ln() + growth * tau
+    } else {
+      self.utility(w) * ((1.0 - self.gamma) * growth * tau).exp()
+    }
+  }
+
+  /// Optimal consumption per unit wealth of the infinite horizon problem
+  /// max E[int e^(-rho t) U(c(t)) dt],
+  /// (rho - (1 - gamma) (r + theta^2 / (2 gamma))) / gamma with the Sharpe ratio theta
+  pub fn consumption_rate(&self) -> f64 {
+    let nu =
+      (self.rho - (1.0 - self.gamma) * (self.r + 0.5 * self.sharpe2() / self.gamma)) / self.gamma;
+    assert!(
+      nu > 0.0,
+      "The consumption problem is ill-posed for these parameters"
+    );
+    nu
+  }
+
+  /// Monte Carlo expected utility of the terminal wealth under the policy
+  /// `fraction(t, w)`, the fraction of wealth in the stock, held over `n` steps.
+  /// Wealth is simulated exactly between the rebalancing times.
+  pub fn evaluate<F>(&self, fraction: F, n: usize, paths: usize, seed: u64) -> Estimate
+  where
+    F: Fn(f64, f64) -> f64 + Sync,
+  {
+    assert!(n > 0, "At least one step is needed");
+    let dt = self.t / n as f64;
+
+    let utilities = (0..paths)
+      .into_par_iter()
+      .map(|i| {
+        with_seed(path_seed(seed, i as u64), || {
+          let mut rng = thread_rng();
+          let mut w = self.w0;
+          for k in 0..n {
+            let pi = fraction(k as f64 * dt, w);
+            let z: f64 = StandardNormal.sample(&mut rng);
+            let drift = self.r + pi * (self.mu - self.r) - 0.5 * (pi * self.sigma).powi(2);
+            w *= (drift * dt + pi * self.sigma * dt.sqrt() * z).exp();
+          }
+          self.utility(w)
+        })
+      })
+      .collect::<Vec<_>>();
+
+    Estimate::from_samples(Array1::from(utilities).view())
+  }
+}
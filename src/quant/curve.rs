@@ -0,0 +1,106 @@
+use ndarray::{array, Array1, ArrayView1};
+
+use crate::stochastic::schedule::Schedule;
+
+/// Discount curve given by discount factors at pillar times.
+/// Interpolation is log-linear in the discount factors (piecewise flat forward rates),
+/// with the last forward rate extrapolated after the last pillar.
+#[derive(Debug, Clone)]
+pub struct YieldCurve {
+  /// Pillar times in years, increasing and positive
+  pub times: Vec<f64>,
+  /// Discount factors at the pillars
+  pub discount_factors: Vec<f64>,
+}
+
+impl YieldCurve {
+  #[must_use]
+  pub fn new(times: ArrayView1<f64>, discount_factors: ArrayView1<f64>) -> Self {
+    assert_eq!(
+      times.len(),
+      discount_factors.len(),
+      "Yield curve needs one discount factor per pillar"
+    );
+    assert!(!times.is_empty(), "Yield curve needs a pillar");
+    assert!(
+      times[0] > 0.0 && times.windows(2).into_iter().all(|w| w[0] < w[1]),
+      "Pillar times must be positive and increasing"
+    );
+    assert!(
+      discount_factors.iter().all(|&d| d > 0.0),
+      "Discount factors must be positive"
+    );
+
+    Self {
+      times: times.to_vec(),
+      discount_factors: discount_factors.to_vec(),
+    }
+  }
+
+  /// Curve with a constant continuously compounded rate
+  #[must_use]
+  pub fn flat(rate: f64) -> Self {
+    Self::new(array![1.0].view(), array![(-rate).exp()].view())
+  }
+
+  /// Discount factor to time t
+  pub fn discount_factor(&self, t: f64) -> f64 {
+    if t <= 0.0 {
+      return 1.0;
+    }
+
+    // node 0 is the origin with P(0) = 1, node j the (j - 1)-th pillar
+    let node = |j: usize| match j {
+      0 => (0.0, 0.0),
+      j => (self.times[j - 1], self.discount_factors[j - 1].ln()),
+    };
+    let j1 = (self.times.partition_point(|&ti| ti < t) + 1).min(self.times.len());
+    let ((t0, ln_p0), (t1, ln_p1)) = (node(j1 - 1), node(j1));
+
+    (ln_p0 + (ln_p1 - ln_p0) * (t - t0) / (t1 - t0)).exp()
+  }
+
+  /// Curve with the zero rates shifted by a constant spread, exact at every maturity with
+  /// the log-linear interpolation, e.g. a funding curve over the OIS curve
+  #[must_use]
+  pub fn shifted(&self, spread: f64) -> Self {
+    let discount_factors = self
+      .times
+      .iter()
+      .zip(&self.discount_factors)
+      .map(|(t, df)| df * (-spread * t).exp())
+      .collect::<Array1<f64>>();
+    Self::new(ArrayView1::from(&self.times), discount_factors.view())
+  }
+
+  /// Continuously compounded zero rate to time t
+  pub fn zero_rate(&self, t: f64) -> f64 {
+    // short rate, the forward rate of the first segment
+    if t <= 0.0 {
+      return self.zero_rate(self.times[0]);
+    }
+
+    -self.discount_factor(t).ln() / t
+  }
+
+  /// Continuously compounded forward rate between t1 and t2
+  pub fn forward_rate(&self, t1: f64, t2: f64) -> f64 {
+    assert!(t2 > t1, "Forward period must be positive");
+    (self.discount_factor(t1) / self.discount_factor(t2)).ln() / (t2 - t1)
+  }
+
+  /// Drift of an Euler scheme with n steps up to t, so that the expected growth over
+  /// every step equals the forward of the rate curve net of the dividend curve:
+  /// `1 + mu_i dt = (P(t_i) / P(t_i+1)) / (Q(t_i) / Q(t_i+1))`
+  pub fn drift_schedule(&self, dividends: Option<&YieldCurve>, n: usize, t: f64) -> Schedule {
+    let dt = t / n as f64;
+    let growth = |curve: &YieldCurve, i: usize| {
+      curve.discount_factor(i as f64 * dt) / curve.discount_factor((i + 1) as f64 * dt)
+    };
+
+    Schedule::Steps(Array1::from_shape_fn(n, |i| {
+      let q = dividends.map_or(1.0, |d| growth(d, i));
+      (growth(self, i) / q - 1.0) / dt
+    }))
+  }
+}
@@ -0,0 +1,131 @@
+use num_complex::Complex64;
+
+use self::{cos::Cos, lewis::lewis, swift::Swift};
+use crate::quant::volatility::piecewise_heston::gil_pelaez;
+
+pub mod cos;
+pub mod lewis;
+pub mod swift;
+
+/// Inversion of the characteristic function of a model into European option prices
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Engine {
+  /// Gil-Pelaez inversion of the two exercise probabilities
+  #[default]
+  GilPelaez,
+  /// Lewis single integral
+  Lewis,
+  /// Fang-Oosterlee cosine expansion
+  Cos(Cos),
+  /// Ortiz-Gracia-Oosterlee Shannon wavelet expansion
+  Swift(Swift),
+}
+
+impl Engine {
+  /// Call and put prices from the characteristic function of the log price at maturity
+  /// (including ln s0)
+  pub fn price<F>(&self, cf: F, s0: f64, k: f64, r: f64, q: f64, t: f64) -> (f64, f64)
+  where
+    F: Fn(Complex64) -> Complex64,
+  {
+    match self {
+      Self::GilPelaez => gil_pelaez(cf, s0, k, r, q, t),
+      Self::Lewis => lewis(cf, s0, k, r, q, t),
+      Self::Cos(cos) => cos.price(cf, s0, k, r, q, t),
+      Self::Swift(swift) => swift.price(cf, s0, k, r, q, t),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use approx::assert_relative_eq;
+  use statrs::distribution::{ContinuousCDF, Normal};
+
+  use super::*;
+  use crate::quant::{r#trait::Pricer, volatility::heston::HestonPricer};
+
+  fn heston(tau: f64, sigma: f64, rho: f64) -> HestonPricer {
+    HestonPricer::new(&HestonPricer {
+      s0: 100.0,
+      v0: 0.05,
+      k: 105.0,
+      r: 0.03,
+      q: 0.01,
+      rho,
+      kappa: 2.0,
+      theta: 0.04,
+      sigma,
+      tau,
+      ..Default::default()
+    })
+  }
+
+  /// The Gil-Pelaez inversion integrates its two probabilities to a looser tolerance
+  fn engines() -> [Engine; 3] {
+    [
+      Engine::Lewis,
+      Engine::Cos(Cos::default()),
+      Engine::Swift(Swift::default()),
+    ]
+  }
+
+  fn price(engine: &Engine, pricer: &HestonPricer) -> (f64, f64) {
+    engine.price(
+      |u| pricer.characteristic_function(u, pricer.tau),
+      pricer.s0,
+      pricer.k,
+      pricer.r,
+      pricer.q,
+      pricer.tau,
+    )
+  }
+
+  #[test]
+  fn engines_agree_with_each_other_and_the_closed_form() {
+    for tau in [0.25, 0.5, 1.0, 3.0] {
+      let mut pricer = heston(tau, 0.5, -0.7);
+      pricer.calculate_price();
+      let (call, put) = price(&Engine::Lewis, &pricer);
+      for engine in engines() {
+        let prices = price(&engine, &pricer);
+        assert_relative_eq!(prices.0, call, max_relative = 1e-6);
+        assert_relative_eq!(prices.1, put, max_relative = 1e-6);
+      }
+      // the closed form integrates with the tolerance 1e-5 up to u = 50 and crosses the
+      // branch cut of its logarithm at long maturities
+      if tau <= 1.0 {
+        assert_relative_eq!(call, pricer.prices().0, max_relative = 1e-3);
+        assert_relative_eq!(put, pricer.prices().1, max_relative = 1e-3);
+      }
+    }
+  }
+
+  #[test]
+  fn engines_match_the_deterministic_variance_limit() {
+    // without vol of vol the log price is normal with the integrated variance
+    let n = Normal::default();
+    let strikes = [80.0, 95.0, 100.0, 105.0, 130.0];
+    for tau in [1.0 / 365.0, 0.5, 5.0] {
+      let pricer = heston(tau, 1e-4, 0.0);
+      let w = 0.04 * tau + 0.01 * (1.0 - (-2.0 * tau).exp()) / 2.0;
+      let cf = |u| pricer.characteristic_function(u, tau);
+      // the expansions price the whole strip at once, SWIFT resolves the narrow short
+      // maturity density with many wavelets
+      let cos = Cos::default().prices(cf, 100.0, &strikes, 0.03, 0.01, tau);
+      let swift = Swift::default().prices(cf, 100.0, &strikes, 0.03, 0.01, tau);
+      for (i, &k) in strikes.iter().enumerate() {
+        let d1 = ((100.0 / k).ln() + 0.02 * tau + 0.5 * w) / w.sqrt();
+        let bs =
+          100.0 * (-0.01 * tau).exp() * n.cdf(d1) - k * (-0.03 * tau).exp() * n.cdf(d1 - w.sqrt());
+        let pricer = HestonPricer {
+          k,
+          ..pricer.clone()
+        };
+        assert_relative_eq!(price(&Engine::Lewis, &pricer).0, bs, epsilon = 1e-6);
+        assert_relative_eq!(cos[i].0, bs, epsilon = 1e-6);
+        assert_relative_eq!(swift[i].0, bs, epsilon = 1e-6);
+      }
+    }
+  }
+}
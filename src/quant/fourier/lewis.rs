@@ -0,0 +1,137 @@
+use std::f64::consts::FRAC_1_PI;
+
+use num_complex::Complex64;
+use quadrature::double_exponential;
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+use super::cos::cumulants;
+
+/// Lewis single-integral price of the call and the put from the characteristic function
+/// of the log price at maturity (including ln s0):
+/// C = S e^(-qT) - sqrt(S K) e^(-(r + q) T / 2) / pi int_0^inf Re[e^(i u k) phi(u - i / 2)]
+/// / (u^2 + 1 / 4) du, with phi the characteristic function of ln(S_T / F) and
+/// k = ln(F / K). The integrand decays like 1 / u^2 without the singularity at 0 of the
+/// Gil-Pelaez integrals. The tail from u = 1 is mapped to a finite interval on the scale
+/// 1 / sqrt(c2) of the decay of the characteristic function, so short maturities, whose
+/// integrand reaches far out, are not truncated.
+/// https://doi.org/10.2139/ssrn.282110 (Lewis 2001)
+pub fn lewis<F>(cf: F, s0: f64, k: f64, r: f64, q: f64, t: f64) -> (f64, f64)
+where
+  F: Fn(Complex64) -> Complex64,
+{
+  let i = Complex64::i();
+  let ln_f = s0.ln() + (r - q) * t;
+  let x = ln_f - k.ln();
+  let integrand = |u: f64| {
+    let z = Complex64::new(u, -0.5);
+    ((i * u * x).exp() * cf(z) * (-i * z * ln_f).exp()).re / (u * u + 0.25)
+  };
+  // u = 1 + c x / (1 - x) on [0, 1), c the inverse standard deviation of the log price
+  let (_, c2, _) = cumulants(&cf);
+  let c = if c2 > 0.0 && c2.is_finite() {
+    1.0 / c2.sqrt()
+  } else {
+    1.0
+  };
+  let tail = |x: f64| {
+    let value = integrand(1.0 + c * x / (1.0 - x)) * c / (1.0 - x).powi(2);
+    if value.is_finite() {
+      value
+    } else {
+      0.0
+    }
+  };
+  let integral = double_exponential::integrate(integrand, 0.0, 1.0, 1e-12).integral
+    + double_exponential::integrate(tail, 0.0, 1.0, 1e-12).integral;
+
+  let call =
+    s0 * (-q * t).exp() - (s0 * k).sqrt() * (-(r + q) * t / 2.0).exp() * FRAC_1_PI * integral;
+  let put = call + k * (-r * t).exp() - s0 * (-q * t).exp();
+  (call, put)
+}
+
+/// Second order expansion of the price in the volatility of volatility around the
+/// Black-Scholes price with the total variance w of the forward variance curve,
+/// P = P_BS + C^(x xi) d_x d_w P_BS + C^(xi xi) / 2 d_w^2 P_BS +
+/// (C^(x xi))^2 / 2 d_x^2 d_w^2 P_BS + C^mu d_x^2 d_w P_BS, with the integrated covariance
+/// C^(x xi) of the log price and the forward variances, the integrated variance C^(xi xi)
+/// of the forward variances and the integrated covariance C^mu of the log price and
+/// C^(x xi). Accurate for a moderate volatility of volatility and cheap enough for a
+/// calibration loop, the expansion of Lewis for the Heston model.
+/// https://doi.org/10.2139/ssrn.1967470 (Bergomi, Guyon 2012)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VolOfVolExpansion {
+  /// Total variance int_0^T xi_0^t dt
+  pub w: f64,
+  pub c_x_xi: f64,
+  pub c_xi_xi: f64,
+  pub c_mu: f64,
+}
+
+impl VolOfVolExpansion {
+  /// Expansion of the Heston model dv = kappa (theta - v) dt + sigma sqrt(v) dW, with the
+  /// forward variances xi^u = theta + (v0 - theta) e^(-kappa u) and their volatility
+  /// sigma sqrt(v) e^(-kappa (u - t))
+  #[must_use]
+  pub fn heston(v0: f64, kappa: f64, theta: f64, sigma: f64, rho: f64, t: f64) -> Self {
+    let xi = |u: f64| theta + (v0 - theta) * (-kappa * u).exp();
+    // int_s^T e^(-kappa (u - s)) du
+    let decay = |s: f64| {
+      if kappa.abs() < 1e-12 {
+        t - s
+      } else {
+        (1.0 - (-kappa * (t - s)).exp()) / kappa
+      }
+    };
+    // int_s^T e^(-kappa (u - s)) decay(u) du
+    let decay2 = |s: f64| {
+      if kappa.abs() < 1e-12 {
+        0.5 * (t - s).powi(2)
+      } else {
+        (decay(s) - (t - s) * (-kappa * (t - s)).exp()) / kappa
+      }
+    };
+    let integrate =
+      |f: &dyn Fn(f64) -> f64| double_exponential::integrate(f, 0.0, t, 1e-12).integral;
+
+    Self {
+      w: integrate(&|s| xi(s)),
+      c_x_xi: rho * sigma * integrate(&|s| xi(s) * decay(s)),
+      c_xi_xi: sigma * sigma * integrate(&|s| xi(s) * decay(s).powi(2)),
+      c_mu: (rho * sigma).powi(2) * integrate(&|s| xi(s) * decay2(s)),
+    }
+  }
+
+  /// Call and put prices
+  pub fn price(&self, s0: f64, k: f64, r: f64, q: f64, t: f64) -> (f64, f64) {
+    let n = Normal::default();
+    let forward = s0 * ((r - q) * t).exp();
+    let df = (-r * t).exp();
+    let s = self.w.sqrt();
+    let d2 = (forward / k).ln() / s - 0.5 * s;
+    let d1 = d2 + s;
+
+    // derivatives of the undiscounted call in the log forward x and the total variance w,
+    // with V = d_w P = K n(d2) / (2 sqrt(w)) and d_w = (d_x^2 - d_x) / 2
+    let v = k * n.pdf(d2) / (2.0 * s);
+    let dx = [
+      v,
+      -v * d2 / s,
+      v * (d2 * d2 - 1.0) / s.powi(2),
+      v * d2 * (3.0 - d2 * d2) / s.powi(3),
+      v * (d2.powi(4) - 6.0 * d2 * d2 + 3.0) / s.powi(4),
+    ];
+    let d_xw = dx[1];
+    let d_ww = 0.5 * (dx[2] - dx[1]);
+    let d_xxww = 0.5 * (dx[4] - dx[3]);
+
+    let call = forward * n.cdf(d1) - k * n.cdf(d2)
+      + self.c_x_xi * d_xw
+      + 0.5 * self.c_xi_xi * d_ww
+      + 0.5 * self.c_x_xi.powi(2) * d_xxww
+      + self.c_mu * dx[2];
+    let call = df * call;
+    let put = call + k * df - s0 * (-q * t).exp();
+    (call, put)
+  }
+}
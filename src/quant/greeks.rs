@@ -0,0 +1,367 @@
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use rand_distr::{Distribution, StandardNormal};
+use rayon::prelude::*;
+
+use crate::quant::OptionType;
+use crate::rng::{path_seed, thread_rng, with_seed};
+
+/// Monte Carlo estimate with its standard error
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Estimate {
+  pub value: f64,
+  pub std_error: f64,
+}
+
+impl Estimate {
+  /// Sample mean and standard error of i.i.d. samples
+  pub fn from_samples(samples: ArrayView1<f64>) -> Self {
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+    Self {
+      value: mean,
+      std_error: (var / n).sqrt(),
+    }
+  }
+}
+
+/// Sensitivity estimated by several Monte Carlo methods on the same number of paths
+#[derive(Default, Debug, Clone, Copy)]
+pub struct GreekComparison {
+  /// Central bump-and-revalue with common random numbers
+  pub bump: Estimate,
+  /// Pathwise (IPA) estimator
+  pub pathwise: Option<Estimate>,
+  /// Likelihood ratio estimator
+  pub lrm: Option<Estimate>,
+}
+
+/// Central bump-and-revalue sensitivity of the discounted payoff with respect to a parameter.
+/// `payoff(theta)` simulates one path with the parameter theta from the samplers of the
+/// crate. Every path runs in the deterministic mode on the seed `path_seed(seed, i)` for both
+/// the up and the down bump, so both use common random numbers.
+pub fn bump_and_revalue<F>(payoff: F, theta: f64, h: f64, paths: usize, seed: u64) -> Estimate
+where
+  F: Fn(f64) -> f64 + Sync,
+{
+  let samples = (0..paths)
+    .into_par_iter()
+    .map(|i| {
+      let path_seed = path_seed(seed, i as u64);
+      let up = with_seed(path_seed, || payoff(theta + h));
+      let down = with_seed(path_seed, || payoff(theta - h));
+      (up - down) / (2.0 * h)
+    })
+    .collect::<Vec<_>>();
+
+  Estimate::from_samples(ArrayView1::from(&samples))
+}
+
+/// Mean and standard error of a per-path derivative estimator (pathwise or likelihood ratio),
+/// the paths are seeded in the same way as in `bump_and_revalue`
+pub fn path_estimator<F>(estimator: F, paths: usize, seed: u64) -> Estimate
+where
+  F: Fn() -> f64 + Sync,
+{
+  let samples = (0..paths)
+    .into_par_iter()
+    .map(|i| with_seed(path_seed(seed, i as u64), &estimator))
+    .collect::<Vec<_>>();
+
+  Estimate::from_samples(ArrayView1::from(&samples))
+}
+
+/// Grid of spot and volatility shifts around a base state, priced in one parallel pass
+/// with common random numbers: every path is simulated with the same seed at every grid
+/// point, so the P&L between two points has a much smaller error than the prices.
+#[derive(Debug, Clone, Default)]
+pub struct RiskLadder {
+  /// Relative spot shifts, the spots are s0 (1 + shift)
+  pub spot_shifts: Vec<f64>,
+  /// Absolute volatility shifts, the volatilities are v0 + shift
+  pub vol_shifts: Vec<f64>,
+  pub paths: usize,
+  pub seed: u64,
+}
+
+/// Prices of a risk ladder, one row per spot and one column per volatility
+#[derive(Debug, Clone)]
+pub struct Ladder {
+  pub spots: Array1<f64>,
+  pub vols: Array1<f64>,
+  pub prices: Array2<f64>,
+  pub std_errors: Array2<f64>,
+  /// Price at the base spot and volatility
+  pub base: Estimate,
+  /// Price minus the base price, estimated path by path
+  pub pnl: Array2<f64>,
+  pub pnl_std_errors: Array2<f64>,
+}
+
+/// Derivative of values on a non-uniform grid, second order central differences inside and
+/// one-sided differences at the ends
+fn gradient(values: ArrayView1<f64>, x: ArrayView1<f64>) -> Array1<f64> {
+  let n = values.len();
+  if n < 2 {
+    return Array1::from_elem(n, f64::NAN);
+  }
+  Array1::from_shape_fn(n, |i| {
+    if i == 0 {
+      (values[1] - values[0]) / (x[1] - x[0])
+    } else if i == n - 1 {
+      (values[n - 1] - values[n - 2]) / (x[n - 1] - x[n - 2])
+    } else {
+      let (h0, h1) = (x[i] - x[i - 1], x[i + 1] - x[i]);
+      (h0 * h0 * values[i + 1] - h1 * h1 * values[i - 1] + (h1 * h1 - h0 * h0) * values[i])
+        / (h0 * h1 * (h0 + h1))
+    }
+  })
+}
+
+impl Ladder {
+  fn along(&self, values: &Array2<f64>, axis: Axis) -> Array2<f64> {
+    let x = if axis == Axis(0) {
+      &self.spots
+    } else {
+      &self.vols
+    };
+    let mut out = Array2::<f64>::zeros(values.dim());
+    for (lane, mut out_lane) in values.lanes(axis).into_iter().zip(out.lanes_mut(axis)) {
+      out_lane.assign(&gradient(lane, x.view()));
+    }
+    out
+  }
+
+  /// Delta surface, derivative of the prices along the spots
+  pub fn delta(&self) -> Array2<f64> {
+    self.along(&self.pnl, Axis(0))
+  }
+
+  /// Gamma surface, derivative of the deltas along the spots
+  pub fn gamma(&self) -> Array2<f64> {
+    self.along(&self.delta(), Axis(0))
+  }
+
+  /// Vega surface, derivative of the prices along the volatilities
+  pub fn vega(&self) -> Array2<f64> {
+    self.along(&self.pnl, Axis(1))
+  }
+}
+
+impl RiskLadder {
+  /// Ladder of the discounted payoff around the spot s0 and the volatility v0,
+  /// `payoff(spot, vol)` simulates one path of the model with the given spot and
+  /// volatility (e.g. the initial variance v0^2 of a stochastic volatility model).
+  /// The paths are seeded as in `bump_and_revalue`.
+  pub fn run<F>(&self, s0: f64, v0: f64, payoff: F) -> Ladder
+  where
+    F: Fn(f64, f64) -> f64 + Sync,
+  {
+    assert!(self.paths >= 2, "At least two paths are needed");
+    let spots = Array1::from_iter(self.spot_shifts.iter().map(|h| s0 * (1.0 + h)));
+    let vols = Array1::from_iter(self.vol_shifts.iter().map(|h| v0 + h));
+    let dim = (spots.len(), vols.len());
+
+    // sums of the base price, its square, and of the prices and P&L with their squares
+    let zeros = || (0.0, 0.0, [0, 1, 2, 3].map(|_| Array2::<f64>::zeros(dim)));
+    let (base, base2, [sum, sum2, pnl, pnl2]) = (0..self.paths)
+      .into_par_iter()
+      .fold(zeros, |(mut b, mut b2, mut acc), i| {
+        let path_seed = path_seed(self.seed, i as u64);
+        let base = with_seed(path_seed, || payoff(s0, v0));
+        b += base;
+        b2 += base * base;
+        for ((j, k), &s) in spots
+          .iter()
+          .enumerate()
+          .flat_map(|(j, s)| (0..vols.len()).map(move |k| ((j, k), s)))
+        {
+          let price = with_seed(path_seed, || payoff(s, vols[k]));
+          acc[0][(j, k)] += price;
+          acc[1][(j, k)] += price * price;
+          acc[2][(j, k)] += price - base;
+          acc[3][(j, k)] += (price - base).powi(2);
+        }
+        (b, b2, acc)
+      })
+      .reduce(zeros, |(b, b2, mut acc), (c, c2, other)| {
+        for (a, o) in acc.iter_mut().zip(other) {
+          *a += &o;
+        }
+        (b + c, b2 + c2, acc)
+      });
+
+    let n = self.paths as f64;
+    let std_error = |sum: f64, sum2: f64| ((sum2 - sum * sum / n) / (n - 1.0) / n).max(0.0).sqrt();
+    let std_errors = Array2::from_shape_fn(dim, |ix| std_error(sum[ix], sum2[ix]));
+    let pnl_std_errors = Array2::from_shape_fn(dim, |ix| std_error(pnl[ix], pnl2[ix]));
+
+    Ladder {
+      spots,
+      vols,
+      prices: sum / n,
+      std_errors,
+      base: Estimate {
+        value: base / n,
+        std_error: std_error(base, base2),
+      },
+      pnl: pnl / n,
+      pnl_std_errors,
+    }
+  }
+}
+
+/// European option under geometric Brownian motion, used to compare
+/// the Monte Carlo delta estimators against each other and the closed form.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct EuropeanGbm {
+  /// Underlying price
+  pub s0: f64,
+  /// Strike price
+  pub k: f64,
+  /// Risk-free rate
+  pub r: f64,
+  /// Volatility
+  pub sigma: f64,
+  /// Time to maturity in years
+  pub tau: f64,
+  /// Option type
+  pub option_type: OptionType,
+}
+
+impl EuropeanGbm {
+  fn terminal(&self, s0: f64, z: f64) -> f64 {
+    s0 * ((self.r - 0.5 * self.sigma.powi(2)) * self.tau + self.sigma * self.tau.sqrt() * z).exp()
+  }
+
+  fn payoff(&self, s: f64) -> f64 {
+    match self.option_type {
+      OptionType::Call => (s - self.k).max(0.0),
+      OptionType::Put => (self.k - s).max(0.0),
+    }
+  }
+
+  /// Delta by bump-and-revalue, pathwise and likelihood ratio estimators
+  pub fn delta_comparison(&self, paths: usize, seed: u64, h: f64) -> GreekComparison {
+    let df = (-self.r * self.tau).exp();
+
+    let bump = bump_and_revalue(
+      |s0| {
+        let z: f64 = StandardNormal.sample(&mut thread_rng());
+        df * self.payoff(self.terminal(s0, z))
+      },
+      self.s0,
+      h,
+      paths,
+      seed,
+    );
+
+    let pathwise = path_estimator(
+      || {
+        let z: f64 = StandardNormal.sample(&mut thread_rng());
+        let s = self.terminal(self.s0, z);
+        let itm = match self.option_type {
+          OptionType::Call => (s > self.k) as i32 as f64,
+          OptionType::Put => -((s < self.k) as i32 as f64),
+        };
+        df * itm * s / self.s0
+      },
+      paths,
+      seed,
+    );
+
+    let lrm = path_estimator(
+      || {
+        let z: f64 = StandardNormal.sample(&mut thread_rng());
+        let s = self.terminal(self.s0, z);
+        df * self.payoff(s) * z / (self.s0 * self.sigma * self.tau.sqrt())
+      },
+      paths,
+      seed,
+    );
+
+    GreekComparison {
+      bump,
+      pathwise: Some(pathwise),
+      lrm: Some(lrm),
+    }
+  }
+
+  /// Risk ladder over the spot and the volatility
+  pub fn risk_ladder(&self, ladder: &RiskLadder) -> Ladder {
+    let df = (-self.r * self.tau).exp();
+    ladder.run(self.s0, self.sigma, |s0, sigma| {
+      let z: f64 = StandardNormal.sample(&mut thread_rng());
+      let model = Self { sigma, ..*self };
+      df * model.payoff(model.terminal(s0, z))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::stochastic::{diffusion::gbm::GBM, Sampling};
+
+  fn terminal(s0: f64) -> f64 {
+    let gbm = GBM::new(&GBM {
+      mu: 0.05,
+      sigma: 0.2,
+      n: 16,
+      x0: Some(s0),
+      t: Some(1.0),
+      ..Default::default()
+    });
+    gbm.sample()[16]
+  }
+
+  #[test]
+  fn bumps_of_crate_samplers_share_their_noise() {
+    // the Euler path of the GBM is linear in x0 for the same increments
+    let up = with_seed(path_seed(3, 0), || terminal(101.0));
+    let down = with_seed(path_seed(3, 0), || terminal(99.0));
+    assert!((up / down - 101.0 / 99.0).abs() < 1e-12);
+
+    let call = |s0: f64| (terminal(s0) - 100.0).max(0.0);
+    let bump = bump_and_revalue(call, 100.0, 0.01, 5_000, 3);
+    let pathwise = path_estimator(
+      || {
+        let s = terminal(100.0);
+        if s > 100.0 {
+          s / 100.0
+        } else {
+          0.0
+        }
+      },
+      5_000,
+      3,
+    );
+
+    // path by path the bump is the pathwise derivative except near the strike
+    assert!(
+      (bump.value - pathwise.value).abs() < 1e-3,
+      "{bump:?} {pathwise:?}"
+    );
+    assert!(bump.std_error < 0.01, "{bump:?}");
+  }
+
+  #[test]
+  fn ladder_pnl_of_crate_samplers_has_common_noise() {
+    let ladder = RiskLadder {
+      spot_shifts: vec![-0.01, 0.0, 0.01],
+      vol_shifts: vec![0.0],
+      paths: 2_000,
+      seed: 5,
+    }
+    .run(100.0, 0.2, |s0, _| terminal(s0));
+
+    // the terminal value is linear in the spot, the P&L of a 1% shift is 1% of the price
+    // path by path, with independent noise its error would exceed the one of the price
+    for j in [0, 2] {
+      let ratio = ladder.pnl_std_errors[(j, 0)] / ladder.std_errors[(j, 0)];
+      assert!(ratio < 0.0102, "{ratio}");
+    }
+    assert!(ladder.pnl[(1, 0)].abs() < 1e-12);
+  }
+}
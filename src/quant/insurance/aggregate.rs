@@ -0,0 +1,170 @@
+use ndarray::{Array1, ArrayView1};
+use ndrustfft::{ndfft, ndifft, FftHandler};
+use num_complex::Complex64;
+
+/// Claim count distribution of the (a, b, 0) class, P(N = k) = (a + b / k) P(N = k - 1)
+#[derive(Debug, Clone, Copy)]
+pub enum Frequency {
+  Poisson {
+    lambda: f64,
+  },
+  Binomial {
+    n: u64,
+    p: f64,
+  },
+  /// Negative binomial with the mean r beta
+  NegativeBinomial {
+    r: f64,
+    beta: f64,
+  },
+}
+
+impl Frequency {
+  /// Coefficients a and b of the recursion
+  fn ab(&self) -> (f64, f64) {
+    match *self {
+      Self::Poisson { lambda } => (0.0, lambda),
+      Self::Binomial { n, p } => (-p / (1.0 - p), (n as f64 + 1.0) * p / (1.0 - p)),
+      Self::NegativeBinomial { r, beta } => (beta / (1.0 + beta), (r - 1.0) * beta / (1.0 + beta)),
+    }
+  }
+
+  /// Probability generating function E[z^N]
+  pub fn pgf(&self, z: Complex64) -> Complex64 {
+    match *self {
+      Self::Poisson { lambda } => (lambda * (z - 1.0)).exp(),
+      Self::Binomial { n, p } => (1.0 + p * (z - 1.0)).powf(n as f64),
+      Self::NegativeBinomial { r, beta } => (1.0 - beta * (z - 1.0)).powf(-r),
+    }
+  }
+
+  pub fn mean(&self) -> f64 {
+    match *self {
+      Self::Poisson { lambda } => lambda,
+      Self::Binomial { n, p } => n as f64 * p,
+      Self::NegativeBinomial { r, beta } => r * beta,
+    }
+  }
+}
+
+/// Severity distribution on the grid 0, h, 2h, ... of m points by the rounding method,
+/// f_0 = F(h / 2) and f_j = F((j + 1/2) h) - F((j - 1/2) h), from the claim size cdf
+pub fn discretize_severity<F>(cdf: F, h: f64, m: usize) -> Array1<f64>
+where
+  F: Fn(f64) -> f64,
+{
+  assert!(
+    h > 0.0 && m > 1,
+    "Grid must have a positive step and two points"
+  );
+  Array1::from_shape_fn(m, |j| {
+    let upper = cdf((j as f64 + 0.5) * h);
+    if j == 0 {
+      upper
+    } else {
+      upper - cdf((j as f64 - 0.5) * h)
+    }
+  })
+}
+
+/// Distribution of the aggregate loss S = X_1 + ... + X_N on the grid 0, h, 2h, ...
+/// The grid must be long enough for the tail beyond it to be negligible.
+#[derive(Debug, Clone)]
+pub struct AggregateLoss {
+  pub h: f64,
+  /// P(S = j h)
+  pub probabilities: Array1<f64>,
+}
+
+impl AggregateLoss {
+  /// Panjer recursion
+  /// g_0 = P(f_0), g_k = sum_(j=1..k) (a + b j / k) f_j g_(k-j) / (1 - a f_0)
+  /// https://doi.org/10.1017/S0515036100006796 (Panjer 1981)
+  pub fn panjer(frequency: &Frequency, severity: ArrayView1<f64>, h: f64) -> Self {
+    let (a, b) = frequency.ab();
+    let m = severity.len();
+    let mut g = Array1::<f64>::zeros(m);
+    g[0] = frequency.pgf(Complex64::new(severity[0], 0.0)).re;
+    let scale = 1.0 - a * severity[0];
+
+    for k in 1..m {
+      g[k] = (1..=k)
+        .map(|j| (a + b * j as f64 / k as f64) * severity[j] * g[k - j])
+        .sum::<f64>()
+        / scale;
+    }
+
+    Self {
+      h,
+      probabilities: g,
+    }
+  }
+
+  /// FFT of the severity through the generating function of the claim count,
+  /// zero padded to twice the grid to limit the wrap-around of the tail
+  pub fn fft(frequency: &Frequency, severity: ArrayView1<f64>, h: f64) -> Self {
+    let m = severity.len();
+    let len = (2 * m).next_power_of_two();
+    let mut padded = Array1::<Complex64>::zeros(len);
+    for (j, &f) in severity.iter().enumerate() {
+      padded[j] = Complex64::new(f, 0.0);
+    }
+
+    let handler = FftHandler::new(len);
+    let mut transform = Array1::<Complex64>::zeros(len);
+    ndfft(&padded, &mut transform, &handler, 0);
+    let transform = transform.mapv(|z| frequency.pgf(z));
+    let mut g = Array1::<Complex64>::zeros(len);
+    ndifft(&transform, &mut g, &handler, 0);
+
+    Self {
+      h,
+      probabilities: Array1::from_iter(g.iter().take(m).map(|z| z.re.max(0.0))),
+    }
+  }
+
+  /// Loss amounts of the grid
+  pub fn grid(&self) -> Array1<f64> {
+    Array1::from_shape_fn(self.probabilities.len(), |j| j as f64 * self.h)
+  }
+
+  pub fn mean(&self) -> f64 {
+    (&self.grid() * &self.probabilities).sum()
+  }
+
+  pub fn variance(&self) -> f64 {
+    let mean = self.mean();
+    (&self.grid().mapv(|x| (x - mean).powi(2)) * &self.probabilities).sum()
+  }
+
+  /// P(S <= x)
+  pub fn cdf(&self, x: f64) -> f64 {
+    let k = (x / self.h + 1e-9).floor();
+    if k < 0.0 {
+      return 0.0;
+    }
+    self.probabilities.iter().take(k as usize + 1).sum::<f64>()
+  }
+
+  /// Smallest loss of the grid with P(S <= x) >= p, the value at risk of the aggregate loss
+  pub fn quantile(&self, p: f64) -> f64 {
+    let mut cumulative = 0.0;
+    for (j, &g) in self.probabilities.iter().enumerate() {
+      cumulative += g;
+      if cumulative >= p {
+        return j as f64 * self.h;
+      }
+    }
+    (self.probabilities.len() - 1) as f64 * self.h
+  }
+
+  /// Stop-loss premium E[(S - d)^+] of the retention d
+  pub fn stop_loss(&self, d: f64) -> f64 {
+    self
+      .grid()
+      .iter()
+      .zip(self.probabilities.iter())
+      .map(|(&x, &g)| (x - d).max(0.0) * g)
+      .sum()
+  }
+}
@@ -0,0 +1,95 @@
+use ndarray::Array1;
+use rand_distr::{Distribution, Exp};
+use rayon::prelude::*;
+
+use crate::{
+  quant::greeks::Estimate,
+  rng::{path_seed, thread_rng, with_seed},
+};
+
+/// Cramér-Lundberg surplus process U(t) = u0 + c t - (X_1 + ... + X_N(t)),
+/// with the premium rate c, claims arriving as a Poisson process of intensity lambda
+/// and i.i.d. claim sizes X drawn from `claims`
+#[derive(Debug, Clone, Copy)]
+pub struct CramerLundberg<D> {
+  /// Initial capital
+  pub u0: f64,
+  /// Premium rate
+  pub c: f64,
+  /// Claim intensity
+  pub lambda: f64,
+  pub claims: D,
+}
+
+impl<D: Distribution<f64> + Sync> CramerLundberg<D> {
+  #[must_use]
+  pub fn new(u0: f64, c: f64, lambda: f64, claims: D) -> Self {
+    assert!(u0 >= 0.0, "Initial capital must be non-negative");
+    assert!(
+      c > 0.0 && lambda > 0.0,
+      "Premium rate and intensity must be positive"
+    );
+
+    Self {
+      u0,
+      c,
+      lambda,
+      claims,
+    }
+  }
+
+  /// Time of ruin before the horizon, None if the surplus stays non-negative.
+  /// Between the claims the surplus increases, so ruin can only happen at a claim.
+  pub fn sample_ruin_time(&self, horizon: f64) -> Option<f64> {
+    let mut rng = thread_rng();
+    let arrivals = Exp::new(self.lambda).unwrap();
+    let mut t = 0.0;
+    let mut claims = 0.0;
+
+    loop {
+      t += arrivals.sample(&mut rng);
+      if t > horizon {
+        return None;
+      }
+      claims += self.claims.sample(&mut rng);
+      if self.u0 + self.c * t - claims < 0.0 {
+        return Some(t);
+      }
+    }
+  }
+
+  /// Monte Carlo probability of ruin before the horizon, a long horizon approximates
+  /// the infinite-horizon probability when the safety loading is positive
+  pub fn ruin_probability(&self, horizon: f64, paths: usize, seed: u64) -> Estimate {
+    let ruined = (0..paths)
+      .into_par_iter()
+      .map(|i| {
+        with_seed(path_seed(seed, i as u64), || {
+          f64::from(u8::from(self.sample_ruin_time(horizon).is_some()))
+        })
+      })
+      .collect::<Vec<_>>();
+
+    Estimate::from_samples(Array1::from(ruined).view())
+  }
+}
+
+/// Infinite-horizon ruin probability of the Pollaczek-Khinchine formula for exponential
+/// claims with the mean `mean`, psi(u) = exp(-theta u / ((1 + theta) mean)) / (1 + theta),
+/// where theta = c / (lambda mean) - 1 is the safety loading
+pub fn ruin_probability_exponential(u0: f64, c: f64, lambda: f64, mean: f64) -> f64 {
+  let theta = c / (lambda * mean) - 1.0;
+  assert!(
+    theta > 0.0,
+    "Ruin is certain without a positive safety loading"
+  );
+  (-theta * u0 / ((1.0 + theta) * mean)).exp() / (1.0 + theta)
+}
+
+/// Lundberg adjustment coefficient R of exponential claims, the positive root of
+/// lambda (M_X(r) - 1) = c r, which bounds the ruin probability by exp(-R u)
+pub fn adjustment_coefficient_exponential(c: f64, lambda: f64, mean: f64) -> f64 {
+  let r = 1.0 / mean - lambda / c;
+  assert!(r > 0.0, "Ruin is certain without a positive safety loading");
+  r
+}
@@ -0,0 +1,3 @@
+pub mod execution;
+pub mod order_book;
+pub mod resampling;
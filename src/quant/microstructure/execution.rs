@@ -0,0 +1,161 @@
+use ndarray::{Array1, ArrayView1};
+use rayon::prelude::*;
+
+use crate::{
+  rng::{path_seed, with_seed},
+  stochastic::Sampling,
+};
+
+/// Almgren-Chriss liquidation of `x0` shares over `n` equal intervals of the horizon `t`,
+/// with linear impact. The unaffected price moves with the arithmetic volatility `sigma`,
+/// every sale of n_k shares lowers the price permanently by gamma n_k and is executed at
+/// the price less the temporary impact epsilon + eta n_k / tau.
+/// The optimal trajectory minimizes E[cost] + lambda Var[cost].
+/// https://doi.org/10.21314/JOR.2001.041 (Almgren, Chriss)
+#[derive(Default, Debug, Clone)]
+pub struct AlmgrenChriss {
+  /// Shares to sell
+  pub x0: f64,
+  /// Arithmetic price volatility per square root of time
+  pub sigma: f64,
+  /// Permanent impact per share
+  pub gamma: f64,
+  /// Temporary impact per share per unit trading rate
+  pub eta: f64,
+  /// Fixed cost per share (half spread and fees)
+  pub epsilon: f64,
+  /// Risk aversion
+  pub lambda: f64,
+  /// Number of trading intervals
+  pub n: usize,
+  /// Liquidation horizon
+  pub t: f64,
+}
+
+impl AlmgrenChriss {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.n > 0, "At least one trading interval is needed");
+    assert!(params.t > 0.0, "Horizon must be positive");
+    assert!(params.lambda >= 0.0, "Risk aversion must be non-negative");
+    assert!(
+      params.eta - 0.5 * params.gamma * params.t / params.n as f64 > 0.0,
+      "Temporary impact must dominate the permanent impact, eta > gamma tau / 2"
+    );
+
+    params.clone()
+  }
+
+  fn tau(&self) -> f64 {
+    self.t / self.n as f64
+  }
+
+  /// Temporary impact corrected for the discretization, eta - gamma tau / 2
+  fn eta_tilde(&self) -> f64 {
+    self.eta - 0.5 * self.gamma * self.tau()
+  }
+
+  /// Urgency kappa of the optimal trajectory, the solution of
+  /// 2 (cosh(kappa tau) - 1) / tau^2 = lambda sigma^2 / eta_tilde
+  pub fn kappa(&self) -> f64 {
+    let tau = self.tau();
+    let kappa_tilde2 = self.lambda * self.sigma.powi(2) / self.eta_tilde();
+    (0.5 * tau * tau * kappa_tilde2 + 1.0).acosh() / tau
+  }
+
+  /// Optimal holdings x_k = x0 sinh(kappa (T - t_k)) / sinh(kappa T) at the n + 1 trading
+  /// times, linear for a risk-neutral trader
+  pub fn trajectory(&self) -> Array1<f64> {
+    let kappa = self.kappa();
+    let tau = self.tau();
+
+    Array1::from_shape_fn(self.n + 1, |k| {
+      let remaining = self.t - k as f64 * tau;
+      if kappa * self.t < 1e-10 {
+        self.x0 * remaining / self.t
+      } else {
+        self.x0 * (kappa * remaining).sinh() / (kappa * self.t).sinh()
+      }
+    })
+  }
+
+  /// Shares sold in every interval
+  pub fn trade_list(&self) -> Array1<f64> {
+    let x = self.trajectory();
+    Array1::from_shape_fn(self.n, |k| x[k] - x[k + 1])
+  }
+
+  /// Expected implementation shortfall of the optimal trajectory
+  /// gamma x0^2 / 2 + epsilon sum |n_k| + eta_tilde / tau sum n_k^2
+  pub fn expected_cost(&self) -> f64 {
+    let trades = self.trade_list();
+    0.5 * self.gamma * self.x0.powi(2)
+      + self.epsilon * trades.mapv(f64::abs).sum()
+      + self.eta_tilde() / self.tau() * trades.mapv(|v| v * v).sum()
+  }
+
+  /// Variance of the implementation shortfall sigma^2 tau sum x_k^2
+  pub fn cost_variance(&self) -> f64 {
+    let x = self.trajectory();
+    self.sigma.powi(2) * self.tau() * x.iter().skip(1).map(|v| v * v).sum::<f64>()
+  }
+
+  /// Efficient frontier, the expected cost and the variance of the optimal trajectories
+  /// for the risk aversions
+  pub fn efficient_frontier(&self, lambdas: &[f64]) -> Vec<(f64, f64)> {
+    lambdas
+      .iter()
+      .map(|&lambda| {
+        let model = Self {
+          lambda,
+          ..self.clone()
+        };
+        (model.expected_cost(), model.cost_variance())
+      })
+      .collect()
+  }
+
+  /// Implementation shortfall x0 S(0) - sum n_k S~_k of the trade list against the
+  /// unaffected price path at the n + 1 trading times, with the impacts applied
+  pub fn shortfall(&self, trades: ArrayView1<f64>, prices: ArrayView1<f64>) -> f64 {
+    assert_eq!(trades.len(), self.n, "One trade per interval is needed");
+    assert_eq!(
+      prices.len(),
+      self.n + 1,
+      "Prices are needed at the n + 1 trading times"
+    );
+    let tau = self.tau();
+    let mut sold = 0.0;
+    let mut proceeds = 0.0;
+
+    for (k, &nk) in trades.iter().enumerate() {
+      let price = prices[k] - self.gamma * sold;
+      proceeds += nk * (price - self.epsilon * nk.signum() - self.eta * nk / tau);
+      sold += nk;
+    }
+
+    self.x0 * prices[0] - proceeds
+  }
+
+  /// Distribution of the shortfall of the optimal trade list over `paths` price paths of a
+  /// process, which must have n + 1 points over the horizon
+  pub fn simulate_costs<S: Sampling<f64>>(
+    &self,
+    process: &S,
+    paths: usize,
+    seed: u64,
+  ) -> Array1<f64> {
+    let trades = self.trade_list();
+
+    Array1::from(
+      (0..paths)
+        .into_par_iter()
+        .map(|i| {
+          with_seed(path_seed(seed, i as u64), || {
+            self.shortfall(trades.view(), process.sample().view())
+          })
+        })
+        .collect::<Vec<_>>(),
+    )
+  }
+}
@@ -0,0 +1,285 @@
+use ndarray::Array1;
+use rand::Rng;
+use rand_distr::{Distribution, Exp};
+
+use crate::rng::thread_rng;
+
+/// Time series of a simulated limit order book on the time grid
+#[derive(Debug, Clone)]
+pub struct OrderBookPath {
+  /// Mid-price
+  pub mid: Array1<f64>,
+  /// Bid-ask spread
+  pub spread: Array1<f64>,
+  /// Number of shares at the best bid
+  pub bid_depth: Array1<f64>,
+  /// Number of shares at the best ask
+  pub ask_depth: Array1<f64>,
+}
+
+/// Limit order book on a price grid driven by Poisson order flows (Cont, Stoikov and Talreja).
+/// - Limit orders arrive at i ticks from the opposite best quote with rate `limit_rates[i - 1]`.
+/// - Market orders arrive on each side with rate `market_rate`, optionally self-exciting
+///   as a Hawkes process with jump `market_alpha` and decay `market_beta`.
+/// - Each order at i ticks from the opposite best quote is cancelled with rate `cancel_rates[i - 1]`.
+///
+/// The last share of a side is never removed, so both sides of the book stay non-empty.
+/// https://doi.org/10.1287/opre.1090.0780
+#[derive(Default)]
+pub struct OrderBook {
+  /// Number of price levels
+  pub levels: usize,
+  /// Limit order arrival rate by distance (in ticks) from the opposite best quote
+  pub limit_rates: Vec<f64>,
+  /// Market order arrival rate on each side
+  pub market_rate: f64,
+  /// Cancellation rate per order by distance (in ticks) from the opposite best quote
+  pub cancel_rates: Vec<f64>,
+  /// Jump of the market order intensity after a market order
+  pub market_alpha: Option<f64>,
+  /// Decay rate of the market order excitation
+  pub market_beta: Option<f64>,
+  /// Initial number of shares at each level (default 5)
+  pub depth: Option<u64>,
+  /// Initial mid-price (default 100.0)
+  pub p0: Option<f64>,
+  /// Tick size (default 0.01)
+  pub tick: Option<f64>,
+  /// Number of time steps
+  pub n: usize,
+  /// Time horizon
+  pub t: Option<f64>,
+}
+
+enum Event {
+  LimitBuy(usize),
+  LimitSell(usize),
+  MarketBuy,
+  MarketSell,
+  CancelBuy(usize),
+  CancelSell(usize),
+}
+
+struct Book {
+  bids: Vec<u64>,
+  asks: Vec<u64>,
+}
+
+impl Book {
+  fn best_bid(&self) -> usize {
+    (0..self.bids.len())
+      .rev()
+      .find(|&p| self.bids[p] > 0)
+      .unwrap()
+  }
+
+  fn best_ask(&self) -> usize {
+    (0..self.asks.len()).find(|&p| self.asks[p] > 0).unwrap()
+  }
+}
+
+impl OrderBook {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.levels >= 4, "The book needs at least 4 price levels");
+    assert_eq!(
+      params.limit_rates.len(),
+      params.cancel_rates.len(),
+      "Limit and cancellation rates must be given for the same distances"
+    );
+
+    Self {
+      levels: params.levels,
+      limit_rates: params.limit_rates.clone(),
+      market_rate: params.market_rate,
+      cancel_rates: params.cancel_rates.clone(),
+      market_alpha: params.market_alpha,
+      market_beta: params.market_beta,
+      depth: params.depth,
+      p0: params.p0,
+      tick: params.tick,
+      n: params.n,
+      t: params.t,
+    }
+  }
+
+  /// Rates of the possible events in the current state of the book
+  fn events(&self, book: &Book, excitation: f64) -> Vec<(Event, f64)> {
+    let (bid, ask) = (book.best_bid(), book.best_ask());
+    let mut events = Vec::new();
+
+    for (d, (&lambda, &theta)) in self.limit_rates.iter().zip(&self.cancel_rates).enumerate() {
+      let i = d + 1;
+
+      if ask >= i {
+        events.push((Event::LimitBuy(ask - i), lambda));
+        events.push((Event::CancelBuy(ask - i), theta * book.bids[ask - i] as f64));
+      }
+
+      if bid + i < self.levels {
+        events.push((Event::LimitSell(bid + i), lambda));
+        events.push((
+          Event::CancelSell(bid + i),
+          theta * book.asks[bid + i] as f64,
+        ));
+      }
+    }
+
+    let market = self.market_rate + excitation;
+    let total_bids = book.bids.iter().sum::<u64>();
+    let total_asks = book.asks.iter().sum::<u64>();
+
+    if total_asks > 1 {
+      events.push((Event::MarketBuy, market));
+    }
+
+    if total_bids > 1 {
+      events.push((Event::MarketSell, market));
+    }
+
+    // never empty a side of the book by cancellations
+    events.retain(|(e, _)| match e {
+      Event::CancelBuy(_) => total_bids > 1,
+      Event::CancelSell(_) => total_asks > 1,
+      _ => true,
+    });
+
+    events
+  }
+
+  fn apply(book: &mut Book, event: &Event) {
+    match *event {
+      Event::LimitBuy(p) => book.bids[p] += 1,
+      Event::LimitSell(p) => book.asks[p] += 1,
+      Event::CancelBuy(p) => book.bids[p] -= 1,
+      Event::CancelSell(p) => book.asks[p] -= 1,
+      Event::MarketBuy => {
+        let p = book.best_ask();
+        book.asks[p] -= 1;
+      }
+      Event::MarketSell => {
+        let p = book.best_bid();
+        book.bids[p] -= 1;
+      }
+    }
+  }
+
+  /// Simulate the book and record its state on the time grid
+  pub fn sample(&self) -> OrderBookPath {
+    let t_max = self.t.unwrap_or(1.0);
+    let dt = t_max / self.n as f64;
+    let tick = self.tick.unwrap_or(0.01);
+    let depth = self.depth.unwrap_or(5);
+    let alpha = self.market_alpha.unwrap_or(0.0);
+    let beta = self.market_beta.unwrap_or(1.0);
+    let center = self.levels / 2;
+    let mut rng = thread_rng();
+
+    let mut book = Book {
+      bids: (0..self.levels)
+        .map(|p| if p < center { depth } else { 0 })
+        .collect(),
+      asks: (0..self.levels)
+        .map(|p| if p >= center { depth } else { 0 })
+        .collect(),
+    };
+
+    let mut path = OrderBookPath {
+      mid: Array1::zeros(self.n + 1),
+      spread: Array1::zeros(self.n + 1),
+      bid_depth: Array1::zeros(self.n + 1),
+      ask_depth: Array1::zeros(self.n + 1),
+    };
+
+    // mid-price of the initial book is center - 1/2 ticks
+    let record = |path: &mut OrderBookPath, book: &Book, i: usize| {
+      let (bid, ask) = (book.best_bid(), book.best_ask());
+      path.mid[i] =
+        self.p0.unwrap_or(100.0) + ((bid + ask) as f64 / 2.0 - center as f64 + 0.5) * tick;
+      path.spread[i] = (ask - bid) as f64 * tick;
+      path.bid_depth[i] = book.bids[bid] as f64;
+      path.ask_depth[i] = book.asks[ask] as f64;
+    };
+
+    record(&mut path, &book, 0);
+    let mut next = 1;
+    let mut t = 0.0;
+    let mut excitation = 0.0;
+
+    while next <= self.n {
+      let events = self.events(&book, excitation);
+      let rate = events.iter().map(|(_, r)| r).sum::<f64>();
+
+      // the market order excitation only decays between events, so the current
+      // total rate bounds the intensity until the next event (thinning)
+      let w = if rate > 0.0 {
+        Exp::new(rate).unwrap().sample(&mut rng)
+      } else {
+        f64::INFINITY
+      };
+
+      while next <= self.n && t + w > next as f64 * dt {
+        record(&mut path, &book, next);
+        next += 1;
+      }
+
+      if next > self.n {
+        break;
+      }
+
+      t += w;
+      let decay = (-beta * w).exp();
+      let market_before = self.market_rate + excitation;
+      excitation *= decay;
+      let market_after = self.market_rate + excitation;
+
+      let u = rng.gen::<f64>() * rate;
+      let mut acc = 0.0;
+
+      for (event, r) in &events {
+        let r = match event {
+          // without market orders (market_rate 0 and no excitation) their rate stays 0
+          Event::MarketBuy | Event::MarketSell if market_before > 0.0 => {
+            r * market_after / market_before
+          }
+          _ => *r,
+        };
+        acc += r;
+
+        if u < acc {
+          Self::apply(&mut book, event);
+
+          if matches!(event, Event::MarketBuy | Event::MarketSell) {
+            excitation += alpha;
+          }
+
+          break;
+        }
+      }
+    }
+
+    path
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn book_without_market_orders_stays_finite() {
+    let book = OrderBook::new(&OrderBook {
+      levels: 20,
+      limit_rates: vec![1.0, 0.5, 0.25],
+      market_rate: 0.0,
+      cancel_rates: vec![0.2, 0.2, 0.2],
+      market_alpha: Some(0.5),
+      n: 100,
+      t: Some(10.0),
+      ..Default::default()
+    });
+    let path = crate::rng::with_seed(1, || book.sample());
+    assert!(path.mid.iter().all(|m| m.is_finite()));
+    assert!(path.spread.iter().all(|&s| s > 0.0));
+  }
+}
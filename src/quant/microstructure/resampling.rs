@@ -0,0 +1,117 @@
+use ndarray::{Array1, ArrayView1};
+
+/// Calendar-time bars
+#[derive(Debug, Clone)]
+pub struct Bars {
+  /// Start time of each bar
+  pub times: Array1<f64>,
+  pub open: Array1<f64>,
+  pub high: Array1<f64>,
+  pub low: Array1<f64>,
+  pub close: Array1<f64>,
+  /// Traded volume (number of ticks if no volumes are given)
+  pub volume: Array1<f64>,
+  /// Volume-weighted average price, the close for bars without trades
+  pub vwap: Array1<f64>,
+}
+
+/// Aggregate irregularly spaced ticks to calendar-time bars of length `bar`
+/// starting at `t0`. Bars without ticks repeat the previous close with zero volume,
+/// ticks before `t0` are ignored.
+pub fn calendar_bars(
+  times: ArrayView1<f64>,
+  prices: ArrayView1<f64>,
+  volumes: Option<ArrayView1<f64>>,
+  t0: f64,
+  bar: f64,
+  n_bars: usize,
+) -> Bars {
+  assert_eq!(
+    times.len(),
+    prices.len(),
+    "Times and prices must have the same length"
+  );
+  assert!(!prices.is_empty(), "There must be at least one tick");
+  assert!(bar > 0.0, "Bar length must be positive");
+
+  let mut bars = Bars {
+    times: Array1::from_shape_fn(n_bars, |i| t0 + i as f64 * bar),
+    open: Array1::zeros(n_bars),
+    high: Array1::zeros(n_bars),
+    low: Array1::zeros(n_bars),
+    close: Array1::zeros(n_bars),
+    volume: Array1::zeros(n_bars),
+    vwap: Array1::zeros(n_bars),
+  };
+
+  let mut k = 0;
+  let mut last = prices[0];
+
+  while k < times.len() && times[k] < t0 {
+    last = prices[k];
+    k += 1;
+  }
+
+  for i in 0..n_bars {
+    let end = t0 + (i + 1) as f64 * bar;
+    let (mut open, mut high, mut low) = (f64::NAN, f64::NEG_INFINITY, f64::INFINITY);
+    let (mut volume, mut notional) = (0.0, 0.0);
+
+    while k < times.len() && times[k] < end {
+      let (p, v) = (prices[k], volumes.map_or(1.0, |v| v[k]));
+
+      if open.is_nan() {
+        open = p;
+      }
+
+      high = high.max(p);
+      low = low.min(p);
+      volume += v;
+      notional += p * v;
+      last = p;
+      k += 1;
+    }
+
+    if open.is_nan() {
+      (open, high, low) = (last, last, last);
+    }
+
+    bars.open[i] = open;
+    bars.high[i] = high;
+    bars.low[i] = low;
+    bars.close[i] = last;
+    bars.volume[i] = volume;
+    bars.vwap[i] = if volume > 0.0 {
+      notional / volume
+    } else {
+      last
+    };
+  }
+
+  bars
+}
+
+/// Number of events up to (and at) each grid point, e.g. from the event times
+/// of a Poisson or Hawkes process
+pub fn transaction_counts(event_times: ArrayView1<f64>, grid: ArrayView1<f64>) -> Array1<f64> {
+  let mut counts = Array1::<f64>::zeros(grid.len());
+  let mut k = 0;
+
+  for (i, &t) in grid.iter().enumerate() {
+    while k < event_times.len() && event_times[k] <= t {
+      k += 1;
+    }
+
+    counts[i] = k as f64;
+  }
+
+  counts
+}
+
+/// Subordinate an event-time path X_k, k = 0, 1, ... by a transaction count
+/// process, Y(t_i) = X_{N(t_i)}. Counts beyond the path are clamped to its end.
+pub fn subordinate(path: ArrayView1<f64>, counts: ArrayView1<f64>) -> Array1<f64> {
+  assert!(!path.is_empty(), "Path must not be empty");
+
+  counts.mapv(|c| path[(c.max(0.0) as usize).min(path.len() - 1)])
+}
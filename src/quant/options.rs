@@ -1 +1,7 @@
 pub mod bsm;
+pub mod chain;
+pub mod fx;
+pub mod lsm;
+pub mod mesh;
+pub mod multiasset;
+pub mod swaption;
@@ -23,7 +23,7 @@ pub enum BSMCoc {
 }
 
 /// Black-Scholes-Merton model
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct BSM {
   /// Underlying price
   pub s: f64,
@@ -42,8 +42,10 @@ pub struct BSM {
   /// Time to maturity in years
   pub tau: Option<f64>,
   /// Evaluation date
+  #[cfg(feature = "dates")]
   pub eval: Option<chrono::NaiveDate>,
   /// Expiration date
+  #[cfg(feature = "dates")]
   pub expiration: Option<chrono::NaiveDate>,
   /// Option type
   pub option_type: OptionType,
@@ -72,10 +74,12 @@ impl Price for BSM {
     self.tau
   }
 
+  #[cfg(feature = "dates")]
   fn eval(&self) -> Option<chrono::NaiveDate> {
     self.eval
   }
 
+  #[cfg(feature = "dates")]
   fn expiration(&self) -> Option<chrono::NaiveDate> {
     self.expiration
   }
@@ -85,19 +89,12 @@ impl BSM {
   /// Create a new BSM model
   #[must_use]
   pub fn new(params: &Self) -> Self {
+    #[cfg(feature = "dates")]
     if params.tau.is_none() && params.eval.is_none() && params.expiration.is_none() {
       panic!("At least one of the following parameters is missing: tau, eval, expiration");
     }
 
-    let tau = if let Some(tau) = params.tau {
-      tau
-    } else {
-      let eval = params
-        .eval
-        .unwrap_or(chrono::Local::now().naive_local().into());
-      let expiration = params.expiration.unwrap();
-      (expiration - eval).num_days() as f64 / 365.0
-    };
+    let tau = params.calculate_tau_in_years();
 
     Self {
       s: params.s,
@@ -108,7 +105,9 @@ impl BSM {
       r_f: params.r_f,
       q: params.q,
       tau: Some(tau),
+      #[cfg(feature = "dates")]
       eval: params.eval,
+      #[cfg(feature = "dates")]
       expiration: params.expiration,
       option_type: params.option_type,
       b: params.b,
@@ -343,6 +342,48 @@ impl BSM {
     n.pdf(d2) * (-self.r * self.tau()).exp() / (self.k * self.v * self.tau().sqrt())
   }
 
+  /// Implied volatility of the given option price, by Newton's method
+  /// safeguarded with bisection. Returns NaN if the price is outside the no-arbitrage bounds.
+  pub fn implied_volatility(&self, price: f64) -> f64 {
+    let mut bsm = BSM::new(self);
+    let (mut lo, mut hi) = (1e-8, 10.0);
+
+    bsm.v = lo;
+    let p_lo = bsm.price();
+    bsm.v = hi;
+    let p_hi = bsm.price();
+
+    if price < p_lo || price > p_hi {
+      return f64::NAN;
+    }
+
+    bsm.v = 0.2;
+
+    for _ in 0..100 {
+      let diff = bsm.price() - price;
+
+      if diff.abs() < 1e-12 {
+        break;
+      }
+
+      if diff > 0.0 {
+        hi = bsm.v;
+      } else {
+        lo = bsm.v;
+      }
+
+      let vega = bsm.vega();
+      let newton = bsm.v - diff / vega;
+      bsm.v = if vega > 1e-12 && newton > lo && newton < hi {
+        newton
+      } else {
+        0.5 * (lo + hi)
+      };
+    }
+
+    bsm.v
+  }
+
   fn tau(&self) -> f64 {
     self.tau.unwrap()
   }
@@ -0,0 +1,195 @@
+use std::fmt;
+
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+  quant::{curve::YieldCurve, OptionType},
+  stochastic::diffusion::gbm::GBM,
+};
+
+/// Currency pair FOR/DOM, the price of one unit of the foreign currency in the domestic one
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct CurrencyPair {
+  /// Foreign (base) currency code, e.g. EUR
+  pub foreign: String,
+  /// Domestic (quote) currency code, e.g. USD
+  pub domestic: String,
+}
+
+impl CurrencyPair {
+  #[must_use]
+  pub fn new(foreign: &str, domestic: &str) -> Self {
+    Self {
+      foreign: foreign.to_uppercase(),
+      domestic: domestic.to_uppercase(),
+    }
+  }
+}
+
+impl fmt::Display for CurrencyPair {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}{}", self.foreign, self.domestic)
+  }
+}
+
+/// Standard normal cdf
+fn cdf(x: f64) -> f64 {
+  Normal::default().cdf(x)
+}
+
+/// European FX option under Garman-Kohlhagen with domestic and foreign discount curves,
+/// the price is in domestic currency per unit of foreign notional
+#[derive(Debug, Clone)]
+pub struct FxOption {
+  /// Spot rate, domestic per foreign
+  pub spot: f64,
+  /// Strike price
+  pub k: f64,
+  /// Volatility of the rate
+  pub sigma: f64,
+  /// Discount curve of the domestic currency
+  pub domestic: YieldCurve,
+  /// Discount curve of the foreign currency
+  pub foreign: YieldCurve,
+  /// Time to maturity in years
+  pub tau: f64,
+  pub option_type: OptionType,
+  /// Currencies of the option
+  pub pair: Option<CurrencyPair>,
+}
+
+impl FxOption {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.spot > 0.0, "Spot rate must be positive");
+    assert!(params.tau > 0.0, "Time to maturity must be positive");
+
+    params.clone()
+  }
+
+  /// Outright forward rate at maturity, by covered interest parity
+  pub fn forward(&self) -> f64 {
+    self.spot * self.foreign.discount_factor(self.tau) / self.domestic.discount_factor(self.tau)
+  }
+
+  fn d1_d2(&self) -> (f64, f64) {
+    let sd = self.sigma * self.tau.sqrt();
+    let d1 = ((self.forward() / self.k).ln() + 0.5 * sd.powi(2)) / sd;
+    (d1, d1 - sd)
+  }
+
+  /// Garman-Kohlhagen price
+  pub fn price(&self) -> f64 {
+    let (d1, d2) = self.d1_d2();
+    let (f, df) = (self.forward(), self.domestic.discount_factor(self.tau));
+
+    match self.option_type {
+      OptionType::Call => df * (f * cdf(d1) - self.k * cdf(d2)),
+      OptionType::Put => df * (self.k * cdf(-d2) - f * cdf(-d1)),
+    }
+  }
+
+  /// Spot delta in foreign notional
+  pub fn delta(&self) -> f64 {
+    let (d1, _) = self.d1_d2();
+    let df = self.foreign.discount_factor(self.tau);
+
+    match self.option_type {
+      OptionType::Call => df * cdf(d1),
+      OptionType::Put => -df * cdf(-d1),
+    }
+  }
+
+  /// Premium-adjusted spot delta, the market convention when the premium is paid
+  /// in the foreign currency
+  pub fn premium_adjusted_delta(&self) -> f64 {
+    self.delta() - self.price() / self.spot
+  }
+
+  /// Simulator of the rate with the drift of the two curves, n steps to maturity and m paths
+  pub fn sampler(&self, n: usize, m: Option<usize>) -> GBM {
+    GBM::with_curves(
+      &GBM {
+        sigma: self.sigma,
+        n,
+        x0: Some(self.spot),
+        t: Some(self.tau),
+        m,
+        ..Default::default()
+      },
+      &self.domestic,
+      Some(&self.foreign),
+    )
+  }
+}
+
+/// Drift adjustment of an asset in the foreign currency under the domestic measure,
+/// with rho the correlation of the asset and the FX rate (domestic per foreign)
+pub fn quanto_drift_adjustment(rho: f64, sigma_s: f64, sigma_fx: f64) -> f64 {
+  -rho * sigma_s * sigma_fx
+}
+
+/// Quanto option: the payoff of an option on an asset quoted in the foreign currency
+/// is paid in the domestic currency at the fixed rate `fx`
+#[derive(Debug, Clone)]
+pub struct QuantoOption {
+  /// Asset price in foreign currency
+  pub s: f64,
+  /// Strike price in foreign currency
+  pub k: f64,
+  /// Volatility of the asset
+  pub sigma_s: f64,
+  /// Volatility of the FX rate (domestic per foreign)
+  pub sigma_fx: f64,
+  /// Correlation of the asset and the FX rate
+  pub rho: f64,
+  /// Dividend yield of the asset
+  pub q: Option<f64>,
+  /// Fixed conversion rate, domestic per foreign
+  pub fx: f64,
+  /// Discount curve of the domestic (payment) currency
+  pub domestic: YieldCurve,
+  /// Discount curve of the foreign (asset) currency
+  pub foreign: YieldCurve,
+  /// Time to maturity in years
+  pub tau: f64,
+  pub option_type: OptionType,
+  /// Currencies of the option
+  pub pair: Option<CurrencyPair>,
+}
+
+impl QuantoOption {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      (-1.0..=1.0).contains(&params.rho),
+      "Correlation must be in [-1, 1]"
+    );
+
+    params.clone()
+  }
+
+  /// Forward of the asset under the domestic measure
+  pub fn quanto_forward(&self) -> f64 {
+    let r_f = self.foreign.zero_rate(self.tau);
+    let drift =
+      r_f - self.q.unwrap_or(0.0) + quanto_drift_adjustment(self.rho, self.sigma_s, self.sigma_fx);
+    self.s * (drift * self.tau).exp()
+  }
+
+  /// Price in domestic currency
+  pub fn price(&self) -> f64 {
+    let f = self.quanto_forward();
+    let sd = self.sigma_s * self.tau.sqrt();
+    let d1 = ((f / self.k).ln() + 0.5 * sd.powi(2)) / sd;
+    let d2 = d1 - sd;
+    let df = self.domestic.discount_factor(self.tau);
+
+    self.fx
+      * df
+      * match self.option_type {
+        OptionType::Call => f * cdf(d1) - self.k * cdf(d2),
+        OptionType::Put => self.k * cdf(-d2) - f * cdf(-d1),
+      }
+  }
+}
@@ -0,0 +1,104 @@
+use nalgebra::{DMatrix, DVector};
+use ndarray::{Array1, ArrayView1, ArrayView2, ArrayView3};
+
+use crate::quant::greeks::Estimate;
+
+/// Result of a Longstaff-Schwartz valuation
+#[derive(Debug, Clone, Default)]
+pub struct LsmResult {
+  pub price: Estimate,
+  /// Fraction of the paths exercised at every exercise date
+  pub exercise_probability: Array1<f64>,
+  /// Range of the first state variable over the paths whose regression said exercise at
+  /// every date, an estimate of the exercise boundary, None where no path was exercised
+  pub boundary: Vec<Option<(f64, f64)>>,
+}
+
+/// Polynomial basis 1, x_f, x_f^2, .. x_f^degree of every state variable
+fn basis(state: ArrayView1<f64>, degree: usize) -> Vec<f64> {
+  let mut row = vec![1.0];
+  for x in state {
+    row.extend((1..=degree).map(|k| x.powi(k as i32)));
+  }
+  row
+}
+
+/// Longstaff-Schwartz estimator of an option exercisable on a set of dates: going
+/// backwards, the continuation value is regressed on a polynomial of the state over the
+/// paths in the money and the option is exercised where the exercise value beats it.
+/// `exercise` holds the exercise values deflated by the numeraire (the discount factor or
+/// the bank account), one row per path and one column per exercise date, `state` the
+/// regression variables, path x date x variable. The price uses the regression of the
+/// same paths and is therefore slightly biased high in small samples, an independent set
+/// of paths gives a low biased estimate.
+/// https://doi.org/10.1093/rfs/14.1.113 (Longstaff, Schwartz 2001)
+pub fn longstaff_schwartz(
+  exercise: ArrayView2<f64>,
+  state: ArrayView3<f64>,
+  degree: usize,
+) -> LsmResult {
+  let (paths, dates) = exercise.dim();
+  assert_eq!(
+    (state.dim().0, state.dim().1),
+    (paths, dates),
+    "One state per path and exercise date"
+  );
+
+  let mut cash = exercise.column(dates - 1).to_owned();
+  let mut stopping = Array1::from_shape_fn(paths, |p| (cash[p] > 0.0).then_some(dates - 1));
+  let mut boundary = vec![None; dates];
+  boundary[dates - 1] = range(
+    (0..paths).filter(|&p| cash[p] > 0.0),
+    state.slice(ndarray::s![.., dates - 1, 0]),
+  );
+
+  for d in (0..dates - 1).rev() {
+    let itm = (0..paths)
+      .filter(|&p| exercise[(p, d)] > 0.0)
+      .collect::<Vec<_>>();
+    let columns = 1 + degree * state.dim().2;
+    if itm.len() <= columns {
+      continue;
+    }
+
+    let x = DMatrix::from_fn(itm.len(), columns, |i, c| {
+      basis(state.slice(ndarray::s![itm[i], d, ..]), degree)[c]
+    });
+    let y = DVector::from_iterator(itm.len(), itm.iter().map(|&p| cash[p]));
+    let beta = x
+      .clone()
+      .svd(true, true)
+      .solve(&y, 1e-12)
+      .expect("Regression of the continuation value failed");
+    let continuation = &x * beta;
+
+    let exercised = itm
+      .iter()
+      .enumerate()
+      .filter(|(i, &p)| exercise[(p, d)] > continuation[*i])
+      .map(|(_, &p)| p)
+      .collect::<Vec<_>>();
+    for &p in &exercised {
+      cash[p] = exercise[(p, d)];
+      stopping[p] = Some(d);
+    }
+    boundary[d] = range(exercised.into_iter(), state.slice(ndarray::s![.., d, 0]));
+  }
+
+  let exercise_probability = Array1::from_shape_fn(dates, |d| {
+    stopping.iter().filter(|s| **s == Some(d)).count() as f64 / paths as f64
+  });
+
+  LsmResult {
+    price: Estimate::from_samples(cash.view()),
+    exercise_probability,
+    boundary,
+  }
+}
+
+fn range(paths: impl Iterator<Item = usize>, x: ArrayView1<f64>) -> Option<(f64, f64)> {
+  paths.map(|p| x[p]).fold(None, |acc, v| match acc {
+    None => Some((v, v)),
+    Some((lo, hi)) => Some((f64::min(lo, v), f64::max(hi, v))),
+  })
+}
@@ -0,0 +1,168 @@
+use ndarray::{Array2, Array3};
+
+use crate::{
+  quant::{
+    options::{
+      lsm::{longstaff_schwartz, LsmResult},
+      multiasset::black,
+    },
+    OptionType,
+  },
+  rng::with_seed,
+  stochastic::interest::lmm::{Lmm, LmmPath},
+};
+
+/// Bermudan swaption on the LIBOR market model: the right to enter, on any of the tenor
+/// dates T_first..T_N-1, the swap of the remaining accrual periods up to T_N at the fixed
+/// rate k. The call is the payer swaption (pay fixed), the put the receiver swaption.
+#[derive(Default, Clone)]
+pub struct BermudanSwaption {
+  pub lmm: Lmm,
+  /// Fixed rate
+  pub k: f64,
+  /// Index of the first exercise date
+  pub first_exercise: usize,
+  /// Notional (default 1)
+  pub notional: Option<f64>,
+  pub option_type: OptionType,
+  /// Degree of the polynomial of the swap rate in the regression (default 2)
+  pub degree: Option<usize>,
+}
+
+impl BermudanSwaption {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.first_exercise >= 1 && params.first_exercise < params.lmm.dim(),
+      "The first exercise date must be between T_1 and T_N-1"
+    );
+
+    params.clone()
+  }
+
+  /// Swap rate and annuity at T_k of the swap from T_k to T_N on a path
+  fn swap(&self, path: &LmmPath, k: usize) -> (f64, f64) {
+    let n = self.lmm.dim();
+    let delta = self.lmm.accruals();
+    let annuity = (k..n)
+      .map(|i| delta[i] * path.bond(delta.view(), k, i + 1))
+      .sum::<f64>();
+    ((1.0 - path.bond(delta.view(), k, n)) / annuity, annuity)
+  }
+
+  fn payoff(&self, swap_rate: f64) -> f64 {
+    match self.option_type {
+      OptionType::Call => (swap_rate - self.k).max(0.0),
+      OptionType::Put => (self.k - swap_rate).max(0.0),
+    }
+  }
+
+  /// Longstaff-Schwartz price with the exercise diagnostics, the state of the regression
+  /// is the swap rate of the exercise date
+  pub fn price(&self, paths: usize, seed: u64) -> LsmResult {
+    let lmm = Lmm {
+      m: Some(paths),
+      ..self.lmm.clone()
+    };
+    let sample = with_seed(seed, || lmm.sample_par());
+    let dates = (self.first_exercise..self.lmm.dim()).collect::<Vec<_>>();
+    let notional = self.notional.unwrap_or(1.0);
+
+    let mut exercise = Array2::<f64>::zeros((paths, dates.len()));
+    let mut state = Array3::<f64>::zeros((paths, dates.len(), 1));
+    for (p, path) in sample.iter().enumerate() {
+      for (d, &k) in dates.iter().enumerate() {
+        let (swap_rate, annuity) = self.swap(path, k);
+        exercise[(p, d)] = notional * annuity * self.payoff(swap_rate) / path.numeraire[k];
+        state[(p, d, 0)] = swap_rate;
+      }
+    }
+
+    longstaff_schwartz(exercise.view(), state.view(), self.degree.unwrap_or(2))
+  }
+
+  /// Black price of the European swaption exercisable at T_k only, with Rebonato's
+  /// approximation of the swap rate volatility by frozen weights of the forward rates,
+  /// a lower bound of the Bermudan price
+  pub fn european(&self, k: usize) -> f64 {
+    let n = self.lmm.dim();
+    let delta = self.lmm.accruals();
+    let annuity = (k..n).map(|i| delta[i] * self.lmm.bond(i + 1)).sum::<f64>();
+    let swap_rate = (self.lmm.bond(k) - self.lmm.bond(n)) / annuity;
+    // dS / dL_i ~ w_i = delta_i P(0, T_i+1) / A(0), frozen at time 0
+    let weight = |i: usize| delta[i] * self.lmm.bond(i + 1) / annuity * self.lmm.l0[i];
+    let variance = (k..n)
+      .flat_map(|i| (k..n).map(move |j| (i, j)))
+      .map(|(i, j)| {
+        weight(i) * weight(j) * self.lmm.correlation(i, j) * self.lmm.sigma[i] * self.lmm.sigma[j]
+      })
+      .sum::<f64>()
+      * self.lmm.tenors[k]
+      / swap_rate.powi(2);
+
+    self.notional.unwrap_or(1.0)
+      * black(
+        swap_rate,
+        self.k,
+        variance.sqrt(),
+        annuity,
+        self.option_type,
+      )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::Array1;
+
+  use super::*;
+
+  fn swaption(first_exercise: usize) -> BermudanSwaption {
+    let n = 6;
+    BermudanSwaption::new(&BermudanSwaption {
+      lmm: Lmm::new(&Lmm {
+        tenors: Array1::linspace(0.0, 3.0, n + 1),
+        l0: Array1::from_elem(n, 0.04),
+        sigma: Array1::from_elem(n, 0.2),
+        beta: 0.1,
+        steps: Some(4),
+        ..Default::default()
+      }),
+      k: 0.04,
+      first_exercise,
+      option_type: OptionType::Call,
+      ..Default::default()
+    })
+  }
+
+  #[test]
+  fn single_exercise_date_matches_black() {
+    let european = swaption(3);
+    // exercisable on T_3 and T_4 and T_5, the Monte Carlo European is the swaption of T_5
+    let last = swaption(5);
+    let mc = last.price(40_000, 1);
+    assert!(
+      (mc.price.value - last.european(5)).abs() < 4.0 * mc.price.std_error + 1e-5,
+      "{} {} {}",
+      mc.price.value,
+      mc.price.std_error,
+      last.european(5)
+    );
+    assert!(european.european(3) > 0.0);
+  }
+
+  #[test]
+  fn bermudan_is_worth_at_least_every_european() {
+    let swaption = swaption(1);
+    let bermudan = swaption.price(20_000, 2);
+    for k in 1..6 {
+      assert!(
+        bermudan.price.value + 3.0 * bermudan.price.std_error >= swaption.european(k),
+        "T_{}: {} < {}",
+        k,
+        bermudan.price.value,
+        swaption.european(k)
+      );
+    }
+  }
+}
@@ -0,0 +1,256 @@
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2};
+use rayon::prelude::*;
+
+use crate::{
+  rng::{path_seed, with_seed},
+  stochastic::diffusion::multi_gbm::MultiGBM,
+};
+
+/// Rebalancing policy of a portfolio
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rebalancing {
+  /// The initial weights drift with the prices
+  BuyAndHold,
+  /// Back to the target weights every `every` steps, fixed weights for every = 1
+  Periodic { every: usize },
+  /// Back to the target weights when a weight leaves the band target +- band
+  Threshold { band: f64 },
+  /// Target weights scaled every `every` steps by target / realized volatility of the
+  /// target portfolio over the last `window` steps, capped at `max_leverage`,
+  /// the rest is held in cash
+  VolatilityTarget {
+    target: f64,
+    window: usize,
+    every: usize,
+    max_leverage: f64,
+  },
+}
+
+impl Default for Rebalancing {
+  fn default() -> Self {
+    Self::Periodic { every: 1 }
+  }
+}
+
+/// Self-financing portfolio of risky assets and cash, the weights not invested in the
+/// assets earn the cash rate
+#[derive(Default, Debug, Clone)]
+pub struct Portfolio {
+  /// Target weights of the assets
+  pub weights: Array1<f64>,
+  pub rebalancing: Rebalancing,
+  pub initial_wealth: f64,
+  /// Continuously compounded cash rate
+  pub rate: f64,
+  /// Proportional transaction cost of the traded amount
+  pub cost: f64,
+  /// Time between the observations of the prices in years
+  pub dt: f64,
+}
+
+/// Simulated wealth of a portfolio
+#[derive(Default, Debug, Clone)]
+pub struct PortfolioPath {
+  pub wealth: Array1<f64>,
+  /// Weights of the assets after every step, one row per asset
+  pub weights: Array2<f64>,
+  /// Traded amount over wealth at every step
+  pub turnover: Array1<f64>,
+  /// Transaction costs paid at every step
+  pub costs: Array1<f64>,
+}
+
+/// Performance and risk statistics of a wealth path
+#[derive(Default, Debug, Clone, Copy)]
+pub struct PerformanceStats {
+  pub total_return: f64,
+  /// Compound annual growth rate
+  pub annualized_return: f64,
+  /// Annualized volatility of the log returns
+  pub volatility: f64,
+  /// Annualized excess log return over volatility
+  pub sharpe: f64,
+  /// Largest fall from a running maximum, relative to the maximum
+  pub max_drawdown: f64,
+  /// Annualized turnover
+  pub turnover: f64,
+}
+
+impl Portfolio {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(!params.weights.is_empty(), "At least one asset is needed");
+    assert!(
+      params.initial_wealth > 0.0,
+      "Initial wealth must be positive"
+    );
+    assert!(params.dt > 0.0, "Time step must be positive");
+    assert!(params.cost >= 0.0, "Transaction cost must be non-negative");
+    match params.rebalancing {
+      Rebalancing::Periodic { every } => assert!(every > 0, "Period must be positive"),
+      Rebalancing::VolatilityTarget { window, every, .. } => {
+        assert!(
+          window > 1 && every > 0,
+          "Window and period must be positive"
+        )
+      }
+      _ => {}
+    }
+
+    params.clone()
+  }
+
+  /// Wealth path over the price paths of the assets, one row per asset
+  pub fn simulate(&self, prices: ArrayView2<f64>) -> PortfolioPath {
+    let (d, n) = prices.dim();
+    assert_eq!(d, self.weights.len(), "One price path per weight is needed");
+    assert!(n > 1, "At least two observations are needed");
+
+    let growth = (self.rate * self.dt).exp();
+    let mut target = self.weights.clone();
+    let mut holdings = &self.weights * self.initial_wealth;
+    let mut cash = self.initial_wealth - holdings.sum();
+    let mut path = PortfolioPath {
+      wealth: Array1::zeros(n),
+      weights: Array2::zeros((d, n)),
+      turnover: Array1::zeros(n),
+      costs: Array1::zeros(n),
+    };
+    path.wealth[0] = self.initial_wealth;
+    path.weights.column_mut(0).assign(&self.weights);
+
+    // log returns of the unlevered target portfolio, for volatility targeting
+    let mut returns = Vec::with_capacity(n);
+
+    for i in 1..n {
+      for a in 0..d {
+        holdings[a] *= prices[[a, i]] / prices[[a, i - 1]];
+      }
+      cash *= growth;
+      let wealth = holdings.sum() + cash;
+      returns.push(
+        (0..d)
+          .map(|a| self.weights[a] * (prices[[a, i]] / prices[[a, i - 1]]).ln())
+          .sum::<f64>(),
+      );
+
+      let rebalance = match self.rebalancing {
+        Rebalancing::BuyAndHold => false,
+        Rebalancing::Periodic { every } => i.is_multiple_of(every),
+        Rebalancing::Threshold { band } => {
+          (0..d).any(|a| (holdings[a] / wealth - target[a]).abs() > band)
+        }
+        Rebalancing::VolatilityTarget {
+          target: vol,
+          window,
+          every,
+          max_leverage,
+        } => {
+          if i.is_multiple_of(every) && returns.len() >= window {
+            let recent = ArrayView1::from(&returns[returns.len() - window..]);
+            let realized = recent.std(1.0) / self.dt.sqrt();
+            let leverage = if realized > 0.0 {
+              (vol / realized).min(max_leverage)
+            } else {
+              max_leverage
+            };
+            target = &self.weights * leverage;
+            true
+          } else {
+            false
+          }
+        }
+      };
+
+      let wealth = if rebalance {
+        let traded = (0..d)
+          .map(|a| (target[a] * wealth - holdings[a]).abs())
+          .sum::<f64>();
+        let cost = self.cost * traded;
+        let after = wealth - cost;
+        holdings = &target * after;
+        cash = after - holdings.sum();
+        path.turnover[i] = traded / wealth;
+        path.costs[i] = cost;
+        after
+      } else {
+        wealth
+      };
+
+      path.wealth[i] = wealth;
+      path.weights.column_mut(i).assign(&(&holdings / wealth));
+    }
+
+    path
+  }
+
+  /// Wealth paths over `paths` simulated price paths of a multi-asset model, in parallel
+  pub fn simulate_batch(&self, model: &MultiGBM, paths: usize, seed: u64) -> Vec<PortfolioPath> {
+    let dt = model.t.unwrap_or(1.0) / model.n as f64;
+    assert!(
+      (dt - self.dt).abs() < 1e-12,
+      "Time step of the model and the portfolio must match"
+    );
+
+    (0..paths)
+      .into_par_iter()
+      .map(|i| {
+        with_seed(path_seed(seed, i as u64), || {
+          self.simulate(model.sample().view())
+        })
+      })
+      .collect()
+  }
+}
+
+impl PortfolioPath {
+  pub fn statistics(&self, dt: f64, rate: f64) -> PerformanceStats {
+    let n = self.wealth.len();
+    let horizon = dt * (n - 1) as f64;
+    let log_returns = Array1::from_shape_fn(n - 1, |i| (self.wealth[i + 1] / self.wealth[i]).ln());
+    let growth = self.wealth[n - 1] / self.wealth[0];
+
+    let volatility = if n > 2 {
+      log_returns.std(1.0) / dt.sqrt()
+    } else {
+      0.0
+    };
+    let excess = log_returns.sum() / horizon - rate;
+
+    let mut peak = f64::NEG_INFINITY;
+    let mut max_drawdown = 0.0f64;
+    for &w in &self.wealth {
+      peak = peak.max(w);
+      max_drawdown = max_drawdown.max(1.0 - w / peak);
+    }
+
+    PerformanceStats {
+      total_return: growth - 1.0,
+      annualized_return: growth.powf(1.0 / horizon) - 1.0,
+      volatility,
+      sharpe: if volatility > 0.0 {
+        excess / volatility
+      } else {
+        0.0
+      },
+      max_drawdown,
+      turnover: self.turnover.sum() / horizon,
+    }
+  }
+}
+
+/// Value at risk and expected shortfall of the terminal loss W(0) - W(T) over a batch of
+/// wealth paths at the confidence level, e.g. 0.99
+pub fn terminal_risk(paths: &[PortfolioPath], level: f64) -> (f64, f64) {
+  assert!(!paths.is_empty(), "At least one path is needed");
+  assert!(level > 0.0 && level < 1.0, "Level must be in (0, 1)");
+  let mut losses = paths
+    .iter()
+    .map(|p| p.wealth[0] - p.wealth[p.wealth.len() - 1])
+    .collect::<Vec<_>>();
+  losses.sort_by(|a, b| a.total_cmp(b));
+
+  let index = ((level * losses.len() as f64).ceil() as usize).clamp(1, losses.len()) - 1;
+  let tail = &losses[index..];
+  (losses[index], tail.iter().sum::<f64>() / tail.len() as f64)
+}
@@ -0,0 +1,89 @@
+use ndarray::{Array1, Array2, Array3, ArrayView2, Axis};
+use rayon::prelude::*;
+
+use crate::{
+  rng::{path_seed, with_seed},
+  stats::{
+    copula::{pseudo_observations, Archimedean, ArchimedeanFamily},
+    garch::Garch,
+    quantile::interpolate,
+  },
+};
+
+/// Copula-GARCH scenario engine: a GARCH(1, 1) per asset, an Archimedean copula across the
+/// standardized residuals and the empirical distribution of the residuals as the marginal
+/// of the innovations (filtered historical simulation).
+#[derive(Debug, Clone)]
+pub struct CopulaGarch {
+  pub marginals: Vec<Garch>,
+  pub copula: Archimedean,
+  /// Sorted standardized residuals of every asset
+  pub residuals: Vec<Vec<f64>>,
+  /// Conditional variance of the next return of every asset
+  pub variances: Array1<f64>,
+}
+
+impl CopulaGarch {
+  /// Fit to the returns, one row per time and one column per asset
+  pub fn fit(returns: ArrayView2<f64>, family: ArchimedeanFamily) -> Self {
+    let marginals = returns
+      .axis_iter(Axis(1))
+      .into_par_iter()
+      .map(Garch::fit)
+      .collect::<Vec<_>>();
+
+    let mut residuals = Array2::<f64>::zeros(returns.dim());
+    for (j, garch) in marginals.iter().enumerate() {
+      residuals
+        .column_mut(j)
+        .assign(&garch.residuals(returns.column(j)));
+    }
+    let copula = Archimedean::fit(pseudo_observations(residuals.view()).view(), family);
+    let variances = Array1::from_iter(
+      marginals
+        .iter()
+        .zip(returns.axis_iter(Axis(1)))
+        .map(|(garch, r)| garch.variances(r)[r.len()]),
+    );
+
+    Self {
+      marginals,
+      copula,
+      residuals: residuals
+        .axis_iter(Axis(1))
+        .map(|z| {
+          let mut sorted = z.to_vec();
+          sorted.sort_by(|a, b| a.total_cmp(b));
+          sorted
+        })
+        .collect(),
+      variances,
+    }
+  }
+
+  /// Joint return scenarios over the next `horizon` periods,
+  /// scenario x time x asset
+  pub fn simulate(&self, horizon: usize, scenarios: usize, seed: u64) -> Array3<f64> {
+    let d = self.marginals.len();
+    let paths = (0..scenarios)
+      .into_par_iter()
+      .map(|i| {
+        let u = with_seed(path_seed(seed, i as u64), || self.copula.sample(horizon, d));
+        let mut returns = Array2::<f64>::zeros((horizon, d));
+        for j in 0..d {
+          let innovations = u.column(j).mapv(|p| interpolate(&self.residuals[j], p));
+          returns
+            .column_mut(j)
+            .assign(&self.marginals[j].simulate(innovations.view(), self.variances[j]));
+        }
+        returns
+      })
+      .collect::<Vec<_>>();
+
+    let mut out = Array3::<f64>::zeros((scenarios, horizon, d));
+    for (i, path) in paths.iter().enumerate() {
+      out.index_axis_mut(Axis(0), i).assign(path);
+    }
+    out
+  }
+}
@@ -0,0 +1,182 @@
+use ndarray::Array2;
+
+use crate::quant::{
+  curve::YieldCurve,
+  greeks::EuropeanGbm,
+  options::{bsm::BSM, chain::OptionChainGenerator},
+  volatility::heston::HestonPricer,
+};
+
+/// Bump of a market quantity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bump {
+  /// x + h
+  Absolute(f64),
+  /// x (1 + h)
+  Relative(f64),
+}
+
+impl Bump {
+  pub fn apply(&self, x: f64) -> f64 {
+    match self {
+      Self::Absolute(h) => x + h,
+      Self::Relative(h) => x * (1.0 + h),
+    }
+  }
+}
+
+/// Market scenario: bumps of the spot and of the volatility and a parallel shift of the
+/// continuously compounded rates, the quantities without a bump are unchanged.
+/// The volatility bump applies to the volatility, for a variance model to the square
+/// root of the initial and the long-run variance, i.e. a parallel shift of the volatility
+/// surface of the model.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Shock {
+  pub name: String,
+  pub spot: Option<Bump>,
+  pub vol: Option<Bump>,
+  /// Parallel shift of the rates, e.g. 0.01 for +100bp
+  pub rate: Option<f64>,
+}
+
+impl Shock {
+  #[must_use]
+  pub fn new(name: &str) -> Self {
+    Self {
+      name: name.to_string(),
+      ..Default::default()
+    }
+  }
+
+  #[must_use]
+  pub fn with_spot(mut self, bump: Bump) -> Self {
+    self.spot = Some(bump);
+    self
+  }
+
+  #[must_use]
+  pub fn with_vol(mut self, bump: Bump) -> Self {
+    self.vol = Some(bump);
+    self
+  }
+
+  #[must_use]
+  pub fn with_rate(mut self, shift: f64) -> Self {
+    self.rate = Some(shift);
+    self
+  }
+
+  pub fn spot(&self, s: f64) -> f64 {
+    self.spot.map_or(s, |bump| bump.apply(s))
+  }
+
+  pub fn vol(&self, v: f64) -> f64 {
+    self.vol.map_or(v, |bump| bump.apply(v).max(0.0))
+  }
+
+  /// Bumped variance, the volatility bump applied to its square root
+  pub fn variance(&self, v: f64) -> f64 {
+    self.vol(v.sqrt()).powi(2)
+  }
+
+  pub fn rate(&self, r: f64) -> f64 {
+    r + self.rate.unwrap_or(0.0)
+  }
+
+  /// Applies the shock to a model
+  pub fn apply<M: Shockable>(&self, model: &M) -> M {
+    model.shocked(self)
+  }
+}
+
+/// Model or market object that can be moved to a shocked state
+pub trait Shockable {
+  fn shocked(&self, shock: &Shock) -> Self;
+}
+
+impl Shockable for BSM {
+  fn shocked(&self, shock: &Shock) -> Self {
+    Self {
+      s: shock.spot(self.s),
+      v: shock.vol(self.v),
+      r: shock.rate(self.r),
+      r_d: self.r_d.map(|r| shock.rate(r)),
+      ..self.clone()
+    }
+  }
+}
+
+impl Shockable for HestonPricer {
+  fn shocked(&self, shock: &Shock) -> Self {
+    Self::new(&Self {
+      s0: shock.spot(self.s0),
+      v0: shock.variance(self.v0),
+      theta: shock.variance(self.theta),
+      r: shock.rate(self.r),
+      ..self.clone()
+    })
+  }
+}
+
+impl Shockable for OptionChainGenerator {
+  fn shocked(&self, shock: &Shock) -> Self {
+    Self {
+      s0: shock.spot(self.s0),
+      v0: shock.variance(self.v0),
+      theta: shock.variance(self.theta),
+      r: shock.rate(self.r),
+      ..self.clone()
+    }
+  }
+}
+
+impl Shockable for EuropeanGbm {
+  fn shocked(&self, shock: &Shock) -> Self {
+    Self {
+      s0: shock.spot(self.s0),
+      sigma: shock.vol(self.sigma),
+      r: shock.rate(self.r),
+      ..*self
+    }
+  }
+}
+
+impl Shockable for YieldCurve {
+  /// Parallel shift of the zero rates
+  fn shocked(&self, shock: &Shock) -> Self {
+    self.shifted(shock.rate.unwrap_or(0.0))
+  }
+}
+
+/// Stress test: the price of the model under every shock and its change from the base price
+pub fn stress<M, F>(model: &M, shocks: &[Shock], price: F) -> Vec<(f64, f64)>
+where
+  M: Shockable,
+  F: Fn(&M) -> f64,
+{
+  let base = price(model);
+  shocks
+    .iter()
+    .map(|shock| {
+      let shocked = price(&shock.apply(model));
+      (shocked, shocked - base)
+    })
+    .collect()
+}
+
+/// P&L ladder of a model with a closed form or deterministic price, one row per relative
+/// spot shift and one column per absolute volatility shift, the counterpart of
+/// `RiskLadder` for the Monte Carlo engine
+pub fn ladder<M, F>(model: &M, spot_shifts: &[f64], vol_shifts: &[f64], price: F) -> Array2<f64>
+where
+  M: Shockable,
+  F: Fn(&M) -> f64,
+{
+  let base = price(model);
+  Array2::from_shape_fn((spot_shifts.len(), vol_shifts.len()), |(i, j)| {
+    let shock = Shock::default()
+      .with_spot(Bump::Relative(spot_shifts[i]))
+      .with_vol(Bump::Absolute(vol_shifts[j]));
+    price(&shock.apply(model)) - base
+  })
+}
@@ -1,3 +1,4 @@
+#[cfg(feature = "dates")]
 use chrono::Local;
 use nalgebra::DVector;
 
@@ -22,31 +23,35 @@ pub trait Price {
 
   /// Calculate the valuation date of an instrument.
   fn calculate_tau_in_days(&self) -> f64 {
-    if let Some(tau) = self.tau() {
-      tau
-    } else {
-      let eval = self
-        .eval()
-        .unwrap_or_else(|| Local::now().naive_local().into());
-      let expiration = self.expiration().unwrap();
-      (expiration - eval).num_days() as f64
-    }
+    self.tau().unwrap_or_else(|| self.days_to_expiration())
   }
 
   /// Calculate the valuation date of an instrument.
   fn calculate_tau_in_years(&self) -> f64 {
-    if let Some(tau) = self.tau() {
-      tau
-    } else {
-      let eval = self
-        .eval()
-        .unwrap_or_else(|| Local::now().naive_local().into());
-      let expiration = self.expiration().unwrap();
-      (expiration - eval).num_days() as f64 / 365.0
-    }
+    self
+      .tau()
+      .unwrap_or_else(|| self.days_to_expiration() / 365.0)
+  }
+
+  /// Days from the evaluation date (default today) to the expiration date.
+  #[cfg(feature = "dates")]
+  fn days_to_expiration(&self) -> f64 {
+    let eval = self
+      .eval()
+      .unwrap_or_else(|| Local::now().naive_local().into());
+    let expiration = self.expiration().unwrap();
+    (expiration - eval).num_days() as f64
+  }
+
+  /// Days to the expiration date, only available with the `dates` feature.
+  #[cfg(not(feature = "dates"))]
+  fn days_to_expiration(&self) -> f64 {
+    panic!("Time to maturity must be given when the dates feature is disabled")
   }
 
   fn tau(&self) -> Option<f64>;
+  #[cfg(feature = "dates")]
   fn eval(&self) -> Option<chrono::NaiveDate>;
+  #[cfg(feature = "dates")]
   fn expiration(&self) -> Option<chrono::NaiveDate>;
 }
@@ -1,11 +1,20 @@
+pub mod double_heston;
+pub mod forecast;
+pub mod forward_start;
 pub mod heston;
+pub mod piecewise_heston;
+pub mod risk_neutral;
+pub mod ssvi;
+pub mod surface;
+pub mod variance_swap;
 
 use std::cell::RefCell;
 
 use levenberg_marquardt::LeastSquaresProblem;
 use nalgebra::{DMatrix, DVector, Dyn, Owned};
 
-use super::{r#trait::Pricer, OptionType};
+use super::{calibration::transform::ParameterSpace, r#trait::Pricer, OptionType};
+use crate::progress::{Hooks, Tracker};
 
 /// A calibrator.
 pub(crate) struct Calibrator<'a, P>
@@ -24,6 +33,12 @@ where
   pricer: &'a RefCell<P>,
   /// Derivate matrix.
   derivates: RefCell<Vec<Vec<f64>>>,
+  /// Counts the objective evaluations and checks for cancellation.
+  tracker: Tracker<'a>,
+  /// If given, the params are unconstrained and mapped by the space.
+  space: Option<&'a ParameterSpace>,
+  /// Square roots of the weights of the squared pricing errors.
+  sqrt_weights: Option<DVector<f64>>,
 }
 
 impl<'a, P> Calibrator<'a, P>
@@ -37,14 +52,68 @@ where
     k: Vec<f64>,
     option_type: &'a OptionType,
     pricer: &'a RefCell<P>,
+    hooks: &'a Hooks,
+    space: Option<&'a ParameterSpace>,
   ) -> Self {
     Self {
-      params,
+      params: match space {
+        Some(space) => DVector::from_vec(space.unconstrain(params.as_slice())),
+        None => params,
+      },
       c_market: DVector::from_vec(c_market),
       k: DVector::from_vec(k),
       option_type,
       pricer,
       derivates: RefCell::new(Vec::new()),
+      tracker: Tracker::new(hooks, None, 0),
+      space,
+      sqrt_weights: None,
+    }
+  }
+
+  /// Weight the squared pricing errors, one weight per option
+  #[must_use]
+  pub(crate) fn with_weights(mut self, weights: Option<&[f64]>) -> Self {
+    if let Some(weights) = weights {
+      assert_eq!(
+        weights.len(),
+        self.c_market.len(),
+        "One weight per option is needed"
+      );
+      self.sqrt_weights = Some(DVector::from_iterator(
+        weights.len(),
+        weights.iter().map(|w| w.sqrt()),
+      ));
+    }
+    self
+  }
+
+  /// Constrained parameters of the optimizer parameters
+  pub(crate) fn constrained(&self, params: &DVector<f64>) -> DVector<f64> {
+    match self.space {
+      Some(space) => DVector::from_vec(space.constrain(params.as_slice())),
+      None => params.clone(),
+    }
+  }
+}
+
+impl<'a, P> Calibrator<'a, P>
+where
+  P: Pricer,
+{
+  /// Square roots of the penalties of the space at the unconstrained params, appended to
+  /// the residuals so the sum of squares carries the penalties
+  fn penalty_residuals(&self, u: &[f64]) -> Vec<f64> {
+    match self.space {
+      Some(space) if !space.penalties.is_empty() => {
+        let x = space.constrain(u);
+        space
+          .penalties
+          .iter()
+          .map(|p| p(&x).max(0.0).sqrt())
+          .collect()
+      }
+      _ => Vec::new(),
     }
   }
 }
@@ -58,7 +127,10 @@ where
   type ResidualStorage = Owned<f64, Dyn>;
 
   fn set_params(&mut self, params: &DVector<f64>) {
-    self.pricer.borrow_mut().update_params(params.clone());
+    self
+      .pricer
+      .borrow_mut()
+      .update_params(self.constrained(params));
     self.params.copy_from(params);
   }
 
@@ -67,6 +139,11 @@ where
   }
 
   fn residuals(&self) -> Option<DVector<f64>> {
+    // no residuals stop the minimization
+    if self.tracker.is_cancelled() {
+      return None;
+    }
+
     self.pricer.borrow_mut().calculate_price();
     let mut c_model = DVector::zeros(self.c_market.len());
     let mut derivates = Vec::new();
@@ -86,7 +163,17 @@ where
     }
 
     self.derivates.replace(derivates);
-    Some(c_model - self.c_market.clone())
+    self.tracker.advance(1);
+    let residuals = c_model - self.c_market.clone();
+    let residuals = match &self.sqrt_weights {
+      Some(sqrt_weights) => residuals.component_mul(sqrt_weights),
+      None => residuals,
+    };
+    let penalties = self.penalty_residuals(self.params.as_slice());
+    Some(DVector::from_iterator(
+      residuals.len() + penalties.len(),
+      residuals.iter().copied().chain(penalties),
+    ))
   }
 
   fn jacobian(&self) -> Option<DMatrix<f64>> {
@@ -96,11 +183,44 @@ where
 
     // The Jacobian matrix is a matrix of partial derivatives
     // of the residuals with respect to the parameters.
-    let jacobian = DMatrix::from_vec(
+    let mut jacobian = DMatrix::from_vec(
       derivates.len() / self.params.len(),
       self.params.len(),
       derivates,
     );
+
+    if let Some(sqrt_weights) = &self.sqrt_weights {
+      for (i, w) in sqrt_weights.iter().enumerate() {
+        jacobian.row_mut(i).scale_mut(*w);
+      }
+    }
+
+    // chain rule of the parameter transforms
+    if let Some(space) = self.space {
+      for (j, d) in space.derivatives(self.params.as_slice()).iter().enumerate() {
+        jacobian.column_mut(j).scale_mut(*d);
+      }
+    }
+
+    // rows of the penalty residuals by central differences in unconstrained space
+    let penalties = self.penalty_residuals(self.params.as_slice()).len();
+    if penalties > 0 {
+      let rows = jacobian.nrows();
+      jacobian = jacobian.insert_rows(rows, penalties, 0.0);
+      let mut u = self.params.as_slice().to_vec();
+      for j in 0..u.len() {
+        let h = 1e-6 * (1.0 + u[j].abs());
+        u[j] += h;
+        let up = self.penalty_residuals(&u);
+        u[j] -= 2.0 * h;
+        let down = self.penalty_residuals(&u);
+        u[j] += h;
+        for (i, (up, down)) in up.iter().zip(&down).enumerate() {
+          jacobian[(rows + i, j)] = (up - down) / (2.0 * h);
+        }
+      }
+    }
+
     Some(jacobian)
   }
 }
@@ -0,0 +1,64 @@
+use num_complex::Complex64;
+
+use super::piecewise_heston::{gil_pelaez, riccati_step};
+
+/// Parameters of one variance factor of the double Heston model
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VarianceFactor {
+  pub v0: f64,
+  pub kappa: f64,
+  pub theta: f64,
+  pub sigma: f64,
+  pub rho: f64,
+}
+
+/// European options under the double Heston model, whose characteristic function is the
+/// product of the Heston characteristic functions of the two independent factors
+/// https://doi.org/10.1016/j.jedc.2009.03.005 (Christoffersen, Heston, Jacobs 2009)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DoubleHestonPricer {
+  pub s0: f64,
+  pub r: f64,
+  pub q: f64,
+  pub factors: [VarianceFactor; 2],
+}
+
+impl DoubleHestonPricer {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params
+        .factors
+        .iter()
+        .all(|f| f.kappa > 0.0 && f.sigma > 0.0),
+      "Mean reversion and volatility of volatility must be positive"
+    );
+
+    *params
+  }
+
+  /// Characteristic function E[exp(i u ln S(t))]
+  pub fn characteristic_function(&self, u: Complex64, t: f64) -> Complex64 {
+    let zero = Complex64::new(0.0, 0.0);
+    let exponent = self.factors.iter().fold(
+      Complex64::i() * u * (self.s0.ln() + (self.r - self.q) * t),
+      |acc, f| {
+        let (c, d) = riccati_step(u, t, f.kappa, f.theta, f.sigma, f.rho, zero, zero);
+        acc + c + d * f.v0
+      },
+    );
+    exponent.exp()
+  }
+
+  /// Prices of the European call and put with the strike k and the maturity t
+  pub fn price(&self, k: f64, t: f64) -> (f64, f64) {
+    gil_pelaez(
+      |u| self.characteristic_function(u, t),
+      self.s0,
+      k,
+      self.r,
+      self.q,
+      t,
+    )
+  }
+}
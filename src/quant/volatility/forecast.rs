@@ -0,0 +1,95 @@
+use ndarray::ArrayView1;
+use statrs::{
+  distribution::{ContinuousCDF, Normal},
+  function::gamma::gamma,
+};
+
+use crate::stats::rough::{fit_rfsv, RfsvParams};
+
+/// Forecast of the realized variance at a horizon
+#[derive(Default, Debug, Clone, Copy)]
+pub struct VarianceForecast {
+  /// Horizon in years
+  pub horizon: f64,
+  /// Conditional expectation of the variance
+  pub variance: f64,
+  /// Conditional expectation of the log variance
+  pub log_variance: f64,
+  /// Prediction interval of the variance
+  pub interval: (f64, f64),
+}
+
+/// Realized variance forecasts under the RFSV model.
+/// The log variance given its history is Gaussian with the mean
+/// cos(H pi) / pi d^(H + 1/2) int log sigma^2(s) / ((t - s + d) (t - s)^(H + 1/2)) ds
+/// and the variance 4 c nu^2 d^(2H), c = Gamma(3/2 - H) / (Gamma(H + 1/2) Gamma(2 - 2H)).
+/// https://doi.org/10.1080/14697688.2017.1393551 (Gatheral, Jaisson, Rosenbaum)
+pub struct RoughVolForecaster {
+  /// Fitted model
+  pub params: RfsvParams,
+  /// Sampling interval of the history in years
+  pub dt: f64,
+}
+
+impl RoughVolForecaster {
+  #[must_use]
+  pub fn new(params: RfsvParams, dt: f64) -> Self {
+    assert!(
+      params.hurst > 0.0 && params.hurst < 0.5,
+      "Hurst parameter must be between 0 and 0.5"
+    );
+
+    Self { params, dt }
+  }
+
+  /// Fit the model to the realized variance history with lags 1..=max_lag
+  #[must_use]
+  pub fn fit(realized_variance: ArrayView1<f64>, dt: f64, max_lag: usize) -> Self {
+    Self::new(fit_rfsv(realized_variance, dt, max_lag), dt)
+  }
+
+  /// Forecast of the variance `horizon` years after the last observation with a
+  /// prediction interval at the given confidence level
+  pub fn forecast(
+    &self,
+    realized_variance: ArrayView1<f64>,
+    horizon: f64,
+    confidence: f64,
+  ) -> VarianceForecast {
+    assert!(horizon > 0.0, "Horizon must be positive");
+    let RfsvParams { hurst, nu } = self.params;
+    let n = realized_variance.len();
+
+    // the weights of the integral at the midpoints of the observation intervals,
+    // normalized so a constant history is forecast by itself
+    let weights = (0..n)
+      .map(|i| {
+        let lag = (n - 1 - i) as f64 * self.dt + 0.5 * self.dt;
+        self.dt / ((lag + horizon) * lag.powf(hurst + 0.5))
+      })
+      .collect::<Vec<_>>();
+    let total = weights.iter().sum::<f64>();
+    let log_variance = realized_variance
+      .iter()
+      .zip(&weights)
+      .map(|(v, w)| v.ln() * w)
+      .sum::<f64>()
+      / total;
+
+    let c = gamma(1.5 - hurst) / (gamma(hurst + 0.5) * gamma(2.0 - 2.0 * hurst));
+    let var = 4.0 * c * nu.powi(2) * horizon.powf(2.0 * hurst);
+    let z = Normal::new(0.0, 1.0)
+      .unwrap()
+      .inverse_cdf(0.5 + 0.5 * confidence);
+
+    VarianceForecast {
+      horizon,
+      variance: (log_variance + 0.5 * var).exp(),
+      log_variance,
+      interval: (
+        (log_variance - z * var.sqrt()).exp(),
+        (log_variance + z * var.sqrt()).exp(),
+      ),
+    }
+  }
+}
@@ -0,0 +1,180 @@
+use std::f64::consts::FRAC_1_PI;
+
+use num_complex::Complex64;
+use quadrature::double_exponential;
+
+use super::heston::HestonPricer;
+
+/// Forward-start options under the Heston model.
+/// Conditional on the variance at the start date t0 the option is a Heston call with unit
+/// spot, so its price is the expectation of the Heston call over the (noncentral chi-squared)
+/// distribution of v(t0). The expectation is taken inside the Fourier integral, where it
+/// becomes the moment generating function of v(t0) at the D coefficient of the
+/// characteristic function.
+/// https://doi.org/10.1142/S0219024906003822 (Kruse, Nögel)
+pub struct HestonForwardStart {
+  /// Model, s0, v0, r, q, rho, kappa, theta, sigma and lambda are used
+  pub pricer: HestonPricer,
+  /// Start date, when the strike is fixed
+  pub t0: f64,
+  /// Maturity
+  pub t: f64,
+  /// Strike as a fraction of the spot at the start date
+  pub k: f64,
+}
+
+impl HestonForwardStart {
+  #[must_use]
+  pub fn new(pricer: &HestonPricer, t0: f64, t: f64, k: f64) -> Self {
+    assert!(t0 >= 0.0 && t > t0, "Maturity must be after the start date");
+
+    Self {
+      pricer: HestonPricer::new(pricer),
+      t0,
+      t,
+      k,
+    }
+  }
+
+  /// Price of the call paying (S(T) - k S(t0))^+ at T
+  pub fn price(&self) -> f64 {
+    self.pricer.s0
+      * (-self.pricer.q * self.t0).exp()
+      * expected_call(&self.pricer, self.k, self.t0, self.t, true)
+  }
+
+  /// Price of the call on the return paying (S(T) / S(t0) - k)^+ at T
+  pub fn return_price(&self) -> f64 {
+    (-self.pricer.r * self.t0).exp() * expected_call(&self.pricer, self.k, self.t0, self.t, false)
+  }
+
+  /// Price of the put paying (k S(t0) - S(T))^+ at T, by the forward-start parity
+  pub fn put_price(&self) -> f64 {
+    let tau = self.t - self.t0;
+    self.price() - self.pricer.s0 * (-self.pricer.q * self.t).exp()
+      + self.k * self.pricer.s0 * (-self.pricer.q * self.t0).exp() * (-self.pricer.r * tau).exp()
+  }
+}
+
+/// Cliquet paying the sum of the period returns S(t_i) / S(t_i-1) - 1, each clamped to
+/// the local floor and cap, at the last reset date
+pub struct HestonCliquet {
+  pub pricer: HestonPricer,
+  /// Reset dates t_0 < t_1 < ... < t_n, the first one is usually 0
+  pub resets: Vec<f64>,
+  pub local_floor: Option<f64>,
+  pub local_cap: Option<f64>,
+  pub notional: f64,
+}
+
+impl HestonCliquet {
+  #[must_use]
+  pub fn new(
+    pricer: &HestonPricer,
+    resets: Vec<f64>,
+    local_floor: Option<f64>,
+    local_cap: Option<f64>,
+    notional: f64,
+  ) -> Self {
+    assert!(resets.len() >= 2, "At least one period is needed");
+    assert!(
+      resets[0] >= 0.0 && resets.windows(2).all(|w| w[0] < w[1]),
+      "Reset dates must be increasing"
+    );
+    if let (Some(floor), Some(cap)) = (local_floor, local_cap) {
+      assert!(floor < cap, "Local floor must be below the local cap");
+    }
+
+    Self {
+      pricer: HestonPricer::new(pricer),
+      resets,
+      local_floor,
+      local_cap,
+      notional,
+    }
+  }
+
+  pub fn price(&self) -> f64 {
+    let (r, q) = (self.pricer.r, self.pricer.q);
+    let maturity = *self.resets.last().unwrap();
+
+    let expected = self
+      .resets
+      .windows(2)
+      .map(|w| {
+        let (t0, t1) = (w[0], w[1]);
+        // undiscounted E[(S(t1) / S(t0) - k)^+]
+        let call = |k: f64| (r * (t1 - t0)).exp() * expected_call(&self.pricer, k, t0, t1, false);
+        let forward = ((r - q) * (t1 - t0)).exp();
+
+        // clamp(x, f, c) = f + (x - f)^+ - (x - c)^+
+        match (self.local_floor, self.local_cap) {
+          (Some(f), Some(c)) => f + call(1.0 + f) - call(1.0 + c),
+          (Some(f), None) => f + call(1.0 + f),
+          (None, Some(c)) => forward - 1.0 - call(1.0 + c),
+          (None, None) => forward - 1.0,
+        }
+      })
+      .sum::<f64>();
+
+    self.notional * (-r * maturity).exp() * expected
+  }
+}
+
+/// Moment generating function E[exp(s v(t))] of the CIR variance, under the share measure
+/// (mean reversion kappa + lambda - rho sigma) or the risk-neutral measure
+fn variance_mgf(pricer: &HestonPricer, s: Complex64, t: f64, share: bool) -> Complex64 {
+  let kappa = if share { pricer.b(1) } else { pricer.b(2) };
+  let theta = pricer.kappa * pricer.theta / kappa;
+  let c = pricer.sigma.powi(2) * (1.0 - (-kappa * t).exp()) / (4.0 * kappa);
+  let denom = 1.0 - 2.0 * s * c;
+
+  (s * pricer.v0 * (-kappa * t).exp() / denom).exp()
+    * denom.powf(-2.0 * kappa * theta / pricer.sigma.powi(2))
+}
+
+/// Expectation of the Heston call with unit spot and strike k from t0 to t,
+/// valued at t0, over the distribution of v(t0)
+fn expected_call(pricer: &HestonPricer, k: f64, t0: f64, t: f64, share: bool) -> f64 {
+  let tau = t - t0;
+  let p = |j: u8| {
+    let integrand = |phi: f64| {
+      let cf =
+        (pricer.C(j, phi, tau)).exp() * variance_mgf(pricer, pricer.D(j, phi, tau), t0, share);
+      (cf * (-Complex64::i() * phi * k.ln()).exp() / (Complex64::i() * phi)).re
+    };
+    0.5 + FRAC_1_PI * double_exponential::integrate(integrand, 0.00001, 50.0, 10e-6).integral
+  };
+
+  (-pricer.q * tau).exp() * p(1) - k * (-pricer.r * tau).exp() * p(2)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::quant::r#trait::Pricer;
+
+  fn pricer() -> HestonPricer {
+    HestonPricer::new(&HestonPricer {
+      s0: 100.0,
+      v0: 0.04,
+      k: 100.0,
+      r: 0.03,
+      q: 0.01,
+      rho: -0.7,
+      kappa: 2.0,
+      theta: 0.04,
+      sigma: 0.5,
+      tau: 1.0,
+      ..Default::default()
+    })
+  }
+
+  #[test]
+  fn starting_today_is_vanilla() {
+    let mut vanilla = pricer();
+    vanilla.calculate_price();
+    let forward_start = HestonForwardStart::new(&pricer(), 0.0, 1.0, 1.0);
+    approx::assert_relative_eq!(forward_start.price(), vanilla.prices().0, epsilon = 1e-6);
+  }
+}
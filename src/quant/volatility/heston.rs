@@ -2,12 +2,23 @@ use std::{cell::RefCell, f64::consts::FRAC_1_PI};
 
 use levenberg_marquardt::LevenbergMarquardt;
 use nalgebra::DVector;
+use ndarray::ArrayView1;
 use num_complex::Complex64;
 use quadrature::double_exponential;
 
 use crate::{
-  quant::{r#trait::Pricer, volatility::Calibrator, OptionType},
+  progress::Hooks,
+  quant::{
+    calibration::{
+      global::DifferentialEvolution, sensitivity::ParameterUncertainty, transform::ParameterSpace,
+    },
+    fourier::{lewis::VolOfVolExpansion, Engine},
+    r#trait::Pricer,
+    volatility::{variance_swap::VarianceSwap, Calibrator},
+    OptionType,
+  },
   stats::mle::nmle_heston,
+  stochastic::volatility::diagnostics::{HestonDiagnostics, HestonParams},
 };
 
 #[derive(Default, Clone)]
@@ -35,8 +46,10 @@ pub struct HestonPricer {
   /// Time to maturity
   pub tau: f64,
   /// Evaluation date
+  #[cfg(feature = "dates")]
   pub eval: Option<chrono::NaiveDate>,
   /// Expiration date
+  #[cfg(feature = "dates")]
   pub expiry: Option<chrono::NaiveDate>,
   /// Prices of European call and put options
   pub(crate) prices: Option<(f64, f64)>,
@@ -97,7 +110,9 @@ impl HestonPricer {
       sigma: params.sigma,
       lambda: Some(params.lambda.unwrap_or(0.0)),
       tau: params.tau,
+      #[cfg(feature = "dates")]
       eval: params.eval,
+      #[cfg(feature = "dates")]
       expiry: params.expiry,
       prices: None,
       derivates: None,
@@ -112,7 +127,7 @@ impl HestonPricer {
     }
   }
 
-  pub(self) fn b(&self, j: u8) -> f64 {
+  pub(crate) fn b(&self, j: u8) -> f64 {
     match j {
       1 => self.kappa + self.lambda.unwrap() - self.rho * self.sigma,
       2 => self.kappa + self.lambda.unwrap(),
@@ -131,7 +146,7 @@ impl HestonPricer {
       / (self.b(j) - self.rho * self.sigma * Complex64::i() * phi - self.d(j, phi))
   }
 
-  pub(self) fn C(&self, j: u8, phi: f64, tau: f64) -> Complex64 {
+  pub(crate) fn C(&self, j: u8, phi: f64, tau: f64) -> Complex64 {
     (self.r - self.q) * Complex64::i() * phi * tau
       + (self.kappa * self.theta / self.sigma.powi(2))
         * ((self.b(j) - self.rho * self.sigma * Complex64::i() * phi + self.d(j, phi)) * tau
@@ -139,7 +154,7 @@ impl HestonPricer {
             * ((1.0 - self.g(j, phi) * (self.d(j, phi) * tau).exp()) / (1.0 - self.g(j, phi))).ln())
   }
 
-  pub(self) fn D(&self, j: u8, phi: f64, tau: f64) -> Complex64 {
+  pub(crate) fn D(&self, j: u8, phi: f64, tau: f64) -> Complex64 {
     ((self.b(j) - self.rho * self.sigma * Complex64::i() * phi + self.d(j, phi))
       / self.sigma.powi(2))
       * ((1.0 - (self.d(j, phi) * tau).exp())
@@ -163,6 +178,66 @@ impl HestonPricer {
     0.5 + FRAC_1_PI * double_exponential::integrate(self.re(j, tau), 0.00001, 50.0, 10e-6).integral
   }
 
+  /// Characteristic function of ln S(tau) under the pricing measure, with the mean
+  /// reversion kappa + lambda and the long-run variance kappa theta / (kappa + lambda),
+  /// in the formulation of Albrecher et al. without the branch cut of the logarithm
+  pub fn characteristic_function(&self, u: Complex64, tau: f64) -> Complex64 {
+    let i = Complex64::i();
+    let kappa = self.kappa + self.lambda.unwrap_or(0.0);
+    let sigma2 = self.sigma.powi(2);
+    let beta = kappa - self.rho * self.sigma * i * u;
+    let d = (beta.powi(2) + sigma2 * (i * u + u.powi(2))).sqrt();
+    let g = (beta - d) / (beta + d);
+    let e = (-d * tau).exp();
+
+    let c = (self.r - self.q) * i * u * tau
+      + self.kappa * self.theta / sigma2
+        * ((beta - d) * tau - 2.0 * ((1.0 - g * e) / (1.0 - g)).ln());
+    let dv = (beta - d) / sigma2 * (1.0 - e) / (1.0 - g * e);
+    (c + dv * self.v0 + i * u * self.s0.ln()).exp()
+  }
+
+  /// Prices of the European call and put by an inversion engine of the characteristic
+  /// function
+  pub fn price_with(&self, engine: &Engine) -> (f64, f64) {
+    engine.price(
+      |u| self.characteristic_function(u, self.tau),
+      self.s0,
+      self.k,
+      self.r,
+      self.q,
+      self.tau,
+    )
+  }
+
+  /// Approximate prices of the European call and put by the second order expansion in
+  /// the volatility of variance, for fast pricing inside calibration loops
+  pub fn vol_of_vol_expansion(&self) -> (f64, f64) {
+    let kappa = self.kappa + self.lambda.unwrap_or(0.0);
+    VolOfVolExpansion::heston(
+      self.v0,
+      kappa,
+      self.kappa * self.theta / kappa,
+      self.sigma,
+      self.rho,
+      self.tau,
+    )
+    .price(self.s0, self.k, self.r, self.q, self.tau)
+  }
+
+  /// Variance and volatility swaps to the maturity under the pricing measure
+  pub fn variance_swap(&self) -> VarianceSwap {
+    let kappa = self.kappa + self.lambda.unwrap_or(0.0);
+    VarianceSwap::new(&VarianceSwap {
+      v0: self.v0,
+      kappa,
+      theta: self.kappa * self.theta / kappa,
+      sigma: self.sigma,
+      tau: self.tau,
+      ..Default::default()
+    })
+  }
+
   /// Partial derivative of the C function with respect to parameters
   /// https://www.sciencedirect.com/science/article/abs/pii/S0377221717304460
 
@@ -251,6 +326,28 @@ impl HestonPricer {
   }
 }
 
+impl HestonDiagnostics for HestonPricer {
+  fn heston_params(&self) -> HestonParams {
+    HestonParams {
+      v0: self.v0,
+      kappa: self.kappa,
+      theta: self.theta,
+      sigma: self.sigma,
+      rho: self.rho,
+      t: self.tau,
+    }
+  }
+}
+
+/// Default bounds of v0, theta, rho, kappa and sigma for the global calibration
+pub const HESTON_BOUNDS: [(f64, f64); 5] = [
+  (1e-4, 1.0),
+  (1e-4, 1.0),
+  (-0.99, 0.99),
+  (1e-2, 10.0),
+  (1e-2, 2.0),
+];
+
 /// Heston calibrator
 pub struct HestonCalibrator {
   /// Implied volatility vector
@@ -271,6 +368,15 @@ pub struct HestonCalibrator {
   pricer: HestonPricer,
   /// Initial guess for the calibration from the NMLE method
   initial_guess: Option<DVector<f64>>,
+  /// Progress and cancellation hooks of the calibration
+  pub hooks: Hooks,
+  /// Parameter space of the calibration, the optimizer works in unconstrained space
+  /// if it is given (default none). `ParameterSpace::heston()` keeps the parameters
+  /// valid and adds the Feller penalty as a residual.
+  pub space: Option<ParameterSpace>,
+  /// Weights of the squared pricing errors, one per strike, e.g. from
+  /// `Objective::price_weights` (default uniform)
+  pub weights: Option<Vec<f64>>,
 }
 
 impl HestonCalibrator {
@@ -295,6 +401,9 @@ impl HestonCalibrator {
       option_type,
       pricer,
       initial_guess: None,
+      hooks: Hooks::default(),
+      space: None,
+      weights: None,
     }
   }
 
@@ -324,23 +433,25 @@ impl HestonCalibrator {
 
     // Calibrate the Heston model
     let pricer = RefCell::new(self.pricer.clone());
-    let (result, report) = LevenbergMarquardt::new().minimize(Calibrator::new(
-      self.initial_guess.as_ref().unwrap().clone(),
-      self.c_market.clone(),
-      self.k.clone(),
-      &self.option_type,
-      &pricer,
-    ));
+    let (result, report) = LevenbergMarquardt::new().minimize(
+      Calibrator::new(
+        self.initial_guess.as_ref().unwrap().clone(),
+        self.c_market.clone(),
+        self.k.clone(),
+        &self.option_type,
+        &pricer,
+        &self.hooks,
+        self.space.as_ref(),
+      )
+      .with_weights(self.weights.as_deref()),
+    );
+    let params = result.constrained(&result.params);
 
     // Print the result of the calibration
     println!("Calibration report: {:?}", report);
 
     // Overwrite the pricer with the calibrated parameters
-    self.pricer.v0 = result.params[0];
-    self.pricer.theta = result.params[1];
-    self.pricer.rho = result.params[2];
-    self.pricer.kappa = result.params[3];
-    self.pricer.sigma = result.params[4];
+    self.pricer.update_params(params);
 
     // Print the calibrated parameters
     println!(
@@ -349,6 +460,63 @@ impl HestonCalibrator {
     );
   }
 
+  /// Pricing errors of the parameters v0, theta, rho, kappa, sigma, scaled by the square
+  /// roots of the weights
+  pub fn residuals(&self, params: &[f64]) -> Vec<f64> {
+    if let Some(weights) = &self.weights {
+      assert_eq!(
+        weights.len(),
+        self.c_market.len(),
+        "One weight per option is needed"
+      );
+    }
+    let mut pricer = self.pricer.clone();
+    pricer.s0 = self.s0;
+    pricer.r = self.r;
+    pricer.q = self.q.unwrap_or(0.0);
+    pricer.update_params(DVector::from_column_slice(params));
+
+    self
+      .k
+      .iter()
+      .zip(&self.c_market)
+      .enumerate()
+      .map(|(i, (&k, &c_market))| {
+        pricer.update_strike(k);
+        pricer.calculate_price();
+        let (call, put) = pricer.prices();
+        let price = match self.option_type {
+          OptionType::Call => call,
+          OptionType::Put => put,
+        };
+        self.weights.as_ref().map_or(1.0, |w| w[i].sqrt()) * (price - c_market)
+      })
+      .collect()
+  }
+
+  /// Sum of the weighted squared pricing errors of the parameters v0, theta, rho, kappa, sigma
+  pub fn objective(&self, params: &[f64]) -> f64 {
+    self.residuals(params).iter().map(|e| e * e).sum()
+  }
+
+  /// Standard errors and correlations of the calibrated v0, theta, rho, kappa, sigma from
+  /// the finite difference Jacobian of the pricing errors, e.g. to spot the kappa-sigma
+  /// trade-off with `ill_identified`
+  pub fn uncertainty(&self) -> ParameterUncertainty {
+    ParameterUncertainty::from_residuals(|x| self.residuals(x), self.params().as_slice(), None)
+  }
+
+  /// Global calibration: differential evolution over the bounds of v0, theta, rho, kappa
+  /// and sigma (see `HESTON_BOUNDS`) finds the basin of the global minimum, which is then
+  /// polished by the Levenberg-Marquardt calibration
+  pub fn calibrate_global(&mut self, de: &DifferentialEvolution) {
+    assert_eq!(de.bounds.len(), 5, "Heston calibration has 5 parameters");
+    let best = de
+      .minimize(|x| self.objective(x) + self.space.as_ref().map_or(0.0, |space| space.penalty(x)));
+    self.initial_guess = Some(DVector::from_vec(best.params));
+    self.calibrate();
+  }
+
   /// Returns the calibrated Heston pricer
   pub fn pricer(&self) -> HestonPricer {
     self.pricer.clone()
@@ -370,14 +538,14 @@ impl HestonCalibrator {
   /// http://scis.scichina.com/en/2018/042202.pdf
   ///
   /// Using NMLE (Normal Maximum Likelihood Estimation) method
-  pub fn initial_guess(&mut self, s: Vec<f64>, v: Vec<f64>, r: f64) {
-    self.initial_guess = Some(DVector::from_vec(nmle_heston(s, v, r)));
+  pub fn initial_guess(&mut self, s: ArrayView1<f64>, v: ArrayView1<f64>, r: f64) {
+    self.initial_guess = Some(DVector::from_vec(nmle_heston(s, v, r).to_vec()));
   }
 }
 
 #[cfg(test)]
 mod tests {
-
+  #[cfg(feature = "market-data")]
   use crate::quant::yahoo::Yahoo;
 
   use super::*;
@@ -434,6 +602,7 @@ mod tests {
   }
 
   #[test]
+  #[cfg(feature = "market-data")]
   fn test_heston_calibrate() {
     let mut yahoo = Yahoo::default();
     yahoo.set_symbol("GOOG");
@@ -501,7 +670,24 @@ mod tests {
 
     let mut calibrator =
       HestonCalibrator::new(v[0], s0, k, 0.05, None, c_market, pricer, OptionType::Call);
-    calibrator.initial_guess(s, v, r);
+    calibrator.initial_guess(ArrayView1::from(&s), ArrayView1::from(&v), r);
     calibrator.calibrate();
   }
+
+  #[test]
+  #[should_panic(expected = "One weight per option is needed")]
+  fn residuals_need_one_weight_per_option() {
+    let mut calibrator = HestonCalibrator::new(
+      0.04,
+      100.0,
+      vec![95.0, 100.0, 105.0],
+      0.03,
+      None,
+      vec![8.0, 5.0, 2.5],
+      HestonPricer::default(),
+      OptionType::Call,
+    );
+    calibrator.weights = Some(vec![1.0, 1.0]);
+    calibrator.residuals(&[0.04, 0.04, -0.7, 2.0, 0.5]);
+  }
 }
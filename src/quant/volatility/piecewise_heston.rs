@@ -0,0 +1,168 @@
+use std::f64::consts::FRAC_1_PI;
+
+use num_complex::Complex64;
+use quadrature::double_exponential;
+
+/// Heston parameters of the maturity bucket ending at `end`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HestonPiece {
+  pub end: f64,
+  pub kappa: f64,
+  pub theta: f64,
+  pub sigma: f64,
+  pub rho: f64,
+}
+
+/// Heston (or Bates, with lognormal jumps) model with piecewise-constant kappa, theta,
+/// sigma and rho. The characteristic function is exponential affine in the variance and
+/// its coefficients are propagated backwards through the buckets, every bucket solving the
+/// Riccati equations of the constant model from the coefficients at its end.
+/// The last bucket extends beyond its end.
+/// https://doi.org/10.1002/wilm.42820030113 (Mikhailov, Nögel 2003)
+#[derive(Debug, Clone, Default)]
+pub struct PiecewiseHestonPricer {
+  pub s0: f64,
+  pub v0: f64,
+  pub r: f64,
+  pub q: f64,
+  /// Buckets in increasing order of their ends
+  pub pieces: Vec<HestonPiece>,
+  /// Jump intensity, 0 for the Heston model
+  pub jump_intensity: f64,
+  /// Mean of the log jump size
+  pub jump_mean: f64,
+  /// Volatility of the log jump size
+  pub jump_vol: f64,
+}
+
+impl PiecewiseHestonPricer {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(!params.pieces.is_empty(), "At least one bucket is needed");
+    assert!(
+      params.pieces[0].end > 0.0 && params.pieces.windows(2).all(|w| w[0].end < w[1].end),
+      "Bucket ends must be positive and increasing"
+    );
+    assert!(
+      params.pieces.iter().all(|p| p.sigma > 0.0),
+      "Volatility of volatility must be positive"
+    );
+
+    params.clone()
+  }
+
+  /// Copy with the parameters v0, then kappa, theta, sigma and rho of every bucket,
+  /// the layout of a calibration parameter vector
+  #[must_use]
+  pub fn with_params(&self, params: &[f64]) -> Self {
+    assert_eq!(
+      params.len(),
+      1 + 4 * self.pieces.len(),
+      "v0 and four parameters per bucket are needed"
+    );
+    let mut pricer = self.clone();
+    pricer.v0 = params[0];
+    for (piece, p) in pricer.pieces.iter_mut().zip(params[1..].chunks(4)) {
+      piece.kappa = p[0];
+      piece.theta = p[1];
+      piece.sigma = p[2];
+      piece.rho = p[3];
+    }
+    pricer
+  }
+
+  /// Characteristic function E[exp(i u ln S(t))]
+  pub fn characteristic_function(&self, u: Complex64, t: f64) -> Complex64 {
+    let i = Complex64::i();
+    let mut c = Complex64::new(0.0, 0.0);
+    let mut d = Complex64::new(0.0, 0.0);
+    let mut end = t;
+
+    // from the maturity back to 0 through the buckets
+    for (k, piece) in self.pieces.iter().enumerate().rev() {
+      let start = if k == 0 { 0.0 } else { self.pieces[k - 1].end };
+      if start >= t {
+        continue;
+      }
+      let tau = end - start;
+      let HestonPiece {
+        kappa,
+        theta,
+        sigma,
+        rho,
+        ..
+      } = *piece;
+      (c, d) = riccati_step(u, tau, kappa, theta, sigma, rho, c, d);
+      end = start;
+    }
+
+    let jumps = if self.jump_intensity > 0.0 {
+      let mean_jump = (self.jump_mean + 0.5 * self.jump_vol.powi(2)).exp() - 1.0;
+      self.jump_intensity
+        * t
+        * ((i * u * self.jump_mean - 0.5 * u * u * self.jump_vol.powi(2)).exp()
+          - 1.0
+          - i * u * mean_jump)
+    } else {
+      Complex64::new(0.0, 0.0)
+    };
+
+    (i * u * (self.s0.ln() + (self.r - self.q) * t) + c + d * self.v0 + jumps).exp()
+  }
+
+  /// Prices of the European call and put with the strike k and the maturity t
+  pub fn price(&self, k: f64, t: f64) -> (f64, f64) {
+    gil_pelaez(
+      |u| self.characteristic_function(u, t),
+      self.s0,
+      k,
+      self.r,
+      self.q,
+      t,
+    )
+  }
+}
+
+/// Coefficients (C, D) of the Heston characteristic function exp(C + D v) after tau more
+/// time to maturity with constant parameters, from (C, D) = (c0, d0)
+pub(crate) fn riccati_step(
+  u: Complex64,
+  tau: f64,
+  kappa: f64,
+  theta: f64,
+  sigma: f64,
+  rho: f64,
+  c0: Complex64,
+  d0: Complex64,
+) -> (Complex64, Complex64) {
+  let i = Complex64::i();
+  let s2 = sigma * sigma;
+  let b = kappa - rho * sigma * i * u;
+  let root = (b * b + s2 * (u * u + i * u)).sqrt();
+  let g = (b - root - s2 * d0) / (b + root - s2 * d0);
+  let e = (-root * tau).exp();
+
+  (
+    c0 + kappa * theta / s2 * ((b - root) * tau - 2.0 * ((1.0 - g * e) / (1.0 - g)).ln()),
+    ((b - root) - (b + root) * g * e) / (s2 * (1.0 - g * e)),
+  )
+}
+
+/// Call and put prices from the characteristic function of the log price at maturity
+/// by the Gil-Pelaez inversion of the two exercise probabilities
+pub(crate) fn gil_pelaez<F>(cf: F, s0: f64, k: f64, r: f64, q: f64, t: f64) -> (f64, f64)
+where
+  F: Fn(Complex64) -> Complex64,
+{
+  let i = Complex64::i();
+  let forward = cf(-i);
+  let integrate = |f: &dyn Fn(f64) -> f64| {
+    0.5 + FRAC_1_PI * double_exponential::integrate(f, 0.00001, 100.0, 10e-8).integral
+  };
+  let p1 = integrate(&|u: f64| ((-i * u * k.ln()).exp() * cf(u - i) / (i * u * forward)).re);
+  let p2 = integrate(&|u: f64| ((-i * u * k.ln()).exp() * cf(Complex64::new(u, 0.0)) / (i * u)).re);
+
+  let call = s0 * (-q * t).exp() * p1 - k * (-r * t).exp() * p2;
+  let put = call + k * (-r * t).exp() - s0 * (-q * t).exp();
+  (call, put)
+}
@@ -0,0 +1,141 @@
+use ndarray::{Array1, ArrayView1};
+use statrs::distribution::{Continuous, Normal};
+
+use super::ssvi::Essvi;
+use crate::{
+  quant::{options::chain::OptionQuote, OptionType},
+  stats::spline::SmoothingSpline,
+};
+
+/// Risk-neutral density of the price at a maturity on a strike grid, by the
+/// Breeden-Litzenberger relation q(K) = e^(rT) d^2 C / dK^2. The density of noisy prices
+/// can be negative in places, the moments integrate it as it is.
+/// https://doi.org/10.1086/296025 (Breeden, Litzenberger 1978)
+#[derive(Debug, Clone)]
+pub struct RiskNeutralDensity {
+  /// Strikes, increasing
+  pub strikes: Array1<f64>,
+  pub density: Array1<f64>,
+}
+
+impl RiskNeutralDensity {
+  /// Density from the second derivative of a smoothing spline of the calls at the
+  /// maturity `tau`, on `points` strikes between the smallest and largest quoted strike.
+  /// The puts are turned into calls by the put-call parity and of a call and a put with
+  /// the same strike the out of the money one is kept. The smoothing parameter is in
+  /// units of the squared price per squared second derivative.
+  pub fn from_quotes(
+    quotes: &[OptionQuote],
+    s0: f64,
+    r: f64,
+    q: f64,
+    tau: f64,
+    smoothing: f64,
+    points: usize,
+  ) -> Self {
+    let forward = s0 * ((r - q) * tau).exp();
+    let df = (-r * tau).exp();
+    let mut calls = quotes
+      .iter()
+      .filter(|quote| (quote.tau - tau).abs() < 1e-12)
+      .map(|quote| match quote.option_type {
+        OptionType::Call => (quote.k, quote.mid, quote.k >= forward),
+        OptionType::Put => (
+          quote.k,
+          quote.mid + df * (forward - quote.k),
+          quote.k <= forward,
+        ),
+      })
+      .collect::<Vec<_>>();
+    calls.sort_by(|a, b| a.0.total_cmp(&b.0).then(b.2.cmp(&a.2)));
+    calls.dedup_by(|b, a| a.0 == b.0);
+    assert!(calls.len() >= 3, "At least three strikes are needed");
+
+    let k = Array1::from_iter(calls.iter().map(|c| c.0));
+    let c = Array1::from_iter(calls.iter().map(|c| c.1));
+    let spline = SmoothingSpline::fit(k.view(), c.view(), smoothing);
+    let strikes = Array1::linspace(k[0], k[k.len() - 1], points);
+    let density = strikes.mapv(|k| spline.second_derivative(k) / df);
+
+    Self { strikes, density }
+  }
+
+  /// Density of a fitted eSSVI surface at the maturity `tau` on the strikes, in closed
+  /// form from the total implied variance w(k) of the log-moneyness k = ln(K / F),
+  /// q(K) = g(k) n(d2) / (K sqrt(w)) with
+  /// g = (1 - k w' / (2w))^2 - w'^2 / 4 (1 / w + 1 / 4) + w'' / 2
+  pub fn from_essvi(
+    surface: &Essvi,
+    s0: f64,
+    r: f64,
+    q: f64,
+    tau: f64,
+    strikes: ArrayView1<f64>,
+  ) -> Self {
+    let forward = s0 * ((r - q) * tau).exp();
+    let slice = surface.slice(tau);
+    let n = Normal::default();
+    let density = strikes.mapv(|strike| {
+      let k = (strike / forward).ln();
+      let w = slice.total_variance(k);
+      let (dk, dkk) = slice.derivatives(k);
+      let g = (1.0 - k * dk / (2.0 * w)).powi(2) - 0.25 * dk * dk * (1.0 / w + 0.25) + 0.5 * dkk;
+      let d2 = -k / w.sqrt() - 0.5 * w.sqrt();
+      g * n.pdf(d2) / (strike * w.sqrt())
+    });
+
+    Self {
+      strikes: strikes.to_owned(),
+      density,
+    }
+  }
+
+  /// Trapezoidal integral of f(K) q(K) over the grid
+  fn integrate(&self, f: impl Fn(f64) -> f64) -> f64 {
+    self
+      .strikes
+      .windows(2)
+      .into_iter()
+      .zip(self.density.windows(2))
+      .map(|(k, q)| 0.5 * (k[1] - k[0]) * (f(k[0]) * q[0] + f(k[1]) * q[1]))
+      .sum()
+  }
+
+  /// Probability mass on the grid, below 1 when the grid misses the tails
+  pub fn mass(&self) -> f64 {
+    self.integrate(|_| 1.0)
+  }
+
+  /// Mean of the price, the forward for an exact density
+  pub fn mean(&self) -> f64 {
+    self.integrate(|k| k) / self.mass()
+  }
+
+  pub fn variance(&self) -> f64 {
+    let mean = self.mean();
+    self.integrate(|k| (k - mean).powi(2)) / self.mass()
+  }
+
+  pub fn skewness(&self) -> f64 {
+    let mean = self.mean();
+    self.integrate(|k| (k - mean).powi(3)) / self.mass() / self.variance().powf(1.5)
+  }
+
+  /// Excess kurtosis
+  pub fn kurtosis(&self) -> f64 {
+    let mean = self.mean();
+    self.integrate(|k| (k - mean).powi(4)) / self.mass() / self.variance().powi(2) - 3.0
+  }
+
+  /// Distribution function on the grid, the cumulative trapezoidal integral
+  pub fn cdf(&self) -> Array1<f64> {
+    let mut total = 0.0;
+    let mut cdf = Array1::zeros(self.strikes.len());
+    for i in 1..self.strikes.len() {
+      total +=
+        0.5 * (self.strikes[i] - self.strikes[i - 1]) * (self.density[i] + self.density[i - 1]);
+      cdf[i] = total;
+    }
+    cdf
+  }
+}
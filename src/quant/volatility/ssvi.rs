@@ -0,0 +1,362 @@
+use ndarray::{Array2, ArrayView1};
+use statrs::distribution::{Continuous, Normal};
+
+use super::surface::VolSurface;
+use crate::quant::{
+  calibration::{
+    simplex::nelder_mead,
+    transform::{ParameterSpace, Transform},
+  },
+  options::{
+    bsm::{BSMCoc, BSM},
+    chain::OptionQuote,
+  },
+  OptionType,
+};
+
+/// Slice of an SSVI surface, the total implied variance
+/// w(k) = (theta + rho psi k + sqrt((psi k + rho theta)^2 + (1 - rho^2) theta^2)) / 2
+/// of the log-moneyness k = ln(K / F), with the ATM total variance theta and the ATM
+/// skew psi = theta phi(theta)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SsviSlice {
+  /// Maturity
+  pub tau: f64,
+  /// ATM total variance
+  pub theta: f64,
+  pub rho: f64,
+  pub psi: f64,
+}
+
+impl SsviSlice {
+  fn root(&self, k: f64) -> f64 {
+    ((self.psi * k + self.rho * self.theta).powi(2) + (1.0 - self.rho.powi(2)) * self.theta.powi(2))
+      .sqrt()
+  }
+
+  pub fn total_variance(&self, k: f64) -> f64 {
+    0.5 * (self.theta + self.rho * self.psi * k + self.root(k))
+  }
+
+  /// First and second derivatives of the total variance in k
+  pub fn derivatives(&self, k: f64) -> (f64, f64) {
+    let root = self.root(k);
+    (
+      0.5 * self.psi * (self.rho + (self.psi * k + self.rho * self.theta) / root),
+      0.5 * (self.psi * self.theta).powi(2) * (1.0 - self.rho.powi(2)) / root.powi(3),
+    )
+  }
+
+  /// Butterfly arbitrage free, psi (1 + |rho|) <= 4 and psi^2 (1 + |rho|) <= 4 theta
+  pub fn is_butterfly_free(&self) -> bool {
+    let a = 1.0 + self.rho.abs();
+    self.psi * a <= 4.0 + 1e-12 && self.psi.powi(2) * a <= 4.0 * self.theta + 1e-12
+  }
+
+  /// Free of calendar arbitrage against an earlier slice, by the sufficient condition
+  /// theta >= theta' and psi - psi' >= |rho psi - rho' psi'|
+  pub fn is_calendar_free(&self, earlier: &Self) -> bool {
+    self.theta >= earlier.theta - 1e-12
+      && self.psi - earlier.psi >= (self.rho * self.psi - earlier.rho * earlier.psi).abs() - 1e-12
+  }
+}
+
+/// Extended SSVI surface of Hendriks and Martini, SSVI slices with a correlation that
+/// depends on the maturity. Between the slices theta is linear in the maturity and psi and
+/// rho psi are linear in theta, which keeps the surface free of arbitrage, and the
+/// surface is extrapolated before the first slice towards w = 0 and after the last one
+/// with the last slope of theta, following the robust calibration of Corbetta, Cohort,
+/// Laachir and Martini (2019).
+#[derive(Debug, Clone, Default)]
+pub struct Essvi {
+  /// Slices in increasing order of maturity
+  pub slices: Vec<SsviSlice>,
+}
+
+/// SSVI surface of Gatheral and Jacquier with a constant correlation and the power law
+/// phi(theta) = eta / (theta^gamma (1 + theta)^(1 - gamma)), free of static arbitrage for
+/// eta (1 + |rho|) <= 2, 0 < gamma <= 1/2 and an increasing ATM total variance curve.
+/// https://doi.org/10.1080/14697688.2013.819986 (Gatheral, Jacquier 2014)
+#[derive(Debug, Clone, Default)]
+pub struct Ssvi {
+  pub rho: f64,
+  pub eta: f64,
+  pub gamma: f64,
+  /// ATM total variance curve, (maturity, theta) in increasing order of maturity
+  pub theta: Vec<(f64, f64)>,
+}
+
+/// Market total variances of the quotes, (maturity, [(k, w)]) in increasing order of
+/// maturity and log-moneyness. Of a call and a put with the same strike only the out of
+/// the money one is kept, and the quotes more than about four standard deviations out of
+/// the money, n(d1) < 1e-4, are dropped, their implied volatilities are unreliable.
+fn market_slices(quotes: &[OptionQuote], s0: f64, r: f64, q: f64) -> Vec<(f64, Vec<(f64, f64)>)> {
+  let n = Normal::default();
+  let mut points = quotes
+    .iter()
+    .filter_map(|quote| {
+      let iv = BSM::new(&BSM {
+        s: s0,
+        v: 0.2,
+        k: quote.k,
+        r,
+        q: Some(q),
+        tau: Some(quote.tau),
+        option_type: quote.option_type,
+        b: BSMCoc::MERTON1973,
+        ..Default::default()
+      })
+      .implied_volatility(quote.mid);
+      let forward = s0 * ((r - q) * quote.tau).exp();
+      let out_of_the_money = match quote.option_type {
+        OptionType::Call => quote.k >= forward,
+        OptionType::Put => quote.k <= forward,
+      };
+      let k = (quote.k / forward).ln();
+      let s = iv * quote.tau.sqrt();
+      let informative = n.pdf(-k / s + 0.5 * s) >= 1e-4;
+      (iv.is_finite() && iv > 0.0 && informative).then_some((
+        quote.tau,
+        k,
+        iv * iv * quote.tau,
+        out_of_the_money,
+      ))
+    })
+    .collect::<Vec<_>>();
+  points.sort_by(|a, b| {
+    a.0
+      .total_cmp(&b.0)
+      .then(a.1.total_cmp(&b.1))
+      .then(b.3.cmp(&a.3))
+  });
+  points.dedup_by(|b, a| a.0 == b.0 && a.1 == b.1);
+
+  let mut slices: Vec<(f64, Vec<(f64, f64)>)> = Vec::new();
+  for (tau, k, w, _) in points {
+    match slices.last_mut() {
+      Some((t, slice)) if (*t - tau).abs() < 1e-12 => slice.push((k, w)),
+      _ => slices.push((tau, vec![(k, w)])),
+    }
+  }
+  slices
+}
+
+/// Total variance at k = 0 by linear interpolation of the nearest strikes
+fn atm_total_variance(slice: &[(f64, f64)]) -> f64 {
+  match slice.iter().position(|&(k, _)| k >= 0.0) {
+    Some(0) => slice[0].1,
+    None => slice[slice.len() - 1].1,
+    Some(j) => {
+      let ((k0, w0), (k1, w1)) = (slice[j - 1], slice[j]);
+      w0 + (w1 - w0) * (0.0 - k0) / (k1 - k0)
+    }
+  }
+}
+
+/// Squared errors of the implied volatilities of a slice
+fn slice_error(slice: &SsviSlice, market: &[(f64, f64)]) -> f64 {
+  market
+    .iter()
+    .map(|&(k, w)| ((slice.total_variance(k) / slice.tau).sqrt() - (w / slice.tau).sqrt()).powi(2))
+    .sum()
+}
+
+impl Essvi {
+  /// Slice by slice fit of the implied volatilities of the quotes. Theta is the market
+  /// ATM total variance, kept increasing, and rho and psi are fitted within the region
+  /// free of butterfly arbitrage and of calendar arbitrage against the previous slice.
+  pub fn fit(quotes: &[OptionQuote], s0: f64, r: f64, q: f64) -> Self {
+    let market = market_slices(quotes, s0, r, q);
+    assert!(!market.is_empty(), "No quote with an implied volatility");
+
+    let space = ParameterSpace::new(vec![
+      Transform::Bounded(-1.0, 1.0),
+      Transform::Bounded(0.0, 1.0),
+    ]);
+    let mut slices: Vec<SsviSlice> = Vec::with_capacity(market.len());
+    for (tau, points) in &market {
+      let previous = slices.last().copied().unwrap_or_default();
+      let theta = atm_total_variance(points).max(previous.theta);
+
+      // rho and the position of psi between its bounds given rho
+      let slice = |x: &[f64]| {
+        let rho = x[0];
+        let lower = (previous.psi * (1.0 - previous.rho) / (1.0 - rho))
+          .max(previous.psi * (1.0 + previous.rho) / (1.0 + rho));
+        let upper = (4.0 / (1.0 + rho.abs())).min((4.0 * theta / (1.0 + rho.abs())).sqrt());
+        (lower <= upper).then_some(SsviSlice {
+          tau: *tau,
+          theta,
+          rho,
+          psi: lower + (upper - lower) * x[1],
+        })
+      };
+      let best = nelder_mead(
+        space.objective(|x| slice(x).map_or(f64::MAX, |s| slice_error(&s, points))),
+        &space.unconstrain(&[0.0, 0.5]),
+        0.5,
+        2000,
+        1e-14,
+      );
+      slices.push(slice(&space.constrain(&best.params)).unwrap_or(SsviSlice {
+        tau: *tau,
+        theta,
+        ..previous
+      }));
+    }
+
+    Self { slices }
+  }
+
+  /// Every slice free of butterfly arbitrage and of calendar arbitrage against the
+  /// previous one
+  pub fn is_arbitrage_free(&self) -> bool {
+    self.slices.iter().all(SsviSlice::is_butterfly_free)
+      && self.slices.windows(2).all(|w| w[1].is_calendar_free(&w[0]))
+  }
+
+  /// Slice at a maturity, interpolated between the fitted slices
+  pub fn slice(&self, tau: f64) -> SsviSlice {
+    assert!(!self.slices.is_empty(), "The surface has no slice");
+    let first = self.slices[0];
+    let last = self.slices[self.slices.len() - 1];
+
+    if tau <= first.tau {
+      // theta linear from 0, psi proportional to theta
+      let a = tau / first.tau;
+      return SsviSlice {
+        tau,
+        theta: a * first.theta,
+        psi: a * first.psi,
+        rho: first.rho,
+      };
+    }
+    if tau >= last.tau {
+      let slope = match self.slices.len() {
+        1 => last.theta / last.tau,
+        n => (last.theta - self.slices[n - 2].theta) / (last.tau - self.slices[n - 2].tau),
+      };
+      return SsviSlice {
+        tau,
+        theta: last.theta + slope * (tau - last.tau),
+        ..last
+      };
+    }
+
+    let j = self.slices.iter().position(|s| s.tau >= tau).unwrap();
+    let (s0, s1) = (self.slices[j - 1], self.slices[j]);
+    let theta = s0.theta + (s1.theta - s0.theta) * (tau - s0.tau) / (s1.tau - s0.tau);
+    let a = if s1.theta > s0.theta {
+      (theta - s0.theta) / (s1.theta - s0.theta)
+    } else {
+      (tau - s0.tau) / (s1.tau - s0.tau)
+    };
+    let psi = s0.psi + a * (s1.psi - s0.psi);
+    let rho_psi = s0.rho * s0.psi + a * (s1.rho * s1.psi - s0.rho * s0.psi);
+    SsviSlice {
+      tau,
+      theta,
+      rho: if psi > 0.0 { rho_psi / psi } else { s1.rho },
+      psi,
+    }
+  }
+
+  pub fn total_variance(&self, k: f64, tau: f64) -> f64 {
+    self.slice(tau).total_variance(k)
+  }
+
+  pub fn implied_volatility(&self, k: f64, tau: f64) -> f64 {
+    (self.total_variance(k, tau) / tau).sqrt()
+  }
+
+  /// Surface on a grid of log-moneyness and maturities
+  pub fn surface(&self, log_moneyness: ArrayView1<f64>, maturities: ArrayView1<f64>) -> VolSurface {
+    VolSurface::new(&VolSurface {
+      log_moneyness: log_moneyness.to_owned(),
+      maturities: maturities.to_owned(),
+      vols: Array2::from_shape_fn((maturities.len(), log_moneyness.len()), |(i, j)| {
+        self.implied_volatility(log_moneyness[j], maturities[i])
+      }),
+    })
+  }
+
+  /// Dupire local volatility at the log-moneyness k from the total implied variance,
+  /// sigma^2 = d_T w / (1 - k / w d_k w + (-1/4 - 1/w + k^2 / w^2) (d_k w)^2 / 4
+  /// + d_k^2 w / 2), with the maturity derivative by central differences
+  pub fn local_volatility(&self, k: f64, tau: f64) -> f64 {
+    let h = 1e-4 * tau;
+    let slice = self.slice(tau);
+    let w = slice.total_variance(k);
+    let (dk, dkk) = slice.derivatives(k);
+    let dt = (self.total_variance(k, tau + h) - self.total_variance(k, tau - h)) / (2.0 * h);
+
+    let denominator =
+      1.0 - k / w * dk + 0.25 * (-0.25 - 1.0 / w + (k / w).powi(2)) * dk * dk + 0.5 * dkk;
+    (dt / denominator).max(0.0).sqrt()
+  }
+}
+
+impl Ssvi {
+  /// Fit of rho, eta and gamma to the implied volatilities of all the quotes, within the
+  /// arbitrage-free region, with the market ATM total variances kept increasing
+  pub fn fit(quotes: &[OptionQuote], s0: f64, r: f64, q: f64) -> Self {
+    let market = market_slices(quotes, s0, r, q);
+    assert!(!market.is_empty(), "No quote with an implied volatility");
+
+    let mut theta = Vec::with_capacity(market.len());
+    for (tau, points) in &market {
+      let previous = theta.last().map_or(0.0, |&(_, t)| t);
+      theta.push((*tau, atm_total_variance(points).max(previous)));
+    }
+
+    // rho, eta as a fraction of its bound 2 / (1 + |rho|) and gamma
+    let space = ParameterSpace::new(vec![
+      Transform::Bounded(-1.0, 1.0),
+      Transform::Bounded(0.0, 1.0),
+      Transform::Bounded(0.0, 0.5),
+    ]);
+    let from = |x: &[f64]| Self {
+      rho: x[0],
+      eta: 2.0 / (1.0 + x[0].abs()) * x[1],
+      gamma: x[2],
+      theta: theta.clone(),
+    };
+    let best = nelder_mead(
+      space.objective(|x| {
+        let ssvi = from(x).essvi();
+        ssvi
+          .slices
+          .iter()
+          .zip(&market)
+          .map(|(slice, (_, points))| slice_error(slice, points))
+          .sum()
+      }),
+      &space.unconstrain(&[(-0.3f64).tanh(), 0.5, 0.25]),
+      0.5,
+      5000,
+      1e-14,
+    );
+
+    from(&space.constrain(&best.params))
+  }
+
+  pub fn phi(&self, theta: f64) -> f64 {
+    self.eta / (theta.powf(self.gamma) * (1.0 + theta).powf(1.0 - self.gamma))
+  }
+
+  /// eSSVI surface with the slices of the ATM total variance curve
+  pub fn essvi(&self) -> Essvi {
+    Essvi {
+      slices: self
+        .theta
+        .iter()
+        .map(|&(tau, theta)| SsviSlice {
+          tau,
+          theta,
+          rho: self.rho,
+          psi: theta * self.phi(theta),
+        })
+        .collect(),
+    }
+  }
+}
@@ -0,0 +1,360 @@
+use ndarray::{Array1, Array2};
+use statrs::distribution::{Continuous, Normal};
+
+use crate::quant::{
+  options::{
+    bsm::{BSMCoc, BSM},
+    multiasset::black,
+  },
+  OptionType,
+};
+
+/// Implied volatility surface on a grid of log-moneyness k = ln(K / F) and maturities.
+/// The arbitrage checks and the repair work on the undiscounted calls normalized by the
+/// forward, c(x, T) = C / (D F) with x = K / F, which are free of static arbitrage when
+/// they lie above (1 - x)^+, decrease and are convex in x and, at a fixed x, increase
+/// with the maturity.
+#[derive(Debug, Clone, Default)]
+pub struct VolSurface {
+  /// Log-moneyness ln(K / F), increasing
+  pub log_moneyness: Array1<f64>,
+  /// Maturities, increasing
+  pub maturities: Array1<f64>,
+  /// Implied volatilities, one row per maturity
+  pub vols: Array2<f64>,
+}
+
+/// Kind of a static arbitrage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arbitrage {
+  /// Call below its intrinsic value (1 - x)^+
+  Bound,
+  /// Call increasing in the strike
+  VerticalSpread,
+  /// Call concave in the strike, a negative butterfly spread
+  Butterfly,
+  /// Call decreasing in the maturity at a fixed moneyness
+  Calendar,
+}
+
+/// A violated no-arbitrage constraint
+#[derive(Debug, Clone, Copy)]
+pub struct Violation {
+  pub kind: Arbitrage,
+  /// Index of the maturity
+  pub maturity: usize,
+  /// Index of the log-moneyness
+  pub strike: usize,
+  /// Size of the violation in normalized call prices
+  pub amount: f64,
+}
+
+/// Violations of the no-arbitrage constraints of a surface
+#[derive(Debug, Clone, Default)]
+pub struct ArbitrageReport {
+  pub violations: Vec<Violation>,
+}
+
+impl ArbitrageReport {
+  pub fn is_free(&self) -> bool {
+    self.violations.is_empty()
+  }
+
+  /// Number of violations of a kind
+  pub fn count(&self, kind: Arbitrage) -> usize {
+    self.violations.iter().filter(|v| v.kind == kind).count()
+  }
+
+  /// Largest violation, 0 for a surface free of arbitrage
+  pub fn max_violation(&self) -> f64 {
+    self.violations.iter().map(|v| v.amount).fold(0.0, f64::max)
+  }
+}
+
+/// Arbitrage-free surface closest to a surface, with the violations before and after
+#[derive(Debug, Clone)]
+pub struct Repair {
+  pub surface: VolSurface,
+  pub before: ArbitrageReport,
+  pub after: ArbitrageReport,
+  /// Largest change of an implied volatility
+  pub max_adjustment: f64,
+  /// Sweeps over the constraints
+  pub iterations: usize,
+}
+
+/// Linear constraint sum a_i c_i <= b on the flattened normalized calls
+struct Constraint {
+  kind: Arbitrage,
+  maturity: usize,
+  strike: usize,
+  terms: Vec<(usize, f64)>,
+  bound: f64,
+}
+
+impl Constraint {
+  fn excess(&self, c: &[f64]) -> f64 {
+    self.terms.iter().map(|&(i, a)| a * c[i]).sum::<f64>() - self.bound
+  }
+}
+
+impl VolSurface {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert_eq!(
+      params.vols.dim(),
+      (params.maturities.len(), params.log_moneyness.len()),
+      "One volatility per maturity and log-moneyness"
+    );
+    assert!(
+      params
+        .log_moneyness
+        .windows(2)
+        .into_iter()
+        .all(|w| w[0] < w[1])
+        && params
+          .maturities
+          .windows(2)
+          .into_iter()
+          .all(|w| w[0] < w[1]),
+      "Log-moneyness and maturities must increase"
+    );
+    assert!(
+      params.log_moneyness.len() >= 2 && !params.maturities.is_empty(),
+      "At least two strikes and one maturity are needed"
+    );
+    assert!(
+      params.maturities.iter().all(|&t| t > 0.0),
+      "Maturities must be positive"
+    );
+
+    params.clone()
+  }
+
+  /// Total implied variances w = sigma^2 T
+  pub fn total_variance(&self) -> Array2<f64> {
+    Array2::from_shape_fn(self.vols.dim(), |(i, j)| {
+      self.vols[(i, j)].powi(2) * self.maturities[i]
+    })
+  }
+
+  /// Undiscounted calls normalized by the forward
+  pub fn normalized_calls(&self) -> Array2<f64> {
+    Array2::from_shape_fn(self.vols.dim(), |(i, j)| {
+      black(
+        1.0,
+        self.log_moneyness[j].exp(),
+        self.vols[(i, j)] * self.maturities[i].sqrt(),
+        1.0,
+        OptionType::Call,
+      )
+    })
+  }
+
+  /// Surface of the normalized calls, 0 volatility where a call is at its intrinsic value
+  #[must_use]
+  pub fn from_normalized_calls(
+    log_moneyness: Array1<f64>,
+    maturities: Array1<f64>,
+    calls: &Array2<f64>,
+  ) -> Self {
+    let vols = Array2::from_shape_fn(calls.dim(), |(i, j)| {
+      let bsm = BSM {
+        s: 1.0,
+        v: 0.2,
+        k: log_moneyness[j].exp(),
+        r: 0.0,
+        q: Some(0.0),
+        tau: Some(maturities[i]),
+        option_type: OptionType::Call,
+        b: BSMCoc::MERTON1973,
+        ..Default::default()
+      };
+      let vol = bsm.implied_volatility(calls[(i, j)]);
+      if vol.is_nan() {
+        0.0
+      } else {
+        vol
+      }
+    });
+
+    Self::new(&Self {
+      log_moneyness,
+      maturities,
+      vols,
+    })
+  }
+
+  /// The no-arbitrage constraints, the calls are convex through the point c(0) = 1 so the
+  /// slopes stay above -1
+  fn constraints(&self) -> Vec<Constraint> {
+    let (n_t, n_k) = self.vols.dim();
+    let x = self.log_moneyness.mapv(f64::exp);
+    let index = |i: usize, j: usize| i * n_k + j;
+    let mut constraints = Vec::new();
+
+    for i in 0..n_t {
+      for j in 0..n_k {
+        constraints.push(Constraint {
+          kind: Arbitrage::Bound,
+          maturity: i,
+          strike: j,
+          terms: vec![(index(i, j), -1.0)],
+          bound: -(1.0 - x[j]).max(0.0),
+        });
+      }
+      for j in 0..n_k - 1 {
+        constraints.push(Constraint {
+          kind: Arbitrage::VerticalSpread,
+          maturity: i,
+          strike: j,
+          terms: vec![(index(i, j + 1), 1.0), (index(i, j), -1.0)],
+          bound: 0.0,
+        });
+      }
+      for j in 0..n_k - 1 {
+        // slopes of the chords before and after x_j, the first chord starts at c(0) = 1
+        let right = 1.0 / (x[j + 1] - x[j]);
+        let (terms, bound) = if j == 0 {
+          let left = 1.0 / x[0];
+          (
+            vec![(index(i, 0), left + right), (index(i, 1), -right)],
+            left,
+          )
+        } else {
+          let left = 1.0 / (x[j] - x[j - 1]);
+          (
+            vec![
+              (index(i, j - 1), -left),
+              (index(i, j), left + right),
+              (index(i, j + 1), -right),
+            ],
+            0.0,
+          )
+        };
+        constraints.push(Constraint {
+          kind: Arbitrage::Butterfly,
+          maturity: i,
+          strike: j,
+          terms,
+          bound,
+        });
+      }
+    }
+    for i in 0..n_t - 1 {
+      for j in 0..n_k {
+        constraints.push(Constraint {
+          kind: Arbitrage::Calendar,
+          maturity: i,
+          strike: j,
+          terms: vec![(index(i, j), 1.0), (index(i + 1, j), -1.0)],
+          bound: 0.0,
+        });
+      }
+    }
+    constraints
+  }
+
+  /// Static arbitrages of the surface larger than the tolerance in normalized call prices
+  pub fn check(&self, tolerance: f64) -> ArbitrageReport {
+    let calls = self.normalized_calls();
+    let c = calls.as_slice().unwrap();
+    ArbitrageReport {
+      violations: self
+        .constraints()
+        .iter()
+        .filter_map(|constraint| {
+          let amount = constraint.excess(c);
+          (amount > tolerance).then_some(Violation {
+            kind: constraint.kind,
+            maturity: constraint.maturity,
+            strike: constraint.strike,
+            amount,
+          })
+        })
+        .collect(),
+    }
+  }
+
+  /// Projection of the normalized calls onto the arbitrage-free set, the intersection of
+  /// the half-spaces of the constraints, by the alternating projections of Dykstra (1983).
+  /// The distance is weighted by the inverse squared Black vegas, so it measures the
+  /// changes of the implied volatilities to first order and the illiquid wings with a
+  /// small vega absorb the repair. Stops when a sweep changes no call by more than 1e-12
+  /// or after `max_iterations` sweeps.
+  pub fn repair(&self, max_iterations: usize) -> Repair {
+    let tolerance = 1e-10;
+    let before = self.check(tolerance);
+    let (n_t, n_k) = self.vols.dim();
+    let n = Normal::default();
+
+    // inverse weights, the squared vegas with a floor relative to the largest one
+    let vega = Array2::from_shape_fn((n_t, n_k), |(i, j)| {
+      let s = self.vols[(i, j)] * self.maturities[i].sqrt();
+      n.pdf(-self.log_moneyness[j] / s + 0.5 * s) * self.maturities[i].sqrt()
+    });
+    let floor = 1e-3 * vega.fold(0.0_f64, |a, &b| a.max(b));
+    let inverse_weight = vega.mapv(|v| v.max(floor).powi(2));
+    let inverse_weight = inverse_weight.as_slice().unwrap();
+
+    let constraints = self.constraints();
+    let calls = self.normalized_calls();
+    let mut c = calls.as_slice().unwrap().to_vec();
+    let mut increments = constraints
+      .iter()
+      .map(|constraint| vec![0.0; constraint.terms.len()])
+      .collect::<Vec<_>>();
+
+    let mut iterations = 0;
+    while iterations < max_iterations {
+      iterations += 1;
+      let mut change = 0.0_f64;
+      for (constraint, increment) in constraints.iter().zip(&mut increments) {
+        let y = constraint
+          .terms
+          .iter()
+          .zip(increment.iter())
+          .map(|(&(i, _), q)| c[i] + q)
+          .collect::<Vec<_>>();
+        let excess = constraint
+          .terms
+          .iter()
+          .zip(&y)
+          .map(|(&(_, a), y)| a * y)
+          .sum::<f64>()
+          - constraint.bound;
+        let norm = constraint
+          .terms
+          .iter()
+          .map(|&(i, a)| a * a * inverse_weight[i])
+          .sum::<f64>();
+        let step = excess.max(0.0) / norm;
+
+        for ((&(i, a), q), y) in constraint.terms.iter().zip(increment.iter_mut()).zip(&y) {
+          let projected = y - step * a * inverse_weight[i];
+          change = change.max((projected - c[i]).abs());
+          *q = y - projected;
+          c[i] = projected;
+        }
+      }
+      if change <= 1e-12 {
+        break;
+      }
+    }
+
+    let calls = Array2::from_shape_vec((n_t, n_k), c).unwrap();
+    let surface =
+      Self::from_normalized_calls(self.log_moneyness.clone(), self.maturities.clone(), &calls);
+    let max_adjustment = (&surface.vols - &self.vols)
+      .iter()
+      .fold(0.0_f64, |a, &b| a.max(b.abs()));
+
+    Repair {
+      after: surface.check(tolerance),
+      surface,
+      before,
+      max_adjustment,
+      iterations,
+    }
+  }
+}
@@ -0,0 +1,132 @@
+use std::f64::consts::PI;
+
+use quadrature::double_exponential;
+
+/// Variance and volatility swaps on the Heston model, or on the Bates model if the jump
+/// parameters are set, from the closed-form moments and Laplace transform of the
+/// quadratic variation QV_T = int_0^T v_t dt + sum J_i^2 of the log price. The moments
+/// are exact control variates for Monte Carlo prices of volatility derivatives, as in
+/// Broadie and Jain (2008).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VarianceSwap {
+  /// Initial variance
+  pub v0: f64,
+  /// Mean reversion rate
+  pub kappa: f64,
+  /// Long-run variance
+  pub theta: f64,
+  /// Volatility of variance
+  pub sigma: f64,
+  /// Jump intensity (Bates)
+  pub lambda: Option<f64>,
+  /// Mean of the log-jumps (Bates)
+  pub mu_j: Option<f64>,
+  /// Volatility of the log-jumps (Bates)
+  pub sigma_j: Option<f64>,
+  /// Maturity
+  pub tau: f64,
+}
+
+impl VarianceSwap {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.kappa > 0.0 && params.sigma > 0.0 && params.tau > 0.0,
+      "Mean reversion, volatility of variance and maturity must be positive"
+    );
+
+    *params
+  }
+
+  /// Jump intensity and the second and fourth moments of the log-jumps
+  fn jump_moments(&self) -> (f64, f64, f64) {
+    let (mu, s) = (self.mu_j.unwrap_or(0.0), self.sigma_j.unwrap_or(0.0));
+    (
+      self.lambda.unwrap_or(0.0),
+      mu * mu + s * s,
+      mu.powi(4) + 6.0 * mu * mu * s * s + 3.0 * s.powi(4),
+    )
+  }
+
+  /// Mean of the integrated variance int_0^T v_t dt
+  pub fn integrated_variance_mean(&self) -> f64 {
+    let (kappa, t) = (self.kappa, self.tau);
+    self.theta * t + (self.v0 - self.theta) * (1.0 - (-kappa * t).exp()) / kappa
+  }
+
+  /// Variance of the integrated variance, 2 int_0^T Var(v_s) (1 - e^(-kappa (T - s)))
+  /// / kappa ds with Var(v_s) = b + (a - 2b) e^(-kappa s) + (b - a) e^(-2 kappa s),
+  /// a = v0 sigma^2 / kappa and b = theta sigma^2 / (2 kappa)
+  pub fn integrated_variance_variance(&self) -> f64 {
+    let (kappa, t) = (self.kappa, self.tau);
+    let e = (-kappa * t).exp();
+    let a = self.v0 * self.sigma.powi(2) / kappa;
+    let b = self.theta * self.sigma.powi(2) / (2.0 * kappa);
+    // int_0^T e^(-n kappa s) ds
+    let decay = |n: i32| (1.0 - e.powi(n)) / (n as f64 * kappa);
+
+    let constant = t - decay(1);
+    let linear = decay(1) - e * t;
+    let quadratic = decay(2) - e * decay(1);
+    2.0 / kappa * (b * constant + (a - 2.0 * b) * linear + (b - a) * quadratic)
+  }
+
+  /// Mean of the quadratic variation
+  pub fn mean(&self) -> f64 {
+    let (lambda, m2, _) = self.jump_moments();
+    self.integrated_variance_mean() + lambda * self.tau * m2
+  }
+
+  /// Variance of the quadratic variation, the jumps are independent of the variance
+  pub fn variance(&self) -> f64 {
+    let (lambda, _, m4) = self.jump_moments();
+    self.integrated_variance_variance() + lambda * self.tau * m4
+  }
+
+  /// Laplace transform E[e^(-s QV_T)], the integrated variance of the square root process
+  /// is exponential affine in v0 and the squared normal log-jumps have the transform
+  /// e^(-s mu^2 / (1 + 2 s sigma^2)) / sqrt(1 + 2 s sigma^2)
+  pub fn laplace(&self, s: f64) -> f64 {
+    let (kappa, t, sigma2) = (self.kappa, self.tau, self.sigma.powi(2));
+    let gamma = (kappa * kappa + 2.0 * sigma2 * s).sqrt();
+    let e = (-gamma * t).exp();
+    let denominator = (gamma + kappa) * (1.0 - e) + 2.0 * gamma * e;
+    let b = 2.0 * s * (1.0 - e) / denominator;
+    let a = 2.0 * kappa * self.theta / sigma2
+      * ((2.0 * gamma / denominator).ln() + 0.5 * (kappa - gamma) * t);
+
+    let (mu, s_j) = (self.mu_j.unwrap_or(0.0), self.sigma_j.unwrap_or(0.0));
+    let spread = 1.0 + 2.0 * s * s_j * s_j;
+    let jump = (-s * mu * mu / spread).exp() / spread.sqrt();
+    (a - b * self.v0 + self.lambda.unwrap_or(0.0) * t * (jump - 1.0)).exp()
+  }
+
+  /// Fair strike of the variance swap, the annualized expected realized variance
+  pub fn fair_strike(&self) -> f64 {
+    self.mean() / self.tau
+  }
+
+  /// Fair strike of the volatility swap E[sqrt(QV_T / T)], from the Laplace transform by
+  /// E[sqrt(X)] = 1 / sqrt(pi) int_0^inf (1 - E[e^(-u^2 X)]) / u^2 du
+  pub fn volatility_strike(&self) -> f64 {
+    // u = x / (1 - x) maps [0, 1) onto [0, inf) with du / u^2 = dx / x^2
+    let integrand = |x: f64| {
+      if x <= 0.0 {
+        return self.mean();
+      }
+      let u = x / (1.0 - x);
+      (1.0 - self.laplace(u * u)) / (x * x)
+    };
+    let integral = double_exponential::integrate(integrand, 0.0, 1.0, 1e-10).integral;
+    integral / (PI * self.tau).sqrt()
+  }
+
+  /// Second order convexity approximation of the volatility swap strike,
+  /// sqrt(E[RV]) - Var[RV] / (8 E[RV]^(3/2)) with the realized variance RV = QV_T / T
+  /// of Brockhaus and Long (2000)
+  pub fn volatility_strike_approx(&self) -> f64 {
+    let mean = self.fair_strike();
+    let variance = self.variance() / self.tau.powi(2);
+    mean.sqrt() - variance / (8.0 * mean.powf(1.5))
+  }
+}
@@ -0,0 +1,218 @@
+use ndarray::{Array1, Array2, Axis};
+
+use crate::{quant::curve::YieldCurve, stats::quantile::interpolate};
+
+/// Collateral agreement (CSA) of a trade, which sets the rate its cash flows are
+/// discounted at
+#[derive(Debug, Clone, Default)]
+pub enum Csa {
+  /// No collateral, the trade is funded at the funding rate of the bank
+  #[default]
+  Uncollateralized,
+  /// Cash collateral remunerated at the OIS rate plus a spread
+  Cash { spread: f64 },
+  /// Collateral earning a given curve, e.g. cash in another currency converted to the
+  /// currency of the trade
+  Curve(YieldCurve),
+}
+
+/// Discounting of a trade: the OIS curve as the risk-free curve, the funding curve of the
+/// bank at a spread over it, and the CSA choosing the curve of the trade. Under a perfect
+/// CSA the price is the expectation discounted at the collateral rate (Piterbarg 2010), so
+/// a price discounted on the OIS curve is moved to the CSA curve by the ratio of the two
+/// discount factors when the spread is deterministic.
+/// https://doi.org/10.2139/ssrn.1608922 (Piterbarg 2010)
+#[derive(Debug, Clone)]
+pub struct Discounting {
+  pub ois: YieldCurve,
+  /// Spread of the funding rate over the OIS rate
+  pub funding_spread: f64,
+  pub csa: Csa,
+}
+
+impl Discounting {
+  #[must_use]
+  pub fn new(ois: YieldCurve, funding_spread: f64, csa: Csa) -> Self {
+    Self {
+      ois,
+      funding_spread,
+      csa,
+    }
+  }
+
+  pub fn funding_curve(&self) -> YieldCurve {
+    self.ois.shifted(self.funding_spread)
+  }
+
+  /// Curve the trade is discounted on under its CSA
+  pub fn discount_curve(&self) -> YieldCurve {
+    match &self.csa {
+      Csa::Uncollateralized => self.funding_curve(),
+      Csa::Cash { spread } => self.ois.shifted(*spread),
+      Csa::Curve(curve) => curve.clone(),
+    }
+  }
+
+  pub fn discount_factor(&self, t: f64) -> f64 {
+    self.discount_curve().discount_factor(t)
+  }
+
+  /// Ratio of the CSA to the OIS discount factor to time t
+  pub fn adjustment(&self, t: f64) -> f64 {
+    self.discount_factor(t) / self.ois.discount_factor(t)
+  }
+
+  /// CSA price of a cash flow at t from its price discounted on the OIS curve, e.g. the
+  /// price of a pricer given the OIS rate
+  pub fn price(&self, ois_price: f64, t: f64) -> f64 {
+    ois_price * self.adjustment(t)
+  }
+}
+
+/// Default model of a party with a constant hazard rate
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Credit {
+  pub hazard: f64,
+  pub recovery: f64,
+}
+
+impl Credit {
+  /// Hazard rate implied by a CDS spread with the credit triangle lambda = s / (1 - R)
+  #[must_use]
+  pub fn from_spread(spread: f64, recovery: f64) -> Self {
+    Self {
+      hazard: spread / (1.0 - recovery),
+      recovery,
+    }
+  }
+
+  pub fn survival(&self, t: f64) -> f64 {
+    (-self.hazard * t).exp()
+  }
+
+  /// Probability of default between t1 and t2
+  pub fn default_probability(&self, t1: f64, t2: f64) -> f64 {
+    self.survival(t1) - self.survival(t2)
+  }
+}
+
+/// Simulated mark-to-market values of a netting set, one row per path and one column
+/// per exposure time, e.g. from a nested simulation
+#[derive(Debug, Clone)]
+pub struct Exposure {
+  pub times: Array1<f64>,
+  pub values: Array2<f64>,
+}
+
+impl Exposure {
+  #[must_use]
+  pub fn new(times: Array1<f64>, values: Array2<f64>) -> Self {
+    assert_eq!(
+      times.len(),
+      values.ncols(),
+      "One column of values per exposure time"
+    );
+    assert!(
+      times[0] > 0.0 && times.windows(2).into_iter().all(|w| w[0] < w[1]),
+      "Exposure times must be positive and increasing"
+    );
+    Self { times, values }
+  }
+
+  /// Exposure net of a variation margin with a threshold and a margin period of risk:
+  /// the collateral held at a time is the part of the value beyond the threshold
+  /// `lag` exposure times earlier
+  pub fn collateralized(&self, threshold: f64, lag: usize) -> Self {
+    let collateral = |v: f64| v.signum() * (v.abs() - threshold).max(0.0);
+    let values = Array2::from_shape_fn(self.values.dim(), |(i, j)| {
+      let held = if j >= lag {
+        collateral(self.values[(i, j - lag)])
+      } else {
+        0.0
+      };
+      self.values[(i, j)] - held
+    });
+    Self::new(self.times.clone(), values)
+  }
+
+  /// Expected positive exposure E[max(V, 0)] at every time
+  pub fn expected_positive(&self) -> Array1<f64> {
+    self.values.mapv(|v| v.max(0.0)).mean_axis(Axis(0)).unwrap()
+  }
+
+  /// Expected negative exposure E[min(V, 0)] at every time
+  pub fn expected_negative(&self) -> Array1<f64> {
+    self.values.mapv(|v| v.min(0.0)).mean_axis(Axis(0)).unwrap()
+  }
+
+  /// Potential future exposure, the quantile of the positive exposure at the level
+  /// (e.g. 0.95) at every time
+  pub fn potential_future(&self, level: f64) -> Array1<f64> {
+    Array1::from_iter(self.values.axis_iter(Axis(1)).map(|column| {
+      let mut sorted = column.mapv(|v| v.max(0.0)).to_vec();
+      sorted.sort_by(|a, b| a.total_cmp(b));
+      interpolate(&sorted, level)
+    }))
+  }
+}
+
+/// Valuation adjustments of a netting set, as positive costs or benefits
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Xva {
+  /// Credit valuation adjustment, the expected loss on a default of the counterparty
+  pub cva: f64,
+  /// Debit valuation adjustment, the expected gain on an own default
+  pub dva: f64,
+  /// Funding cost of the positive exposure
+  pub fca: f64,
+  /// Funding benefit of the negative exposure
+  pub fba: f64,
+}
+
+impl Xva {
+  /// Funding valuation adjustment FCA - FBA
+  pub fn fva(&self) -> f64 {
+    self.fca - self.fba
+  }
+
+  /// Total adjustment to the risk-free value, -CVA + DVA - FVA
+  pub fn total(&self) -> f64 {
+    -self.cva + self.dva - self.fva()
+  }
+}
+
+/// Unilateral CVA and DVA and the funding adjustments of an exposure profile, discounted on
+/// the OIS curve, with the funding spread of the discounting. Without collateral the profile
+/// is funded in full, otherwise it should be the `collateralized` one.
+pub fn xva(
+  exposure: &Exposure,
+  discounting: &Discounting,
+  counterparty: &Credit,
+  own: Option<&Credit>,
+) -> Xva {
+  let epe = exposure.expected_positive();
+  let ene = exposure.expected_negative();
+  let times = exposure.times.view();
+  let sum = |f: &dyn Fn(usize, f64, f64) -> f64| {
+    (0..times.len())
+      .map(|i| {
+        let t0 = if i == 0 { 0.0 } else { times[i - 1] };
+        f(i, t0, times[i]) * discounting.ois.discount_factor(times[i])
+      })
+      .sum::<f64>()
+  };
+  let survival = |credit: Option<&Credit>, t: f64| credit.map_or(1.0, |c| c.survival(t));
+  let spread = discounting.funding_spread;
+
+  Xva {
+    cva: (1.0 - counterparty.recovery)
+      * sum(&|i, t0, t1| epe[i] * counterparty.default_probability(t0, t1)),
+    dva: own.map_or(0.0, |own| {
+      -(1.0 - own.recovery) * sum(&|i, t0, t1| ene[i] * own.default_probability(t0, t1))
+    }),
+    fca: spread
+      * sum(&|i, t0, t1| epe[i] * (t1 - t0) * counterparty.survival(t1) * survival(own, t1)),
+    fba: -spread
+      * sum(&|i, t0, t1| ene[i] * (t1 - t0) * counterparty.survival(t1) * survival(own, t1)),
+  }
+}
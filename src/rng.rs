@@ -0,0 +1,384 @@
+//! # Random number generation
+//!
+//! The samplers draw their random numbers from [`thread_rng`], which is `rand::thread_rng`
+//! by default: fast, but not reproducible. After [`seed`] (or inside [`with_seed`]) the
+//! current thread switches to the deterministic mode, a ChaCha20 generator seeded by
+//! `ChaCha20Rng::seed_from_u64`. Its output stream is portable across platforms and
+//! does not change within a major version of the crate, see [`STREAM_VERSION`].
+//!
+//! In deterministic mode `sample_par` draws one seed per path from the current generator,
+//! so the paths depend neither on the number of threads nor on the scheduling.
+//!
+//! [`Generator::Philox`] (see [`seed_with`] and [`with_generator`]) switches to the
+//! counter-based [`Philox4x32`]: `sample_par` then draws a single key and path i is the
+//! stream i under that key, and [`with_stream`] runs any code on the stream
+//! (seed, index). The streams are derived without coordination, so user code that
+//! distributes paths over its own threads stays reproducible for any number of threads.
+//!
+//! [`NoiseCache`] stores the random words of a set of paths and replays them to every
+//! model run through it, the common random numbers of finite difference Greeks and
+//! calibration objectives.
+//!
+//! The FFT based samplers (fGn and the processes driven by it) also depend on the
+//! floating point operations of the FFT backend, which picks SIMD instructions at
+//! runtime, so their paths are only reproducible on machines with the same instruction set.
+
+use std::cell::RefCell;
+
+use rand::{rngs::ThreadRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+pub mod noise_cache;
+pub mod philox;
+
+pub use noise_cache::NoiseCache;
+pub use philox::Philox4x32;
+
+/// Version of the deterministic stream, it is only bumped in major releases
+pub const STREAM_VERSION: u32 = 1;
+
+thread_local! {
+  static DETERMINISTIC: RefCell<Option<Deterministic>> = const { RefCell::new(None) };
+}
+
+/// Algorithm of the deterministic mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Generator {
+  /// ChaCha20 seeded by `ChaCha20Rng::seed_from_u64`
+  #[default]
+  ChaCha20,
+  /// Philox4x32-10 with the seed as key, path i of `sample_par` is the stream i
+  Philox,
+}
+
+/// State of the deterministic mode, it lives in a thread local and is replaced per path, so
+/// it is not boxed
+#[allow(clippy::large_enum_variant)]
+enum Deterministic {
+  ChaCha20(ChaCha20Rng),
+  Philox(Philox4x32),
+  Replay(noise_cache::Replay),
+}
+
+impl Deterministic {
+  fn new(generator: Generator, seed: u64) -> Self {
+    match generator {
+      Generator::ChaCha20 => Self::ChaCha20(ChaCha20Rng::seed_from_u64(seed)),
+      Generator::Philox => Self::Philox(Philox4x32::new(seed, 0)),
+    }
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    match self {
+      Self::ChaCha20(rng) => rng.next_u64(),
+      Self::Philox(rng) => rng.next_u64(),
+      Self::Replay(rng) => rng.next_u64(),
+    }
+  }
+
+  fn rng(&mut self) -> &mut dyn RngCore {
+    match self {
+      Self::ChaCha20(rng) => rng,
+      Self::Philox(rng) => rng,
+      Self::Replay(rng) => rng,
+    }
+  }
+}
+
+/// Seed of one path of `sample_par`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PathSeed {
+  generator: Generator,
+  seed: u64,
+  stream: u64,
+}
+
+/// Generator of the samplers on the current thread
+pub enum SamplerRng {
+  /// `rand::thread_rng`
+  Thread(ThreadRng),
+  /// The seeded generator of the current thread
+  Deterministic,
+}
+
+/// Generator of the samplers on the current thread
+pub fn thread_rng() -> SamplerRng {
+  if is_deterministic() {
+    SamplerRng::Deterministic
+  } else {
+    SamplerRng::Thread(rand::thread_rng())
+  }
+}
+
+fn with_deterministic<T>(f: impl FnOnce(&mut Deterministic) -> T) -> T {
+  DETERMINISTIC.with(|rng| {
+    f(rng
+      .borrow_mut()
+      .as_mut()
+      .expect("Deterministic mode was left while sampling"))
+  })
+}
+
+impl RngCore for SamplerRng {
+  fn next_u32(&mut self) -> u32 {
+    match self {
+      Self::Thread(rng) => rng.next_u32(),
+      Self::Deterministic => with_deterministic(|rng| rng.rng().next_u32()),
+    }
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    match self {
+      Self::Thread(rng) => rng.next_u64(),
+      Self::Deterministic => with_deterministic(|rng| rng.rng().next_u64()),
+    }
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    match self {
+      Self::Thread(rng) => rng.fill_bytes(dest),
+      Self::Deterministic => with_deterministic(|rng| rng.rng().fill_bytes(dest)),
+    }
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+    match self {
+      Self::Thread(rng) => rng.try_fill_bytes(dest),
+      Self::Deterministic => with_deterministic(|rng| rng.rng().try_fill_bytes(dest)),
+    }
+  }
+}
+
+/// Switch the current thread to the deterministic mode
+pub fn seed(seed: u64) {
+  seed_with(Generator::ChaCha20, seed);
+}
+
+/// Switch the current thread to the deterministic mode with the given generator
+pub fn seed_with(generator: Generator, seed: u64) {
+  DETERMINISTIC.with(|rng| *rng.borrow_mut() = Some(Deterministic::new(generator, seed)));
+}
+
+/// Switch the current thread back to `rand::thread_rng`
+pub fn unseed() {
+  DETERMINISTIC.with(|rng| *rng.borrow_mut() = None);
+}
+
+/// Whether the current thread is in deterministic mode
+pub fn is_deterministic() -> bool {
+  DETERMINISTIC.with(|rng| rng.borrow().is_some())
+}
+
+/// Run `f` in deterministic mode with the given seed, then restore the previous generator
+pub fn with_seed<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+  with_generator(Generator::ChaCha20, seed, f)
+}
+
+/// Run `f` in deterministic mode with the given generator and seed, then restore the
+/// previous generator
+pub fn with_generator<T>(generator: Generator, seed: u64, f: impl FnOnce() -> T) -> T {
+  with_state(Deterministic::new(generator, seed), f)
+}
+
+/// Run `f` on the Philox stream `stream` under the key `seed`, then restore the previous
+/// generator. The stream of a path index is independent of the other indices and of the
+/// thread it runs on.
+pub fn with_stream<T>(seed: u64, stream: u64, f: impl FnOnce() -> T) -> T {
+  with_state(Deterministic::Philox(Philox4x32::new(seed, stream)), f)
+}
+
+fn with_state<T>(state: Deterministic, f: impl FnOnce() -> T) -> T {
+  with_state_returned(state, f).0
+}
+
+/// Previous generator of the current thread, restored on drop if `f` panics
+struct Restore(Option<Option<Deterministic>>);
+
+impl Drop for Restore {
+  fn drop(&mut self) {
+    if let Some(previous) = self.0.take() {
+      // the thread local may already be gone when the thread exits
+      let _ = DETERMINISTIC.try_with(|rng| *rng.borrow_mut() = previous);
+    }
+  }
+}
+
+/// Run `f` with the deterministic state, then restore the previous generator and hand the
+/// state back. The previous generator is also restored if `f` panics.
+fn with_state_returned<T>(state: Deterministic, f: impl FnOnce() -> T) -> (T, Deterministic) {
+  let mut restore = Restore(Some(
+    DETERMINISTIC.with(|rng| rng.borrow_mut().replace(state)),
+  ));
+  let result = f();
+  let previous = restore.0.take().flatten();
+  let state = DETERMINISTIC.with(|rng| std::mem::replace(&mut *rng.borrow_mut(), previous));
+  (
+    result,
+    state.expect("Deterministic mode was left while sampling"),
+  )
+}
+
+/// Seed of the i-th path of a run, the SplitMix64 hash of the run seed and the path index
+pub fn path_seed(seed: u64, i: u64) -> u64 {
+  let mut z = seed.wrapping_add(i.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+  z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+  z ^ (z >> 31)
+}
+
+/// Seeds of `m` paths sampled in parallel, one seed per path drawn from a ChaCha20
+/// generator, one key for all paths drawn from a Philox generator or a replayed noise
+pub(crate) fn path_seeds(m: usize) -> Option<Vec<PathSeed>> {
+  is_deterministic().then(|| {
+    with_deterministic(|rng| match rng {
+      Deterministic::ChaCha20(_) => (0..m)
+        .map(|_| PathSeed {
+          generator: Generator::ChaCha20,
+          seed: rng.next_u64(),
+          stream: 0,
+        })
+        .collect(),
+      Deterministic::Philox(_) | Deterministic::Replay(_) => {
+        let seed = rng.next_u64();
+        (0..m as u64)
+          .map(|stream| PathSeed {
+            generator: Generator::Philox,
+            seed,
+            stream,
+          })
+          .collect()
+      }
+    })
+  })
+}
+
+/// Run `f` with the path seed if there is one
+pub(crate) fn seeded<T>(seed: Option<PathSeed>, f: impl FnOnce() -> T) -> T {
+  match seed {
+    Some(PathSeed {
+      generator: Generator::ChaCha20,
+      seed,
+      ..
+    }) => with_seed(seed, f),
+    Some(PathSeed {
+      generator: Generator::Philox,
+      seed,
+      stream,
+    }) => with_stream(seed, stream, f),
+    None => f(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::Array1;
+
+  use super::*;
+  use crate::stochastic::{
+    diffusion::{gbm::GBM, ou::OU},
+    process::{bm::BM, poisson::Poisson},
+    Sampling,
+  };
+
+  fn golden(name: &str) -> Vec<u64> {
+    let path = format!("{}/tests/golden/{}.txt", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(path)
+      .unwrap()
+      .lines()
+      .map(|l| u64::from_str_radix(l, 16).unwrap())
+      .collect()
+  }
+
+  fn bits(x: &Array1<f64>) -> Vec<u64> {
+    x.iter().map(|v| v.to_bits()).collect()
+  }
+
+  #[test]
+  fn gbm_matches_golden_file() {
+    let gbm = GBM::new(&GBM {
+      mu: 0.05,
+      sigma: 0.2,
+      n: 64,
+      x0: Some(100.0),
+      t: Some(1.0),
+      ..Default::default()
+    });
+
+    assert_eq!(bits(&with_seed(42, || gbm.sample())), golden("gbm_seed_42"));
+  }
+
+  #[test]
+  fn ou_matches_golden_file() {
+    let ou = OU::new(&OU {
+      mu: 1.0,
+      sigma: 0.5,
+      theta: 2.0,
+      n: 64,
+      x0: Some(0.0),
+      t: Some(1.0),
+      m: None,
+      ..Default::default()
+    });
+
+    assert_eq!(bits(&with_seed(7, || ou.sample())), golden("ou_seed_7"));
+  }
+
+  #[test]
+  fn bm_par_matches_golden_file() {
+    let bm = BM::new(&BM {
+      n: 32,
+      t: Some(1.0),
+      m: Some(4),
+      ..Default::default()
+    });
+
+    let paths = with_seed(11, || bm.sample_par());
+    assert_eq!(
+      bits(&paths.into_shape_with_order(4 * 32).unwrap()),
+      golden("bm_par_seed_11")
+    );
+  }
+
+  #[test]
+  fn poisson_is_reproducible() {
+    let poisson = Poisson::new(&Poisson {
+      lambda: 3.0,
+      n: None,
+      t_max: Some(10.0),
+      m: None,
+    });
+
+    let first = with_seed(1, || poisson.sample());
+    let second = with_seed(1, || poisson.sample());
+    assert_eq!(bits(&first), bits(&second));
+    assert!(!is_deterministic());
+  }
+
+  #[test]
+  fn panic_restores_the_previous_generator() {
+    let result = std::panic::catch_unwind(|| with_seed(3, || panic!("sampler failed")));
+    assert!(result.is_err());
+    assert!(!is_deterministic());
+
+    seed(5);
+    let expected = with_seed(5, || {
+      BM::new(&BM {
+        n: 8,
+        ..Default::default()
+      })
+      .sample()
+    });
+    let _ = std::panic::catch_unwind(|| with_stream(9, 1, || panic!("sampler failed")));
+    assert_eq!(
+      bits(
+        &BM::new(&BM {
+          n: 8,
+          ..Default::default()
+        })
+        .sample()
+      ),
+      bits(&expected)
+    );
+    unseed();
+  }
+}
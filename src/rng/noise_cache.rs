@@ -0,0 +1,177 @@
+use ndarray::{Array2, Axis};
+use ndrustfft::Zero;
+use rand::{Error, RngCore};
+use rayon::prelude::*;
+
+use super::{with_state_returned, Deterministic, Philox4x32};
+use crate::stochastic::Sampling;
+
+/// Stored random words of one path, replayed from the start on every run and extended
+/// from the Philox stream of the path when a run needs more of them
+#[derive(Debug, Clone)]
+pub(crate) struct Replay {
+  words: Vec<u32>,
+  position: usize,
+  source: Philox4x32,
+}
+
+impl RngCore for Replay {
+  fn next_u32(&mut self) -> u32 {
+    if self.position == self.words.len() {
+      self.words.push(self.source.next_u32());
+    }
+    let word = self.words[self.position];
+    self.position += 1;
+    word
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    let lo = u64::from(self.next_u32());
+    let hi = u64::from(self.next_u32());
+    lo | hi << 32
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    for chunk in dest.chunks_mut(4) {
+      let bytes = self.next_u32().to_le_bytes();
+      chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+    self.fill_bytes(dest);
+    Ok(())
+  }
+}
+
+/// Common random numbers: the random words of `m` paths are generated once, stored, and
+/// fed again to every model run through the cache, so runs with bumped parameters see
+/// the same uniforms and normals and their differences are free of sampling noise.
+/// This is what finite difference Greeks and calibration objectives need to be smooth
+/// in the parameters.
+///
+/// Path i consumes the Philox stream i under the seed, the first run on a path stores
+/// the words it draws and later runs read them back, drawing and storing more when a
+/// model needs more of them. A run is therefore identical to [`super::with_stream`] on
+/// the same stream, and two models are coupled draw by draw as long as they consume the
+/// words in the same order, which holds for a fixed grid.
+#[derive(Debug, Clone)]
+pub struct NoiseCache {
+  seed: u64,
+  paths: Vec<Replay>,
+}
+
+impl NoiseCache {
+  /// Cache of `m` paths of the seed, the words are generated on the first run
+  pub fn new(seed: u64, m: usize) -> Self {
+    Self {
+      seed,
+      paths: (0..m as u64)
+        .map(|stream| Replay {
+          words: Vec::new(),
+          position: 0,
+          source: Philox4x32::new(seed, stream),
+        })
+        .collect(),
+    }
+  }
+
+  pub fn seed(&self) -> u64 {
+    self.seed
+  }
+
+  /// Number of paths
+  pub fn m(&self) -> usize {
+    self.paths.len()
+  }
+
+  /// Stored random words of path i
+  pub fn words(&self, i: usize) -> &[u32] {
+    &self.paths[i].words
+  }
+
+  /// Run `f` on every path in parallel with its noise, `f` receives the path index
+  pub fn run<T, F>(&mut self, f: F) -> Vec<T>
+  where
+    T: Send,
+    F: Fn(usize) -> T + Sync,
+  {
+    self
+      .paths
+      .par_iter_mut()
+      .enumerate()
+      .map(|(i, path)| {
+        // a copy, the stored path stays intact if f panics
+        let replay = Replay {
+          words: path.words.clone(),
+          position: 0,
+          source: path.source.clone(),
+        };
+        let (result, state) = with_state_returned(Deterministic::Replay(replay), || f(i));
+        if let Deterministic::Replay(replay) = state {
+          *path = replay;
+        }
+        result
+      })
+      .collect()
+  }
+
+  /// Paths of the sampler, one row per path of the cache
+  pub fn sample<T, S>(&mut self, sampler: &S) -> Array2<T>
+  where
+    T: Clone + Send + Sync + Zero,
+    S: Sampling<T>,
+  {
+    let rows = self.run(|_| sampler.sample());
+    let n = rows.first().map_or(sampler.n(), |row| row.len());
+    let mut xs = Array2::zeros((rows.len(), n));
+    for (mut x, row) in xs.axis_iter_mut(Axis(0)).zip(rows) {
+      x.assign(&row);
+    }
+    xs
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::panic::{catch_unwind, AssertUnwindSafe};
+
+  use super::*;
+  use crate::{
+    rng::{is_deterministic, with_stream},
+    stochastic::process::bm::BM,
+  };
+
+  #[test]
+  fn replays_the_streams_of_the_paths() {
+    let bm = BM::new(&BM {
+      n: 16,
+      ..Default::default()
+    });
+    let mut cache = NoiseCache::new(4, 3);
+    let first = cache.sample(&bm);
+    assert_eq!(first, cache.sample(&bm));
+    assert_eq!(first.row(2), with_stream(4, 2, || bm.sample()));
+  }
+
+  #[test]
+  fn panic_keeps_the_cache_and_the_generator() {
+    let bm = BM::new(&BM {
+      n: 16,
+      ..Default::default()
+    });
+    let mut cache = NoiseCache::new(4, 3);
+    let first = cache.sample(&bm);
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+      cache.run(|i| {
+        let path = bm.sample();
+        assert!(i != 1, "path failed");
+        path
+      })
+    }));
+    assert!(result.is_err());
+    assert!(!is_deterministic());
+    assert_eq!(first, cache.sample(&bm));
+  }
+}
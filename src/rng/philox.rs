@@ -0,0 +1,155 @@
+use rand::{Error, RngCore, SeedableRng};
+
+const M0: u32 = 0xD251_1F53;
+const M1: u32 = 0xCD9E_8D57;
+const W0: u32 = 0x9E37_79B9;
+const W1: u32 = 0xBB67_AE85;
+
+/// Philox4x32-10 counter-based generator. The block i of the stream s under the key is the
+/// bijection of the 128 bit counter (i, s), so any path index gets its own stream and any
+/// position in it is reached in constant time, without a shared state between threads.
+/// https://doi.org/10.1145/2063384.2063405 (Salmon, Moraes, Dror, Shaw 2011)
+#[derive(Debug, Clone)]
+pub struct Philox4x32 {
+  key: [u32; 2],
+  counter: [u32; 4],
+  buffer: [u32; 4],
+  index: usize,
+}
+
+impl Philox4x32 {
+  /// Stream `stream` of the generator with the key `key`, starting at block 0
+  pub fn new(key: u64, stream: u64) -> Self {
+    Self {
+      key: [key as u32, (key >> 32) as u32],
+      counter: [0, 0, stream as u32, (stream >> 32) as u32],
+      buffer: [0; 4],
+      index: 4,
+    }
+  }
+
+  /// The raw block of 4 words of the counter under the key
+  pub fn block(key: [u32; 2], counter: [u32; 4]) -> [u32; 4] {
+    let mut key = key;
+    let mut x = counter;
+    for round in 0..10 {
+      if round > 0 {
+        key[0] = key[0].wrapping_add(W0);
+        key[1] = key[1].wrapping_add(W1);
+      }
+      let p0 = u64::from(M0) * u64::from(x[0]);
+      let p1 = u64::from(M1) * u64::from(x[2]);
+      x = [
+        (p1 >> 32) as u32 ^ x[1] ^ key[0],
+        p1 as u32,
+        (p0 >> 32) as u32 ^ x[3] ^ key[1],
+        p0 as u32,
+      ];
+    }
+    x
+  }
+
+  /// Jump to the start of the block `block` of the current stream, a block holds 4 words
+  pub fn seek(&mut self, block: u64) {
+    self.counter[0] = block as u32;
+    self.counter[1] = (block >> 32) as u32;
+    self.index = 4;
+  }
+
+  /// Index of the next block of the current stream
+  pub fn position(&self) -> u64 {
+    u64::from(self.counter[0]) | u64::from(self.counter[1]) << 32
+  }
+
+  fn refill(&mut self) {
+    self.buffer = Self::block(self.key, self.counter);
+    self.seek(self.position().wrapping_add(1));
+    self.index = 0;
+  }
+}
+
+impl RngCore for Philox4x32 {
+  fn next_u32(&mut self) -> u32 {
+    if self.index == 4 {
+      self.refill();
+    }
+    let value = self.buffer[self.index];
+    self.index += 1;
+    value
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    let lo = u64::from(self.next_u32());
+    let hi = u64::from(self.next_u32());
+    lo | hi << 32
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    for chunk in dest.chunks_mut(4) {
+      let bytes = self.next_u32().to_le_bytes();
+      chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+    self.fill_bytes(dest);
+    Ok(())
+  }
+}
+
+/// The seed is the little-endian key followed by the little-endian stream
+impl SeedableRng for Philox4x32 {
+  type Seed = [u8; 16];
+
+  fn from_seed(seed: Self::Seed) -> Self {
+    let key = u64::from_le_bytes(seed[..8].try_into().unwrap());
+    let stream = u64::from_le_bytes(seed[8..].try_into().unwrap());
+    Self::new(key, stream)
+  }
+
+  fn seed_from_u64(state: u64) -> Self {
+    Self::new(state, 0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Known-answer vectors of philox4x32-10 from the Random123 distribution (kat_vectors)
+  #[test]
+  fn matches_the_random123_known_answers() {
+    let vectors = [
+      (
+        [0, 0, 0, 0],
+        [0, 0],
+        [0x6627_e8d5, 0xe169_c58d, 0xbc57_ac4c, 0x9b00_dbd8],
+      ),
+      (
+        [0xffff_ffff; 4],
+        [0xffff_ffff; 2],
+        [0x408f_276d, 0x41c8_3b0e, 0xa20b_c7c6, 0x6d54_51fd],
+      ),
+      (
+        [0x243f_6a88, 0x85a3_08d3, 0x1319_8a2e, 0x0370_7344],
+        [0xa409_3822, 0x299f_31d0],
+        [0xd16c_fe09, 0x94fd_cceb, 0x5001_e420, 0x2412_6ea1],
+      ),
+    ];
+    for (counter, key, expected) in vectors {
+      assert_eq!(Philox4x32::block(key, counter), expected);
+    }
+  }
+
+  #[test]
+  fn streams_are_the_blocks_of_their_counters() {
+    let mut rng = Philox4x32::new(0x299f_31d0_a409_3822, 7);
+    let words: Vec<u32> = (0..8).map(|_| rng.next_u32()).collect();
+    let key = [0xa409_3822, 0x299f_31d0];
+    assert_eq!(words[..4], Philox4x32::block(key, [0, 0, 7, 0]));
+    assert_eq!(words[4..], Philox4x32::block(key, [1, 0, 7, 0]));
+
+    rng.seek(1);
+    assert_eq!(rng.next_u32(), words[4]);
+  }
+}
@@ -0,0 +1,425 @@
+//! # Server
+//!
+//! REST service over the [`ModelRegistry`], for callers in other languages or on other
+//! machines. The bodies are JSON. The run configurations are those of [`crate::cli`]
+//! without the output table, and a failed request is answered with the status 400 and
+//! `{"error": message}`.
+//!
+//! - `GET /models`: names of the registered models
+//! - `POST /sample`: paths of a configuration, `{"paths": [[x_0, x_1, ...], ...]}`
+//! - `POST /price`: Monte Carlo price of the option of a configuration with a pricing
+//!   table, `{"price": ..., "std_error": ...}`
+//! - `POST /calibrate`: eSSVI or SSVI surface fitted to the option quotes of a
+//!   [`CalibrationRequest`], `{"slices": [{"tau", "theta", "rho", "psi"}, ...],
+//!   "arbitrage_free": ...}` and for SSVI also rho, eta and gamma
+//!
+//! ```json
+//! {"model": "gbm", "n": 252, "m": 10000, "seed": 42,
+//!  "params": {"mu": 0.03, "sigma": 0.2, "x0": 100.0},
+//!  "pricing": {"strike": 100.0, "rate": 0.03}}
+//! ```
+//!
+//! The work runs on the blocking pool of the tokio runtime, and a request samples at most
+//! [`MAX_POINTS`] points. The memory of a request is bounded by the paths and the shared
+//! plan cache of the fractional noise, which holds at most
+//! [`crate::stochastic::noise::fgn::PLAN_CACHE_BYTES`] and does not keep larger plans.
+//! Only REST is served, there is no gRPC endpoint.
+
+use std::{any::Any, net::SocketAddr, sync::Arc};
+
+use anyhow::Result;
+use axum::{
+  extract::State,
+  http::StatusCode,
+  response::{IntoResponse, Response},
+  routing::{get, post},
+  Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::{net::TcpListener, task};
+
+use crate::{
+  cli::{Config, Payoff, Report},
+  quant::{
+    options::chain::OptionQuote,
+    volatility::ssvi::{Essvi, Ssvi},
+    OptionType,
+  },
+  stochastic::registry::ModelRegistry,
+};
+
+/// Largest number of points (n + 1) m sampled for one request
+pub const MAX_POINTS: usize = 10_000_000;
+
+/// Option quotes to fit a volatility surface to
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CalibrationRequest {
+  /// Surface to fit, eSSVI by default
+  #[serde(default)]
+  pub surface: Surface,
+  /// Spot price
+  pub s0: f64,
+  /// Interest rate
+  #[serde(default)]
+  pub r: f64,
+  /// Dividend yield
+  #[serde(default)]
+  pub q: f64,
+  pub quotes: Vec<Quote>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Surface {
+  #[default]
+  Essvi,
+  Ssvi,
+}
+
+/// Mid price of a European option
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Quote {
+  pub strike: f64,
+  /// Time to maturity in years
+  pub tau: f64,
+  pub price: f64,
+  #[serde(default)]
+  pub payoff: Payoff,
+}
+
+/// Failed request, answered with the status and `{"error": message}`
+struct Error {
+  status: StatusCode,
+  message: String,
+}
+
+impl Error {
+  fn bad_request(message: impl Into<String>) -> Self {
+    Self {
+      status: StatusCode::BAD_REQUEST,
+      message: message.into(),
+    }
+  }
+}
+
+impl IntoResponse for Error {
+  fn into_response(self) -> Response {
+    (self.status, Json(json!({ "error": self.message }))).into_response()
+  }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+  match payload.downcast::<String>() {
+    Ok(message) => *message,
+    Err(payload) => payload.downcast_ref::<&str>().map_or_else(
+      || "The request failed".into(),
+      |message| message.to_string(),
+    ),
+  }
+}
+
+/// Run the work on the blocking pool. An error or a panic of the work is a bad request,
+/// the models assert their parameters.
+async fn blocking<R, F>(f: F) -> Result<R, Error>
+where
+  F: FnOnce() -> Result<R> + Send + 'static,
+  R: Send + 'static,
+{
+  match task::spawn_blocking(f).await {
+    Ok(result) => result.map_err(|err| Error::bad_request(format!("{:#}", err))),
+    Err(err) if err.is_panic() => Err(Error::bad_request(panic_message(err.into_panic()))),
+    Err(err) => Err(Error {
+      status: StatusCode::INTERNAL_SERVER_ERROR,
+      message: err.to_string(),
+    }),
+  }
+}
+
+/// Run a configuration of the command line as JSON
+async fn run(registry: Arc<ModelRegistry>, config: Config) -> Result<Json<Value>, Error> {
+  if config.output.path.is_some() || config.output.format.is_some() {
+    return Err(Error::bad_request(
+      "The server answers with JSON, remove the output table",
+    ));
+  }
+  if config.n.saturating_add(1).saturating_mul(config.m) > MAX_POINTS {
+    return Err(Error::bad_request(format!(
+      "A request samples at most {} points, (n + 1) m",
+      MAX_POINTS
+    )));
+  }
+
+  let report = blocking(move || config.run(&registry)).await?;
+  Ok(Json(match report {
+    Report::Paths(paths) => {
+      let paths: Vec<Vec<f64>> = paths.outer_iter().map(|path| path.to_vec()).collect();
+      json!({ "paths": paths })
+    }
+    Report::Price { price, std_error } => json!({ "price": price, "std_error": std_error }),
+  }))
+}
+
+async fn models(State(registry): State<Arc<ModelRegistry>>) -> Json<Vec<String>> {
+  Json(registry.names().map(String::from).collect())
+}
+
+async fn sample(
+  State(registry): State<Arc<ModelRegistry>>,
+  Json(config): Json<Config>,
+) -> Result<Json<Value>, Error> {
+  if config.pricing.is_some() {
+    return Err(Error::bad_request(
+      "Post configurations with a pricing table to /price",
+    ));
+  }
+  run(registry, config).await
+}
+
+async fn price(
+  State(registry): State<Arc<ModelRegistry>>,
+  Json(config): Json<Config>,
+) -> Result<Json<Value>, Error> {
+  if config.pricing.is_none() {
+    return Err(Error::bad_request("Pricing needs a pricing table"));
+  }
+  run(registry, config).await
+}
+
+async fn calibrate(Json(request): Json<CalibrationRequest>) -> Result<Json<Value>, Error> {
+  blocking(move || {
+    let quotes: Vec<OptionQuote> = request
+      .quotes
+      .iter()
+      .map(|quote| OptionQuote {
+        k: quote.strike,
+        tau: quote.tau,
+        option_type: match quote.payoff {
+          Payoff::Call => OptionType::Call,
+          Payoff::Put => OptionType::Put,
+        },
+        price: quote.price,
+        mid: quote.price,
+        ..Default::default()
+      })
+      .collect();
+    let (s0, r, q) = (request.s0, request.r, request.q);
+
+    let slices = |essvi: &Essvi| -> Vec<Value> {
+      essvi
+        .slices
+        .iter()
+        .map(|slice| {
+          json!({ "tau": slice.tau, "theta": slice.theta, "rho": slice.rho, "psi": slice.psi })
+        })
+        .collect()
+    };
+    let body = match request.surface {
+      Surface::Essvi => {
+        let essvi = Essvi::fit(&quotes, s0, r, q);
+        json!({ "slices": slices(&essvi), "arbitrage_free": essvi.is_arbitrage_free() })
+      }
+      Surface::Ssvi => {
+        let ssvi = Ssvi::fit(&quotes, s0, r, q);
+        let essvi = ssvi.essvi();
+        json!({
+          "rho": ssvi.rho,
+          "eta": ssvi.eta,
+          "gamma": ssvi.gamma,
+          "slices": slices(&essvi),
+          "arbitrage_free": essvi.is_arbitrage_free(),
+        })
+      }
+    };
+    Ok(Json(body))
+  })
+  .await
+}
+
+/// Routes of the service over the registry
+pub fn router(registry: ModelRegistry) -> Router {
+  Router::new()
+    .route("/models", get(models))
+    .route("/sample", post(sample))
+    .route("/price", post(price))
+    .route("/calibrate", post(calibrate))
+    .with_state(Arc::new(registry))
+}
+
+/// Serve the routes on the address until the process ends
+pub async fn serve(addr: SocketAddr, registry: ModelRegistry) -> Result<()> {
+  let listener = TcpListener::bind(addr).await?;
+  axum::serve(listener, router(registry)).await?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    io::{Read, Write},
+    net::TcpStream,
+  };
+
+  use tokio::runtime::Runtime;
+
+  use super::*;
+  use crate::quant::{options::bsm::BSM, r#trait::Price};
+
+  /// Server of the builtin registry on a free local port
+  fn start() -> (Runtime, SocketAddr) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+      .enable_all()
+      .build()
+      .unwrap();
+    let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+    let addr = listener.local_addr().unwrap();
+    runtime.spawn(async move {
+      axum::serve(listener, router(ModelRegistry::default()))
+        .await
+        .unwrap()
+    });
+    (runtime, addr)
+  }
+
+  /// Status and JSON body of a request
+  fn request(addr: SocketAddr, method: &str, path: &str, body: Option<Value>) -> (u16, Value) {
+    let body = body.map(|body| body.to_string()).unwrap_or_default();
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+      stream,
+      "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+       Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+      method,
+      path,
+      addr,
+      body.len(),
+      body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+  }
+
+  #[test]
+  fn lists_the_models() {
+    let (_runtime, addr) = start();
+    let (status, body) = request(addr, "GET", "/models", None);
+    assert_eq!(status, 200);
+    let names: Vec<String> = serde_json::from_value(body).unwrap();
+    assert!(names.iter().any(|name| name == "gbm"));
+  }
+
+  #[test]
+  fn samples_reproducible_paths() {
+    let (_runtime, addr) = start();
+    let config = json!({
+      "model": "gbm", "n": 8, "m": 3, "seed": 7,
+      "params": {"mu": 0.03, "sigma": 0.2, "x0": 100.0},
+    });
+    let (status, first) = request(addr, "POST", "/sample", Some(config.clone()));
+    assert_eq!(status, 200);
+    let paths: Vec<Vec<f64>> = serde_json::from_value(first["paths"].clone()).unwrap();
+    assert_eq!(paths.len(), 3);
+    assert!(paths.iter().all(|path| path.len() == 9 && path[0] == 100.0));
+
+    let (_, second) = request(addr, "POST", "/sample", Some(config));
+    assert_eq!(first, second);
+  }
+
+  #[test]
+  fn prices_a_call_near_black_scholes() {
+    let (_runtime, addr) = start();
+    let config = json!({
+      "model": "gbm", "n": 16, "m": 20000, "seed": 42,
+      "params": {"mu": 0.03, "sigma": 0.2, "x0": 100.0},
+      "pricing": {"strike": 100.0, "rate": 0.03},
+    });
+    let (status, body) = request(addr, "POST", "/price", Some(config));
+    assert_eq!(status, 200);
+    let price = body["price"].as_f64().unwrap();
+    let std_error = body["std_error"].as_f64().unwrap();
+    // Black-Scholes price of the at-the-money call
+    assert!(
+      (price - 9.413).abs() < 4.0 * std_error,
+      "{} {}",
+      price,
+      std_error
+    );
+  }
+
+  #[test]
+  fn calibrates_a_surface() {
+    let (_runtime, addr) = start();
+    let quotes: Vec<Value> = [0.25, 1.0]
+      .iter()
+      .flat_map(|&tau| {
+        [80.0, 90.0, 100.0, 110.0, 120.0].map(|strike| {
+          let price = BSM::new(&BSM {
+            s: 100.0,
+            v: 0.2,
+            k: strike,
+            tau: Some(tau),
+            ..Default::default()
+          })
+          .price();
+          json!({ "strike": strike, "tau": tau, "price": price })
+        })
+      })
+      .collect();
+    let (status, body) = request(
+      addr,
+      "POST",
+      "/calibrate",
+      Some(json!({ "s0": 100.0, "quotes": quotes })),
+    );
+    assert_eq!(status, 200);
+    let slices = body["slices"].as_array().unwrap();
+    assert_eq!(slices.len(), 2);
+    for slice in slices {
+      let tau = slice["tau"].as_f64().unwrap();
+      let theta = slice["theta"].as_f64().unwrap();
+      // flat 20% volatility, total variance 0.04 tau
+      assert!((theta / (0.04 * tau) - 1.0).abs() < 0.05, "{}", slice);
+    }
+  }
+
+  #[test]
+  fn rejects_invalid_requests() {
+    let (_runtime, addr) = start();
+    let gbm = |extra: Value| {
+      let mut config = json!({
+        "model": "gbm", "n": 8, "params": {"mu": 0.03, "sigma": 0.2, "x0": 100.0},
+      });
+      config
+        .as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+      config
+    };
+
+    for (path, config) in [
+      // unknown parameter
+      (
+        "/sample",
+        json!({ "model": "gbm", "n": 8, "params": {"mu": 0.03, "sigma": 0.2, "drift": 1.0} }),
+      ),
+      // unknown model
+      ("/sample", json!({ "model": "nope", "n": 8 })),
+      // pricing table at the wrong route and without one
+      ("/sample", gbm(json!({ "pricing": {"strike": 100.0} }))),
+      ("/price", gbm(json!({}))),
+      // too many points
+      ("/sample", gbm(json!({ "n": 1_000_000, "m": 100 }))),
+    ] {
+      let (status, body) = request(addr, "POST", path, Some(config.clone()));
+      assert_eq!(status, 400, "{} {}", path, config);
+      assert!(body["error"].is_string());
+    }
+  }
+}
@@ -1,3 +1,17 @@
+pub mod change_point;
 pub mod cir;
+pub mod comparison;
+pub mod copula;
+pub mod density;
+pub mod ergodic;
+pub mod evt;
 pub mod fd;
+pub mod fou;
+pub mod garch;
+pub mod likelihood;
 pub mod mle;
+pub mod quantile;
+pub mod rough;
+pub mod spline;
+pub mod walk_forward;
+pub mod wavelet;
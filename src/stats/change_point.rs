@@ -0,0 +1,134 @@
+use ndarray::{s, Array1, ArrayView1};
+
+/// 5% critical value of the sup of a Brownian bridge, the asymptotic
+/// threshold of the normalized CUSUM statistics
+pub const CUSUM_CRITICAL_5: f64 = 1.358;
+
+/// Quantity whose shift is detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shift {
+  /// Mean of the series
+  Mean,
+  /// Variance of the series (Inclán-Tiao)
+  Variance,
+}
+
+/// Most likely change point of a series and its normalized CUSUM statistic,
+/// the change is after `index` (the second regime starts at index + 1)
+#[derive(Default, Debug, Clone, Copy)]
+pub struct ChangePoint {
+  pub index: usize,
+  pub statistic: f64,
+}
+
+/// CUSUM test of a single shift.
+/// For the mean the statistic is max |S_k - k S_T / T| / (s sqrt(T)) with the partial sums S
+/// and the sample deviation s, for the variance it is the Inclán-Tiao statistic
+/// sqrt(T / 2) max |C_k / C_T - k / T| with the partial sums C of the squared centered values.
+/// Both converge to the sup of a Brownian bridge without a change.
+/// https://doi.org/10.1080/01621459.1994.10476824 (Inclán, Tiao)
+pub fn cusum(x: ArrayView1<f64>, shift: Shift) -> ChangePoint {
+  let n = x.len();
+  assert!(n > 2, "Series is too short");
+  let mean = x.sum() / n as f64;
+
+  let (values, scale) = match shift {
+    Shift::Mean => {
+      let sd = (x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt();
+      (x.to_owned(), sd * (n as f64).sqrt())
+    }
+    Shift::Variance => {
+      let squares = x.mapv(|v| (v - mean).powi(2));
+      let total = squares.sum();
+      (squares / total, (2.0 / n as f64).sqrt())
+    }
+  };
+
+  let total = values.sum();
+  let mut partial = 0.0;
+  let mut best = ChangePoint::default();
+
+  for k in 0..n - 1 {
+    partial += values[k];
+    let d = (partial - (k + 1) as f64 / n as f64 * total).abs() / scale.max(f64::MIN_POSITIVE);
+    if d > best.statistic {
+      best = ChangePoint {
+        index: k,
+        statistic: d,
+      };
+    }
+  }
+
+  best
+}
+
+/// Binary segmentation: the CUSUM test is applied to the series and recursively to the
+/// segments on both sides of every detected change, until no statistic exceeds the
+/// critical value or the segments are shorter than `min_size`.
+/// Returns the changes sorted by index.
+pub fn binary_segmentation(
+  x: ArrayView1<f64>,
+  shift: Shift,
+  critical: f64,
+  min_size: usize,
+) -> Vec<ChangePoint> {
+  let mut changes = Vec::new();
+  let mut segments = vec![(0, x.len())];
+
+  while let Some((start, end)) = segments.pop() {
+    if end - start < 2 * min_size.max(2) {
+      continue;
+    }
+
+    let change = cusum(x.slice(s![start..end]), shift);
+    let index = start + change.index;
+    let (left, right) = (index + 1 - start, end - index - 1);
+    if change.statistic > critical && left >= min_size && right >= min_size {
+      changes.push(ChangePoint { index, ..change });
+      segments.push((start, index + 1));
+      segments.push((index + 1, end));
+    }
+  }
+
+  changes.sort_by_key(|c| c.index);
+  changes
+}
+
+/// Hurst exponents of consecutive non-overlapping windows of a path, from the ratio of the
+/// second order quadratic variations at the lags 2 and 1
+pub fn local_hurst(path: ArrayView1<f64>, window: usize) -> Array1<f64> {
+  assert!(window > 4, "Window is too short");
+  let qv = |x: ArrayView1<f64>, k: usize| {
+    (2 * k..x.len())
+      .map(|i| (x[i] - 2.0 * x[i - k] + x[i - 2 * k]).powi(2))
+      .sum::<f64>()
+  };
+
+  Array1::from_iter(path.exact_chunks(window).into_iter().map(|w| {
+    let ratio = qv(w, 2) / qv(w, 1) * (window - 2) as f64 / (window - 4) as f64;
+    0.5 * ratio.log2()
+  }))
+}
+
+/// Changes of the Hurst exponent of a path: binary segmentation of the mean of the local
+/// Hurst exponents of non-overlapping windows. The changes are reported at the last index of
+/// the window before them, `min_windows` is the shortest regime in windows.
+pub fn hurst_change_points(
+  path: ArrayView1<f64>,
+  window: usize,
+  critical: f64,
+  min_windows: usize,
+) -> Vec<ChangePoint> {
+  binary_segmentation(
+    local_hurst(path, window).view(),
+    Shift::Mean,
+    critical,
+    min_windows,
+  )
+  .into_iter()
+  .map(|c| ChangePoint {
+    index: (c.index + 1) * window - 1,
+    ..c
+  })
+  .collect()
+}
@@ -1,9 +1,11 @@
 use num_complex::Complex64;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use rand_distr::StandardNormal;
 use scilib::math::bessel::i_nu;
 use statrs::function::gamma::gamma;
 
+use crate::rng::thread_rng;
+
 /// Cox-Ingersoll-Ross (CIR) process future value.
 pub fn sample(theta: f64, mu: f64, sigma: f64, t: f64, r_t: f64) -> f64 {
   let c = (2.0 * theta) / ((1.0 - (-theta * t).exp()) * sigma.powi(2));
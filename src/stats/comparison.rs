@@ -0,0 +1,191 @@
+use std::fmt;
+
+use ndarray::ArrayView1;
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+use crate::stochastic::TransitionDensity;
+
+/// Maximized log likelihood of a candidate model with its number of free parameters and
+/// of observations
+#[derive(Debug, Clone, Default)]
+pub struct FittedModel {
+  pub name: String,
+  pub log_likelihood: f64,
+  pub parameters: usize,
+  pub observations: usize,
+}
+
+impl FittedModel {
+  #[must_use]
+  pub fn new(name: &str, log_likelihood: f64, parameters: usize, observations: usize) -> Self {
+    Self {
+      name: name.to_string(),
+      log_likelihood,
+      parameters,
+      observations,
+    }
+  }
+
+  /// From a fitted model with a transition density and the path it was fitted to,
+  /// one observation per transition
+  pub fn from_transition_density<D: TransitionDensity>(
+    name: &str,
+    model: &D,
+    path: ArrayView1<f64>,
+    dt: f64,
+    parameters: usize,
+  ) -> Self {
+    Self::new(
+      name,
+      model.log_likelihood(path, dt),
+      parameters,
+      path.len() - 1,
+    )
+  }
+
+  /// Akaike information criterion 2k - 2 ln L
+  pub fn aic(&self) -> f64 {
+    2.0 * self.parameters as f64 - 2.0 * self.log_likelihood
+  }
+
+  /// AIC with the small sample correction 2k (k + 1) / (n - k - 1)
+  pub fn aicc(&self) -> f64 {
+    let (k, n) = (self.parameters as f64, self.observations as f64);
+    assert!(
+      n > k + 1.0,
+      "AICc needs more observations than parameters + 1"
+    );
+    self.aic() + 2.0 * k * (k + 1.0) / (n - k - 1.0)
+  }
+
+  /// Bayesian information criterion k ln n - 2 ln L
+  pub fn bic(&self) -> f64 {
+    self.parameters as f64 * (self.observations as f64).ln() - 2.0 * self.log_likelihood
+  }
+}
+
+/// Likelihood ratio test of a restricted model nested in a full one,
+/// 2 (ln L_full - ln L_restricted) ~ chi^2 with the difference of the parameters as the
+/// degrees of freedom under the restriction
+#[derive(Debug, Clone, Copy)]
+pub struct LikelihoodRatio {
+  pub statistic: f64,
+  pub degrees_of_freedom: usize,
+  pub p_value: f64,
+}
+
+pub fn likelihood_ratio_test(restricted: &FittedModel, full: &FittedModel) -> LikelihoodRatio {
+  assert!(
+    full.parameters > restricted.parameters,
+    "The full model must have more parameters than the restricted one"
+  );
+  let degrees_of_freedom = full.parameters - restricted.parameters;
+  let statistic = (2.0 * (full.log_likelihood - restricted.log_likelihood)).max(0.0);
+  let p_value = 1.0
+    - ChiSquared::new(degrees_of_freedom as f64)
+      .unwrap()
+      .cdf(statistic);
+
+  LikelihoodRatio {
+    statistic,
+    degrees_of_freedom,
+    p_value,
+  }
+}
+
+/// Row of a model comparison
+#[derive(Debug, Clone)]
+pub struct ComparisonRow {
+  pub model: FittedModel,
+  pub aic: f64,
+  pub bic: f64,
+  /// AIC minus the smallest AIC
+  pub delta_aic: f64,
+  /// Akaike weight exp(-delta_aic / 2) normalized over the models, the probability that
+  /// the model is the best of the candidates in the Kullback-Leibler sense
+  pub akaike_weight: f64,
+}
+
+/// Comparison of candidate models fitted to the same data, sorted by AIC
+#[derive(Debug, Clone)]
+pub struct Comparison {
+  pub rows: Vec<ComparisonRow>,
+}
+
+impl Comparison {
+  #[must_use]
+  pub fn new(models: Vec<FittedModel>) -> Self {
+    assert!(!models.is_empty(), "At least one model is needed");
+    assert!(
+      models
+        .iter()
+        .all(|m| m.observations == models[0].observations),
+      "The models must be fitted to the same observations"
+    );
+
+    let min_aic = models
+      .iter()
+      .map(FittedModel::aic)
+      .fold(f64::INFINITY, f64::min);
+    let total = models
+      .iter()
+      .map(|m| (-(m.aic() - min_aic) / 2.0).exp())
+      .sum::<f64>();
+
+    let mut rows = models
+      .into_iter()
+      .map(|model| {
+        let (aic, bic) = (model.aic(), model.bic());
+        ComparisonRow {
+          model,
+          aic,
+          bic,
+          delta_aic: aic - min_aic,
+          akaike_weight: (-(aic - min_aic) / 2.0).exp() / total,
+        }
+      })
+      .collect::<Vec<_>>();
+    rows.sort_by(|a, b| a.aic.total_cmp(&b.aic));
+
+    Self { rows }
+  }
+
+  /// Model with the smallest AIC
+  pub fn best_aic(&self) -> &FittedModel {
+    &self.rows[0].model
+  }
+
+  /// Model with the smallest BIC
+  pub fn best_bic(&self) -> &FittedModel {
+    &self
+      .rows
+      .iter()
+      .min_by(|a, b| a.bic.total_cmp(&b.bic))
+      .unwrap()
+      .model
+  }
+}
+
+impl fmt::Display for Comparison {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(
+      f,
+      "{:<16} {:>4} {:>14} {:>14} {:>14} {:>10} {:>8}",
+      "model", "k", "log lik", "AIC", "BIC", "dAIC", "weight"
+    )?;
+    for row in &self.rows {
+      writeln!(
+        f,
+        "{:<16} {:>4} {:>14.4} {:>14.4} {:>14.4} {:>10.4} {:>8.4}",
+        row.model.name,
+        row.model.parameters,
+        row.model.log_likelihood,
+        row.aic,
+        row.bic,
+        row.delta_aic,
+        row.akaike_weight
+      )?;
+    }
+    Ok(())
+  }
+}
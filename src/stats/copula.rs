@@ -0,0 +1,202 @@
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use quadrature::double_exponential;
+use rand::Rng;
+use rand_distr::{Distribution, Exp1, Gamma};
+
+use crate::rng::thread_rng;
+
+/// Family of an Archimedean copula
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArchimedeanFamily {
+  /// Lower tail dependence, theta > 0
+  #[default]
+  Clayton,
+  /// Upper tail dependence, theta >= 1
+  Gumbel,
+  /// No tail dependence, theta > 0
+  Frank,
+}
+
+/// Exchangeable Archimedean copula C(u) = psi(psi^-1(u_1) + ... + psi^-1(u_d)),
+/// sampled by the frailty construction of Marshall and Olkin, U_i = psi(E_i / V)
+/// with the frailty V whose Laplace transform is psi.
+/// https://doi.org/10.1080/01621459.1988.10478671 (Marshall, Olkin 1988)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Archimedean {
+  pub family: ArchimedeanFamily,
+  pub theta: f64,
+}
+
+/// Kendall's tau of a pair of samples
+pub fn kendall_tau(x: ArrayView1<f64>, y: ArrayView1<f64>) -> f64 {
+  assert_eq!(x.len(), y.len(), "Samples must have the same length");
+  let n = x.len();
+  let mut sum = 0.0;
+  for i in 0..n {
+    for j in i + 1..n {
+      sum += ((x[i] - x[j]) * (y[i] - y[j])).signum();
+    }
+  }
+  2.0 * sum / (n * (n - 1)) as f64
+}
+
+/// Ranks scaled to (0, 1) of every column, rank / (n + 1)
+pub fn pseudo_observations(x: ArrayView2<f64>) -> Array2<f64> {
+  let n = x.nrows();
+  let mut u = Array2::<f64>::zeros(x.dim());
+  for (j, column) in x.axis_iter(Axis(1)).enumerate() {
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| column[a].total_cmp(&column[b]));
+    for (rank, &i) in order.iter().enumerate() {
+      u[[i, j]] = (rank + 1) as f64 / (n + 1) as f64;
+    }
+  }
+  u
+}
+
+/// Debye function D_1(x) = int_0^x t / (e^t - 1) dt / x
+fn debye1(x: f64) -> f64 {
+  double_exponential::integrate(
+    |t| if t > 0.0 { t / t.exp_m1() } else { 1.0 },
+    0.0,
+    x,
+    1e-12,
+  )
+  .integral
+    / x
+}
+
+impl Archimedean {
+  #[must_use]
+  pub fn new(family: ArchimedeanFamily, theta: f64) -> Self {
+    match family {
+      ArchimedeanFamily::Clayton | ArchimedeanFamily::Frank => {
+        assert!(theta > 0.0, "theta must be positive")
+      }
+      ArchimedeanFamily::Gumbel => assert!(theta >= 1.0, "theta must be at least 1"),
+    }
+
+    Self { family, theta }
+  }
+
+  /// Kendall's tau of the copula
+  pub fn kendall_tau(&self) -> f64 {
+    let theta = self.theta;
+    match self.family {
+      ArchimedeanFamily::Clayton => theta / (theta + 2.0),
+      ArchimedeanFamily::Gumbel => 1.0 - 1.0 / theta,
+      ArchimedeanFamily::Frank => 1.0 - 4.0 / theta * (1.0 - debye1(theta)),
+    }
+  }
+
+  /// Copula with the given Kendall's tau in (0, 1)
+  pub fn from_kendall_tau(family: ArchimedeanFamily, tau: f64) -> Self {
+    assert!(
+      tau > 0.0 && tau < 1.0,
+      "Kendall's tau must be in (0, 1) for these families"
+    );
+    let theta = match family {
+      ArchimedeanFamily::Clayton => 2.0 * tau / (1.0 - tau),
+      ArchimedeanFamily::Gumbel => 1.0 / (1.0 - tau),
+      ArchimedeanFamily::Frank => {
+        // tau is increasing in theta
+        let (mut lo, mut hi) = (1e-8, 1e3);
+        for _ in 0..100 {
+          let mid = 0.5 * (lo + hi);
+          let copula = Self { family, theta: mid };
+          if copula.kendall_tau() < tau {
+            lo = mid;
+          } else {
+            hi = mid;
+          }
+        }
+        0.5 * (lo + hi)
+      }
+    };
+    Self::new(family, theta)
+  }
+
+  /// Fit by the inversion of the average pairwise Kendall's tau of the columns
+  pub fn fit(x: ArrayView2<f64>, family: ArchimedeanFamily) -> Self {
+    let d = x.ncols();
+    assert!(d >= 2, "At least two dimensions are needed");
+    let mut taus = Vec::new();
+    for i in 0..d {
+      for j in i + 1..d {
+        taus.push(kendall_tau(x.column(i), x.column(j)));
+      }
+    }
+    let tau = taus.iter().sum::<f64>() / taus.len() as f64;
+    Self::from_kendall_tau(family, tau.clamp(1e-6, 1.0 - 1e-6))
+  }
+
+  /// Generator psi(t), the Laplace transform of the frailty
+  pub fn generator(&self, t: f64) -> f64 {
+    let theta = self.theta;
+    match self.family {
+      ArchimedeanFamily::Clayton => (1.0 + t).powf(-1.0 / theta),
+      ArchimedeanFamily::Gumbel => (-t.powf(1.0 / theta)).exp(),
+      ArchimedeanFamily::Frank => -((-theta).exp_m1() * (-t).exp()).ln_1p() / theta,
+    }
+  }
+
+  /// Inverse generator psi^-1(u)
+  pub fn inverse_generator(&self, u: f64) -> f64 {
+    let theta = self.theta;
+    match self.family {
+      ArchimedeanFamily::Clayton => u.powf(-theta) - 1.0,
+      ArchimedeanFamily::Gumbel => (-u.ln()).powf(theta),
+      ArchimedeanFamily::Frank => -((-theta * u).exp_m1() / (-theta).exp_m1()).ln(),
+    }
+  }
+
+  pub fn cdf(&self, u: ArrayView1<f64>) -> f64 {
+    self.generator(u.iter().map(|&v| self.inverse_generator(v)).sum())
+  }
+
+  fn sample_frailty(&self, rng: &mut impl Rng) -> f64 {
+    let theta = self.theta;
+    match self.family {
+      ArchimedeanFamily::Clayton => Gamma::new(1.0 / theta, 1.0).unwrap().sample(rng),
+      ArchimedeanFamily::Gumbel => {
+        // positive stable of index 1 / theta (Chambers, Mallows, Stuck)
+        let alpha = 1.0 / theta;
+        if alpha == 1.0 {
+          return 1.0;
+        }
+        let u = std::f64::consts::PI * rng.gen::<f64>();
+        let e: f64 = Exp1.sample(rng);
+        (alpha * u).sin() / u.sin().powf(1.0 / alpha)
+          * (((1.0 - alpha) * u).sin() / e).powf((1.0 - alpha) / alpha)
+      }
+      ArchimedeanFamily::Frank => {
+        // logarithmic series with p = 1 - e^(-theta) (Kemp 1981)
+        let p = -(-theta).exp_m1();
+        let v: f64 = rng.gen();
+        if v > p {
+          return 1.0;
+        }
+        let q = -(rng.gen::<f64>() * (-theta)).exp_m1();
+        if v < q * q {
+          (1.0 + v.ln() / q.ln()).floor()
+        } else if v > q {
+          1.0
+        } else {
+          2.0
+        }
+      }
+    }
+  }
+
+  /// m draws of the d-dimensional copula, one per row
+  pub fn sample(&self, m: usize, d: usize) -> Array2<f64> {
+    let mut rng = thread_rng();
+    let mut u = Array2::<f64>::zeros((m, d));
+    for mut row in u.axis_iter_mut(Axis(0)) {
+      let v = self.sample_frailty(&mut rng);
+      let e = Array1::from_shape_fn(d, |_| Exp1.sample(&mut rng));
+      row.assign(&e.mapv(|e: f64| self.generator(e / v)));
+    }
+    u
+  }
+}
@@ -0,0 +1,195 @@
+use ndarray::{Array1, ArrayView1};
+use rayon::prelude::*;
+
+use super::quantile::quantiles;
+
+/// Kernel of the density estimate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KdeKernel {
+  #[default]
+  Gaussian,
+  /// Epanechnikov kernel 3/4 (1 - u^2) on [-1, 1]
+  Epanechnikov,
+}
+
+impl KdeKernel {
+  fn eval(&self, u: f64) -> f64 {
+    match self {
+      Self::Gaussian => (-0.5 * u * u).exp() / (2.0 * std::f64::consts::PI).sqrt(),
+      Self::Epanechnikov => {
+        if u.abs() < 1.0 {
+          0.75 * (1.0 - u * u)
+        } else {
+          0.0
+        }
+      }
+    }
+  }
+
+  fn integral(&self, u: f64) -> f64 {
+    match self {
+      Self::Gaussian => 0.5 * statrs::function::erf::erfc(-u / std::f64::consts::SQRT_2),
+      Self::Epanechnikov => {
+        let u = u.clamp(-1.0, 1.0);
+        0.5 + 0.75 * (u - u.powi(3) / 3.0)
+      }
+    }
+  }
+}
+
+/// Bandwidth selection of the density estimate
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Bandwidth {
+  /// Silverman's rule 0.9 min(s, IQR / 1.34) n^(-1/5)
+  #[default]
+  Silverman,
+  /// Scott's rule 1.06 s n^(-1/5)
+  Scott,
+  Fixed(f64),
+}
+
+/// Sample deviation and interquartile range
+fn spread(x: ArrayView1<f64>) -> (f64, f64) {
+  let q = quantiles(x, &[0.25, 0.75]);
+  (x.std(1.0), q[1] - q[0])
+}
+
+impl Bandwidth {
+  pub fn select(&self, x: ArrayView1<f64>) -> f64 {
+    let n = x.len() as f64;
+    match self {
+      Self::Silverman => {
+        let (sd, iqr) = spread(x);
+        let s = if iqr > 0.0 { sd.min(iqr / 1.34) } else { sd };
+        0.9 * s * n.powf(-0.2)
+      }
+      Self::Scott => 1.06 * x.std(1.0) * n.powf(-0.2),
+      Self::Fixed(h) => *h,
+    }
+  }
+}
+
+/// Kernel density estimate f(x) = 1 / (n h) sum K((x - x_i) / h)
+#[derive(Debug, Clone)]
+pub struct Kde {
+  pub data: Array1<f64>,
+  pub bandwidth: f64,
+  pub kernel: KdeKernel,
+}
+
+impl Kde {
+  pub fn new(data: ArrayView1<f64>, bandwidth: Bandwidth, kernel: KdeKernel) -> Self {
+    assert!(data.len() > 1, "At least two observations are needed");
+    let h = bandwidth.select(data);
+    assert!(h > 0.0, "Bandwidth must be positive");
+
+    Self {
+      data: data.to_owned(),
+      bandwidth: h,
+      kernel,
+    }
+  }
+
+  pub fn pdf(&self, x: f64) -> f64 {
+    self
+      .data
+      .iter()
+      .map(|xi| self.kernel.eval((x - xi) / self.bandwidth))
+      .sum::<f64>()
+      / (self.data.len() as f64 * self.bandwidth)
+  }
+
+  pub fn cdf(&self, x: f64) -> f64 {
+    self
+      .data
+      .iter()
+      .map(|xi| self.kernel.integral((x - xi) / self.bandwidth))
+      .sum::<f64>()
+      / self.data.len() as f64
+  }
+
+  /// Density on a grid, evaluated in parallel
+  pub fn evaluate(&self, grid: ArrayView1<f64>) -> Array1<f64> {
+    Array1::from(
+      grid
+        .to_vec()
+        .into_par_iter()
+        .map(|x| self.pdf(x))
+        .collect::<Vec<_>>(),
+    )
+  }
+}
+
+/// Selection of the number of histogram bins
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BinRule {
+  /// ceil(log2 n) + 1 bins
+  Sturges,
+  /// Width 3.49 s n^(-1/3)
+  Scott,
+  /// Width 2 IQR n^(-1/3)
+  #[default]
+  FreedmanDiaconis,
+  Count(usize),
+}
+
+/// Histogram with equal bins between the smallest and largest observation
+#[derive(Debug, Clone)]
+pub struct Histogram {
+  /// Edges of the bins, one more than the counts
+  pub edges: Array1<f64>,
+  pub counts: Array1<usize>,
+}
+
+impl Histogram {
+  pub fn new(data: ArrayView1<f64>, rule: BinRule) -> Self {
+    assert!(!data.is_empty(), "At least one observation is needed");
+    let n = data.len() as f64;
+    let min = data.fold(f64::INFINITY, |a, &b| a.min(b));
+    let max = data.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+    let range = max - min;
+
+    let from_width = |w: f64| {
+      if w > 0.0 && range > 0.0 {
+        (range / w).ceil() as usize
+      } else {
+        1
+      }
+    };
+    let bins = match rule {
+      BinRule::Sturges => n.log2().ceil() as usize + 1,
+      BinRule::Scott => from_width(3.49 * data.std(1.0) * n.powf(-1.0 / 3.0)),
+      BinRule::FreedmanDiaconis => from_width(2.0 * spread(data).1 * n.powf(-1.0 / 3.0)),
+      BinRule::Count(k) => k,
+    }
+    .max(1);
+
+    let width = if range > 0.0 {
+      range / bins as f64
+    } else {
+      1.0
+    };
+    let edges = Array1::from_shape_fn(bins + 1, |i| min + i as f64 * width);
+    let mut counts = Array1::zeros(bins);
+    for &x in data {
+      counts[(((x - min) / width) as usize).min(bins - 1)] += 1;
+    }
+
+    Self { edges, counts }
+  }
+
+  /// Centers of the bins
+  pub fn centers(&self) -> Array1<f64> {
+    Array1::from_shape_fn(self.counts.len(), |i| {
+      0.5 * (self.edges[i] + self.edges[i + 1])
+    })
+  }
+
+  /// Counts normalized to a density that integrates to one
+  pub fn density(&self) -> Array1<f64> {
+    let total = self.counts.sum() as f64;
+    Array1::from_shape_fn(self.counts.len(), |i| {
+      self.counts[i] as f64 / (total * (self.edges[i + 1] - self.edges[i]))
+    })
+  }
+}
@@ -0,0 +1,45 @@
+use ndarray::{Array1, ArrayView1};
+
+use crate::quant::greeks::Estimate;
+
+/// Long-run average of f(X) along a single path of an ergodic process, after dropping the
+/// first `burn_in` points. The standard error is from batch means: the rest of the path is
+/// cut into `batches` equal batches whose means are treated as independent, which holds
+/// when the batches are much longer than the correlation time of the process.
+pub fn ergodic_average<F>(path: ArrayView1<f64>, f: F, burn_in: usize, batches: usize) -> Estimate
+where
+  F: Fn(f64) -> f64,
+{
+  assert!(batches >= 2, "At least two batches are needed");
+  assert!(
+    path.len() >= burn_in + batches,
+    "Path is too short for the burn-in and the batches"
+  );
+  let size = (path.len() - burn_in) / batches;
+  let kept = path.slice(ndarray::s![path.len() - size * batches..]);
+
+  let means = Array1::from_iter(
+    kept
+      .exact_chunks(size)
+      .into_iter()
+      .map(|batch| batch.iter().map(|&x| f(x)).sum::<f64>() / size as f64),
+  );
+
+  Estimate::from_samples(means.view())
+}
+
+/// Integrated autocorrelation time of f(X) implied by the batch means,
+/// the ratio of the batch means variance to the naive variance of the mean
+pub fn autocorrelation_time<F>(path: ArrayView1<f64>, f: F, burn_in: usize, batches: usize) -> f64
+where
+  F: Fn(f64) -> f64,
+{
+  let estimate = ergodic_average(path, &f, burn_in, batches);
+  let size = (path.len() - burn_in) / batches;
+  let values = path
+    .slice(ndarray::s![path.len() - size * batches..])
+    .mapv(f);
+  let naive = values.var(1.0) / values.len() as f64;
+
+  estimate.std_error.powi(2) / naive
+}
@@ -0,0 +1,292 @@
+use ndarray::{Array1, ArrayView1};
+
+use crate::quant::calibration::{
+  simplex::{nelder_mead, nelder_mead_with_steps},
+  transform::{ParameterSpace, Transform},
+};
+
+/// Shape parameters below this are treated as zero (the exponential and Gumbel limits)
+const XI_EPS: f64 = 1e-8;
+
+/// Generalized Pareto distribution of the exceedances over a threshold
+/// F(y) = 1 - (1 + xi y / beta)^(-1 / xi)
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Gpd {
+  /// Shape, the tail index is 1 / xi for xi > 0
+  pub xi: f64,
+  /// Scale
+  pub beta: f64,
+}
+
+impl Gpd {
+  pub fn cdf(&self, y: f64) -> f64 {
+    if self.xi.abs() < XI_EPS {
+      1.0 - (-y / self.beta).exp()
+    } else {
+      1.0
+        - (1.0 + self.xi * y / self.beta)
+          .max(0.0)
+          .powf(-1.0 / self.xi)
+    }
+  }
+
+  pub fn quantile(&self, p: f64) -> f64 {
+    if self.xi.abs() < XI_EPS {
+      -self.beta * (1.0 - p).ln()
+    } else {
+      self.beta / self.xi * ((1.0 - p).powf(-self.xi) - 1.0)
+    }
+  }
+
+  fn negative_log_likelihood(&self, y: ArrayView1<f64>) -> f64 {
+    if self.beta <= 0.0 {
+      return f64::INFINITY;
+    }
+
+    let n = y.len() as f64;
+    if self.xi.abs() < XI_EPS {
+      return n * self.beta.ln() + y.sum() / self.beta;
+    }
+
+    let mut sum = 0.0;
+    for &v in y {
+      let z = 1.0 + self.xi * v / self.beta;
+      if z <= 0.0 {
+        return f64::INFINITY;
+      }
+      sum += z.ln();
+    }
+    n * self.beta.ln() + (1.0 + 1.0 / self.xi) * sum
+  }
+
+  /// Maximum likelihood fit to exceedances, started from the method of moments
+  pub fn fit(exceedances: ArrayView1<f64>) -> Self {
+    assert!(
+      exceedances.len() > 2,
+      "At least three exceedances are needed"
+    );
+    let mean = exceedances.mean().unwrap();
+    let var = exceedances.var(1.0);
+    let ratio = mean.powi(2) / var;
+    let space = ParameterSpace::new(vec![Transform::Identity, Transform::Positive]);
+    let start = [0.5 * (1.0 - ratio), 0.5 * mean * (ratio + 1.0)];
+
+    let best = nelder_mead(
+      space.objective(|x| {
+        Self {
+          xi: x[0],
+          beta: x[1],
+        }
+        .negative_log_likelihood(exceedances)
+      }),
+      &space.unconstrain(&start),
+      0.1,
+      2000,
+      1e-10,
+    );
+
+    let x = space.constrain(&best.params);
+    Self {
+      xi: x[0],
+      beta: x[1],
+    }
+  }
+}
+
+/// Peaks-over-threshold model: the exceedances of the threshold u are GPD and occur with
+/// the probability zeta = k / n
+#[derive(Default, Debug, Clone, Copy)]
+pub struct PeaksOverThreshold {
+  pub threshold: f64,
+  pub gpd: Gpd,
+  /// Number of exceedances
+  pub exceedances: usize,
+  /// Number of observations
+  pub observations: usize,
+}
+
+impl PeaksOverThreshold {
+  /// Fit to the observations above the threshold
+  pub fn fit(x: ArrayView1<f64>, threshold: f64) -> Self {
+    let exceedances =
+      Array1::from_iter(x.iter().filter(|&&v| v > threshold).map(|v| v - threshold));
+
+    Self {
+      threshold,
+      gpd: Gpd::fit(exceedances.view()),
+      exceedances: exceedances.len(),
+      observations: x.len(),
+    }
+  }
+
+  /// Fit with the threshold at the empirical quantile q, e.g. 0.95
+  pub fn fit_quantile(x: ArrayView1<f64>, q: f64) -> Self {
+    let mut sorted = x.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let threshold = sorted[((q * sorted.len() as f64) as usize).min(sorted.len() - 1)];
+    Self::fit(x, threshold)
+  }
+
+  fn zeta(&self) -> f64 {
+    self.exceedances as f64 / self.observations as f64
+  }
+
+  /// Level exceeded on average once every `period` observations
+  pub fn return_level(&self, period: f64) -> f64 {
+    let m = period * self.zeta();
+    assert!(
+      m >= 1.0,
+      "Return period is inside the body of the distribution"
+    );
+    let Gpd { xi, beta } = self.gpd;
+
+    if xi.abs() < XI_EPS {
+      self.threshold + beta * m.ln()
+    } else {
+      self.threshold + beta / xi * (m.powf(xi) - 1.0)
+    }
+  }
+
+  /// Value at risk, the quantile at the level p above 1 - zeta
+  pub fn value_at_risk(&self, p: f64) -> f64 {
+    self.return_level(1.0 / (1.0 - p))
+  }
+
+  /// Expected shortfall at the level p, finite for xi < 1
+  pub fn expected_shortfall(&self, p: f64) -> f64 {
+    let Gpd { xi, beta } = self.gpd;
+    assert!(xi < 1.0, "Expected shortfall is infinite for xi >= 1");
+    let var = self.value_at_risk(p);
+    (var + beta - xi * self.threshold) / (1.0 - xi)
+  }
+}
+
+/// Generalized extreme value distribution of block maxima
+/// F(z) = exp(-(1 + xi (z - mu) / sigma)^(-1 / xi))
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Gev {
+  /// Location
+  pub mu: f64,
+  /// Scale
+  pub sigma: f64,
+  /// Shape
+  pub xi: f64,
+}
+
+impl Gev {
+  pub fn cdf(&self, z: f64) -> f64 {
+    let s = (z - self.mu) / self.sigma;
+    if self.xi.abs() < XI_EPS {
+      (-(-s).exp()).exp()
+    } else {
+      (-(1.0 + self.xi * s).max(0.0).powf(-1.0 / self.xi)).exp()
+    }
+  }
+
+  pub fn quantile(&self, p: f64) -> f64 {
+    let y = -p.ln();
+    if self.xi.abs() < XI_EPS {
+      self.mu - self.sigma * y.ln()
+    } else {
+      self.mu + self.sigma / self.xi * (y.powf(-self.xi) - 1.0)
+    }
+  }
+
+  /// Level exceeded on average once every `period` blocks
+  pub fn return_level(&self, period: f64) -> f64 {
+    assert!(period > 1.0, "Return period must be longer than one block");
+    self.quantile(1.0 - 1.0 / period)
+  }
+
+  fn negative_log_likelihood(&self, z: ArrayView1<f64>) -> f64 {
+    if self.sigma <= 0.0 {
+      return f64::INFINITY;
+    }
+
+    let mut nll = z.len() as f64 * self.sigma.ln();
+    for &v in z {
+      let s = (v - self.mu) / self.sigma;
+      if self.xi.abs() < XI_EPS {
+        nll += s + (-s).exp();
+      } else {
+        let t = 1.0 + self.xi * s;
+        if t <= 0.0 {
+          return f64::INFINITY;
+        }
+        nll += (1.0 + 1.0 / self.xi) * t.ln() + t.powf(-1.0 / self.xi);
+      }
+    }
+    nll
+  }
+
+  /// Maximum likelihood fit to block maxima, started from the Gumbel moments
+  pub fn fit(maxima: ArrayView1<f64>) -> Self {
+    assert!(maxima.len() > 2, "At least three maxima are needed");
+    let sigma = (6.0 * maxima.var(1.0)).sqrt() / std::f64::consts::PI;
+    let mu = maxima.mean().unwrap() - 0.577_215_664_901_532_9 * sigma;
+
+    let space = ParameterSpace::new(vec![
+      Transform::Identity,
+      Transform::Positive,
+      Transform::Identity,
+    ]);
+
+    // mu moves on the scale of the data, log sigma and xi on the unit scale
+    let best = nelder_mead_with_steps(
+      space.objective(|x| {
+        Self {
+          mu: x[0],
+          sigma: x[1],
+          xi: x[2],
+        }
+        .negative_log_likelihood(maxima)
+      }),
+      &space.unconstrain(&[mu, sigma, 0.1]),
+      &[0.1 * sigma.max(1e-3), 0.1, 0.1],
+      5000,
+      1e-10,
+    );
+
+    let x = space.constrain(&best.params);
+    Self {
+      mu: x[0],
+      sigma: x[1],
+      xi: x[2],
+    }
+  }
+}
+
+/// Maxima of consecutive blocks of the given size, an incomplete last block is dropped
+pub fn block_maxima(x: ArrayView1<f64>, block: usize) -> Array1<f64> {
+  assert!(block > 0, "Block size must be positive");
+  Array1::from_iter(
+    x.exact_chunks(block)
+      .into_iter()
+      .map(|b| b.fold(f64::NEG_INFINITY, |a, &v| a.max(v))),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::Rng;
+
+  use super::*;
+  use crate::rng::{thread_rng, with_seed};
+
+  #[test]
+  fn gev_fit_recovers_parameters_on_the_scale_of_the_data() {
+    let (mu, sigma, xi) = (100.0, 20.0, 0.1);
+    let maxima = with_seed(3, || {
+      let mut rng = thread_rng();
+      Array1::from_shape_fn(5000, |_| {
+        let u = rng.gen::<f64>();
+        mu + sigma * ((-u.ln()).powf(-xi) - 1.0) / xi
+      })
+    });
+
+    let fit = Gev::fit(maxima.view());
+    assert!((fit.mu - mu).abs() < 1.5, "{}", fit.mu);
+    assert!((fit.sigma / sigma - 1.0).abs() < 0.05, "{}", fit.sigma);
+    assert!((fit.xi - xi).abs() < 0.05, "{}", fit.xi);
+  }
+}
@@ -0,0 +1,96 @@
+use ndarray::ArrayView1;
+use rayon::prelude::*;
+use statrs::function::gamma::gamma;
+
+use crate::{
+  rng::{path_seed, with_seed},
+  stochastic::{diffusion::fou::FOU, Sampling},
+};
+
+/// Parameters of the fractional Ornstein-Uhlenbeck process
+/// dX(t) = theta (mu - X(t)) dt + sigma dB_H(t)
+#[derive(Default, Debug, Clone, Copy)]
+pub struct FouParams {
+  pub hurst: f64,
+  pub theta: f64,
+  pub sigma: f64,
+  pub mu: f64,
+}
+
+/// Sum of the squared second order differences of the path at the lag k
+fn quadratic_variation(x: ArrayView1<f64>, k: usize) -> f64 {
+  (2 * k..x.len())
+    .map(|i| (x[i] - 2.0 * x[i - k] + x[i - 2 * k]).powi(2))
+    .sum::<f64>()
+    / (x.len() - 2 * k) as f64
+}
+
+/// Joint estimate of the fOU parameters from a path observed every dt.
+/// H is the ratio of the second order quadratic variations at the lags 2 and 1,
+/// sigma follows from E[(X(t + 2dt) - 2X(t + dt) + X(t))^2] ~ sigma^2 (4 - 2^2H) dt^2H, and
+/// theta from the ergodic variance sigma^2 Gamma(2H + 1) / (2 theta^2H) of the sample.
+/// The estimators are consistent as the horizon grows and the step shrinks.
+/// https://doi.org/10.1007/s11203-013-9085-2 (Brouste, Iacus)
+pub fn estimate_fou(path: ArrayView1<f64>, dt: f64) -> FouParams {
+  assert!(path.len() > 4, "Path is too short");
+  assert!(dt > 0.0, "Time step must be positive");
+
+  let v1 = quadratic_variation(path, 1);
+  let v2 = quadratic_variation(path, 2);
+  let hurst = (0.5 * (v2 / v1).log2()).clamp(0.01, 0.99);
+  let sigma = (v1 / ((4.0 - 2f64.powf(2.0 * hurst)) * dt.powf(2.0 * hurst))).sqrt();
+
+  let n = path.len() as f64;
+  let mu = path.sum() / n;
+  let variance = path.iter().map(|x| (x - mu).powi(2)).sum::<f64>() / n;
+  let theta = (2.0 * variance / (sigma.powi(2) * gamma(2.0 * hurst + 1.0))).powf(-0.5 / hurst);
+
+  FouParams {
+    hurst,
+    theta,
+    sigma,
+    mu,
+  }
+}
+
+/// Estimate with a parametric bootstrap bias correction: the path is re-simulated
+/// `simulations` times at the first estimate, and the mean bias of the estimates
+/// on the simulated paths is subtracted
+pub fn estimate_fou_corrected(
+  path: ArrayView1<f64>,
+  dt: f64,
+  simulations: usize,
+  seed: u64,
+) -> FouParams {
+  assert!(simulations > 0, "At least one simulation is needed");
+  let first = estimate_fou(path, dt);
+  let n = path.len();
+  let fou = FOU::new(&FOU {
+    hurst: first.hurst,
+    mu: first.mu,
+    sigma: first.sigma,
+    theta: first.theta,
+    n,
+    x0: Some(path[0]),
+    t: Some(dt * n as f64),
+    m: None,
+    ..Default::default()
+  });
+
+  let estimates = (0..simulations)
+    .into_par_iter()
+    .map(|i| {
+      with_seed(path_seed(seed, i as u64), || {
+        estimate_fou(fou.sample().view(), dt)
+      })
+    })
+    .collect::<Vec<_>>();
+  let mean = |f: fn(&FouParams) -> f64| estimates.iter().map(f).sum::<f64>() / simulations as f64;
+
+  FouParams {
+    hurst: (2.0 * first.hurst - mean(|p| p.hurst)).clamp(0.01, 0.99),
+    theta: (2.0 * first.theta - mean(|p| p.theta)).max(f64::MIN_POSITIVE),
+    sigma: (2.0 * first.sigma - mean(|p| p.sigma)).max(f64::MIN_POSITIVE),
+    mu: 2.0 * first.mu - mean(|p| p.mu),
+  }
+}
@@ -0,0 +1,114 @@
+use ndarray::{Array1, ArrayView1};
+
+use crate::quant::calibration::{
+  simplex::nelder_mead,
+  transform::{ParameterSpace, Transform},
+};
+
+/// GARCH(1, 1) returns r_t = mu + sigma_t z_t with the conditional variance
+/// sigma_t^2 = omega + alpha (r_(t-1) - mu)^2 + beta sigma_(t-1)^2
+/// https://doi.org/10.1016/0304-4076(86)90063-1 (Bollerslev 1986)
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Garch {
+  pub mu: f64,
+  pub omega: f64,
+  pub alpha: f64,
+  pub beta: f64,
+}
+
+impl Garch {
+  /// Long-run variance omega / (1 - alpha - beta)
+  pub fn unconditional_variance(&self) -> f64 {
+    assert!(
+      self.alpha + self.beta < 1.0,
+      "The process is not covariance stationary"
+    );
+    self.omega / (1.0 - self.alpha - self.beta)
+  }
+
+  /// Conditional variances of the returns, started from the sample variance, with the
+  /// forecast of the next return as the last value (n + 1 values for n returns)
+  pub fn variances(&self, returns: ArrayView1<f64>) -> Array1<f64> {
+    let mut variances = Array1::<f64>::zeros(returns.len() + 1);
+    variances[0] = returns.var(0.0);
+    for t in 1..=returns.len() {
+      variances[t] =
+        self.omega + self.alpha * (returns[t - 1] - self.mu).powi(2) + self.beta * variances[t - 1];
+    }
+    variances
+  }
+
+  /// Standardized residuals (r_t - mu) / sigma_t
+  pub fn residuals(&self, returns: ArrayView1<f64>) -> Array1<f64> {
+    let variances = self.variances(returns);
+    Array1::from_shape_fn(returns.len(), |t| {
+      (returns[t] - self.mu) / variances[t].sqrt()
+    })
+  }
+
+  /// Gaussian negative log likelihood up to a constant
+  pub fn negative_log_likelihood(&self, returns: ArrayView1<f64>) -> f64 {
+    let variances = self.variances(returns);
+    0.5
+      * returns
+        .iter()
+        .zip(variances.iter())
+        .map(|(r, v)| v.ln() + (r - self.mu).powi(2) / v)
+        .sum::<f64>()
+  }
+
+  /// Gaussian log likelihood, for the comparison with other models
+  pub fn log_likelihood(&self, returns: ArrayView1<f64>) -> f64 {
+    -self.negative_log_likelihood(returns)
+      - 0.5 * returns.len() as f64 * (2.0 * std::f64::consts::PI).ln()
+  }
+
+  /// Gaussian quasi maximum likelihood estimate, with omega > 0 and
+  /// alpha, beta >= 0, alpha + beta < 1 imposed by the parametrization
+  /// (mu, omega, alpha + beta, alpha / (alpha + beta))
+  pub fn fit(returns: ArrayView1<f64>) -> Self {
+    assert!(returns.len() > 10, "At least ten returns are needed");
+    let mean = returns.mean().unwrap();
+    let var = returns.var(0.0);
+    let space = ParameterSpace::new(vec![
+      Transform::Identity,
+      Transform::Positive,
+      Transform::Bounded(0.0, 1.0),
+      Transform::Bounded(0.0, 1.0),
+    ]);
+    let from = |x: &[f64]| Self {
+      mu: x[0],
+      omega: x[1],
+      alpha: x[2] * x[3],
+      beta: x[2] * (1.0 - x[3]),
+    };
+
+    let best = nelder_mead(
+      space.objective(|x| {
+        let nll = from(x).negative_log_likelihood(returns);
+        if nll.is_finite() {
+          nll
+        } else {
+          f64::MAX
+        }
+      }),
+      &space.unconstrain(&[mean, 0.05 * var, 0.95, 0.05 / 0.95]),
+      0.5,
+      5000,
+      1e-12,
+    );
+
+    from(&space.constrain(&best.params))
+  }
+
+  /// Returns driven by the standardized innovations, starting from the conditional
+  /// variance of the first return
+  pub fn simulate(&self, innovations: ArrayView1<f64>, variance: f64) -> Array1<f64> {
+    let mut v = variance;
+    innovations.mapv(|z| {
+      let shock = v.sqrt() * z;
+      v = self.omega + self.alpha * shock * shock + self.beta * v;
+      self.mu + shock
+    })
+  }
+}
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use ndarray::ArrayView1;
+use statrs::function::gamma::ln_gamma;
+
+use crate::{
+  quant::calibration::global::{DifferentialEvolution, Minimum},
+  stochastic::TransitionDensity,
+};
+
+/// Logarithm of the modified Bessel function I_nu(z) for nu > -1 and z > 0, from the power
+/// series summed outward from its largest term, so it does not overflow for large z
+pub(crate) fn ln_bessel_i(nu: f64, z: f64) -> f64 {
+  assert!(nu > -1.0 && z > 0.0, "ln I_nu(z) needs nu > -1 and z > 0");
+  let half = 0.5 * z;
+  let log_term = |k: f64| (2.0 * k + nu) * half.ln() - ln_gamma(k + 1.0) - ln_gamma(k + nu + 1.0);
+  // the terms grow while (z / 2)^2 > (k + 1) (k + 1 + nu)
+  let peak = ((-(nu + 2.0) + (nu * nu + z * z).sqrt()) / 2.0)
+    .max(0.0)
+    .floor();
+  let top = log_term(peak);
+
+  let mut sum = 1.0;
+  let mut term = 1.0;
+  let mut k = peak;
+  loop {
+    term *= half * half / ((k + 1.0) * (k + 1.0 + nu));
+    sum += term;
+    k += 1.0;
+    if term < 1e-17 * sum {
+      break;
+    }
+  }
+
+  let mut term = 1.0;
+  let mut k = peak;
+  while k > 0.0 {
+    term *= k * (k + nu) / (half * half);
+    sum += term;
+    k -= 1.0;
+    if term < 1e-17 * sum {
+      break;
+    }
+  }
+
+  top + sum.ln()
+}
+
+/// Coefficient of a time-homogeneous one-dimensional diffusion
+pub type Coefficient = Arc<dyn Fn(f64) -> f64 + Send + Sync>;
+
+const GAUSS_NODES: [f64; 4] = [
+  0.183_434_642_495_649_8,
+  0.525_532_409_916_329,
+  0.796_666_477_413_626_7,
+  0.960_289_856_497_536_3,
+];
+const GAUSS_WEIGHTS: [f64; 4] = [
+  0.362_683_783_378_362,
+  0.313_706_645_877_887_3,
+  0.222_381_034_453_374_5,
+  0.101_228_536_290_376_3,
+];
+
+/// Closed-form expansion of the transition density of dX = mu(X) dt + sigma(X) dW to the
+/// second order in dt. The Lamperti transform Y = int dx / sigma(x) has unit diffusion and
+/// the log density of Y is expanded as
+/// -ln(2 pi dt) / 2 - (y - y0)^2 / (2 dt) + C0 + C1 dt + C2 dt^2 / 2.
+/// The integrals are computed by Gauss-Legendre quadrature and the derivatives of the
+/// coefficients by central differences.
+/// https://doi.org/10.1111/1468-0262.00274 (Aït-Sahalia 2002)
+#[derive(Clone)]
+pub struct AitSahalia {
+  pub drift: Coefficient,
+  pub diffusion: Coefficient,
+}
+
+impl AitSahalia {
+  #[must_use]
+  pub fn new(
+    drift: impl Fn(f64) -> f64 + Send + Sync + 'static,
+    diffusion: impl Fn(f64) -> f64 + Send + Sync + 'static,
+  ) -> Self {
+    Self {
+      drift: Arc::new(drift),
+      diffusion: Arc::new(diffusion),
+    }
+  }
+
+  fn derivative(f: impl Fn(f64) -> f64, x: f64) -> f64 {
+    let h = 1e-5 * x.abs().max(1.0);
+    (f(x + h) - f(x - h)) / (2.0 * h)
+  }
+
+  /// Drift of the Lamperti transform mu / sigma - sigma' / 2 at x
+  fn drift_y(&self, x: f64) -> f64 {
+    (self.drift)(x) / (self.diffusion)(x) - 0.5 * Self::derivative(|v| (self.diffusion)(v), x)
+  }
+
+  /// lambda = -(mu_Y^2 + dmu_Y / dy) / 2 at x
+  fn lambda(&self, x: f64) -> f64 {
+    let mu = self.drift_y(x);
+    let dmu = (self.diffusion)(x) * Self::derivative(|v| self.drift_y(v), x);
+    -0.5 * (mu * mu + dmu)
+  }
+
+  /// int_x0^x1 f(x) / sigma(x) dx
+  fn integral(&self, f: impl Fn(f64) -> f64, x0: f64, x1: f64) -> f64 {
+    let (mid, half) = (0.5 * (x0 + x1), 0.5 * (x1 - x0));
+    GAUSS_NODES
+      .iter()
+      .zip(GAUSS_WEIGHTS)
+      .map(|(&node, weight)| {
+        [mid - half * node, mid + half * node]
+          .iter()
+          .map(|&x| weight * f(x) / (self.diffusion)(x))
+          .sum::<f64>()
+      })
+      .sum::<f64>()
+      * half
+  }
+}
+
+impl TransitionDensity for AitSahalia {
+  fn log_density(&self, x0: f64, x1: f64, dt: f64) -> f64 {
+    let sigma1 = (self.diffusion)(x1);
+    if !(sigma1 > 0.0 && (self.diffusion)(x0) > 0.0) {
+      return f64::NEG_INFINITY;
+    }
+
+    let u = self.integral(|_| 1.0, x0, x1);
+    let c0 = self.integral(|x| self.drift_y(x), x0, x1);
+    let (c1, c2) = if u.abs() < 1e-6 {
+      (self.lambda(x0), 0.0)
+    } else {
+      let c1 = self.integral(|x| self.lambda(x), x0, x1) / u;
+      (c1, (self.lambda(x0) + self.lambda(x1) - 2.0 * c1) / (u * u))
+    };
+
+    -0.5 * (2.0 * std::f64::consts::PI * dt).ln() - sigma1.ln() - u * u / (2.0 * dt)
+      + c0
+      + c1 * dt
+      + 0.5 * c2 * dt * dt
+  }
+}
+
+/// Maximum likelihood estimate of the parameters of a model with a transition density from a
+/// path observed every dt, `model` builds the model from a parameter vector within the
+/// bounds of the optimizer. The value of the minimum is the negative log likelihood.
+pub fn fit_mle<D, F>(
+  path: ArrayView1<f64>,
+  dt: f64,
+  model: F,
+  optimizer: &DifferentialEvolution,
+) -> Minimum
+where
+  D: TransitionDensity,
+  F: Fn(&[f64]) -> D + Sync,
+{
+  assert!(path.len() > 1, "At least two observations are needed");
+  optimizer.minimize(|x| {
+    let nll = -model(x).log_likelihood(path, dt);
+    if nll.is_finite() {
+      nll
+    } else {
+      f64::MAX
+    }
+  })
+}
@@ -1,14 +1,16 @@
+use ndarray::{array, Array1, ArrayView1};
+
 /// Maximum likelihood estimation for Heston model
 /// http://scis.scichina.com/en/2018/042202.pdf
 ///
 /// # Arguments
-/// s: Vec<f64> - stock prices
-/// v: Vec<f64> - volatility
+/// s: ArrayView1<f64> - stock prices
+/// v: ArrayView1<f64> - volatility
 /// r: f64 - risk-free rate
 ///
 /// # Returns
-/// Vec<f64> - estimated parameters
-pub fn nmle_heston(s: Vec<f64>, v: Vec<f64>, r: f64) -> Vec<f64> {
+/// Array1<f64> - estimated parameters
+pub fn nmle_heston(s: ArrayView1<f64>, v: ArrayView1<f64>, r: f64) -> Array1<f64> {
   let n = v.len();
   let delta = 1.0 / n as f64;
   let mut sum = [0.0; 4];
@@ -56,11 +58,5 @@ pub fn nmle_heston(s: Vec<f64>, v: Vec<f64>, r: f64) -> Vec<f64> {
 
   let rho_hat = sum_dw1dw2 / (n as f64 * delta);
 
-  vec![
-    *v.first().unwrap(),
-    theta_hat,
-    rho_hat,
-    kappa_hat,
-    sigma_hat,
-  ]
+  array![v[0], theta_hat, rho_hat, kappa_hat, sigma_hat,]
 }
@@ -0,0 +1,194 @@
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rayon::prelude::*;
+
+/// Linear interpolation between the order statistics of a sorted sample
+/// at the position p (n - 1) (the default definition of R and numpy)
+pub(crate) fn interpolate(sorted: &[f64], p: f64) -> f64 {
+  assert!((0.0..=1.0).contains(&p), "Probability must be in [0, 1]");
+  let h = p * (sorted.len() - 1) as f64;
+  let i = h.floor() as usize;
+  if i + 1 >= sorted.len() {
+    sorted[sorted.len() - 1]
+  } else {
+    sorted[i] + (h - i as f64) * (sorted[i + 1] - sorted[i])
+  }
+}
+
+/// Quantile of a sample by selection in linear time
+pub fn quantile(x: ArrayView1<f64>, p: f64) -> f64 {
+  assert!(!x.is_empty(), "At least one observation is needed");
+  assert!((0.0..=1.0).contains(&p), "Probability must be in [0, 1]");
+  let mut values = x.to_vec();
+  let h = p * (values.len() - 1) as f64;
+  let i = h.floor() as usize;
+  let (_, &mut lower, upper) = values.select_nth_unstable_by(i, |a, b| a.total_cmp(b));
+
+  match upper.iter().copied().min_by(|a, b| a.total_cmp(b)) {
+    Some(next) => lower + (h - i as f64) * (next - lower),
+    None => lower,
+  }
+}
+
+/// Several quantiles of a sample with a single sort
+pub fn quantiles(x: ArrayView1<f64>, probabilities: &[f64]) -> Array1<f64> {
+  assert!(!x.is_empty(), "At least one observation is needed");
+  let mut sorted = x.to_vec();
+  sorted.sort_unstable_by(|a, b| a.total_cmp(b));
+  Array1::from_iter(probabilities.iter().map(|&p| interpolate(&sorted, p)))
+}
+
+/// Quantiles across a batch of paths (one path per row) at every time step,
+/// one row per probability
+pub fn path_quantiles(paths: ArrayView2<f64>, probabilities: &[f64]) -> Array2<f64> {
+  let columns = paths
+    .axis_iter(Axis(1))
+    .into_par_iter()
+    .map(|column| quantiles(column, probabilities))
+    .collect::<Vec<_>>();
+
+  Array2::from_shape_fn((probabilities.len(), columns.len()), |(i, j)| columns[j][i])
+}
+
+/// Streaming quantile sketch (merging t-digest): the sample is summarized by weighted
+/// centroids, small near the tails, with the scale function
+/// k(q) = compression / (2 pi) asin(2q - 1).
+/// Digests of separate batches can be merged.
+/// https://doi.org/10.1016/j.simpa.2020.100049 (Dunning)
+#[derive(Debug, Clone)]
+pub struct TDigest {
+  pub compression: f64,
+  /// Centroids (mean, weight) sorted by the mean
+  centroids: Vec<(f64, f64)>,
+  buffer: Vec<(f64, f64)>,
+  count: f64,
+  min: f64,
+  max: f64,
+}
+
+impl Default for TDigest {
+  fn default() -> Self {
+    Self::new(100.0)
+  }
+}
+
+impl TDigest {
+  pub fn new(compression: f64) -> Self {
+    assert!(compression >= 10.0, "Compression is too small");
+    Self {
+      compression,
+      centroids: Vec::new(),
+      buffer: Vec::new(),
+      count: 0.0,
+      min: f64::INFINITY,
+      max: f64::NEG_INFINITY,
+    }
+  }
+
+  /// Number of values added
+  pub fn count(&self) -> f64 {
+    self.count
+  }
+
+  pub fn add(&mut self, x: f64) {
+    self.add_weighted(x, 1.0);
+  }
+
+  pub fn extend(&mut self, x: ArrayView1<f64>) {
+    for &v in x {
+      self.add(v);
+    }
+  }
+
+  fn add_weighted(&mut self, x: f64, weight: f64) {
+    self.buffer.push((x, weight));
+    self.count += weight;
+    self.min = self.min.min(x);
+    self.max = self.max.max(x);
+    if self.buffer.len() as f64 >= 5.0 * self.compression {
+      self.compress();
+    }
+  }
+
+  /// Add the centroids of another digest
+  pub fn merge(&mut self, other: &TDigest) {
+    for &(mean, weight) in other.centroids.iter().chain(&other.buffer) {
+      self.add_weighted(mean, weight);
+    }
+  }
+
+  /// Merge the buffered values into the centroids
+  fn compress(&mut self) {
+    if self.buffer.is_empty() {
+      return;
+    }
+
+    let mut all = std::mem::take(&mut self.centroids);
+    all.append(&mut self.buffer);
+    all.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let delta = self.compression;
+    let k = |q: f64| delta / (2.0 * std::f64::consts::PI) * (2.0 * q - 1.0).asin();
+    let q_limit = |q: f64| {
+      let next = k(q) + 1.0;
+      if next >= delta / 4.0 {
+        1.0
+      } else {
+        0.5 * ((2.0 * std::f64::consts::PI * next / delta).sin() + 1.0)
+      }
+    };
+
+    let mut merged = Vec::with_capacity(all.len());
+    let mut current = all[0];
+    let mut so_far = current.1;
+    let mut limit = q_limit(0.0) * self.count;
+
+    for &(mean, weight) in &all[1..] {
+      if so_far + weight <= limit {
+        current.1 += weight;
+        current.0 += weight * (mean - current.0) / current.1;
+      } else {
+        merged.push(current);
+        limit = q_limit(so_far / self.count) * self.count;
+        current = (mean, weight);
+      }
+      so_far += weight;
+    }
+    merged.push(current);
+
+    self.centroids = merged;
+  }
+
+  pub fn quantile(&mut self, p: f64) -> f64 {
+    assert!(self.count > 0.0, "Digest is empty");
+    assert!((0.0..=1.0).contains(&p), "Probability must be in [0, 1]");
+    self.compress();
+
+    let c = &self.centroids;
+    if c.len() == 1 {
+      return c[0].0;
+    }
+
+    let target = p * self.count;
+    let first = c[0].1 / 2.0;
+    if target < first {
+      return self.min + (c[0].0 - self.min) * target / first;
+    }
+
+    let mut center = first;
+    for i in 0..c.len() - 1 {
+      let next = center + (c[i].1 + c[i + 1].1) / 2.0;
+      if target < next {
+        return c[i].0 + (c[i + 1].0 - c[i].0) * (target - center) / (next - center);
+      }
+      center = next;
+    }
+
+    let last = c[c.len() - 1];
+    let rest = self.count - center;
+    if rest > 0.0 {
+      last.0 + (self.max - last.0) * ((target - center) / rest).min(1.0)
+    } else {
+      self.max
+    }
+  }
+}
@@ -0,0 +1,46 @@
+use linreg::linear_regression;
+use ndarray::ArrayView1;
+
+/// Parameters of the rough fractional stochastic volatility (RFSV) model
+/// log sigma(t + d) - log sigma(t) = nu (W^H(t + d) - W^H(t))
+#[derive(Default, Debug, Clone, Copy)]
+pub struct RfsvParams {
+  /// Hurst parameter of the log volatility
+  pub hurst: f64,
+  /// Volatility of the log volatility
+  pub nu: f64,
+}
+
+/// Fit the RFSV model to a history of realized variances sampled every `dt` years,
+/// by the regression of the second moment of the log volatility increments
+/// m(d) = E[|log sigma(t + d) - log sigma(t)|^2] = nu^2 d^(2H) on the lags 1..=max_lag.
+/// https://doi.org/10.1080/14697688.2017.1393551 (Gatheral, Jaisson, Rosenbaum)
+pub fn fit_rfsv(realized_variance: ArrayView1<f64>, dt: f64, max_lag: usize) -> RfsvParams {
+  assert!(max_lag >= 2, "At least two lags are needed");
+  assert!(
+    realized_variance.len() > max_lag + 1,
+    "The history must be longer than the largest lag"
+  );
+  assert!(
+    realized_variance.iter().all(|&v| v > 0.0),
+    "Realized variances must be positive"
+  );
+
+  let log_vol = realized_variance.mapv(|v| 0.5 * v.ln());
+  let (x, y): (Vec<f64>, Vec<f64>) = (1..=max_lag)
+    .map(|lag| {
+      let m = (lag..log_vol.len())
+        .map(|i| (log_vol[i] - log_vol[i - lag]).powi(2))
+        .sum::<f64>()
+        / (log_vol.len() - lag) as f64;
+      ((lag as f64 * dt).ln(), m.ln())
+    })
+    .unzip();
+
+  let (slope, intercept): (f64, f64) = linear_regression(&x, &y).unwrap();
+
+  RfsvParams {
+    hurst: slope / 2.0,
+    nu: (intercept / 2.0).exp(),
+  }
+}
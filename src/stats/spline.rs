@@ -0,0 +1,107 @@
+use nalgebra::{DMatrix, DVector};
+use ndarray::{Array1, ArrayView1};
+
+/// Natural cubic smoothing spline, the minimizer of sum (y_i - f(x_i))^2 + lambda int f''^2
+/// over the twice differentiable functions, by the Reinsch algorithm: the second derivatives
+/// gamma at the interior knots solve (R + lambda Q^T Q) gamma = Q^T y and the fitted values
+/// are y - lambda Q gamma (Reinsch 1967). lambda = 0 interpolates the data, a large lambda
+/// tends to the least squares line.
+#[derive(Debug, Clone)]
+pub struct SmoothingSpline {
+  /// Knots, increasing
+  pub x: Array1<f64>,
+  /// Fitted values at the knots
+  pub fitted: Array1<f64>,
+  /// Second derivatives at the knots, 0 at the ends
+  pub second_derivatives: Array1<f64>,
+}
+
+impl SmoothingSpline {
+  pub fn fit(x: ArrayView1<f64>, y: ArrayView1<f64>, lambda: f64) -> Self {
+    let n = x.len();
+    assert_eq!(n, y.len(), "One value per knot");
+    assert!(n >= 3, "At least three knots are needed");
+    assert!(
+      x.windows(2).into_iter().all(|w| w[0] < w[1]),
+      "Knots must increase"
+    );
+    assert!(
+      lambda >= 0.0,
+      "The smoothing parameter must be non-negative"
+    );
+
+    let h = Array1::from_shape_fn(n - 1, |i| x[i + 1] - x[i]);
+    let mut q = DMatrix::<f64>::zeros(n, n - 2);
+    let mut r = DMatrix::<f64>::zeros(n - 2, n - 2);
+    for j in 0..n - 2 {
+      q[(j, j)] = 1.0 / h[j];
+      q[(j + 1, j)] = -1.0 / h[j] - 1.0 / h[j + 1];
+      q[(j + 2, j)] = 1.0 / h[j + 1];
+      r[(j, j)] = (h[j] + h[j + 1]) / 3.0;
+      if j + 1 < n - 2 {
+        r[(j, j + 1)] = h[j + 1] / 6.0;
+        r[(j + 1, j)] = h[j + 1] / 6.0;
+      }
+    }
+
+    let y = DVector::from_iterator(n, y.iter().copied());
+    let gamma = (&r + lambda * q.transpose() * &q)
+      .cholesky()
+      .expect("The system of the smoothing spline is positive definite")
+      .solve(&(q.transpose() * &y));
+    let fitted = &y - lambda * &q * &gamma;
+
+    let mut second_derivatives = Array1::zeros(n);
+    for j in 0..n - 2 {
+      second_derivatives[j + 1] = gamma[j];
+    }
+    Self {
+      x: x.to_owned(),
+      fitted: Array1::from_iter(fitted.iter().copied()),
+      second_derivatives,
+    }
+  }
+
+  /// Interval of the knots containing x, the first or last one outside the knots
+  fn interval(&self, x: f64) -> usize {
+    let n = self.x.len();
+    self
+      .x
+      .iter()
+      .position(|&t| t > x)
+      .map_or(n - 2, |i| i.saturating_sub(1).min(n - 2))
+  }
+
+  /// Value of the spline, linear outside the knots
+  pub fn evaluate(&self, x: f64) -> f64 {
+    let n = self.x.len();
+    let (g, gamma) = (&self.fitted, &self.second_derivatives);
+    if x < self.x[0] {
+      let h = self.x[1] - self.x[0];
+      let slope = (g[1] - g[0]) / h - h * gamma[1] / 6.0;
+      return g[0] + slope * (x - self.x[0]);
+    }
+    if x > self.x[n - 1] {
+      let h = self.x[n - 1] - self.x[n - 2];
+      let slope = (g[n - 1] - g[n - 2]) / h + h * gamma[n - 2] / 6.0;
+      return g[n - 1] + slope * (x - self.x[n - 1]);
+    }
+
+    let i = self.interval(x);
+    let h = self.x[i + 1] - self.x[i];
+    let (a, b) = (x - self.x[i], self.x[i + 1] - x);
+    (a * g[i + 1] + b * g[i]) / h
+      - a * b / 6.0 * ((1.0 + a / h) * gamma[i + 1] + (1.0 + b / h) * gamma[i])
+  }
+
+  /// Second derivative of the spline, linear between the knots and 0 outside
+  pub fn second_derivative(&self, x: f64) -> f64 {
+    let n = self.x.len();
+    if x < self.x[0] || x > self.x[n - 1] {
+      return 0.0;
+    }
+    let i = self.interval(x);
+    let a = (x - self.x[i]) / (self.x[i + 1] - self.x[i]);
+    (1.0 - a) * self.second_derivatives[i] + a * self.second_derivatives[i + 1]
+  }
+}
@@ -0,0 +1,188 @@
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use rayon::prelude::*;
+
+use crate::rng::{path_seed, with_seed};
+
+use super::quantile::interpolate;
+
+/// Estimation window of a walk-forward run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+  /// The last `n` observations
+  Rolling(usize),
+  /// All the observations up to the origin, starting with `min`
+  Expanding { min: usize },
+}
+
+impl Default for Window {
+  fn default() -> Self {
+    Self::Rolling(250)
+  }
+}
+
+/// Walk-forward run: at every origin the model is re-estimated on the window of the history
+/// up to the origin and `scenarios` paths of the next `horizon` values are simulated from the
+/// last observation, to be compared with the realized values. The origins move by `step`
+/// and are processed in parallel, the paths of the origin t are simulated in deterministic
+/// mode with the seeds `path_seed(path_seed(seed, t), j)`, so a run is reproducible.
+#[derive(Debug, Clone, Default)]
+pub struct WalkForward {
+  pub window: Window,
+  pub horizon: usize,
+  /// Observations between two origins (default 1)
+  pub step: Option<usize>,
+  pub scenarios: usize,
+  pub seed: u64,
+}
+
+/// Forecast of one origin
+#[derive(Debug, Clone)]
+pub struct Forecast<M> {
+  /// Index of the last observation of the estimation window
+  pub origin: usize,
+  /// Model estimated on the window
+  pub model: M,
+  /// Simulated paths, scenario x step ahead
+  pub paths: Array2<f64>,
+  /// Realized values of the next `horizon` observations
+  pub realized: Array1<f64>,
+}
+
+impl<M> Forecast<M> {
+  /// Mean of the simulated values, one per step ahead
+  pub fn mean(&self) -> Array1<f64> {
+    self.paths.mean_axis(Axis(0)).unwrap()
+  }
+
+  /// Quantile of the simulated values, one per step ahead
+  pub fn quantile(&self, p: f64) -> Array1<f64> {
+    Array1::from_iter(self.paths.axis_iter(Axis(1)).map(|column| {
+      let mut sorted = column.to_vec();
+      sorted.sort_by(|a, b| a.total_cmp(b));
+      interpolate(&sorted, p)
+    }))
+  }
+
+  /// Probability integral transform of the realized values, the fraction of the simulated
+  /// values below them, uniform for a well calibrated forecast
+  pub fn pit(&self) -> Array1<f64> {
+    Array1::from_iter(
+      self
+        .paths
+        .axis_iter(Axis(1))
+        .zip(self.realized.iter())
+        .map(|(column, &y)| {
+          column.iter().filter(|&&x| x <= y).count() as f64 / column.len() as f64
+        }),
+    )
+  }
+}
+
+/// Out-of-sample result of a walk-forward run
+#[derive(Debug, Clone)]
+pub struct WalkForwardResult<M> {
+  pub forecasts: Vec<Forecast<M>>,
+}
+
+impl<M> WalkForwardResult<M> {
+  fn per_step(&self, f: impl Fn(&Forecast<M>) -> Array1<f64>) -> Array1<f64> {
+    let mut sum = Array1::<f64>::zeros(self.forecasts[0].realized.len());
+    for forecast in &self.forecasts {
+      sum += &f(forecast);
+    }
+    sum / self.forecasts.len() as f64
+  }
+
+  /// Root mean squared error of the mean forecast, one per step ahead
+  pub fn rmse(&self) -> Array1<f64> {
+    self
+      .per_step(|f| (f.mean() - &f.realized).mapv(|e| e * e))
+      .mapv(f64::sqrt)
+  }
+
+  /// Mean absolute error of the mean forecast, one per step ahead
+  pub fn mae(&self) -> Array1<f64> {
+    self.per_step(|f| (f.mean() - &f.realized).mapv(f64::abs))
+  }
+
+  /// Fraction of the realized values within the central interval of the given level
+  /// (e.g. 0.9), one per step ahead, close to the level for a well calibrated forecast
+  pub fn coverage(&self, level: f64) -> Array1<f64> {
+    let tail = 0.5 * (1.0 - level);
+    self.per_step(|f| {
+      let (lo, hi) = (f.quantile(tail), f.quantile(1.0 - tail));
+      Array1::from_shape_fn(f.realized.len(), |j| {
+        if (lo[j]..=hi[j]).contains(&f.realized[j]) {
+          1.0
+        } else {
+          0.0
+        }
+      })
+    })
+  }
+}
+
+impl WalkForward {
+  /// Origins t of the run, the last index of each estimation window
+  pub fn origins(&self, len: usize) -> Vec<usize> {
+    let first = match self.window {
+      Window::Rolling(n) => n,
+      Window::Expanding { min } => min,
+    };
+    assert!(first >= 1, "The window must have at least one observation");
+    (first - 1..len.saturating_sub(self.horizon))
+      .step_by(self.step.unwrap_or(1).max(1))
+      .collect()
+  }
+
+  /// Run over the series with `estimate` fitting a model to a window and `simulate`
+  /// drawing one path of the next `horizon` values from the model and the last observation
+  pub fn run<M, E, S>(
+    &self,
+    data: ArrayView1<f64>,
+    estimate: E,
+    simulate: S,
+  ) -> WalkForwardResult<M>
+  where
+    M: Send,
+    E: Fn(ArrayView1<f64>) -> M + Sync,
+    S: Fn(&M, f64, usize) -> Array1<f64> + Sync,
+  {
+    assert!(self.horizon >= 1, "The horizon must be at least one step");
+    assert!(self.scenarios >= 1, "At least one scenario is needed");
+    let origins = self.origins(data.len());
+    assert!(
+      !origins.is_empty(),
+      "The series is too short for the window"
+    );
+
+    let forecasts = origins
+      .into_par_iter()
+      .map(|t| {
+        let start = match self.window {
+          Window::Rolling(n) => t + 1 - n,
+          Window::Expanding { .. } => 0,
+        };
+        let model = estimate(data.slice(ndarray::s![start..=t]));
+        let seed = path_seed(self.seed, t as u64);
+        let mut paths = Array2::<f64>::zeros((self.scenarios, self.horizon));
+        for (j, mut row) in paths.axis_iter_mut(Axis(0)).enumerate() {
+          let path = with_seed(path_seed(seed, j as u64), || {
+            simulate(&model, data[t], self.horizon)
+          });
+          assert_eq!(path.len(), self.horizon, "A path must have horizon values");
+          row.assign(&path);
+        }
+
+        Forecast {
+          origin: t,
+          model,
+          paths,
+          realized: data.slice(ndarray::s![t + 1..=t + self.horizon]).to_owned(),
+        }
+      })
+      .collect();
+
+    WalkForwardResult { forecasts }
+  }
+}
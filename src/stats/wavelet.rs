@@ -0,0 +1,178 @@
+use linreg::linear_regression;
+use ndarray::{Array1, ArrayView1};
+
+/// Orthogonal wavelet of the discrete transform
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Wavelet {
+  #[default]
+  Haar,
+  /// Daubechies wavelet with 2 vanishing moments (4 coefficients)
+  Daubechies4,
+  /// Daubechies wavelet with 3 vanishing moments (6 coefficients)
+  Daubechies6,
+}
+
+impl Wavelet {
+  /// Low-pass (scaling) filter
+  pub fn low_pass(&self) -> Vec<f64> {
+    match self {
+      Self::Haar => vec![std::f64::consts::FRAC_1_SQRT_2; 2],
+      Self::Daubechies4 => {
+        let s3 = 3f64.sqrt();
+        let norm = 4.0 * 2f64.sqrt();
+        vec![
+          (1.0 + s3) / norm,
+          (3.0 + s3) / norm,
+          (3.0 - s3) / norm,
+          (1.0 - s3) / norm,
+        ]
+      }
+      Self::Daubechies6 => vec![
+        0.332_670_552_950_082_6,
+        0.806_891_509_311_092_6,
+        0.459_877_502_118_491_4,
+        -0.135_011_020_010_254_6,
+        -0.085_441_273_882_026_66,
+        0.035_226_291_885_709_54,
+      ],
+    }
+  }
+
+  /// High-pass (wavelet) filter, the quadrature mirror of the low-pass filter
+  pub fn high_pass(&self) -> Vec<f64> {
+    let h = self.low_pass();
+    let l = h.len();
+    (0..l)
+      .map(|k| {
+        if k % 2 == 0 {
+          h[l - 1 - k]
+        } else {
+          -h[l - 1 - k]
+        }
+      })
+      .collect()
+  }
+}
+
+/// Multilevel wavelet decomposition
+#[derive(Debug, Clone)]
+pub struct WaveletDecomposition {
+  pub wavelet: Wavelet,
+  /// Approximation coefficients at the coarsest level
+  pub approximation: Array1<f64>,
+  /// Detail coefficients from the finest (level 1) to the coarsest level
+  pub details: Vec<Array1<f64>>,
+}
+
+/// Discrete wavelet transform with periodic boundaries over `levels` levels,
+/// the length of the series must be divisible by 2^levels
+pub fn dwt(x: ArrayView1<f64>, wavelet: Wavelet, levels: usize) -> WaveletDecomposition {
+  assert!(levels > 0, "At least one level is needed");
+  assert!(
+    x.len().is_multiple_of(1 << levels),
+    "Length of the series must be divisible by 2^levels"
+  );
+  let (h, g) = (wavelet.low_pass(), wavelet.high_pass());
+  let mut approximation = x.to_owned();
+  let mut details = Vec::with_capacity(levels);
+
+  for _ in 0..levels {
+    let n = approximation.len();
+    let filter = |f: &[f64]| {
+      Array1::from_shape_fn(n / 2, |i| {
+        f.iter()
+          .enumerate()
+          .map(|(k, c)| c * approximation[(2 * i + k) % n])
+          .sum::<f64>()
+      })
+    };
+    let detail = filter(&g);
+    approximation = filter(&h);
+    details.push(detail);
+  }
+
+  WaveletDecomposition {
+    wavelet,
+    approximation,
+    details,
+  }
+}
+
+/// Inverse of the periodic discrete wavelet transform
+pub fn idwt(decomposition: &WaveletDecomposition) -> Array1<f64> {
+  let (h, g) = (
+    decomposition.wavelet.low_pass(),
+    decomposition.wavelet.high_pass(),
+  );
+  let mut x = decomposition.approximation.clone();
+
+  for detail in decomposition.details.iter().rev() {
+    let n = 2 * x.len();
+    let mut finer = Array1::<f64>::zeros(n);
+    for i in 0..x.len() {
+      for k in 0..h.len() {
+        finer[(2 * i + k) % n] += h[k] * x[i] + g[k] * detail[i];
+      }
+    }
+    x = finer;
+  }
+
+  x
+}
+
+/// Wavelet variance of every level, the mean squared detail coefficient.
+/// Only the coefficients whose support does not wrap around the end of the series are used.
+pub fn wavelet_variance(x: ArrayView1<f64>, wavelet: Wavelet, levels: usize) -> Array1<f64> {
+  let n = x.len();
+  let width = wavelet.low_pass().len() - 1;
+
+  Array1::from_iter(
+    dwt(x, wavelet, levels)
+      .details
+      .iter()
+      .enumerate()
+      .map(|(j, d)| {
+        let scale = 1 << (j + 1);
+        // the coefficient i covers the samples scale * i ..= scale * i + width * (scale - 1)
+        let support = width * (scale - 1);
+        let interior = if n > support {
+          ((n - 1 - support) / scale + 1).min(d.len())
+        } else {
+          0
+        };
+        assert!(interior > 0, "Series is too short for the level {}", j + 1);
+        d.iter().take(interior).map(|c| c * c).sum::<f64>() / interior as f64
+      }),
+  )
+}
+
+/// Hurst exponent of a fractional Brownian path from the slope 2H + 1 of the log2 wavelet
+/// variance against the level (2H - 1 for the increments of the path).
+/// https://doi.org/10.1109/18.650986 (Abry, Veitch)
+pub fn wavelet_hurst(path: ArrayView1<f64>, wavelet: Wavelet, levels: usize) -> f64 {
+  assert!(levels >= 2, "At least two levels are needed");
+  let variance = wavelet_variance(path, wavelet, levels);
+  let x = (1..=levels).map(|j| j as f64).collect::<Vec<_>>();
+  let y = variance.iter().map(|v| v.log2()).collect::<Vec<_>>();
+  let (slope, _): (f64, f64) = linear_regression(&x, &y).unwrap();
+
+  0.5 * (slope - 1.0)
+}
+
+/// Wavelet denoising by soft thresholding of the detail coefficients at the universal
+/// threshold sigma sqrt(2 ln n), with the noise level sigma from the median absolute
+/// deviation of the finest details
+/// https://doi.org/10.1093/biomet/81.3.425 (Donoho, Johnstone)
+pub fn denoise(x: ArrayView1<f64>, wavelet: Wavelet, levels: usize) -> Array1<f64> {
+  let mut decomposition = dwt(x, wavelet, levels);
+  let mut finest = decomposition.details[0].mapv(f64::abs).to_vec();
+  finest.sort_by(|a, b| a.total_cmp(b));
+  let sigma = finest[finest.len() / 2] / 0.6745;
+  let threshold = sigma * (2.0 * (x.len() as f64).ln()).sqrt();
+
+  for detail in decomposition.details.iter_mut() {
+    detail.mapv_inplace(|c| c.signum() * (c.abs() - threshold).max(0.0));
+  }
+
+  idwt(&decomposition)
+}
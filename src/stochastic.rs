@@ -1,21 +1,39 @@
+pub mod adaptive;
+pub mod batch;
+pub mod commodity;
 pub mod diffusion;
+pub mod ensemble;
 pub mod interest;
+pub mod interpolation;
 pub mod jump;
 pub mod malliavin;
 pub mod noise;
+pub mod population;
 pub mod process;
+pub mod registry;
+pub mod schedule;
+pub mod solver;
 pub mod volatility;
-
-use std::sync::{Arc, Mutex};
+pub mod weather;
 
 use ndarray::parallel::prelude::*;
-use ndarray::{Array1, Array2, Axis};
+use ndarray::{Array1, Array2, ArrayView1, Axis};
 use ndrustfft::Zero;
 use num_complex::Complex64;
 use rand_distr::Distribution as RandDistribution;
 
+use crate::{
+  progress::{Hooks, Tracker},
+  rng::{path_seeds, seeded},
+};
+
 pub trait ProcessDistribution: RandDistribution<f64> + Copy + Send + Sync + Default {}
 
+/// Sampler of a process.
+///
+/// Samplers are `Send + Sync`: sampling takes `&self` and keeps its scratch buffers per call,
+/// so one configured instance (with its FFT plans and eigenvalues) can be shared through a
+/// reference or an `Arc` across a rayon pool or the handlers of a server.
 pub trait Sampling<T: Clone + Send + Sync + Zero>: Send + Sync {
   fn sample(&self) -> Array1<T>;
   fn sample_par(&self) -> Array2<T> {
@@ -23,19 +41,59 @@ pub trait Sampling<T: Clone + Send + Sync + Zero>: Send + Sync {
       panic!("m must be specified for parallel sampling");
     }
 
-    let mut xs = Array2::zeros((self.m().unwrap(), self.n()));
+    let m = self.m().unwrap();
+    let seeds = path_seeds(m);
+    let paths: Vec<Array1<T>> = (0..m)
+      .into_par_iter()
+      .map(|i| seeded(seeds.as_ref().map(|s| s[i]), || self.sample()))
+      .collect();
+
+    stack_paths(paths, self.n())
+  }
+  /// Parallel sampling with progress reports and cooperative cancellation,
+  /// returns None if it was cancelled
+  fn sample_par_with(&self, hooks: &Hooks) -> Option<Array2<T>> {
+    let m = self.m().expect("m must be specified for parallel sampling");
+    let seeds = path_seeds(m);
+    let tracker = Tracker::new(hooks, Some(m), 0);
 
-    xs.axis_iter_mut(Axis(0)).into_par_iter().for_each(|mut x| {
-      x.assign(&self.sample());
-    });
+    let paths: Vec<Option<Array1<T>>> = (0..m)
+      .into_par_iter()
+      .map(|i| {
+        if tracker.is_cancelled() {
+          return None;
+        }
 
-    xs
+        let path = seeded(seeds.as_ref().map(|s| s[i]), || self.sample());
+        tracker.advance(1);
+        Some(path)
+      })
+      .collect();
+
+    if tracker.is_cancelled() {
+      return None;
+    }
+    paths
+      .into_iter()
+      .collect::<Option<Vec<_>>>()
+      .map(|paths| stack_paths(paths, self.n()))
   }
   fn n(&self) -> usize;
   fn m(&self) -> Option<usize>;
   fn distribution(&mut self) {}
 }
 
+/// Paths as the rows of a matrix, whose width is the length of the paths (n or n + 1
+/// points depending on the sampler)
+fn stack_paths<T: Clone + Zero>(paths: Vec<Array1<T>>, n: usize) -> Array2<T> {
+  let width = paths.first().map_or(n, |path| path.len());
+  let mut xs = Array2::zeros((paths.len(), width));
+  for (mut x, path) in xs.axis_iter_mut(Axis(0)).zip(paths) {
+    x.assign(&path);
+  }
+  xs
+}
+
 pub trait Sampling2D<T: Clone + Send + Sync + Zero>: Send + Sync {
   fn sample(&self) -> [Array1<T>; 2];
   fn sample_par(&self) -> [Array2<T>; 2] {
@@ -43,18 +101,23 @@ pub trait Sampling2D<T: Clone + Send + Sync + Zero>: Send + Sync {
       panic!("m must be specified for parallel sampling");
     }
 
-    let m = self.m().unwrap(); // m értékét előre kinyerjük, hogy ne kelljen többször unwrap-elni
-    let xs1 = Arc::new(Mutex::new(Array2::zeros((self.m().unwrap(), self.n()))));
-    let xs2 = Arc::new(Mutex::new(Array2::zeros((self.m().unwrap(), self.n()))));
+    let m = self.m().unwrap();
+    let mut xs1 = Array2::zeros((m, self.n()));
+    let mut xs2 = Array2::zeros((m, self.n()));
+    let seeds = path_seeds(m);
 
-    (0..m).into_par_iter().for_each(|i| {
-      let [x1, x2] = self.sample(); // Minden szálon mintavételezünk
-      xs1.lock().unwrap().row_mut(i).assign(&x1); // Az első mintavételezés eredményét beírjuk az első mátrix i. sorába
-      xs2.lock().unwrap().row_mut(i).assign(&x2); // A második mintavételezés eredményét beírjuk a második mátrix i. sorába
-    });
+    // every row is written by exactly one task, no lock is needed
+    xs1
+      .axis_iter_mut(Axis(0))
+      .into_par_iter()
+      .zip(xs2.axis_iter_mut(Axis(0)).into_par_iter())
+      .enumerate()
+      .for_each(|(i, (mut x1, mut x2))| {
+        let [s1, s2] = seeded(seeds.as_ref().map(|s| s[i]), || self.sample());
+        x1.assign(&s1);
+        x2.assign(&s2);
+      });
 
-    let xs1 = xs1.lock().unwrap().clone();
-    let xs2 = xs2.lock().unwrap().clone();
     [xs1, xs2]
   }
   fn n(&self) -> usize;
@@ -70,6 +133,51 @@ pub trait Sampling3D<T: Clone + Send + Sync + Zero>: Send + Sync {
   fn m(&self) -> Option<usize>;
 }
 
+/// Closed-form moments of a stochastic process, used to check simulations
+/// against theory and to build control variates.
+pub trait TheoreticalMoments {
+  /// Mean of the process at time t
+  fn mean(&self, t: f64) -> f64;
+
+  /// Variance of the process at time t
+  fn variance(&self, t: f64) -> f64;
+
+  /// Covariance of the process between time s and t, if it is known
+  fn covariance(&self, _s: f64, _t: f64) -> Option<f64> {
+    None
+  }
+}
+
+/// Transition density of a time-homogeneous Markov process, for likelihood-based estimation
+pub trait TransitionDensity {
+  /// Log density of X(t + dt) = x1 given X(t) = x0
+  fn log_density(&self, x0: f64, x1: f64, dt: f64) -> f64;
+
+  /// Log likelihood of a path observed every dt
+  fn log_likelihood(&self, path: ArrayView1<f64>, dt: f64) -> f64 {
+    path
+      .windows(2)
+      .into_iter()
+      .map(|w| self.log_density(w[0], w[1], dt))
+      .sum()
+  }
+}
+
+/// Stationary distribution of an ergodic process, to start paths in equilibrium
+pub trait StationaryDistribution: Sized {
+  /// Draw from the stationary distribution
+  fn sample_stationary(&self) -> f64;
+
+  /// Mean of the stationary distribution
+  fn stationary_mean(&self) -> f64;
+
+  /// Variance of the stationary distribution
+  fn stationary_variance(&self) -> f64;
+
+  /// Copy of the process started from a draw of the stationary distribution
+  fn stationary_start(&self) -> Self;
+}
+
 pub trait Distribution {
   /// Characteristic function of the distribution
   fn characteristic_function(&self, _t: f64) -> Complex64 {
@@ -131,3 +239,30 @@ pub trait Distribution {
     0.0
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::quant::{
+    options::bsm::BSM,
+    volatility::heston::{HestonCalibrator, HestonPricer},
+  };
+  use crate::stochastic::{
+    noise::fgn::FGN,
+    process::{bm::BM, fbm::Fbm},
+    volatility::{heston::Heston, rbergomi::RoughBergomi},
+  };
+
+  fn assert_send_sync<T: Send + Sync>() {}
+
+  #[test]
+  fn models_are_send_and_sync() {
+    assert_send_sync::<FGN>();
+    assert_send_sync::<Fbm>();
+    assert_send_sync::<BM>();
+    assert_send_sync::<Heston>();
+    assert_send_sync::<RoughBergomi>();
+    assert_send_sync::<BSM>();
+    assert_send_sync::<HestonPricer>();
+    assert_send_sync::<HestonCalibrator>();
+  }
+}
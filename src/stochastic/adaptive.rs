@@ -0,0 +1,205 @@
+use ndarray::{s, ArrayView1};
+use rayon::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{
+  progress::{Hooks, Tracker},
+  quant::greeks::Estimate,
+  rng::{path_seed, with_seed},
+};
+
+/// Convergence diagnostics of a Monte Carlo run
+#[derive(Default, Debug, Clone, Copy)]
+pub struct ConvergenceDiagnostics {
+  /// Geweke z-score: difference of the means of the first 10% and the last 50%
+  /// of the samples over its standard error, |z| > 2 hints at a drift
+  pub geweke_z: f64,
+  /// Gelman-Rubin potential scale reduction factor over the batches as chains,
+  /// close to 1 if the batches agree
+  pub r_hat: f64,
+}
+
+/// Result of an adaptive Monte Carlo run
+#[derive(Default, Debug, Clone, Copy)]
+pub struct AdaptiveEstimate {
+  /// Mean and standard error of all the samples
+  pub estimate: Estimate,
+  /// Confidence interval of the mean
+  pub ci: (f64, f64),
+  /// Number of simulated paths
+  pub paths: usize,
+  /// Whether the standard error reached the tolerance within the budget
+  pub converged: bool,
+  /// Convergence diagnostics, if requested
+  pub diagnostics: Option<ConvergenceDiagnostics>,
+}
+
+/// Adaptive Monte Carlo: simulates batches of paths until the standard error of the mean
+/// falls below `tolerance` or `max_paths` paths are used. Path i is simulated in deterministic
+/// mode with the seed `path_seed(seed, i)`, so a run is reproducible and a larger budget
+/// only appends paths.
+pub struct AdaptiveMonteCarlo {
+  /// Target standard error of the mean
+  pub tolerance: f64,
+  /// Paths per batch, the stopping rule is checked after every batch
+  pub batch: usize,
+  /// Minimum number of paths before the stopping rule is checked
+  pub min_paths: Option<usize>,
+  /// Maximum number of paths
+  pub max_paths: usize,
+  /// Confidence level of the interval (default 0.95)
+  pub confidence: Option<f64>,
+  /// Seed of the run
+  pub seed: u64,
+  /// Compute the convergence diagnostics
+  pub diagnostics: bool,
+  /// Progress and cancellation hooks
+  pub hooks: Hooks,
+}
+
+impl AdaptiveMonteCarlo {
+  #[must_use]
+  pub fn new(tolerance: f64, batch: usize, max_paths: usize, seed: u64) -> Self {
+    assert!(tolerance > 0.0, "Tolerance must be positive");
+    assert!(batch > 1, "Batch size must be greater than 1");
+
+    Self {
+      tolerance,
+      batch,
+      min_paths: None,
+      max_paths,
+      confidence: None,
+      seed,
+      diagnostics: false,
+      hooks: Hooks::default(),
+    }
+  }
+
+  /// Run the simulation, `f` simulates one path (with the samplers of the crate)
+  /// and returns its functional, e.g. a discounted payoff
+  pub fn run<F>(&self, f: F) -> AdaptiveEstimate
+  where
+    F: Fn() -> f64 + Sync,
+  {
+    let tracker = Tracker::new(&self.hooks, Some(self.max_paths), 0);
+    let min_paths = self.min_paths.unwrap_or(self.batch);
+    let mut samples = Vec::<f64>::new();
+    let mut converged = false;
+
+    while samples.len() < self.max_paths && !tracker.is_cancelled() {
+      let start = samples.len();
+      let size = self.batch.min(self.max_paths - start);
+      let batch = (start..start + size)
+        .into_par_iter()
+        .map(|i| {
+          let x = with_seed(path_seed(self.seed, i as u64), &f);
+          tracker.advance(1);
+          x
+        })
+        .collect::<Vec<_>>();
+      samples.extend(batch);
+
+      if samples.len() >= min_paths
+        && Estimate::from_samples(ArrayView1::from(&samples)).std_error <= self.tolerance
+      {
+        converged = true;
+        break;
+      }
+    }
+
+    // cancelled or a budget below two paths, no statistics
+    if samples.len() < 2 {
+      return AdaptiveEstimate {
+        estimate: Estimate {
+          value: f64::NAN,
+          std_error: f64::NAN,
+        },
+        ci: (f64::NAN, f64::NAN),
+        paths: samples.len(),
+        converged: false,
+        diagnostics: None,
+      };
+    }
+
+    let estimate = Estimate::from_samples(ArrayView1::from(&samples));
+    let z = Normal::new(0.0, 1.0)
+      .unwrap()
+      .inverse_cdf(0.5 + 0.5 * self.confidence.unwrap_or(0.95));
+
+    AdaptiveEstimate {
+      estimate,
+      ci: (
+        estimate.value - z * estimate.std_error,
+        estimate.value + z * estimate.std_error,
+      ),
+      paths: samples.len(),
+      converged,
+      diagnostics: self
+        .diagnostics
+        .then(|| diagnostics(ArrayView1::from(&samples), self.batch)),
+    }
+  }
+}
+
+/// Geweke and Gelman-Rubin diagnostics of the samples, the chains are the batches.
+/// The statistics are NaN if there are too few samples.
+pub fn diagnostics(samples: ArrayView1<f64>, batch: usize) -> ConvergenceDiagnostics {
+  let n = samples.len();
+  if n < 4 {
+    return ConvergenceDiagnostics {
+      geweke_z: f64::NAN,
+      r_hat: f64::NAN,
+    };
+  }
+  let (a, b) = (
+    Estimate::from_samples(samples.slice(s![..(n / 10).max(2)])),
+    Estimate::from_samples(samples.slice(s![n - (n / 2).max(2)..])),
+  );
+  let geweke_z = (a.value - b.value) / (a.std_error.powi(2) + b.std_error.powi(2)).sqrt();
+
+  // complete batches only, all chains must have the same length
+  let chains = samples
+    .exact_chunks(batch)
+    .into_iter()
+    .map(Estimate::from_samples)
+    .collect::<Vec<_>>();
+  let r_hat = if chains.len() < 2 {
+    f64::NAN
+  } else {
+    let (m, l) = (chains.len() as f64, batch as f64);
+    let mean = chains.iter().map(|c| c.value).sum::<f64>() / m;
+    let between = l / (m - 1.0) * chains.iter().map(|c| (c.value - mean).powi(2)).sum::<f64>();
+    // the chain variance is the squared standard error times the chain length
+    let within = chains.iter().map(|c| c.std_error.powi(2) * l).sum::<f64>() / m;
+    (((l - 1.0) / l * within + between / l) / within).sqrt()
+  };
+
+  ConvergenceDiagnostics { geweke_z, r_hat }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::Rng;
+
+  use super::*;
+  use crate::rng::thread_rng;
+
+  #[test]
+  fn converges_to_the_mean_and_is_reproducible() {
+    let mc = AdaptiveMonteCarlo::new(0.005, 1000, 100_000, 3);
+    let uniform = || thread_rng().gen::<f64>();
+    let first = mc.run(uniform);
+    assert!(first.converged);
+    assert!(first.ci.0 < 0.5 && 0.5 < first.ci.1);
+    assert_eq!(first.estimate.value, mc.run(uniform).estimate.value);
+  }
+
+  #[test]
+  fn too_small_budget_is_not_converged() {
+    let mc = AdaptiveMonteCarlo::new(1e-3, 10, 1, 0);
+    let result = mc.run(|| 1.0);
+    assert!(!result.converged);
+    assert_eq!(result.paths, 1);
+    assert!(result.estimate.value.is_nan() && result.ci.0.is_nan());
+  }
+}
@@ -0,0 +1,370 @@
+use std::{
+  fs::{self, File},
+  io::{self, BufReader, BufWriter, Read, Write},
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use rayon::prelude::*;
+
+use crate::{
+  progress::{Hooks, Tracker},
+  rng::{path_seed, with_seed},
+  stochastic::Sampling,
+};
+
+const MAGIC: &[u8; 8] = b"SRSCKPT1";
+
+/// Running mean and variance of the paths at every time step (Welford's algorithm)
+#[derive(Default, Debug, Clone)]
+pub struct PathStatistics {
+  /// Number of paths
+  pub count: u64,
+  /// Mean of the paths
+  pub mean: Array1<f64>,
+  /// Sum of squared deviations from the mean
+  pub m2: Array1<f64>,
+}
+
+impl PathStatistics {
+  pub fn update(&mut self, path: ArrayView1<f64>) {
+    if self.count == 0 {
+      self.mean = Array1::zeros(path.len());
+      self.m2 = Array1::zeros(path.len());
+    }
+
+    assert_eq!(
+      path.len(),
+      self.mean.len(),
+      "Paths must have the same length"
+    );
+    self.count += 1;
+
+    for (i, &x) in path.iter().enumerate() {
+      let delta = x - self.mean[i];
+      self.mean[i] += delta / self.count as f64;
+      self.m2[i] += delta * (x - self.mean[i]);
+    }
+  }
+
+  /// Sample variance of the paths
+  pub fn variance(&self) -> Array1<f64> {
+    &self.m2 / (self.count as f64 - 1.0)
+  }
+}
+
+/// State of a batch run, everything needed to resume it
+#[derive(Default, Debug, Clone)]
+pub struct Checkpoint {
+  /// Seed of the run
+  pub seed: u64,
+  /// Number of completed paths
+  pub completed: usize,
+  /// Statistics of the completed paths
+  pub statistics: PathStatistics,
+  /// Completed paths, if they are kept
+  pub paths: Option<Array2<f64>>,
+}
+
+fn write_u64(w: &mut impl Write, x: u64) -> io::Result<()> {
+  w.write_all(&x.to_le_bytes())
+}
+
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+  let mut buf = [0u8; 8];
+  r.read_exact(&mut buf)?;
+  Ok(u64::from_le_bytes(buf))
+}
+
+fn write_f64s<'a>(w: &mut impl Write, xs: impl Iterator<Item = &'a f64>) -> io::Result<()> {
+  for x in xs {
+    w.write_all(&x.to_le_bytes())?;
+  }
+
+  Ok(())
+}
+
+fn read_f64s(r: &mut impl Read, len: usize) -> io::Result<Vec<f64>> {
+  (0..len).map(|_| read_u64(r).map(f64::from_bits)).collect()
+}
+
+fn invalid(msg: &str) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl Checkpoint {
+  /// Write the checkpoint, first to a temporary file which is then renamed,
+  /// so an interrupted write never corrupts the previous checkpoint
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+    let tmp = path.as_ref().with_extension("tmp");
+
+    {
+      let mut w = BufWriter::new(File::create(&tmp)?);
+      w.write_all(MAGIC)?;
+      write_u64(&mut w, self.seed)?;
+      write_u64(&mut w, self.completed as u64)?;
+      write_u64(&mut w, self.statistics.count)?;
+      write_u64(&mut w, self.statistics.mean.len() as u64)?;
+      write_f64s(&mut w, self.statistics.mean.iter())?;
+      write_f64s(&mut w, self.statistics.m2.iter())?;
+
+      match &self.paths {
+        Some(paths) => {
+          write_u64(&mut w, 1)?;
+          write_u64(&mut w, paths.nrows() as u64)?;
+          write_u64(&mut w, paths.ncols() as u64)?;
+          write_f64s(&mut w, paths.iter())?;
+        }
+        None => write_u64(&mut w, 0)?,
+      }
+
+      w.flush()?;
+    }
+
+    fs::rename(tmp, path)
+  }
+
+  pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+    let mut r = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; 8];
+    r.read_exact(&mut magic)?;
+
+    if &magic != MAGIC {
+      return Err(invalid("Not a checkpoint file"));
+    }
+
+    let seed = read_u64(&mut r)?;
+    let completed = read_u64(&mut r)? as usize;
+    let count = read_u64(&mut r)?;
+    let len = read_u64(&mut r)? as usize;
+    let mean = Array1::from(read_f64s(&mut r, len)?);
+    let m2 = Array1::from(read_f64s(&mut r, len)?);
+
+    let paths = match read_u64(&mut r)? {
+      0 => None,
+      1 => {
+        let rows = read_u64(&mut r)? as usize;
+        let cols = read_u64(&mut r)? as usize;
+        let data = read_f64s(&mut r, rows * cols)?;
+        Some(Array2::from_shape_vec((rows, cols), data).map_err(|_| invalid("Corrupt paths"))?)
+      }
+      _ => return Err(invalid("Corrupt paths")),
+    };
+
+    Ok(Self {
+      seed,
+      completed,
+      statistics: PathStatistics { count, mean, m2 },
+      paths,
+    })
+  }
+}
+
+/// Batch runner that samples `paths` paths in batches of `batch` paths in parallel.
+/// Path i is sampled in deterministic mode with the seed `path_seed(seed, i)`, so a run
+/// resumed from its checkpoint gives exactly the same paths and statistics as an
+/// uninterrupted one. The checkpoint is written after every batch.
+///
+/// If the run is cancelled through the hooks, the unfinished batch is dropped and the
+/// state of the last completed batch is returned, the run can be resumed later.
+pub struct BatchRunner<'a, S: Sampling<f64>> {
+  /// Sampler of the paths
+  pub sampler: &'a S,
+  /// Total number of paths
+  pub paths: usize,
+  /// Number of paths between checkpoints
+  pub batch: usize,
+  /// Seed of the run
+  pub seed: u64,
+  /// Checkpoint file, the run is resumed from it if it exists
+  pub checkpoint: Option<PathBuf>,
+  /// Keep the paths in the result, otherwise only the statistics
+  pub keep_paths: bool,
+  /// Progress and cancellation hooks
+  pub hooks: Hooks,
+  /// Initial value of every path and the sampler restarted from a value
+  pub initial: Option<(Array1<f64>, Restart<S>)>,
+}
+
+/// Copy of a sampler started from another initial value
+pub type Restart<S> = Arc<dyn Fn(&S, f64) -> S + Send + Sync>;
+
+impl<'a, S: Sampling<f64>> BatchRunner<'a, S> {
+  #[must_use]
+  pub fn new(
+    sampler: &'a S,
+    paths: usize,
+    batch: usize,
+    seed: u64,
+    checkpoint: Option<PathBuf>,
+    keep_paths: bool,
+  ) -> Self {
+    assert!(batch > 0, "Batch size must be positive");
+
+    Self {
+      sampler,
+      paths,
+      batch,
+      seed,
+      checkpoint,
+      keep_paths,
+      hooks: Hooks::default(),
+      initial: None,
+    }
+  }
+
+  /// Start the path i from `values[i]` instead of the initial value of the sampler,
+  /// e.g. an ensemble of initial variances from `ensemble::initial_ensemble`.
+  /// `restart` builds the sampler started from a value.
+  #[must_use]
+  pub fn with_initial(
+    mut self,
+    values: Array1<f64>,
+    restart: impl Fn(&S, f64) -> S + Send + Sync + 'static,
+  ) -> Self {
+    assert_eq!(
+      values.len(),
+      self.paths,
+      "One initial value per path is needed"
+    );
+    self.initial = Some((values, Arc::new(restart)));
+    self
+  }
+
+  /// Run (or resume) the simulation
+  pub fn run(&self) -> io::Result<Checkpoint> {
+    let mut state = match &self.checkpoint {
+      Some(path) if path.exists() => {
+        let state = Checkpoint::load(path)?;
+
+        if state.seed != self.seed {
+          return Err(invalid("Checkpoint was written by a run with another seed"));
+        }
+
+        state
+      }
+      _ => Checkpoint {
+        seed: self.seed,
+        ..Default::default()
+      },
+    };
+
+    let tracker = Tracker::new(&self.hooks, Some(self.paths), state.completed);
+
+    while state.completed < self.paths {
+      let size = self.batch.min(self.paths - state.completed);
+      let batch = (state.completed..state.completed + size)
+        .into_par_iter()
+        .filter_map(|i| {
+          if tracker.is_cancelled() {
+            return None;
+          }
+
+          let path = with_seed(path_seed(self.seed, i as u64), || match &self.initial {
+            Some((values, restart)) => restart(self.sampler, values[i]).sample(),
+            None => self.sampler.sample(),
+          });
+          tracker.advance(1);
+          Some(path)
+        })
+        .collect::<Vec<_>>();
+
+      if batch.len() < size {
+        break;
+      }
+
+      for path in &batch {
+        state.statistics.update(path.view());
+      }
+
+      if self.keep_paths {
+        let views = batch.iter().map(|p| p.view()).collect::<Vec<_>>();
+        let rows = ndarray::stack(Axis(0), &views)
+          .map_err(|_| invalid("Paths must have the same length"))?;
+
+        state.paths = Some(match state.paths.take() {
+          Some(paths) => ndarray::concatenate(Axis(0), &[paths.view(), rows.view()])
+            .map_err(|_| invalid("Paths must have the same length"))?,
+          None => rows,
+        });
+      }
+
+      state.completed += size;
+
+      if let Some(path) = &self.checkpoint {
+        state.save(path)?;
+      }
+    }
+
+    Ok(state)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{progress::CancellationToken, stochastic::diffusion::gbm::GBM};
+
+  fn gbm() -> GBM {
+    GBM::new(&GBM {
+      mu: 0.05,
+      sigma: 0.2,
+      n: 32,
+      x0: Some(100.0),
+      t: Some(1.0),
+      ..Default::default()
+    })
+  }
+
+  #[test]
+  fn resumed_run_matches_an_uninterrupted_run() {
+    let sampler = gbm();
+    let file = std::env::temp_dir().join(format!("batch-resume-{}.ckpt", std::process::id()));
+    let _ = fs::remove_file(&file);
+
+    // cancelled from the progress callback during the third batch
+    let token = CancellationToken::new();
+    let mut interrupted = BatchRunner::new(&sampler, 50, 10, 9, Some(file.clone()), true);
+    let cancel = token.clone();
+    interrupted.hooks = Hooks {
+      progress: Some(Arc::new(move |p| {
+        if p.completed >= 25 {
+          cancel.cancel();
+        }
+      })),
+      cancel: Some(token),
+    };
+    let partial = interrupted.run().unwrap();
+    assert!(partial.completed < 50 && partial.completed.is_multiple_of(10));
+    assert_eq!(
+      Checkpoint::load(&file).unwrap().completed,
+      partial.completed
+    );
+
+    let resumed = BatchRunner::new(&sampler, 50, 10, 9, Some(file.clone()), true)
+      .run()
+      .unwrap();
+    let uninterrupted = BatchRunner::new(&sampler, 50, 10, 9, None, true)
+      .run()
+      .unwrap();
+    fs::remove_file(&file).unwrap();
+
+    assert_eq!(resumed.completed, 50);
+    assert_eq!(resumed.paths, uninterrupted.paths);
+    assert_eq!(resumed.statistics.mean, uninterrupted.statistics.mean);
+    assert_eq!(resumed.statistics.m2, uninterrupted.statistics.m2);
+  }
+
+  #[test]
+  fn checkpoint_of_another_seed_is_rejected() {
+    let sampler = gbm();
+    let file = std::env::temp_dir().join(format!("batch-seed-{}.ckpt", std::process::id()));
+    BatchRunner::new(&sampler, 4, 2, 1, Some(file.clone()), false)
+      .run()
+      .unwrap();
+    let resumed = BatchRunner::new(&sampler, 8, 2, 2, Some(file.clone()), false).run();
+    fs::remove_file(&file).unwrap();
+    assert_eq!(resumed.unwrap_err().kind(), io::ErrorKind::InvalidData);
+  }
+}
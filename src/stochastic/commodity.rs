@@ -0,0 +1,2 @@
+pub mod electricity;
+pub mod schwartz;
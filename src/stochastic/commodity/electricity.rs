@@ -0,0 +1,162 @@
+use ndarray::Array1;
+use ndarray_rand::RandomExt;
+use rand::Rng;
+use rand_distr::{Distribution, Poisson, StandardNormal};
+
+use crate::{
+  rng::thread_rng,
+  stochastic::{schedule::Schedule, ProcessDistribution, Sampling, Sampling2D},
+};
+
+/// Signed Pareto jumps: upward with probability p, the size is Pareto with the scale
+/// and the tail index alpha (the variance is infinite for alpha <= 2)
+#[derive(Debug, Clone, Copy)]
+pub struct SignedPareto {
+  /// Probability of an upward jump
+  pub p: f64,
+  pub scale: f64,
+  /// Tail index
+  pub alpha: f64,
+}
+
+impl Default for SignedPareto {
+  fn default() -> Self {
+    Self {
+      p: 1.0,
+      scale: 1.0,
+      alpha: 3.0,
+    }
+  }
+}
+
+impl Distribution<f64> for SignedPareto {
+  fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+    let size = self.scale * (1.0 - rng.gen::<f64>()).powf(-1.0 / self.alpha);
+
+    if rng.gen::<f64>() < self.p {
+      size
+    } else {
+      -size
+    }
+  }
+}
+
+impl ProcessDistribution for SignedPareto {}
+
+/// Electricity spot price as a seasonal trend plus a base and a spike component
+/// dX(t) = -kappa X(t) dt + sigma dW(t)
+/// dY(t) = -beta Y(t) dt + dJ(t)
+/// with the compound Poisson process J of intensity lambda. The price is
+/// s(t) + X(t) + Y(t), or exp(s(t) + X(t) + Y(t)) for the geometric model.
+/// The spikes decay fast when beta is large compared to kappa.
+/// https://doi.org/10.1111/j.1467-9965.2007.00318.x (Benth, Kallsen, Meyer-Brandis)
+#[derive(Default, Clone)]
+pub struct ElectricitySpot<D>
+where
+  D: ProcessDistribution,
+{
+  /// Mean reversion speed of the base component
+  pub kappa: f64,
+  /// Volatility of the base component
+  pub sigma: f64,
+  /// Decay speed of the spikes
+  pub beta: f64,
+  /// Spike intensity
+  pub lambda: f64,
+  pub jump_distribution: D,
+  /// Seasonal trend s(t) (of the log price for the geometric model)
+  pub seasonality: Option<Schedule>,
+  /// Whether the components drive the log price
+  pub geometric: bool,
+  /// Initial base component
+  pub x0: Option<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl<D: ProcessDistribution> ElectricitySpot<D> {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.kappa > 0.0, "Mean reversion speed must be positive");
+    assert!(params.beta > 0.0, "Spike decay speed must be positive");
+    assert!(params.lambda >= 0.0, "Spike intensity must be non-negative");
+
+    params.clone()
+  }
+
+  /// Seasonal trend on the grid
+  fn trend(&self, dt: f64) -> Array1<f64> {
+    Array1::from_shape_fn(self.n + 1, |i| {
+      self.seasonality.as_ref().map_or(0.0, |s| s.at(i, dt))
+    })
+  }
+
+  /// Price path
+  pub fn price(&self, components: &[Array1<f64>; 2]) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let level = self.trend(dt) + &components[0] + &components[1];
+
+    if self.geometric {
+      level.mapv(f64::exp)
+    } else {
+      level
+    }
+  }
+}
+
+impl<D: ProcessDistribution> Sampling2D<f64> for ElectricitySpot<D> {
+  /// Base and spike components from their exact transitions,
+  /// the spikes of a step decay from its end
+  fn sample(&self) -> [Array1<f64>; 2] {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let base_decay = (-self.kappa * dt).exp();
+    let spike_decay = (-self.beta * dt).exp();
+    let sd = self.sigma * ((1.0 - base_decay.powi(2)) / (2.0 * self.kappa)).sqrt();
+    let mut rng = thread_rng();
+    let z = Array1::<f64>::random_using(self.n, StandardNormal, &mut rng);
+
+    let mut x = Array1::<f64>::zeros(self.n + 1);
+    let mut y = Array1::<f64>::zeros(self.n + 1);
+    x[0] = self.x0.unwrap_or(0.0);
+
+    for i in 1..=self.n {
+      let jumps = if self.lambda > 0.0 {
+        let count = Poisson::new(self.lambda * dt).unwrap().sample(&mut rng) as usize;
+        (0..count)
+          .map(|_| self.jump_distribution.sample(&mut rng))
+          .sum::<f64>()
+      } else {
+        0.0
+      };
+
+      x[i] = x[i - 1] * base_decay + sd * z[i - 1];
+      y[i] = y[i - 1] * spike_decay + jumps;
+    }
+
+    [x, y]
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl<D: ProcessDistribution> Sampling<f64> for ElectricitySpot<D> {
+  /// Price path
+  fn sample(&self) -> Array1<f64> {
+    self.price(&Sampling2D::sample(self))
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
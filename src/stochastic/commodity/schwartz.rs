@@ -0,0 +1,293 @@
+use ndarray::{Array1, ArrayView1};
+use ndarray_rand::RandomExt;
+use rand_distr::StandardNormal;
+
+use crate::{
+  quant::calibration::{
+    global::DifferentialEvolution,
+    transform::{barrier, ParameterSpace, Transform},
+  },
+  rng::thread_rng,
+  stochastic::{noise::cgns::CGNS, schedule::Schedule, Sampling, Sampling2D},
+};
+
+/// Log seasonal factor at time t of the grid with step dt, zero without seasonality
+fn season(seasonality: &Option<Schedule>, t: f64, dt: f64) -> f64 {
+  seasonality.as_ref().map_or(0.0, |s| s.value_on(t, dt))
+}
+
+/// Sum of squared log errors of a model futures curve against a strip
+fn strip_error(
+  futures: impl Fn(f64) -> f64,
+  maturities: ArrayView1<f64>,
+  prices: ArrayView1<f64>,
+) -> f64 {
+  maturities
+    .iter()
+    .zip(prices)
+    .map(|(&t, &p)| (futures(t).ln() - p.ln()).powi(2))
+    .sum()
+}
+
+/// Schwartz (1997) one-factor model, the log spot mean reverts
+/// ln S(t) = X(t) + s(t), dX(t) = kappa (alpha - X(t)) dt + sigma dW(t)
+/// with the deterministic log seasonal factor s.
+/// https://doi.org/10.1111/j.1540-6261.1997.tb02721.x
+#[derive(Default, Clone)]
+pub struct Schwartz1F {
+  /// Mean reversion speed
+  pub kappa: f64,
+  /// Long-run mean of the deseasonalized log spot
+  pub alpha: f64,
+  pub sigma: f64,
+  /// Market price of risk, the risk-neutral long-run mean is alpha - lambda / kappa
+  pub lambda: Option<f64>,
+  /// Spot price
+  pub s0: f64,
+  /// Log seasonal factor s(t)
+  pub seasonality: Option<Schedule>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl Schwartz1F {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.kappa > 0.0, "Mean reversion speed must be positive");
+    assert!(params.s0 > 0.0, "Spot price must be positive");
+
+    params.clone()
+  }
+
+  /// Step of the simulation grid
+  fn dt(&self) -> f64 {
+    self.t.unwrap_or(1.0) / self.n as f64
+  }
+
+  fn x0(&self) -> f64 {
+    self.s0.ln() - season(&self.seasonality, 0.0, self.dt())
+  }
+
+  /// Futures price for delivery at T
+  pub fn futures(&self, t: f64) -> f64 {
+    let alpha = self.alpha - self.lambda.unwrap_or(0.0) / self.kappa;
+    let decay = (-self.kappa * t).exp();
+
+    (decay * self.x0()
+      + (1.0 - decay) * alpha
+      + self.sigma.powi(2) / (4.0 * self.kappa) * (1.0 - decay.powi(2))
+      + season(&self.seasonality, t, self.dt()))
+    .exp()
+  }
+
+  /// Futures curve at the maturities
+  pub fn futures_curve(&self, maturities: ArrayView1<f64>) -> Array1<f64> {
+    maturities.mapv(|t| self.futures(t))
+  }
+
+  /// Fits kappa and the risk-neutral alpha to a futures strip (lambda is set to zero),
+  /// sigma and the seasonality are kept
+  #[must_use]
+  pub fn calibrate(
+    &self,
+    maturities: ArrayView1<f64>,
+    prices: ArrayView1<f64>,
+    de: &DifferentialEvolution,
+  ) -> Self {
+    assert_eq!(
+      de.bounds.len(),
+      2,
+      "Schwartz one-factor calibration has 2 parameters"
+    );
+    // kappa > 0, the strip does not identify a mean-fleeing spot
+    let space =
+      ParameterSpace::new(vec![Transform::Identity; 2]).with_penalty(barrier(|x| x[0] > 0.0));
+    let with = |x: &[f64]| Self {
+      kappa: x[0],
+      alpha: x[1],
+      lambda: None,
+      ..self.clone()
+    };
+    let best =
+      de.minimize(space.objective(|x| strip_error(|t| with(x).futures(t), maturities, prices)));
+
+    with(&best.params)
+  }
+}
+
+impl Sampling<f64> for Schwartz1F {
+  /// Spot path from the exact transition of the OU factor
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.dt();
+    let decay = (-self.kappa * dt).exp();
+    let sd = self.sigma * ((1.0 - decay.powi(2)) / (2.0 * self.kappa)).sqrt();
+    let z = Array1::<f64>::random_using(self.n, StandardNormal, &mut thread_rng());
+
+    let mut x = self.x0();
+    let mut s = Array1::<f64>::zeros(self.n + 1);
+    s[0] = self.s0;
+
+    for i in 1..=self.n {
+      x = x * decay + self.alpha * (1.0 - decay) + sd * z[i - 1];
+      s[i] = (x + season(&self.seasonality, i as f64 * dt, dt)).exp();
+    }
+
+    s
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+/// Schwartz (1997) two-factor model of the spot and the convenience yield
+/// dS(t) = (mu - delta(t)) S(t) dt + sigma1 S(t) dW1(t)
+/// d delta(t) = kappa (alpha - delta(t)) dt + sigma2 dW2(t), d<W1, W2> = rho dt,
+/// the spot is multiplied by the seasonal factor exp(s(t) - s(0)).
+/// https://doi.org/10.1111/j.1540-6261.1997.tb02721.x
+#[derive(Default, Clone)]
+pub struct Schwartz2F {
+  /// Drift of the spot
+  pub mu: f64,
+  /// Mean reversion speed of the convenience yield
+  pub kappa: f64,
+  /// Long-run mean of the convenience yield
+  pub alpha: f64,
+  pub sigma1: f64,
+  pub sigma2: f64,
+  pub rho: f64,
+  /// Market price of convenience yield risk, the risk-neutral mean is alpha - lambda / kappa
+  pub lambda: Option<f64>,
+  /// Risk-free rate
+  pub r: f64,
+  /// Spot price
+  pub s0: f64,
+  /// Convenience yield
+  pub delta0: f64,
+  /// Log seasonal factor s(t)
+  pub seasonality: Option<Schedule>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl Schwartz2F {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.kappa > 0.0, "Mean reversion speed must be positive");
+    assert!(params.s0 > 0.0, "Spot price must be positive");
+    assert!(
+      (-1.0..=1.0).contains(&params.rho),
+      "Correlation must be in [-1, 1]"
+    );
+
+    params.clone()
+  }
+
+  /// Step of the simulation grid
+  fn dt(&self) -> f64 {
+    self.t.unwrap_or(1.0) / self.n as f64
+  }
+
+  /// Futures price for delivery at T
+  pub fn futures(&self, t: f64) -> f64 {
+    let Self {
+      kappa,
+      sigma1,
+      sigma2,
+      rho,
+      ..
+    } = *self;
+    let alpha = self.alpha - self.lambda.unwrap_or(0.0) / kappa;
+    let decay = (-kappa * t).exp();
+
+    let a = (self.r - alpha + 0.5 * sigma2.powi(2) / kappa.powi(2) - sigma1 * sigma2 * rho / kappa)
+      * t
+      + 0.25 * sigma2.powi(2) * (1.0 - decay.powi(2)) / kappa.powi(3)
+      + (alpha * kappa + sigma1 * sigma2 * rho - sigma2.powi(2) / kappa) * (1.0 - decay)
+        / kappa.powi(2);
+
+    self.s0
+      * (a - self.delta0 * (1.0 - decay) / kappa + season(&self.seasonality, t, self.dt())
+        - season(&self.seasonality, 0.0, self.dt()))
+      .exp()
+  }
+
+  /// Futures curve at the maturities
+  pub fn futures_curve(&self, maturities: ArrayView1<f64>) -> Array1<f64> {
+    maturities.mapv(|t| self.futures(t))
+  }
+
+  /// Fits the convenience yield, kappa and the risk-neutral alpha to a futures strip
+  /// (lambda is set to zero), the volatilities, the correlation and the seasonality are kept
+  #[must_use]
+  pub fn calibrate(
+    &self,
+    maturities: ArrayView1<f64>,
+    prices: ArrayView1<f64>,
+    de: &DifferentialEvolution,
+  ) -> Self {
+    assert_eq!(
+      de.bounds.len(),
+      3,
+      "Schwartz two-factor calibration has 3 parameters"
+    );
+    // kappa > 0, the convenience yield mean reverts
+    let space =
+      ParameterSpace::new(vec![Transform::Identity; 3]).with_penalty(barrier(|x| x[1] > 0.0));
+    let with = |x: &[f64]| Self {
+      delta0: x[0],
+      kappa: x[1],
+      alpha: x[2],
+      lambda: None,
+      ..self.clone()
+    };
+    let best =
+      de.minimize(space.objective(|x| strip_error(|t| with(x).futures(t), maturities, prices)));
+
+    with(&best.params)
+  }
+}
+
+impl Sampling2D<f64> for Schwartz2F {
+  /// Spot and convenience yield paths, log-Euler for the spot
+  fn sample(&self) -> [Array1<f64>; 2] {
+    let dt = self.dt();
+    let [dw1, dw2] = CGNS::new(&CGNS {
+      rho: self.rho,
+      n: self.n,
+      t: self.t,
+      m: self.m,
+    })
+    .sample();
+
+    let mut log_s = self.s0.ln();
+    let mut s = Array1::<f64>::zeros(self.n + 1);
+    let mut delta = Array1::<f64>::zeros(self.n + 1);
+    s[0] = self.s0;
+    delta[0] = self.delta0;
+    let s_start = season(&self.seasonality, 0.0, self.dt());
+
+    for i in 1..=self.n {
+      log_s += (self.mu - delta[i - 1] - 0.5 * self.sigma1.powi(2)) * dt + self.sigma1 * dw1[i - 1];
+      delta[i] =
+        delta[i - 1] + self.kappa * (self.alpha - delta[i - 1]) * dt + self.sigma2 * dw2[i - 1];
+      s[i] = (log_s + season(&self.seasonality, i as f64 * dt, dt) - s_start).exp();
+    }
+
+    [s, delta]
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
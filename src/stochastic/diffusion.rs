@@ -1,3 +1,4 @@
+pub mod boundary;
 pub mod cir;
 pub mod fcir;
 pub mod fgbm;
@@ -5,4 +6,9 @@ pub mod fjacobi;
 pub mod fou;
 pub mod gbm;
 pub mod jacobi;
+pub mod multi_gbm;
 pub mod ou;
+pub mod regime_switching;
+pub mod skew;
+pub mod stochastic_correlation;
+pub mod stochastic_drift_gbm;
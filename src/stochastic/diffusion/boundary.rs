@@ -0,0 +1,274 @@
+use ndarray::Array1;
+use rand::Rng;
+use rand_distr::{Distribution, StandardNormal};
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+use super::regime_switching::Coefficients;
+
+/// Path of a reflected diffusion with the local times pushing it back at the boundaries
+#[derive(Debug, Clone)]
+pub struct ReflectedPath {
+  pub x: Array1<f64>,
+  /// Cumulative push up at the lower boundary
+  pub lower_local_time: Array1<f64>,
+  /// Cumulative push down at the upper boundary
+  pub upper_local_time: Array1<f64>,
+}
+
+/// Minimum (or maximum) over a step of the Brownian bridge from x to y with the variance
+/// sigma^2 dt, sampled exactly
+fn bridge_extremum(x: f64, y: f64, variance: f64, maximum: bool, rng: &mut impl Rng) -> f64 {
+  let u = 1.0 - rng.gen::<f64>();
+  let spread = ((y - x).powi(2) - 2.0 * variance * u.ln()).sqrt();
+  if maximum {
+    0.5 * (x + y + spread)
+  } else {
+    0.5 * (x + y - spread)
+  }
+}
+
+/// Euler step of dX = a(X) dt + b(X) dW reflected at the boundaries, with the increments of
+/// the lower and upper local times
+pub(crate) fn reflected_step(
+  process: &impl Coefficients,
+  prev: f64,
+  dt: f64,
+  lower: f64,
+  upper: f64,
+  rng: &mut impl Rng,
+) -> (f64, f64, f64) {
+  let b = process.diffusion(prev);
+  let z: f64 = StandardNormal.sample(rng);
+  let mut next = prev + process.drift(prev) * dt + b * dt.sqrt() * z;
+  let variance = b * b * dt;
+
+  let mut push_up = 0.0;
+  if lower.is_finite() {
+    push_up = (lower - bridge_extremum(prev, next, variance, false, rng)).max(0.0);
+    next += push_up;
+  }
+  let mut push_down = 0.0;
+  if upper.is_finite() {
+    push_down = (bridge_extremum(prev, next, variance, true, rng) - upper).max(0.0);
+    next -= push_down;
+  }
+
+  // both boundaries within one step, only on a coarse grid
+  (next.clamp(lower, upper), push_up, push_down)
+}
+
+/// Diffusion dX = a(X) dt + b(X) dW + dL - dU kept in [lower, upper] by reflection, where
+/// the local times L and U increase only when X is at a boundary (Skorokhod problem).
+/// Every Euler step is reflected with the exact extremum of the Brownian bridge over the
+/// step, so the local time is accumulated inside the step instead of by clamping the
+/// end point.
+/// https://doi.org/10.1016/0378-4754(95)00016-N (Lépingle 1995)
+#[derive(Default)]
+pub struct Reflected<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  pub process: P,
+  pub lower: Option<f64>,
+  pub upper: Option<f64>,
+  pub x0: Option<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl<P> Reflected<P>
+where
+  P: Coefficients + Send + Sync + Clone,
+{
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    let lower = params.lower.unwrap_or(f64::NEG_INFINITY);
+    let upper = params.upper.unwrap_or(f64::INFINITY);
+    assert!(lower < upper, "lower must be below upper");
+    let x0 = params.x0.unwrap_or(0.0);
+    assert!(
+      (lower..=upper).contains(&x0),
+      "x0 must be between the boundaries"
+    );
+
+    Self {
+      process: params.process.clone(),
+      lower: params.lower,
+      upper: params.upper,
+      x0: params.x0,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    }
+  }
+}
+
+impl<P> Reflected<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  pub fn sample_with_local_time(&self) -> ReflectedPath {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let lower = self.lower.unwrap_or(f64::NEG_INFINITY);
+    let upper = self.upper.unwrap_or(f64::INFINITY);
+    let mut rng = thread_rng();
+
+    let mut x = Array1::<f64>::zeros(self.n + 1);
+    let mut lower_local_time = Array1::<f64>::zeros(self.n + 1);
+    let mut upper_local_time = Array1::<f64>::zeros(self.n + 1);
+    x[0] = self.x0.unwrap_or(0.0);
+
+    for i in 1..=self.n {
+      let (next, push_up, push_down) =
+        reflected_step(&self.process, x[i - 1], dt, lower, upper, &mut rng);
+      x[i] = next;
+      lower_local_time[i] = lower_local_time[i - 1] + push_up;
+      upper_local_time[i] = upper_local_time[i - 1] + push_down;
+    }
+
+    ReflectedPath {
+      x,
+      lower_local_time,
+      upper_local_time,
+    }
+  }
+}
+
+impl<P> Sampling<f64> for Reflected<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  fn sample(&self) -> Array1<f64> {
+    self.sample_with_local_time().x
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+/// Diffusion dX = a(X) dt + b(X) dW stopped at the first exit from (lower, upper) and held at
+/// the boundary afterwards. Besides the end points of the Euler steps, the crossings inside
+/// a step are detected with the Brownian bridge crossing probability
+/// exp(-2 (x - l)(y - l) / (b^2 dt)), which removes the O(sqrt(dt)) bias of the exit time.
+/// https://doi.org/10.1016/S0304-4149(00)00010-1 (Gobet 2000)
+#[derive(Default)]
+pub struct Absorbed<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  pub process: P,
+  pub lower: Option<f64>,
+  pub upper: Option<f64>,
+  pub x0: Option<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl<P> Absorbed<P>
+where
+  P: Coefficients + Send + Sync + Clone,
+{
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    let lower = params.lower.unwrap_or(f64::NEG_INFINITY);
+    let upper = params.upper.unwrap_or(f64::INFINITY);
+    assert!(lower < upper, "lower must be below upper");
+    let x0 = params.x0.unwrap_or(0.0);
+    assert!(
+      (lower..=upper).contains(&x0),
+      "x0 must be between the boundaries"
+    );
+
+    Self {
+      process: params.process.clone(),
+      lower: params.lower,
+      upper: params.upper,
+      x0: params.x0,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    }
+  }
+}
+
+impl<P> Absorbed<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  /// Path and the time step of the absorption, if any; the absorption time is known up to
+  /// the step
+  pub fn sample_with_exit(&self) -> (Array1<f64>, Option<usize>) {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let lower = self.lower.unwrap_or(f64::NEG_INFINITY);
+    let upper = self.upper.unwrap_or(f64::INFINITY);
+    let mut rng = thread_rng();
+
+    let mut x = Array1::<f64>::zeros(self.n + 1);
+    x[0] = self.x0.unwrap_or(0.0);
+    if x[0] == lower || x[0] == upper {
+      x.fill(x[0]);
+      return (x, Some(0));
+    }
+
+    for i in 1..=self.n {
+      let prev = x[i - 1];
+      let b = self.process.diffusion(prev);
+      let z: f64 = StandardNormal.sample(&mut rng);
+      let next = prev + self.process.drift(prev) * dt + b * dt.sqrt() * z;
+      let variance = b * b * dt;
+
+      let u: f64 = rng.gen();
+      let exit = if next <= lower {
+        Some(lower)
+      } else if next >= upper {
+        Some(upper)
+      } else if variance > 0.0
+        && lower.is_finite()
+        && u < (-2.0 * (prev - lower) * (next - lower) / variance).exp()
+      {
+        Some(lower)
+      } else if variance > 0.0
+        && upper.is_finite()
+        && rng.gen::<f64>() < (-2.0 * (upper - prev) * (upper - next) / variance).exp()
+      {
+        Some(upper)
+      } else {
+        None
+      };
+
+      if let Some(boundary) = exit {
+        x.slice_mut(ndarray::s![i..]).fill(boundary);
+        return (x, Some(i));
+      }
+      x[i] = next;
+    }
+
+    (x, None)
+  }
+}
+
+impl<P> Sampling<f64> for Absorbed<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  fn sample(&self) -> Array1<f64> {
+    self.sample_with_exit().0
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
@@ -1,13 +1,15 @@
 use ndarray::Array1;
 use ndarray_rand::RandomExt;
-use rand_distr::Normal;
+use rand_distr::{Distribution, Gamma, Normal};
 
-use crate::stochastic::Sampling;
+use crate::rng::thread_rng;
+use crate::stats::likelihood::ln_bessel_i;
+use crate::stochastic::{Sampling, StationaryDistribution, TheoreticalMoments, TransitionDensity};
 
 /// Cox-Ingersoll-Ross (CIR) process.
 /// dX(t) = theta(mu - X(t))dt + sigma * sqrt(X(t))dW(t)
 /// where X(t) is the CIR process.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct CIR {
   pub theta: f64,
   pub mu: f64,
@@ -43,7 +45,11 @@ impl Sampling<f64> for CIR {
     );
 
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
 
     let mut cir = Array1::<f64>::zeros(self.n + 1);
     cir[0] = self.x0.unwrap_or(0.0);
@@ -69,3 +75,68 @@ impl Sampling<f64> for CIR {
     self.m
   }
 }
+
+impl TheoreticalMoments for CIR {
+  fn mean(&self, t: f64) -> f64 {
+    self.mu + (self.x0.unwrap_or(0.0) - self.mu) * (-self.theta * t).exp()
+  }
+
+  fn variance(&self, t: f64) -> f64 {
+    let e = (-self.theta * t).exp();
+
+    self.x0.unwrap_or(0.0) * self.sigma.powi(2) / self.theta * (e - e.powi(2))
+      + self.mu * self.sigma.powi(2) / (2.0 * self.theta) * (1.0 - e).powi(2)
+  }
+
+  fn covariance(&self, s: f64, t: f64) -> Option<f64> {
+    Some((-self.theta * (t - s).abs()).exp() * self.variance(s.min(t)))
+  }
+}
+
+/// Exact noncentral chi-square transition
+/// p(x1 | x0) = c e^(-u - v) (v / u)^(q / 2) I_q(2 sqrt(uv))
+/// with c = 2 theta / (sigma^2 (1 - e^(-theta dt))), u = c x0 e^(-theta dt), v = c x1
+/// and q = 2 theta mu / sigma^2 - 1
+impl TransitionDensity for CIR {
+  fn log_density(&self, x0: f64, x1: f64, dt: f64) -> f64 {
+    if x1 <= 0.0 || x0 <= 0.0 {
+      return f64::NEG_INFINITY;
+    }
+    let e = (-self.theta * dt).exp();
+    let c = 2.0 * self.theta / (self.sigma.powi(2) * (1.0 - e));
+    let u = c * x0 * e;
+    let v = c * x1;
+    let q = 2.0 * self.theta * self.mu / self.sigma.powi(2) - 1.0;
+
+    c.ln() - u - v + 0.5 * q * (v / u).ln() + ln_bessel_i(q, 2.0 * (u * v).sqrt())
+  }
+}
+
+/// Gamma stationary law with the shape 2 theta mu / sigma^2 and the rate 2 theta / sigma^2
+impl StationaryDistribution for CIR {
+  fn sample_stationary(&self) -> f64 {
+    assert!(
+      self.theta > 0.0 && self.mu > 0.0,
+      "The process is ergodic for theta, mu > 0"
+    );
+    let rate = 2.0 * self.theta / self.sigma.powi(2);
+    Gamma::new(rate * self.mu, 1.0 / rate)
+      .unwrap()
+      .sample(&mut thread_rng())
+  }
+
+  fn stationary_mean(&self) -> f64 {
+    self.mu
+  }
+
+  fn stationary_variance(&self) -> f64 {
+    self.mu * self.sigma.powi(2) / (2.0 * self.theta)
+  }
+
+  fn stationary_start(&self) -> Self {
+    Self {
+      x0: Some(self.sample_stationary()),
+      ..self.clone()
+    }
+  }
+}
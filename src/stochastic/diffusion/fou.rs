@@ -1,6 +1,6 @@
 use ndarray::{s, Array1};
 
-use crate::stochastic::{noise::fgn::FGN, Sampling};
+use crate::stochastic::{noise::fgn::FGN, Sampling, TheoreticalMoments};
 
 #[derive(Default)]
 pub struct FOU {
@@ -62,3 +62,80 @@ impl Sampling<f64> for FOU {
     self.m
   }
 }
+
+impl TheoreticalMoments for FOU {
+  fn mean(&self, t: f64) -> f64 {
+    self.mu + (self.x0.unwrap_or(0.0) - self.mu) * (-self.theta * t).exp()
+  }
+
+  fn variance(&self, t: f64) -> f64 {
+    self.covariance(t, t).unwrap()
+  }
+
+  /// Y(t) = int_0^t exp(-theta(t - u))dB_H(u) = B_H(t) - theta * int_0^t exp(-theta(t - u))B_H(u)du,
+  /// the covariance is evaluated by midpoint quadrature over the fBm covariance.
+  fn covariance(&self, s: f64, t: f64) -> Option<f64> {
+    const N: usize = 256;
+
+    let h2 = 2.0 * self.hurst;
+    let r = |u: f64, v: f64| 0.5 * (u.powf(h2) + v.powf(h2) - (u - v).abs().powf(h2));
+    let grid = |x: f64| (0..N).map(move |i| (i as f64 + 0.5) * x / N as f64);
+    let (ds, dt) = (s / N as f64, t / N as f64);
+
+    let rs: f64 = grid(t)
+      .map(|v| (-self.theta * (t - v)).exp() * r(s, v) * dt)
+      .sum();
+    let rt: f64 = grid(s)
+      .map(|u| (-self.theta * (s - u)).exp() * r(u, t) * ds)
+      .sum();
+    let rr: f64 = grid(s)
+      .map(|u| {
+        grid(t)
+          .map(|v| (-self.theta * (s - u + t - v)).exp() * r(u, v) * ds * dt)
+          .sum::<f64>()
+      })
+      .sum();
+
+    Some(
+      self.sigma.powi(2) * (r(s, t) - self.theta * rs - self.theta * rt + self.theta.powi(2) * rr),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use approx::assert_relative_eq;
+
+  use crate::stochastic::diffusion::ou::OU;
+
+  use super::*;
+
+  #[test]
+  fn moments_reduce_to_ou() {
+    let fou = FOU {
+      hurst: 0.5,
+      mu: 1.0,
+      sigma: 0.3,
+      theta: 2.0,
+      x0: Some(0.5),
+      ..Default::default()
+    };
+    let ou = OU {
+      mu: 1.0,
+      sigma: 0.3,
+      theta: 2.0,
+      x0: Some(0.5),
+      ..Default::default()
+    };
+
+    for t in [0.1, 0.5, 1.0] {
+      assert_relative_eq!(fou.mean(t), ou.mean(t), epsilon = 1e-12);
+      assert_relative_eq!(fou.variance(t), ou.variance(t), epsilon = 1e-3);
+    }
+    assert_relative_eq!(
+      fou.covariance(0.3, 0.8).unwrap(),
+      ou.covariance(0.3, 0.8).unwrap(),
+      epsilon = 1e-3
+    );
+  }
+}
@@ -7,9 +7,14 @@ use statrs::{
   statistics::{Distribution as StatDistribution, Median, Mode},
 };
 
-use crate::stochastic::{Distribution, Sampling};
+use crate::quant::curve::YieldCurve;
+use crate::rng::thread_rng;
+use crate::stochastic::{
+  schedule::{on_grid, Schedule},
+  Distribution, Sampling, TheoreticalMoments, TransitionDensity,
+};
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct GBM {
   pub mu: f64,
   pub sigma: f64,
@@ -18,6 +23,10 @@ pub struct GBM {
   pub t: Option<f64>,
   pub m: Option<usize>,
   pub distribution: Option<LogNormal>,
+  /// Time-dependent drift, overrides mu
+  pub mu_t: Option<Schedule>,
+  /// Time-dependent volatility, overrides sigma
+  pub sigma_t: Option<Schedule>,
 }
 
 impl GBM {
@@ -31,6 +40,18 @@ impl GBM {
       t: params.t,
       m: params.m,
       distribution: None,
+      mu_t: params.mu_t.clone(),
+      sigma_t: params.sigma_t.clone(),
+    }
+  }
+
+  /// GBM under a discount curve and an optional dividend yield curve instead of a flat
+  /// drift, the expected price at every grid point is the forward of the curves
+  #[must_use]
+  pub fn with_curves(params: &Self, rates: &YieldCurve, dividends: Option<&YieldCurve>) -> Self {
+    Self {
+      mu_t: Some(rates.drift_schedule(dividends, params.n, params.t.unwrap_or(1.0))),
+      ..Self::new(params)
     }
   }
 }
@@ -38,13 +59,20 @@ impl GBM {
 impl Sampling<f64> for GBM {
   fn sample(&self) -> Array1<f64> {
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
+
+    let mu = on_grid(&self.mu_t, self.mu, self.n, dt);
+    let sigma = on_grid(&self.sigma_t, self.sigma, self.n, dt);
 
     let mut gbm = Array1::<f64>::zeros(self.n + 1);
     gbm[0] = self.x0.unwrap_or(0.0);
 
     for i in 1..=self.n {
-      gbm[i] = gbm[i - 1] + self.mu * gbm[i - 1] * dt + self.sigma * gbm[i - 1] * gn[i - 1]
+      gbm[i] = gbm[i - 1] + mu[i - 1] * gbm[i - 1] * dt + sigma[i - 1] * gbm[i - 1] * gn[i - 1]
     }
 
     gbm
@@ -155,3 +183,37 @@ impl Distribution for GBM {
     unimplemented!()
   }
 }
+
+impl TheoreticalMoments for GBM {
+  fn mean(&self, t: f64) -> f64 {
+    self.x0.unwrap_or(0.0) * (self.mu * t).exp()
+  }
+
+  fn variance(&self, t: f64) -> f64 {
+    self.x0.unwrap_or(0.0).powi(2)
+      * (2.0 * self.mu * t).exp()
+      * ((self.sigma.powi(2) * t).exp() - 1.0)
+  }
+
+  fn covariance(&self, s: f64, t: f64) -> Option<f64> {
+    Some(
+      self.x0.unwrap_or(0.0).powi(2)
+        * (self.mu * (s + t)).exp()
+        * ((self.sigma.powi(2) * s.min(t)).exp() - 1.0),
+    )
+  }
+}
+
+/// Exact lognormal transition of the constant-parameter process
+impl TransitionDensity for GBM {
+  fn log_density(&self, x0: f64, x1: f64, dt: f64) -> f64 {
+    if x1 <= 0.0 || x0 <= 0.0 {
+      return f64::NEG_INFINITY;
+    }
+    let variance = self.sigma.powi(2) * dt;
+    let mean = x0.ln() + (self.mu - 0.5 * self.sigma.powi(2)) * dt;
+
+    -0.5 * ((2.0 * std::f64::consts::PI * variance).ln() + (x1.ln() - mean).powi(2) / variance)
+      - x1.ln()
+  }
+}
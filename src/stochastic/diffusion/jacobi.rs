@@ -1,10 +1,11 @@
 use ndarray::Array1;
 use ndarray_rand::RandomExt;
-use rand_distr::Normal;
+use rand_distr::{Beta, Distribution, Normal};
 
-use crate::stochastic::Sampling;
+use crate::rng::thread_rng;
+use crate::stochastic::{Sampling, StationaryDistribution};
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Jacobi {
   pub alpha: f64,
   pub beta: f64,
@@ -38,7 +39,11 @@ impl Sampling<f64> for Jacobi {
     assert!(self.alpha < self.beta, "alpha must be less than beta");
 
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
 
     let mut jacobi = Array1::<f64>::zeros(self.n + 1);
     jacobi[0] = self.x0.unwrap_or(0.0);
@@ -66,3 +71,39 @@ impl Sampling<f64> for Jacobi {
     self.m
   }
 }
+
+/// Beta stationary law with the parameters 2 alpha / sigma^2 and 2 (beta - alpha) / sigma^2
+/// of dX = (alpha - beta X) dt + sigma sqrt(X (1 - X)) dW
+impl StationaryDistribution for Jacobi {
+  fn sample_stationary(&self) -> f64 {
+    let (a, b) = self.beta_parameters();
+    Beta::new(a, b).unwrap().sample(&mut thread_rng())
+  }
+
+  fn stationary_mean(&self) -> f64 {
+    self.alpha / self.beta
+  }
+
+  fn stationary_variance(&self) -> f64 {
+    let (a, b) = self.beta_parameters();
+    a * b / ((a + b).powi(2) * (a + b + 1.0))
+  }
+
+  fn stationary_start(&self) -> Self {
+    Self {
+      x0: Some(self.sample_stationary()),
+      ..self.clone()
+    }
+  }
+}
+
+impl Jacobi {
+  fn beta_parameters(&self) -> (f64, f64) {
+    assert!(
+      self.alpha > 0.0 && self.alpha < self.beta,
+      "The process is ergodic for 0 < alpha < beta"
+    );
+    let s2 = self.sigma.powi(2);
+    (2.0 * self.alpha / s2, 2.0 * (self.beta - self.alpha) / s2)
+  }
+}
@@ -1,10 +1,14 @@
 use ndarray::Array1;
 use ndarray_rand::RandomExt;
-use rand_distr::Normal;
+use rand_distr::{Distribution, Normal};
 
-use crate::stochastic::Sampling;
+use crate::rng::thread_rng;
+use crate::stochastic::{
+  schedule::{on_grid, Schedule},
+  Sampling, StationaryDistribution, TheoreticalMoments, TransitionDensity,
+};
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct OU {
   pub mu: f64,
   pub sigma: f64,
@@ -13,6 +17,12 @@ pub struct OU {
   pub x0: Option<f64>,
   pub t: Option<f64>,
   pub m: Option<usize>,
+  /// Time-dependent mean, overrides mu
+  pub mu_t: Option<Schedule>,
+  /// Time-dependent volatility, overrides sigma
+  pub sigma_t: Option<Schedule>,
+  /// Time-dependent mean reversion speed, overrides theta
+  pub theta_t: Option<Schedule>,
 }
 
 impl OU {
@@ -26,6 +36,9 @@ impl OU {
       x0: params.x0,
       t: params.t,
       m: params.m,
+      mu_t: params.mu_t.clone(),
+      sigma_t: params.sigma_t.clone(),
+      theta_t: params.theta_t.clone(),
     }
   }
 }
@@ -33,13 +46,21 @@ impl OU {
 impl Sampling<f64> for OU {
   fn sample(&self) -> Array1<f64> {
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
+
+    let mu = on_grid(&self.mu_t, self.mu, self.n, dt);
+    let sigma = on_grid(&self.sigma_t, self.sigma, self.n, dt);
+    let theta = on_grid(&self.theta_t, self.theta, self.n, dt);
 
     let mut ou = Array1::<f64>::zeros(self.n + 1);
     ou[0] = self.x0.unwrap_or(0.0);
 
     for i in 1..=self.n {
-      ou[i] = ou[i - 1] + self.theta * (self.mu - ou[i - 1]) * dt + self.sigma * gn[i - 1]
+      ou[i] = ou[i - 1] + theta[i - 1] * (mu[i - 1] - ou[i - 1]) * dt + sigma[i - 1] * gn[i - 1]
     }
 
     ou
@@ -53,3 +74,61 @@ impl Sampling<f64> for OU {
     self.m
   }
 }
+
+/// Moments of the constant-parameter process
+impl TheoreticalMoments for OU {
+  fn mean(&self, t: f64) -> f64 {
+    self.mu + (self.x0.unwrap_or(0.0) - self.mu) * (-self.theta * t).exp()
+  }
+
+  fn variance(&self, t: f64) -> f64 {
+    self.sigma.powi(2) / (2.0 * self.theta) * (1.0 - (-2.0 * self.theta * t).exp())
+  }
+
+  fn covariance(&self, s: f64, t: f64) -> Option<f64> {
+    Some(
+      self.sigma.powi(2) / (2.0 * self.theta)
+        * ((-self.theta * (t - s).abs()).exp() - (-self.theta * (t + s)).exp()),
+    )
+  }
+}
+
+/// Exact Gaussian transition of the constant-parameter process
+impl TransitionDensity for OU {
+  fn log_density(&self, x0: f64, x1: f64, dt: f64) -> f64 {
+    let e = (-self.theta * dt).exp();
+    let mean = self.mu + (x0 - self.mu) * e;
+    let variance = if self.theta.abs() < 1e-12 {
+      self.sigma.powi(2) * dt
+    } else {
+      self.sigma.powi(2) / (2.0 * self.theta) * (1.0 - e * e)
+    };
+
+    -0.5 * ((2.0 * std::f64::consts::PI * variance).ln() + (x1 - mean).powi(2) / variance)
+  }
+}
+
+/// Gaussian stationary law N(mu, sigma^2 / (2 theta)) for theta > 0
+impl StationaryDistribution for OU {
+  fn sample_stationary(&self) -> f64 {
+    Normal::new(self.stationary_mean(), self.stationary_variance().sqrt())
+      .unwrap()
+      .sample(&mut thread_rng())
+  }
+
+  fn stationary_mean(&self) -> f64 {
+    self.mu
+  }
+
+  fn stationary_variance(&self) -> f64 {
+    assert!(self.theta > 0.0, "The process is ergodic for theta > 0");
+    self.sigma.powi(2) / (2.0 * self.theta)
+  }
+
+  fn stationary_start(&self) -> Self {
+    Self {
+      x0: Some(self.sample_stationary()),
+      ..self.clone()
+    }
+  }
+}
@@ -0,0 +1,147 @@
+use ndarray::{Array1, Array2};
+use ndarray_rand::RandomExt;
+use rand_distr::Normal;
+
+use crate::rng::thread_rng;
+use crate::stochastic::{process::markov_chain::MarkovChain, Sampling};
+
+use super::{cir::CIR, gbm::GBM, ou::OU};
+
+/// Drift and diffusion coefficients of a 1D diffusion
+/// dX(t) = a(X(t))dt + b(X(t))dW(t)
+pub trait Coefficients {
+  fn drift(&self, x: f64) -> f64;
+  fn diffusion(&self, x: f64) -> f64;
+}
+
+impl Coefficients for GBM {
+  fn drift(&self, x: f64) -> f64 {
+    self.mu * x
+  }
+
+  fn diffusion(&self, x: f64) -> f64 {
+    self.sigma * x
+  }
+}
+
+impl Coefficients for OU {
+  fn drift(&self, x: f64) -> f64 {
+    self.theta * (self.mu - x)
+  }
+
+  fn diffusion(&self, _x: f64) -> f64 {
+    self.sigma
+  }
+}
+
+impl Coefficients for CIR {
+  fn drift(&self, x: f64) -> f64 {
+    self.theta * (self.mu - x)
+  }
+
+  fn diffusion(&self, x: f64) -> f64 {
+    self.sigma * x.abs().sqrt()
+  }
+}
+
+/// Regime-switching diffusion.
+/// The parameters of the diffusion are switched by a continuous-time Markov chain,
+/// in regime k the process follows the dynamics of `regimes[k]`.
+#[derive(Default)]
+pub struct RegimeSwitching<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  /// Diffusion of each regime
+  pub regimes: Vec<P>,
+  /// Generator matrix of the regime chain
+  pub generator: Array2<f64>,
+  /// Initial regime
+  pub r0: Option<usize>,
+  /// Number of time steps
+  pub n: usize,
+  /// Initial value
+  pub x0: Option<f64>,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+  /// Regime chain
+  pub chain: MarkovChain,
+}
+
+impl<P> RegimeSwitching<P>
+where
+  P: Coefficients + Send + Sync + Clone,
+{
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert_eq!(
+      params.regimes.len(),
+      params.generator.nrows(),
+      "Number of regimes must match the size of the generator"
+    );
+
+    let chain = MarkovChain::new(&MarkovChain {
+      generator: params.generator.clone(),
+      x0: params.r0,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    });
+
+    Self {
+      regimes: params.regimes.clone(),
+      generator: params.generator.clone(),
+      r0: params.r0,
+      n: params.n,
+      x0: params.x0,
+      t: params.t,
+      m: params.m,
+      chain,
+    }
+  }
+}
+
+impl<P> RegimeSwitching<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  /// Sample the process together with the regime path
+  pub fn sample_with_regimes(&self) -> (Array1<f64>, Array1<usize>) {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
+    let regimes = self.chain.sample();
+
+    let mut x = Array1::<f64>::zeros(self.n + 1);
+    x[0] = self.x0.unwrap_or(0.0);
+
+    for i in 1..=self.n {
+      let p = &self.regimes[regimes[i - 1]];
+      x[i] = x[i - 1] + p.drift(x[i - 1]) * dt + p.diffusion(x[i - 1]) * gn[i - 1];
+    }
+
+    (x, regimes)
+  }
+}
+
+impl<P> Sampling<f64> for RegimeSwitching<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  fn sample(&self) -> Array1<f64> {
+    self.sample_with_regimes().0
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
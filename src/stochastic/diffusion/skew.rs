@@ -0,0 +1,185 @@
+use std::f64::consts::PI;
+
+use ndarray::Array1;
+use rand::Rng;
+use rand_distr::{Distribution, StandardNormal};
+
+use crate::rng::thread_rng;
+use crate::stochastic::{Sampling, TransitionDensity};
+
+use super::{boundary::reflected_step, regime_switching::Coefficients};
+
+/// Skew Brownian motion dX = sigma dW + (2p - 1) dL at the barrier: the excursions away from
+/// the barrier are those of a Brownian motion, and each one is above it with probability p.
+/// p = 1/2 is the Brownian motion and p = 1 the Brownian motion reflected at the barrier.
+/// Simulated exactly from |X - barrier|, a reflected Brownian motion, whose sign is redrawn
+/// whenever the Brownian bridge over the step hits the barrier.
+/// https://doi.org/10.1214/154957806000000104 (Lejay 2006)
+#[derive(Default, Clone)]
+pub struct SkewBrownianMotion {
+  /// Probability of an excursion above the barrier
+  pub p: f64,
+  pub sigma: f64,
+  pub barrier: Option<f64>,
+  pub x0: Option<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl SkewBrownianMotion {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!((0.0..=1.0).contains(&params.p), "p must be a probability");
+    assert!(params.sigma > 0.0, "sigma must be positive");
+
+    params.clone()
+  }
+
+  /// E[X(t)] = barrier + (2p - 1) sigma sqrt(2t / pi) when started at the barrier
+  pub fn mean_from_barrier(&self, t: f64) -> f64 {
+    self.barrier.unwrap_or(0.0) + (2.0 * self.p - 1.0) * self.sigma * (2.0 * t / PI).sqrt()
+  }
+}
+
+impl Sampling<f64> for SkewBrownianMotion {
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let barrier = self.barrier.unwrap_or(0.0);
+    let scale = self.sigma * dt.sqrt();
+    let mut rng = thread_rng();
+
+    let mut x = Array1::<f64>::zeros(self.n + 1);
+    x[0] = self.x0.unwrap_or(0.0);
+
+    for i in 1..=self.n {
+      let distance = (x[i - 1] - barrier).abs();
+      let z: f64 = StandardNormal.sample(&mut rng);
+      let end = distance + scale * z;
+      let hit = end <= 0.0 || rng.gen::<f64>() < (-2.0 * distance * end / (scale * scale)).exp();
+      let above = if hit {
+        rng.gen::<f64>() < self.p
+      } else {
+        x[i - 1] > barrier
+      };
+      x[i] = if above {
+        barrier + end.abs()
+      } else {
+        barrier - end.abs()
+      };
+    }
+
+    x
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl TransitionDensity for SkewBrownianMotion {
+  /// phi(y - x) + (2p - 1) sgn(y - b) phi(|x - b| + |y - b|) with phi the N(0, sigma^2 dt)
+  /// density (Walsh 1978)
+  fn log_density(&self, x0: f64, x1: f64, dt: f64) -> f64 {
+    let barrier = self.barrier.unwrap_or(0.0);
+    let variance = self.sigma * self.sigma * dt;
+    let phi = |u: f64| (-0.5 * u * u / variance).exp() / (2.0 * PI * variance).sqrt();
+    let sign = if x1 >= barrier { 1.0 } else { -1.0 };
+    (phi(x1 - x0) + (2.0 * self.p - 1.0) * sign * phi((x0 - barrier).abs() + (x1 - barrier).abs()))
+      .ln()
+  }
+}
+
+/// Diffusion dX = a(X) dt + b(X) 1{X > l} dW + dL reflected at the lower boundary l, which
+/// it sticks to: the time spent at l is stickiness x L, with L the local time of the
+/// reflection. stickiness = 0 is instantaneous reflection. Simulated as the reflected
+/// diffusion Y run on its own clock s and time changed by t(s) = s + stickiness L(s).
+/// https://doi.org/10.1214/12-AOP798 (Engelbert, Peskir 2014)
+#[derive(Default)]
+pub struct Sticky<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  pub process: P,
+  pub lower: f64,
+  /// Time spent at the boundary per unit of local time
+  pub stickiness: f64,
+  pub x0: Option<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl<P> Sticky<P>
+where
+  P: Coefficients + Send + Sync + Clone,
+{
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.stickiness >= 0.0, "stickiness must be non-negative");
+    assert!(
+      params.x0.unwrap_or(0.0) >= params.lower,
+      "x0 must be above the boundary"
+    );
+
+    Self {
+      process: params.process.clone(),
+      lower: params.lower,
+      stickiness: params.stickiness,
+      x0: params.x0,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    }
+  }
+}
+
+impl<P> Sampling<f64> for Sticky<P>
+where
+  P: Coefficients + Send + Sync,
+{
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let mut rng = thread_rng();
+
+    let mut x = Array1::<f64>::zeros(self.n + 1);
+    x[0] = self.x0.unwrap_or(0.0);
+
+    // real time in steps of the grid, every step of Y first holds at the boundary for the
+    // time gained from its local time and then moves
+    let eps = 1e-9;
+    let mut clock = 0.0;
+    let mut y = x[0];
+    let mut i = 1;
+    while i <= self.n {
+      let (next, push, _) =
+        reflected_step(&self.process, y, dt, self.lower, f64::INFINITY, &mut rng);
+      let hold_end = clock + self.stickiness * push / dt;
+      let end = hold_end + 1.0;
+      while i <= self.n && i as f64 <= end + eps {
+        x[i] = if i as f64 <= hold_end + eps {
+          self.lower
+        } else {
+          next
+        };
+        i += 1;
+      }
+      clock = end;
+      y = next;
+    }
+
+    x
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
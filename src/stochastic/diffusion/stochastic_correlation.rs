@@ -0,0 +1,101 @@
+use ndarray::Array1;
+use ndarray_rand::RandomExt;
+use rand_distr::Normal;
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling3D;
+
+/// Two geometric Brownian motions with a stochastic correlation
+/// dS_i = mu_i S_i dt + sigma_i S_i dW_i, d<W_1, W_2> = rho dt,
+/// where the correlation is the mean-reverting Jacobi-type process on [-1, 1]
+/// drho = kappa (rho_bar - rho) dt + eta sqrt(1 - rho^2) dB,
+/// with B independent of the assets. The boundaries are not attained when
+/// kappa (1 - |rho_bar|) >= eta^2.
+#[derive(Default, Clone)]
+pub struct StochasticCorrelation {
+  pub mu1: f64,
+  pub sigma1: f64,
+  pub mu2: f64,
+  pub sigma2: f64,
+  /// Mean reversion speed of the correlation
+  pub kappa: f64,
+  /// Long-run correlation
+  pub rho_bar: f64,
+  /// Volatility of the correlation
+  pub eta: f64,
+  /// Initial correlation, rho_bar if None
+  pub rho0: Option<f64>,
+  pub x01: Option<f64>,
+  pub x02: Option<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl StochasticCorrelation {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.rho_bar > -1.0 && params.rho_bar < 1.0,
+      "Long-run correlation must be in (-1, 1)"
+    );
+    assert!(
+      params.kappa >= 0.0 && params.eta >= 0.0,
+      "Mean reversion and volatility must be non-negative"
+    );
+
+    params.clone()
+  }
+
+  /// The correlation stays inside (-1, 1)
+  pub fn boundaries_unattainable(&self) -> bool {
+    self.kappa * (1.0 - self.rho_bar.abs()) >= self.eta.powi(2)
+  }
+
+  /// Expected correlation at the time t
+  pub fn mean_correlation(&self, t: f64) -> f64 {
+    self.rho_bar + (self.rho0.unwrap_or(self.rho_bar) - self.rho_bar) * (-self.kappa * t).exp()
+  }
+}
+
+impl Sampling3D<f64> for StochasticCorrelation {
+  /// The two asset paths and the correlation path. The assets are stepped exactly given the
+  /// correlation at the start of every step and the correlation by Euler steps clamped to
+  /// [-1, 1].
+  fn sample(&self) -> [Array1<f64>; 3] {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let normal = Normal::new(0.0, dt.sqrt()).unwrap();
+    let mut rng = thread_rng();
+    let w1 = Array1::random_using(self.n, normal, &mut rng);
+    let w2 = Array1::random_using(self.n, normal, &mut rng);
+    let b = Array1::random_using(self.n, normal, &mut rng);
+
+    let mut s1 = Array1::<f64>::zeros(self.n + 1);
+    let mut s2 = Array1::<f64>::zeros(self.n + 1);
+    let mut rho = Array1::<f64>::zeros(self.n + 1);
+    s1[0] = self.x01.unwrap_or(1.0);
+    s2[0] = self.x02.unwrap_or(1.0);
+    rho[0] = self.rho0.unwrap_or(self.rho_bar);
+
+    for i in 1..=self.n {
+      let r = rho[i - 1];
+      let dw2 = r * w1[i - 1] + (1.0 - r * r).sqrt() * w2[i - 1];
+      s1[i] =
+        s1[i - 1] * ((self.mu1 - 0.5 * self.sigma1.powi(2)) * dt + self.sigma1 * w1[i - 1]).exp();
+      s2[i] = s2[i - 1] * ((self.mu2 - 0.5 * self.sigma2.powi(2)) * dt + self.sigma2 * dw2).exp();
+      rho[i] =
+        (r + self.kappa * (self.rho_bar - r) * dt + self.eta * (1.0 - r * r).sqrt() * b[i - 1])
+          .clamp(-1.0, 1.0);
+    }
+
+    [s1, s2, rho]
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
@@ -0,0 +1,147 @@
+use ndarray::Array1;
+use ndarray_rand::RandomExt;
+use rand_distr::Normal;
+
+use crate::rng::thread_rng;
+use crate::stochastic::{noise::fgn::FGN, Sampling, Sampling2D};
+
+/// Parameter of the GBM driven by the OU (or fOU) factor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Modulation {
+  /// The factor is the drift, dS = Y S dt + sigma S dW
+  #[default]
+  Drift,
+  /// The absolute value of the factor is the volatility, dS = mu S dt + |Y| S dW
+  Volatility,
+}
+
+/// GBM whose drift or volatility follows the mean-reverting factor
+/// dY = kappa (theta - Y) dt + eta dB,
+/// where B is a Brownian motion correlated with W by rho, or a fractional Brownian motion
+/// (independent of W) when the Hurst exponent is set. The price is simulated by log-Euler
+/// steps, the factor by Euler steps as `OU` and `FOU`.
+#[derive(Default)]
+pub struct StochasticDriftGbm {
+  /// Drift of the price when the factor is the volatility
+  pub mu: f64,
+  /// Volatility of the price when the factor is the drift
+  pub sigma: f64,
+  /// Mean reversion speed of the factor
+  pub kappa: f64,
+  /// Long-run level of the factor
+  pub theta: f64,
+  /// Volatility of the factor
+  pub eta: f64,
+  /// Correlation between the Brownian drivers of the price and the factor
+  pub rho: f64,
+  /// Hurst exponent of the factor noise, a Brownian factor if None
+  pub hurst: Option<f64>,
+  pub modulation: Modulation,
+  pub n: usize,
+  /// Initial price
+  pub x0: Option<f64>,
+  /// Initial factor, theta if None
+  pub y0: Option<f64>,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+  pub fgn: Option<FGN>,
+}
+
+impl StochasticDriftGbm {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.kappa >= 0.0,
+      "Mean reversion speed must be non-negative"
+    );
+    assert!(
+      (-1.0..=1.0).contains(&params.rho),
+      "Correlation coefficient must be in [-1, 1]"
+    );
+    assert!(
+      params.hurst.is_none() || params.rho == 0.0,
+      "A fractional factor is independent of the price noise"
+    );
+
+    Self {
+      mu: params.mu,
+      sigma: params.sigma,
+      kappa: params.kappa,
+      theta: params.theta,
+      eta: params.eta,
+      rho: params.rho,
+      hurst: params.hurst,
+      modulation: params.modulation,
+      n: params.n,
+      x0: params.x0,
+      y0: params.y0,
+      t: params.t,
+      m: params.m,
+      fgn: params
+        .hurst
+        .map(|hurst| FGN::new(hurst, params.n, params.t, params.m)),
+    }
+  }
+
+  /// Mean and variance of int_0^t Y ds for the Brownian factor
+  fn integrated_factor(&self, t: f64) -> (f64, f64) {
+    let y0 = self.y0.unwrap_or(self.theta);
+    let k = self.kappa;
+    let decay = (1.0 - (-k * t).exp()) / k;
+    let mean = self.theta * t + (y0 - self.theta) * decay;
+    let variance =
+      self.eta.powi(2) / (k * k) * (t - 2.0 * decay + (1.0 - (-2.0 * k * t).exp()) / (2.0 * k));
+    (mean, variance)
+  }
+
+  /// Expected price at the time t of the uncorrelated drift model with a Brownian factor,
+  /// x0 exp(m + v / 2) with the Gaussian mean m and variance v of the integrated drift
+  pub fn expected_price(&self, t: f64) -> f64 {
+    assert!(
+      self.modulation == Modulation::Drift && self.hurst.is_none() && self.rho == 0.0,
+      "The expected price is known for the uncorrelated Brownian drift model"
+    );
+    assert!(self.kappa > 0.0, "Mean reversion speed must be positive");
+    let (mean, variance) = self.integrated_factor(t);
+    self.x0.unwrap_or(1.0) * (mean + 0.5 * variance).exp()
+  }
+}
+
+impl Sampling2D<f64> for StochasticDriftGbm {
+  /// Price and factor paths
+  fn sample(&self) -> [Array1<f64>; 2] {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let normal = Normal::new(0.0, dt.sqrt()).unwrap();
+    let mut rng = thread_rng();
+    let gn = Array1::random_using(self.n, normal, &mut rng);
+    let factor_noise = match &self.fgn {
+      Some(fgn) => fgn.sample(),
+      None => Array1::random_using(self.n, normal, &mut rng),
+    };
+    let price_noise = self.rho * &factor_noise + (1.0 - self.rho.powi(2)).sqrt() * &gn;
+
+    let mut x = Array1::<f64>::zeros(self.n + 1);
+    let mut y = Array1::<f64>::zeros(self.n + 1);
+    x[0] = self.x0.unwrap_or(1.0);
+    y[0] = self.y0.unwrap_or(self.theta);
+
+    for i in 1..=self.n {
+      let (mu, sigma) = match self.modulation {
+        Modulation::Drift => (y[i - 1], self.sigma),
+        Modulation::Volatility => (self.mu, y[i - 1].abs()),
+      };
+      x[i] = x[i - 1] * ((mu - 0.5 * sigma * sigma) * dt + sigma * price_noise[i - 1]).exp();
+      y[i] = y[i - 1] + self.kappa * (self.theta - y[i - 1]) * dt + self.eta * factor_noise[i - 1];
+    }
+
+    [x, y]
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
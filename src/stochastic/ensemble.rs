@@ -0,0 +1,163 @@
+use ndarray::Array1;
+use rand::Rng;
+use statrs::distribution::{ContinuousCDF, Gamma};
+
+use crate::{
+  rng::{thread_rng, with_seed},
+  stats::density::Histogram,
+  stochastic::{Sampling, Sampling2D},
+};
+
+/// Law of the initial values of an ensemble of paths
+#[derive(Debug, Clone)]
+pub enum InitialLaw {
+  /// Gamma law with the shape and rate, the stationary law of a CIR variance
+  Gamma { shape: f64, rate: f64 },
+  /// Piecewise uniform law of a histogram
+  Histogram(Histogram),
+}
+
+impl InitialLaw {
+  /// Stationary law of the variance dv = kappa (theta - v) dt + sigma sqrt(v) dW
+  pub fn cir_stationary(kappa: f64, theta: f64, sigma: f64) -> Self {
+    assert!(
+      kappa > 0.0 && theta > 0.0 && sigma > 0.0,
+      "Parameters must be positive"
+    );
+    let rate = 2.0 * kappa / sigma.powi(2);
+    Self::Gamma {
+      shape: rate * theta,
+      rate,
+    }
+  }
+
+  pub fn quantile(&self, p: f64) -> f64 {
+    match self {
+      Self::Gamma { shape, rate } => Gamma::new(*shape, *rate).unwrap().inverse_cdf(p),
+      Self::Histogram(h) => {
+        let total = h.counts.sum() as f64;
+        let mut cumulative = 0.0;
+        for (i, &c) in h.counts.iter().enumerate() {
+          let w = c as f64 / total;
+          if cumulative + w >= p && w > 0.0 {
+            let fraction = (p - cumulative) / w;
+            return h.edges[i] + fraction * (h.edges[i + 1] - h.edges[i]);
+          }
+          cumulative += w;
+        }
+        h.edges[h.edges.len() - 1]
+      }
+    }
+  }
+
+  pub fn mean(&self) -> f64 {
+    match self {
+      Self::Gamma { shape, rate } => shape / rate,
+      Self::Histogram(h) => {
+        let total = h.counts.sum() as f64;
+        (&h.centers() * &h.counts.mapv(|c| c as f64)).sum() / total
+      }
+    }
+  }
+
+  pub fn variance(&self) -> f64 {
+    match self {
+      Self::Gamma { shape, rate } => shape / rate.powi(2),
+      Self::Histogram(h) => {
+        // every bin is uniform, it adds its width^2 / 12 to the spread of the centers
+        let total = h.counts.sum() as f64;
+        let mean = self.mean();
+        h.counts
+          .iter()
+          .enumerate()
+          .map(|(i, &c)| {
+            let width = h.edges[i + 1] - h.edges[i];
+            let center = 0.5 * (h.edges[i] + h.edges[i + 1]);
+            c as f64 / total * ((center - mean).powi(2) + width * width / 12.0)
+          })
+          .sum()
+      }
+    }
+  }
+
+  /// Smallest value of the support
+  fn lower_bound(&self) -> f64 {
+    match self {
+      Self::Gamma { .. } => 0.0,
+      Self::Histogram(h) => h.edges[0],
+    }
+  }
+}
+
+/// Ensemble of m initial values drawn from the law by inversion.
+/// With `antithetic` the second half of the uniforms mirrors the first (u and 1 - u).
+/// With `moment_matching` the ensemble is shifted and scaled to the exact mean and variance
+/// of the law, and floored at the lower end of its support.
+pub fn initial_ensemble(
+  law: &InitialLaw,
+  m: usize,
+  antithetic: bool,
+  moment_matching: bool,
+  seed: u64,
+) -> Array1<f64> {
+  assert!(m > 1, "At least two values are needed");
+  let uniforms = with_seed(seed, || {
+    let mut rng = thread_rng();
+    let half = if antithetic { m.div_ceil(2) } else { m };
+    let u = (0..half)
+      .map(|_| rng.gen_range(f64::EPSILON..1.0 - f64::EPSILON))
+      .collect::<Vec<_>>();
+    if antithetic {
+      u.iter()
+        .copied()
+        .chain(u.iter().map(|v| 1.0 - v))
+        .take(m)
+        .collect::<Vec<_>>()
+    } else {
+      u
+    }
+  });
+
+  let mut values = Array1::from_iter(uniforms.iter().map(|&u| law.quantile(u)));
+
+  if moment_matching {
+    let mean = values.mean().unwrap();
+    let sd = values.std(0.0);
+    let scale = if sd > 0.0 {
+      law.variance().sqrt() / sd
+    } else {
+      1.0
+    };
+    let (target, floor) = (law.mean(), law.lower_bound());
+    values.mapv_inplace(|v| (target + (v - mean) * scale).max(floor));
+  }
+
+  values
+}
+
+/// One component of a two-dimensional sampler as a sampler of its own, e.g. the price of a
+/// stochastic volatility model, so it can be run by the batch runner
+#[derive(Default)]
+pub struct Marginal<S> {
+  pub sampler: S,
+  /// Index of the component, 0 or 1
+  pub component: usize,
+}
+
+impl<S: Sampling2D<f64>> Sampling<f64> for Marginal<S> {
+  fn sample(&self) -> Array1<f64> {
+    let [a, b] = self.sampler.sample();
+    match self.component {
+      0 => a,
+      _ => b,
+    }
+  }
+
+  fn n(&self) -> usize {
+    self.sampler.n()
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.sampler.m()
+  }
+}
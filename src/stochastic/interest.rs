@@ -4,4 +4,5 @@ pub mod fvasicek;
 pub mod ho_lee;
 pub mod hull_white;
 pub mod hull_white_2f;
+pub mod lmm;
 pub mod vasicek;
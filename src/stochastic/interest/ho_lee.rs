@@ -4,6 +4,7 @@ use ndarray::Array1;
 use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 
+use crate::rng::thread_rng;
 use crate::stochastic::Sampling;
 
 #[allow(non_snake_case)]
@@ -41,9 +42,10 @@ impl<'a> Sampling<f64> for HoLee<'a> {
       "theta or f_T must be provided"
     );
     let dt = self.t / self.n as f64;
-    let gn = Array1::random(
+    let gn = Array1::random_using(
       self.n,
       Normal::new(0.0, (self.t / self.n as f64).sqrt()).unwrap(),
+      &mut thread_rng(),
     );
 
     let mut r = Array1::<f64>::zeros(self.n + 1);
@@ -2,6 +2,7 @@ use ndarray::Array1;
 use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 
+use crate::rng::thread_rng;
 use crate::stochastic::Sampling;
 
 /// Hull-White process.
@@ -35,7 +36,11 @@ impl HullWhite {
 impl Sampling<f64> for HullWhite {
   fn sample(&self) -> Array1<f64> {
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
 
     let mut hw = Array1::<f64>::zeros(self.n + 1);
     hw[0] = self.x0.unwrap_or(0.0);
@@ -0,0 +1,169 @@
+use nalgebra::DMatrix;
+use ndarray::{Array1, Array2, ArrayView1};
+use ndarray_rand::RandomExt;
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+
+use crate::rng::{path_seeds, seeded, thread_rng};
+
+/// LIBOR market model: the forward rates L_j on [T_j, T_j+1] of the tenor dates
+/// 0 = T_0 < ... < T_N are lognormal with constant volatilities sigma_j and the correlation
+/// exp(-beta |T_i - T_j|) under the spot LIBOR measure, whose numeraire rolls over the
+/// shortest bond, dL_j / L_j = sigma_j sum_{i = q(t)}^{j} delta_i rho_ij sigma_i L_i
+/// / (1 + delta_i L_i) dt + sigma_j dW_j. The rates are simulated on the tenor dates with
+/// `steps` log-Euler steps per accrual period and a predictor-corrector drift.
+/// https://doi.org/10.1111/1467-9965.00020 (Brace, Gatarek, Musiela 1997)
+#[derive(Default, Clone)]
+pub struct Lmm {
+  /// Tenor dates T_0 = 0 < ... < T_N
+  pub tenors: Array1<f64>,
+  /// Initial forward rates L_j(0), one per accrual period
+  pub l0: Array1<f64>,
+  /// Volatilities of the forward rates
+  pub sigma: Array1<f64>,
+  /// Decay of the correlation with the distance of the reset dates
+  pub beta: f64,
+  /// Log-Euler steps per accrual period (default 1)
+  pub steps: Option<usize>,
+  pub m: Option<usize>,
+  /// Lower Cholesky factor of the correlation matrix
+  pub cholesky: Array2<f64>,
+}
+
+/// Path of the LIBOR market model on the tenor dates
+#[derive(Debug, Clone)]
+pub struct LmmPath {
+  /// Forward rates, one row per tenor date T_0..T_N-1 and one column per rate, the rates
+  /// past their reset keep their fixing
+  pub forwards: Array2<f64>,
+  /// Spot LIBOR numeraire B(T_k) = prod_{i < k} (1 + delta_i L_i(T_i)), one per tenor date
+  pub numeraire: Array1<f64>,
+}
+
+impl Lmm {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    let n = params.l0.len();
+    assert_eq!(
+      params.tenors.len(),
+      n + 1,
+      "One forward rate per accrual period"
+    );
+    assert_eq!(params.sigma.len(), n, "One volatility per forward rate");
+    assert!(
+      params.tenors[0] == 0.0 && params.tenors.windows(2).into_iter().all(|w| w[0] < w[1]),
+      "Tenor dates must start at 0 and increase"
+    );
+
+    let corr = DMatrix::from_fn(n, n, |i, j| {
+      (-params.beta * (params.tenors[i] - params.tenors[j]).abs()).exp()
+    });
+    let l = corr
+      .cholesky()
+      .expect("Correlation matrix must be positive definite")
+      .l();
+
+    Self {
+      tenors: params.tenors.clone(),
+      l0: params.l0.clone(),
+      sigma: params.sigma.clone(),
+      beta: params.beta,
+      steps: params.steps,
+      m: params.m,
+      cholesky: Array2::from_shape_fn((n, n), |(i, j)| l[(i, j)]),
+    }
+  }
+
+  /// Number of forward rates
+  pub fn dim(&self) -> usize {
+    self.l0.len()
+  }
+
+  /// Accrual fractions delta_j = T_j+1 - T_j
+  pub fn accruals(&self) -> Array1<f64> {
+    Array1::from_shape_fn(self.dim(), |j| self.tenors[j + 1] - self.tenors[j])
+  }
+
+  pub fn correlation(&self, i: usize, j: usize) -> f64 {
+    (-self.beta * (self.tenors[i] - self.tenors[j]).abs()).exp()
+  }
+
+  /// Drifts of the log rates alive from the rate q on, for the rates l
+  fn drifts(&self, l: &Array1<f64>, q: usize, delta: &Array1<f64>) -> Array1<f64> {
+    let n = self.dim();
+    let mut mu = Array1::<f64>::zeros(n);
+    for j in q..n {
+      let sum = (q..=j)
+        .map(|i| delta[i] * self.correlation(i, j) * self.sigma[i] * l[i] / (1.0 + delta[i] * l[i]))
+        .sum::<f64>();
+      mu[j] = self.sigma[j] * sum - 0.5 * self.sigma[j].powi(2);
+    }
+    mu
+  }
+
+  pub fn sample(&self) -> LmmPath {
+    let n = self.dim();
+    let steps = self.steps.unwrap_or(1).max(1);
+    let delta = self.accruals();
+    let z = Array2::<f64>::random_using((n * steps, n), StandardNormal, &mut thread_rng());
+    let dw = z.dot(&self.cholesky.t());
+
+    let mut forwards = Array2::<f64>::zeros((n, n));
+    let mut numeraire = Array1::<f64>::ones(n + 1);
+    let mut l = self.l0.clone();
+    forwards.row_mut(0).assign(&l);
+
+    for k in 0..n - 1 {
+      numeraire[k + 1] = numeraire[k] * (1.0 + delta[k] * l[k]);
+      let dt = delta[k] / steps as f64;
+      // the rates from k + 1 on are alive over (T_k, T_k+1]
+      let q = k + 1;
+      for s in 0..steps {
+        let row = dw.row(k * steps + s);
+        let mu0 = self.drifts(&l, q, &delta);
+        let mut predicted = l.clone();
+        for j in q..n {
+          predicted[j] = l[j] * (mu0[j] * dt + self.sigma[j] * row[j] * dt.sqrt()).exp();
+        }
+        let mu1 = self.drifts(&predicted, q, &delta);
+        for j in q..n {
+          l[j] *= (0.5 * (mu0[j] + mu1[j]) * dt + self.sigma[j] * row[j] * dt.sqrt()).exp();
+        }
+      }
+      forwards.row_mut(k + 1).assign(&l);
+    }
+    numeraire[n] = numeraire[n - 1] * (1.0 + delta[n - 1] * l[n - 1]);
+
+    LmmPath {
+      forwards,
+      numeraire,
+    }
+  }
+
+  /// m paths sampled in parallel
+  pub fn sample_par(&self) -> Vec<LmmPath> {
+    let m = self.m.expect("m must be specified for parallel sampling");
+    let seeds = path_seeds(m);
+    (0..m)
+      .into_par_iter()
+      .map(|i| seeded(seeds.as_ref().map(|s| s[i]), || self.sample()))
+      .collect()
+  }
+
+  /// Price at time 0 of the zero coupon bond maturing at T_j
+  pub fn bond(&self, j: usize) -> f64 {
+    let delta = self.accruals();
+    (0..j)
+      .map(|i| 1.0 / (1.0 + delta[i] * self.l0[i]))
+      .product()
+  }
+}
+
+impl LmmPath {
+  /// Price at T_k of the zero coupon bond maturing at T_j, j >= k
+  pub fn bond(&self, delta: ArrayView1<f64>, k: usize, j: usize) -> f64 {
+    (k..j)
+      .map(|i| 1.0 / (1.0 + delta[i] * self.forwards[(k, i)]))
+      .product()
+  }
+}
@@ -25,6 +25,7 @@ impl Vasicek {
       x0: params.x0,
       t: params.t,
       m: params.m,
+      ..Default::default()
     });
 
     Self {
@@ -0,0 +1,106 @@
+use ndarray::{Array1, ArrayView1};
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+use crate::{
+  rng::thread_rng,
+  stochastic::process::gaussian_process::{sample_conditional, Kernel},
+};
+
+/// Method to move a path to another time grid
+#[derive(Clone)]
+pub enum Interpolation {
+  /// Linear between the knots, constant outside them
+  Linear,
+  /// Last value at or before the time (for jump and counting processes)
+  Previous,
+  /// Brownian bridges of volatility sigma between the knots and a Brownian motion
+  /// after the last one, so the refined path keeps the law of a Brownian path
+  BrownianBridge(f64),
+  /// Conditional sample of a centered Gaussian process with the kernel given the knots,
+  /// consistent with the law of the whole path (fBm, Gaussian processes)
+  Gaussian(Kernel),
+}
+
+/// Index of the last knot at or before t, None before the first knot
+fn knot_before(times: ArrayView1<f64>, t: f64) -> Option<usize> {
+  times
+    .as_slice()
+    .map_or_else(
+      || times.iter().take_while(|&&ti| ti <= t).count(),
+      |times| times.partition_point(|&ti| ti <= t),
+    )
+    .checked_sub(1)
+}
+
+/// Path observed at `times` moved to `new_times`, both increasing
+pub fn resample(
+  times: ArrayView1<f64>,
+  path: ArrayView1<f64>,
+  new_times: ArrayView1<f64>,
+  method: &Interpolation,
+) -> Array1<f64> {
+  assert_eq!(times.len(), path.len(), "One value is needed per time");
+  assert!(!times.is_empty(), "Path must not be empty");
+  assert!(
+    times.windows(2).into_iter().all(|w| w[0] < w[1])
+      && new_times.windows(2).into_iter().all(|w| w[0] < w[1]),
+    "Time grids must be increasing"
+  );
+  let last = times.len() - 1;
+
+  match method {
+    Interpolation::Linear => new_times.mapv(|t| match knot_before(times, t) {
+      None => path[0],
+      Some(k) if k == last => path[last],
+      Some(k) => {
+        let w = (t - times[k]) / (times[k + 1] - times[k]);
+        path[k] + w * (path[k + 1] - path[k])
+      }
+    }),
+    Interpolation::Previous => new_times.mapv(|t| path[knot_before(times, t).unwrap_or(0)]),
+    Interpolation::BrownianBridge(sigma) => {
+      assert!(
+        new_times.first().is_none_or(|&t| t >= times[0]),
+        "Brownian bridge interpolation starts at the first knot"
+      );
+      let mut rng = thread_rng();
+      let mut out = Array1::<f64>::zeros(new_times.len());
+      // last point of the refined path, a knot or a sampled value
+      let (mut s, mut x) = (times[0], path[0]);
+
+      for (i, &t) in new_times.iter().enumerate() {
+        let k = knot_before(times, t).unwrap();
+        if times[k] > s {
+          (s, x) = (times[k], path[k]);
+        }
+
+        let z: f64 = rng.sample(StandardNormal);
+        out[i] = if t == times[k] {
+          path[k]
+        } else if k == last {
+          x + sigma * (t - s).sqrt() * z
+        } else {
+          let (u, y) = (times[k + 1], path[k + 1]);
+          let w = (t - s) / (u - s);
+          x + w * (y - x) + sigma * ((t - s) * (u - t) / (u - s)).sqrt() * z
+        };
+        (s, x) = (t, out[i]);
+      }
+
+      out
+    }
+    Interpolation::Gaussian(kernel) => sample_conditional(kernel, times, path, new_times),
+  }
+}
+
+/// Path on a uniform grid of n + 1 points over [0, t] moved to `new_times`
+pub fn resample_uniform(
+  path: ArrayView1<f64>,
+  t: f64,
+  new_times: ArrayView1<f64>,
+  method: &Interpolation,
+) -> Array1<f64> {
+  let times = Array1::linspace(0.0, t, path.len());
+  resample(times.view(), path, new_times, method)
+}
@@ -1,7 +1,10 @@
 pub mod bates;
+pub mod gh;
+pub mod hawkes_jump_diffusion;
 pub mod ig;
 pub mod jump_fou;
 pub mod levy_diffusion;
+pub mod meixner;
 pub mod merton;
 pub mod nig;
 pub mod vg;
@@ -1,8 +1,10 @@
 use ndarray::Array1;
 
 use crate::stochastic::{
-  noise::cgns::CGNS, process::cpoisson::CompoundPoisson, ProcessDistribution, Sampling2D,
-  Sampling3D,
+  noise::cgns::CGNS,
+  process::cpoisson::CompoundPoisson,
+  volatility::diagnostics::{HestonDiagnostics, HestonParams},
+  ProcessDistribution, Sampling2D,
 };
 
 #[derive(Default)]
@@ -92,12 +94,12 @@ impl<D: ProcessDistribution> Sampling2D<f64> for Bates1996<D> {
     };
 
     for i in 1..=self.n {
-      let [.., jumps] = self.cpoisson.sample();
+      let jumps = self.cpoisson.sample_jumps();
 
       s[i] = s[i - 1]
         + (drift - self.lambda * self.k) * s[i - 1] * dt
         + s[i - 1] * v[i - 1].sqrt() * cgn1[i - 1]
-        + jumps.sum();
+        + jumps.total();
 
       let dv = (self.alpha - self.beta * v[i - 1]) * dt + self.sigma * v[i - 1] * cgn2[i - 1];
 
@@ -118,3 +120,17 @@ impl<D: ProcessDistribution> Sampling2D<f64> for Bates1996<D> {
     self.m
   }
 }
+
+impl<D: ProcessDistribution> HestonDiagnostics for Bates1996<D> {
+  /// The variance follows dv = (alpha - beta * v)dt + ..., so kappa = beta and theta = alpha / beta
+  fn heston_params(&self) -> HestonParams {
+    HestonParams {
+      v0: self.v0.unwrap_or(0.0),
+      kappa: self.beta,
+      theta: self.alpha / self.beta,
+      sigma: self.sigma,
+      rho: self.rho,
+      t: self.t.unwrap_or(1.0),
+    }
+  }
+}
@@ -0,0 +1,115 @@
+use ndarray::Array1;
+use ndarray_rand::RandomExt;
+use rand_distr::Normal;
+
+use crate::rng::thread_rng;
+use crate::stochastic::{process::hawkes::Hawkes, ProcessDistribution, Sampling};
+
+/// Self-exciting jump-diffusion.
+/// dS(t) / S(t-) = mu dt + sigma dW(t) + (exp(J) - 1) dN(t)
+/// where N(t) is a Hawkes process with exponential kernel and J are the log-jump sizes.
+/// Jumps cluster in time, which makes it suitable for flash-crash and contagion studies.
+#[derive(Default)]
+pub struct HawkesJumpDiffusion<D>
+where
+  D: ProcessDistribution,
+{
+  /// Drift
+  pub mu: f64,
+  /// Diffusion volatility
+  pub sigma: f64,
+  /// Baseline jump intensity
+  pub lambda0: f64,
+  /// Jump of the intensity after a jump
+  pub alpha: f64,
+  /// Decay rate of the intensity
+  pub beta: f64,
+  /// Number of time steps
+  pub n: usize,
+  /// Initial price
+  pub s0: Option<f64>,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+  /// Log-jump size distribution
+  pub jump_distribution: D,
+  /// Jump time generator
+  pub hawkes: Hawkes,
+}
+
+impl<D: ProcessDistribution> HawkesJumpDiffusion<D> {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    let hawkes = Hawkes::new(&Hawkes {
+      lambda0: params.lambda0,
+      alpha: params.alpha,
+      beta: params.beta,
+      n: None,
+      t_max: params.t.unwrap_or(1.0),
+      m: params.m,
+    });
+
+    Self {
+      mu: params.mu,
+      sigma: params.sigma,
+      lambda0: params.lambda0,
+      alpha: params.alpha,
+      beta: params.beta,
+      n: params.n,
+      s0: params.s0,
+      t: params.t,
+      m: params.m,
+      jump_distribution: params.jump_distribution,
+      hawkes,
+    }
+  }
+
+  /// Sample the price path and the jump intensity on the time grid
+  pub fn sample_with_intensity(&self) -> [Array1<f64>; 2] {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
+    let events = self.hawkes.sample();
+    let grid = Array1::linspace(0.0, self.t.unwrap_or(1.0), self.n + 1);
+    let mut rng = thread_rng();
+
+    let mut s = Array1::<f64>::zeros(self.n + 1);
+    s[0] = self.s0.unwrap_or(100.0);
+    let mut k = 1;
+
+    for i in 1..=self.n {
+      let mut jumps = 0.0;
+
+      while k < events.len() && events[k] <= grid[i] {
+        jumps += self.jump_distribution.sample(&mut rng);
+        k += 1;
+      }
+
+      s[i] = s[i - 1]
+        * ((self.mu - 0.5 * self.sigma.powi(2)) * dt + self.sigma * gn[i - 1] + jumps).exp();
+    }
+
+    let intensity = self.hawkes.intensity_path(events.view(), grid.view());
+
+    [s, intensity]
+  }
+}
+
+impl<D: ProcessDistribution> Sampling<f64> for HawkesJumpDiffusion<D> {
+  fn sample(&self) -> Array1<f64> {
+    let [s, _] = self.sample_with_intensity();
+    s
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
@@ -1,7 +1,7 @@
 use ndarray::{s, Array1};
 
 use crate::stochastic::{
-  noise::fgn::FGN, process::cpoisson::CompoundPoisson, ProcessDistribution, Sampling, Sampling3D,
+  noise::fgn::FGN, process::cpoisson::CompoundPoisson, ProcessDistribution, Sampling,
 };
 
 #[derive(Default)]
@@ -67,12 +67,12 @@ impl<D: ProcessDistribution> Sampling<f64> for JumpFOU<D> {
     jump_fou[0] = self.x0.unwrap_or(0.0);
 
     for i in 1..=self.n {
-      let [.., jumps] = self.cpoisson.sample();
+      let jumps = self.cpoisson.sample_jumps();
 
       jump_fou[i] = jump_fou[i - 1]
         + self.theta * (self.mu - jump_fou[i - 1]) * dt
         + self.sigma * fgn[i - 1]
-        + jumps.sum();
+        + jumps.total();
     }
 
     jump_fou.slice(s![..self.n()]).to_owned()
@@ -2,9 +2,8 @@ use ndarray::Array1;
 use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 
-use crate::stochastic::{
-  process::cpoisson::CompoundPoisson, ProcessDistribution, Sampling, Sampling3D,
-};
+use crate::rng::thread_rng;
+use crate::stochastic::{process::cpoisson::CompoundPoisson, ProcessDistribution, Sampling};
 
 #[derive(Default)]
 pub struct LevyDiffusion<D>
@@ -53,11 +52,15 @@ impl<D: ProcessDistribution> Sampling<f64> for LevyDiffusion<D> {
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
     let mut levy = Array1::<f64>::zeros(self.n + 1);
     levy[0] = self.x0.unwrap_or(0.0);
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
 
     for i in 1..=self.n {
-      let [.., jumps] = self.cpoisson.sample();
-      levy[i] = levy[i - 1] + self.gamma * dt + self.sigma * gn[i - 1] + jumps.sum();
+      let jumps = self.cpoisson.sample_jumps();
+      levy[i] = levy[i - 1] + self.gamma * dt + self.sigma * gn[i - 1] + jumps.total();
     }
 
     levy
@@ -0,0 +1,136 @@
+use std::f64::consts::PI;
+
+use ndarray::Array1;
+use num_complex::Complex64;
+use rand_distr::{Distribution, Gamma, StandardNormal};
+
+use crate::quant::volatility::piecewise_heston::gil_pelaez;
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+/// Terms of the series of the increments drawn exactly, the rest is matched by a Gaussian
+const TERMS: usize = 64;
+
+/// Meixner process, X(1) with the characteristic function
+/// (cos(b / 2) / cosh((a u - i b) / 2))^(2d) e^(i mu u), a > 0, |b| < pi, d > 0.
+/// The increments are drawn from the product form of cosh, a sum of independent
+/// differences of gamma variables
+/// X(dt) = mu dt + sum_k a / ((2k + 1) pi) (G_k / (1 - b_k) - G'_k / (1 + b_k)),
+/// G_k, G'_k ~ Gamma(2d dt, 1), b_k = b / ((2k + 1) pi).
+/// https://doi.org/10.1007/978-1-4613-0111-0_9 (Schoutens, Teugels 1998)
+#[derive(Default)]
+pub struct Meixner {
+  pub a: f64,
+  pub b: f64,
+  pub d: f64,
+  pub mu: f64,
+  pub n: usize,
+  pub x0: Option<f64>,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl Meixner {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.a > 0.0, "a must be positive");
+    assert!(params.b.abs() < PI, "b must be in (-pi, pi)");
+    assert!(params.d > 0.0, "d must be positive");
+
+    Self {
+      a: params.a,
+      b: params.b,
+      d: params.d,
+      mu: params.mu,
+      n: params.n,
+      x0: params.x0,
+      t: params.t,
+      m: params.m,
+    }
+  }
+
+  /// Characteristic function of X(t)
+  pub fn characteristic_function(&self, u: Complex64, t: f64) -> Complex64 {
+    let i = Complex64::i();
+    // ln cosh(z) = z + ln(1 + e^(-2z)) - ln 2 for Re z >= 0, stable for large u
+    let mut z = (self.a * u - i * self.b) / 2.0;
+    if z.re < 0.0 {
+      z = -z;
+    }
+    let ln_cosh = z + (1.0 + (-2.0 * z).exp()).ln() - std::f64::consts::LN_2;
+    (2.0 * self.d * t * ((self.b / 2.0).cos().ln() - ln_cosh) + i * u * self.mu * t).exp()
+  }
+
+  /// Mean of X(1), mu + a d tan(b / 2)
+  pub fn mean(&self) -> f64 {
+    self.mu + self.a * self.d * (self.b / 2.0).tan()
+  }
+
+  /// Variance of X(1), a^2 d / (2 cos^2(b / 2))
+  pub fn variance(&self) -> f64 {
+    self.a.powi(2) * self.d / (2.0 * (self.b / 2.0).cos().powi(2))
+  }
+
+  /// Call and put prices under the mean-correcting martingale measure,
+  /// S(t) = s0 e^((r - q) t + X(t) - t ln E[e^X(1)])
+  pub fn price(&self, s0: f64, k: f64, r: f64, q: f64, t: f64) -> (f64, f64) {
+    let i = Complex64::i();
+    let compensator = self.characteristic_function(-i, 1.0).ln();
+    gil_pelaez(
+      |u| {
+        (i * u * (s0.ln() + (r - q) * t) - i * u * compensator * t).exp()
+          * self.characteristic_function(u, t)
+      },
+      s0,
+      k,
+      r,
+      q,
+      t,
+    )
+  }
+}
+
+impl Sampling<f64> for Meixner {
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let gamma = Gamma::new(2.0 * self.d * dt, 1.0).unwrap();
+    let mut rng = thread_rng();
+
+    // terms drawn exactly, the remainder is matched in mean and variance
+    let (mut head_mean, mut head_variance) = (0.0, 0.0);
+    let weights = (0..TERMS)
+      .map(|k| {
+        let c = self.a / ((2 * k + 1) as f64 * PI);
+        let bk = self.b / ((2 * k + 1) as f64 * PI);
+        let (up, down) = (c / (1.0 - bk), c / (1.0 + bk));
+        head_mean += 2.0 * self.d * dt * (up - down);
+        head_variance += 2.0 * self.d * dt * (up * up + down * down);
+        (up, down)
+      })
+      .collect::<Vec<_>>();
+    let tail_mean = (self.mean() - self.mu) * dt - head_mean;
+    let tail_std = (self.variance() * dt - head_variance).max(0.0).sqrt();
+
+    let mut x = Array1::<f64>::zeros(self.n + 1);
+    x[0] = self.x0.unwrap_or(0.0);
+
+    for i in 1..=self.n {
+      let head = weights
+        .iter()
+        .map(|(up, down)| up * gamma.sample(&mut rng) - down * gamma.sample(&mut rng))
+        .sum::<f64>();
+      let z: f64 = StandardNormal.sample(&mut rng);
+      x[i] = x[i - 1] + self.mu * dt + head + tail_mean + tail_std * z;
+    }
+
+    x
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
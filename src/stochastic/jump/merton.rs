@@ -2,9 +2,8 @@ use ndarray::Array1;
 use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 
-use crate::stochastic::{
-  process::cpoisson::CompoundPoisson, ProcessDistribution, Sampling, Sampling3D,
-};
+use crate::rng::thread_rng;
+use crate::stochastic::{process::cpoisson::CompoundPoisson, ProcessDistribution, Sampling};
 
 #[derive(Default)]
 pub struct Merton<D>
@@ -55,14 +54,18 @@ impl<D: ProcessDistribution> Sampling<f64> for Merton<D> {
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
     let mut merton = Array1::<f64>::zeros(self.n + 1);
     merton[0] = self.x0.unwrap_or(0.0);
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
 
     for i in 1..=self.n {
-      let [.., jumps] = self.cpoisson.sample();
+      let jumps = self.cpoisson.sample_jumps();
       merton[i] = merton[i - 1]
         + (self.alpha * self.sigma.powf(2.0) / 2.0 - self.lambda * self.theta) * dt
         + self.sigma * gn[i - 1]
-        + jumps.sum();
+        + jumps.total();
     }
 
     merton
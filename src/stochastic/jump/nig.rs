@@ -2,6 +2,7 @@ use ndarray::Array1;
 use ndarray_rand::{rand_distr::InverseGaussian, RandomExt};
 use rand_distr::Normal;
 
+use crate::rng::thread_rng;
 use crate::stochastic::Sampling;
 
 #[derive(Default)]
@@ -36,8 +37,16 @@ impl Sampling<f64> for NIG {
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
     let scale = dt.powf(2.0) / self.kappa;
     let mean = dt / scale;
-    let ig = Array1::random(self.n, InverseGaussian::new(mean, scale).unwrap());
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let ig = Array1::random_using(
+      self.n,
+      InverseGaussian::new(mean, scale).unwrap(),
+      &mut thread_rng(),
+    );
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
     let mut nig = Array1::zeros(self.n + 1);
     nig[0] = self.x0.unwrap_or(0.0);
 
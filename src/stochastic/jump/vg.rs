@@ -3,6 +3,7 @@ use ndarray_rand::rand_distr::Gamma;
 use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 
+use crate::rng::thread_rng;
 use crate::stochastic::Sampling;
 
 #[derive(Default)]
@@ -41,8 +42,12 @@ impl Sampling<f64> for VG {
     let mut vg = Array1::<f64>::zeros(self.n + 1);
     vg[0] = self.x0.unwrap_or(0.0);
 
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
-    let gammas = Array1::random(self.n, Gamma::new(shape, scale).unwrap());
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
+    let gammas = Array1::random_using(self.n, Gamma::new(shape, scale).unwrap(), &mut thread_rng());
 
     for i in 1..=self.n {
       vg[i] = vg[i - 1] + self.mu * gammas[i - 1] + self.sigma * gammas[i - 1].sqrt() * gn[i - 1];
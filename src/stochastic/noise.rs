@@ -1,3 +1,8 @@
 pub mod cfgns;
 pub mod cgns;
+pub mod colored;
 pub mod fgn;
+pub mod power_law;
+pub mod sine_wiener;
+pub mod spectral;
+pub mod telegraph;
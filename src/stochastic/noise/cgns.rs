@@ -2,6 +2,7 @@ use ndarray::{s, Array1, Array2};
 use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 
+use crate::rng::thread_rng;
 use crate::stochastic::Sampling2D;
 
 #[derive(Default)]
@@ -33,8 +34,16 @@ impl Sampling2D<f64> for CGNS {
 
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
     let mut cgns = Array2::<f64>::zeros((2, self.n + 1));
-    let gn1 = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
-    let gn2 = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let gn1 = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
+    let gn2 = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
 
     for i in 1..=self.n {
       cgns[[0, i]] = gn1[i - 1];
@@ -0,0 +1,72 @@
+use ndarray::Array1;
+use ndarray_rand::RandomExt;
+use rand_distr::{Distribution, Normal, StandardNormal};
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+/// Colored (exponentially correlated) noise.
+/// d xi(t) = -xi(t) / tau dt + sqrt(2D) / tau dW(t)
+/// The noise is stationary with variance D / tau and autocorrelation
+/// D / tau * exp(-|t - s| / tau), it tends to white noise of intensity D as tau -> 0.
+#[derive(Default)]
+pub struct ColoredNoise {
+  /// Correlation time
+  pub tau: f64,
+  /// Noise intensity
+  pub d: f64,
+  /// Number of time steps
+  pub n: usize,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl ColoredNoise {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.tau > 0.0, "Correlation time must be positive");
+    assert!(params.d >= 0.0, "Intensity must be non-negative");
+
+    Self {
+      tau: params.tau,
+      d: params.d,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    }
+  }
+}
+
+impl Sampling<f64> for ColoredNoise {
+  /// Exact sampling on the grid from the stationary distribution
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let var = self.d / self.tau;
+    let decay = (-dt / self.tau).exp();
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, (var * (1.0 - decay.powi(2))).sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
+
+    let mut xi = Array1::<f64>::zeros(self.n + 1);
+    let z: f64 = StandardNormal.sample(&mut thread_rng());
+    xi[0] = var.sqrt() * z;
+
+    for i in 1..=self.n {
+      xi[i] = decay * xi[i - 1] + gn[i - 1];
+    }
+
+    xi
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
@@ -1,14 +1,20 @@
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
 
 use ndarray::parallel::prelude::*;
 use ndarray::{concatenate, prelude::*};
 use ndarray_rand::rand_distr::StandardNormal;
 use ndarray_rand::RandomExt;
-use ndrustfft::{ndfft, FftHandler};
+use ndrustfft::{ndfft, ndfft_par, FftHandler};
 use num_complex::{Complex, ComplexDistribution};
 
+use crate::rng::{is_deterministic, path_seeds, seeded, thread_rng};
 use crate::stochastic::Sampling;
 
+/// Fractional Gaussian noise by circulant embedding.
+///
+/// The eigenvalues and the FFT plan are immutable and shared through `Arc`s, so clones
+/// and shared references sample concurrently without copying them.
+#[derive(Clone)]
 pub struct FGN {
   pub hurst: f64,
   pub n: usize,
@@ -34,65 +40,291 @@ impl FGN {
 
     let offset = n.next_power_of_two() - n;
     let n = n.next_power_of_two();
-    let mut r = Array1::linspace(0.0, n as f64, n + 1);
-    r.mapv_inplace(|x| {
-      if x == 0.0 {
-        1.0
-      } else {
-        0.5
-          * ((x + 1.0).powf(2.0 * hurst) - 2.0 * x.powf(2.0 * hurst) + (x - 1.0).powf(2.0 * hurst))
-      }
-    });
-    let r = concatenate(
-      Axis(0),
-      #[allow(clippy::reversed_empty_ranges)]
-      &[r.view(), r.slice(s![..;-1]).slice(s![1..-1]).view()],
-    )
-    .unwrap();
-    let data = r.mapv(|v| Complex::new(v, 0.0));
-    let r_fft = FftHandler::new(r.len());
-    let mut sqrt_eigenvalues = Array1::<Complex<f64>>::zeros(r.len());
-    ndfft(&data, &mut sqrt_eigenvalues, &r_fft, 0);
-    sqrt_eigenvalues.mapv_inplace(|x| Complex::new((x.re / (2.0 * n as f64)).sqrt(), x.im));
+    let fft_handler = fft_handler(2 * n);
+    let sqrt_eigenvalues = sqrt_eigenvalues(hurst, n, &fft_handler);
 
     Self {
       hurst,
       n,
       offset,
       t,
-      sqrt_eigenvalues: Arc::new(sqrt_eigenvalues),
+      sqrt_eigenvalues,
       m,
-      fft_handler: Arc::new(FftHandler::new(2 * n)),
+      fft_handler,
     }
   }
 }
 
-impl Sampling<f64> for FGN {
-  fn sample(&self) -> Array1<f64> {
-    let num_threads = rayon::current_num_threads();
-    let chunk_size = (2 * self.n) / num_threads;
-    let rnd = Arc::new(Mutex::new(Array1::<Complex<f64>>::zeros(2 * self.n)));
+impl FGN {
+  pub fn hurst(&self) -> f64 {
+    self.hurst
+  }
 
-    (0..num_threads).into_par_iter().for_each(|i| {
-      let chunk = Array1::<Complex<f64>>::random(
-        chunk_size,
-        ComplexDistribution::new(StandardNormal, StandardNormal),
-      );
+  /// Length of the sampled paths
+  pub fn n(&self) -> usize {
+    self.n - self.offset
+  }
+
+  pub fn t(&self) -> Option<f64> {
+    self.t
+  }
 
-      let mut result_lock = rnd.lock().unwrap();
-      result_lock
-        .slice_mut(s![i * chunk_size..(i + 1) * chunk_size])
-        .assign(&chunk);
-    });
+  /// Change the time horizon, it only scales the paths so nothing is recomputed
+  pub fn set_t(&mut self, t: Option<f64>) {
+    self.t = t;
+  }
+
+  /// The same noise with another Hurst parameter, only the eigenvalues are recomputed
+  /// (or taken from the cache), the FFT plan is shared
+  #[must_use]
+  pub fn with_hurst(&self, hurst: f64) -> Self {
+    assert!(
+      (0.0..=1.0).contains(&hurst),
+      "Hurst parameter must be between 0 and 1"
+    );
 
-    let fgn = &*self.sqrt_eigenvalues * &*rnd.lock().unwrap();
+    Self {
+      hurst,
+      sqrt_eigenvalues: sqrt_eigenvalues(hurst, self.n, &self.fft_handler),
+      ..self.clone()
+    }
+  }
+}
+
+type EigenvalueKey = (usize, u64);
+
+/// Default memory budget of the plan cache
+pub const PLAN_CACHE_BYTES: usize = 64 << 20;
+
+/// Least recently used values within a byte budget, a value larger than the budget is not
+/// kept
+struct Lru<K, V> {
+  entries: Vec<(K, Arc<V>, usize)>,
+  bytes: usize,
+}
+
+impl<K, V> Default for Lru<K, V> {
+  fn default() -> Self {
+    Self {
+      entries: Vec::new(),
+      bytes: 0,
+    }
+  }
+}
+
+impl<K: PartialEq, V> Lru<K, V> {
+  fn get(&mut self, key: &K) -> Option<Arc<V>> {
+    let i = self.entries.iter().position(|(k, _, _)| k == key)?;
+    let entry = self.entries.remove(i);
+    let value = entry.1.clone();
+    self.entries.push(entry);
+    Some(value)
+  }
+
+  fn insert(&mut self, key: K, value: Arc<V>, bytes: usize, budget: usize) -> Arc<V> {
+    if let Some(existing) = self.get(&key) {
+      return existing;
+    }
+    if bytes > budget {
+      return value;
+    }
+    self.entries.push((key, value.clone(), bytes));
+    self.bytes += bytes;
+    self.evict(budget);
+    value
+  }
+
+  fn evict(&mut self, budget: usize) {
+    while self.bytes > budget {
+      let (_, _, bytes) = self.entries.remove(0);
+      self.bytes -= bytes;
+    }
+  }
+}
+
+/// FFT plans by size and square roots of the circulant eigenvalues by (n, hurst),
+/// shared by every `FGN` (and the processes driven by it) with the same parameters.
+/// The least recently used entries are dropped beyond the memory budget, so sweeps over
+/// many Hurst parameters (datasets, calibrations) do not accumulate eigenvalues.
+struct PlanCache {
+  handlers: Lru<usize, FftHandler<f64>>,
+  eigenvalues: Lru<EigenvalueKey, Array1<Complex<f64>>>,
+  budget: usize,
+}
+
+impl Default for PlanCache {
+  fn default() -> Self {
+    Self {
+      handlers: Lru::default(),
+      eigenvalues: Lru::default(),
+      budget: PLAN_CACHE_BYTES,
+    }
+  }
+}
+
+impl PlanCache {
+  /// Half of the budget for each kind of entry
+  fn evict(&mut self) {
+    self.handlers.evict(self.budget / 2);
+    self.eigenvalues.evict(self.budget / 2);
+  }
+}
+
+fn plan_cache() -> MutexGuard<'static, PlanCache> {
+  static CACHE: OnceLock<Mutex<PlanCache>> = OnceLock::new();
+  CACHE
+    .get_or_init(Default::default)
+    .lock()
+    .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Drop the cached FFT plans and eigenvalues. Existing `FGN` instances keep their own
+/// `Arc`s.
+pub fn clear_plan_cache() {
+  let mut cache = plan_cache();
+  let budget = cache.budget;
+  *cache = PlanCache {
+    budget,
+    ..Default::default()
+  };
+}
+
+/// Memory budget of the cached FFT plans and eigenvalues in bytes ([`PLAN_CACHE_BYTES`]
+/// by default), 0 disables the cache
+pub fn set_plan_cache_budget(bytes: usize) {
+  let mut cache = plan_cache();
+  cache.budget = bytes;
+  cache.evict();
+}
+
+/// Approximate size of an FFT plan of the length, its forward and inverse twiddles
+fn handler_bytes(len: usize) -> usize {
+  2 * len * std::mem::size_of::<Complex<f64>>()
+}
+
+fn fft_handler(len: usize) -> Arc<FftHandler<f64>> {
+  if let Some(handler) = plan_cache().handlers.get(&len) {
+    return handler;
+  }
+
+  let handler = Arc::new(FftHandler::new(len));
+  let mut cache = plan_cache();
+  let budget = cache.budget / 2;
+  cache
+    .handlers
+    .insert(len, handler, handler_bytes(len), budget)
+}
+
+fn sqrt_eigenvalues(
+  hurst: f64,
+  n: usize,
+  fft_handler: &FftHandler<f64>,
+) -> Arc<Array1<Complex<f64>>> {
+  let key = (n, hurst.to_bits());
+
+  if let Some(eigenvalues) = plan_cache().eigenvalues.get(&key) {
+    return eigenvalues;
+  }
+
+  // computed without holding the lock, a concurrent duplicate is harmless
+  let mut r = Array1::linspace(0.0, n as f64, n + 1);
+  r.mapv_inplace(|x| {
+    if x == 0.0 {
+      1.0
+    } else {
+      0.5 * ((x + 1.0).powf(2.0 * hurst) - 2.0 * x.powf(2.0 * hurst) + (x - 1.0).powf(2.0 * hurst))
+    }
+  });
+  let r = concatenate(
+    Axis(0),
+    #[allow(clippy::reversed_empty_ranges)]
+    &[r.view(), r.slice(s![..;-1]).slice(s![1..-1]).view()],
+  )
+  .unwrap();
+  let data = r.mapv(|v| Complex::new(v, 0.0));
+  let mut sqrt_eigenvalues = Array1::<Complex<f64>>::zeros(r.len());
+  ndfft(&data, &mut sqrt_eigenvalues, fft_handler, 0);
+  sqrt_eigenvalues.mapv_inplace(|x| Complex::new((x.re / (2.0 * n as f64)).sqrt(), x.im));
+
+  let bytes = sqrt_eigenvalues.len() * std::mem::size_of::<Complex<f64>>();
+  let mut cache = plan_cache();
+  let budget = cache.budget / 2;
+  cache
+    .eigenvalues
+    .insert(key, Arc::new(sqrt_eigenvalues), bytes, budget)
+}
+
+impl FGN {
+  /// Circulant embedding of the complex standard normals
+  fn transform(&self, rnd: &Array1<Complex<f64>>) -> Array1<f64> {
+    let fgn = &*self.sqrt_eigenvalues * rnd;
     let mut fgn_fft = Array1::<Complex<f64>>::zeros(2 * self.n);
     ndfft(&fgn, &mut fgn_fft, &*self.fft_handler, 0);
     let scale = (self.n as f64).powf(-self.hurst) * self.t.unwrap_or(1.0).powf(self.hurst);
-    let fgn = fgn_fft
+    fgn_fft
       .slice(s![1..self.n - self.offset + 1])
-      .mapv(|x: Complex<f64>| x.re * scale);
-    fgn
+      .mapv(|x: Complex<f64>| x.re * scale)
+  }
+}
+
+impl Sampling<f64> for FGN {
+  fn sample(&self) -> Array1<f64> {
+    // the parallel chunks depend on the number of threads, so draw sequentially when seeded
+    if is_deterministic() {
+      let rnd = Array1::<Complex<f64>>::random_using(
+        2 * self.n,
+        ComplexDistribution::new(StandardNormal, StandardNormal),
+        &mut thread_rng(),
+      );
+      return self.transform(&rnd);
+    }
+
+    // per-call buffer, every chunk is filled by one task
+    let chunk_size = (2 * self.n).div_ceil(rayon::current_num_threads());
+    let mut rnd = Array1::<Complex<f64>>::zeros(2 * self.n);
+
+    rnd
+      .axis_chunks_iter_mut(Axis(0), chunk_size)
+      .into_par_iter()
+      .for_each(|mut chunk| {
+        let len = chunk.len();
+        chunk.assign(&Array1::<Complex<f64>>::random_using(
+          len,
+          ComplexDistribution::new(StandardNormal, StandardNormal),
+          &mut thread_rng(),
+        ));
+      });
+
+    self.transform(&rnd)
+  }
+
+  /// All paths with one batched FFT over the rows of an m x 2n matrix
+  fn sample_par(&self) -> Array2<f64> {
+    let m = self.m.expect("m must be specified for parallel sampling");
+    let seeds = path_seeds(m);
+    let mut rnd = Array2::<Complex<f64>>::zeros((m, 2 * self.n));
+
+    rnd
+      .axis_iter_mut(Axis(0))
+      .into_par_iter()
+      .enumerate()
+      .for_each(|(i, mut row)| {
+        let normals = seeded(seeds.as_ref().map(|s| s[i]), || {
+          Array1::<Complex<f64>>::random_using(
+            2 * self.n,
+            ComplexDistribution::new(StandardNormal, StandardNormal),
+            &mut thread_rng(),
+          )
+        });
+        row.assign(&(&*self.sqrt_eigenvalues * &normals));
+      });
+
+    let mut fgn_fft = Array2::<Complex<f64>>::zeros((m, 2 * self.n));
+    ndfft_par(&rnd, &mut fgn_fft, &*self.fft_handler, 1);
+    let scale = (self.n as f64).powf(-self.hurst) * self.t.unwrap_or(1.0).powf(self.hurst);
+    fgn_fft
+      .slice(s![.., 1..self.n - self.offset + 1])
+      .mapv(|x: Complex<f64>| x.re * scale)
   }
 
   fn n(&self) -> usize {
@@ -105,6 +337,41 @@ impl Sampling<f64> for FGN {
 }
 
 #[cfg(test)]
+mod cache_tests {
+  use super::*;
+
+  #[test]
+  fn cache_stays_within_budget() {
+    for i in 0..200 {
+      let fgn = FGN::new(0.05 + 0.9 * i as f64 / 200.0, 4096, None, None);
+      let cache = plan_cache();
+      assert!(cache.eigenvalues.bytes <= cache.budget / 2);
+      assert!(cache.handlers.bytes <= cache.budget / 2);
+      drop(cache);
+      assert_eq!(fgn.sample().len(), 4096);
+    }
+  }
+
+  #[test]
+  fn lru_drops_oversized_and_least_recent_entries() {
+    let mut lru = Lru::default();
+    let value = lru.insert(1, Arc::new(vec![0.0; 8]), 64, 32);
+    assert_eq!(value.len(), 8);
+    assert!(lru.get(&1).is_none());
+
+    lru.insert(2, Arc::new(vec![0.0]), 16, 32);
+    lru.insert(3, Arc::new(vec![0.0]), 16, 32);
+    lru.get(&2);
+    lru.insert(4, Arc::new(vec![0.0]), 16, 32);
+    assert!(
+      lru.get(&3).is_none(),
+      "the least recently used entry is evicted"
+    );
+    assert!(lru.get(&2).is_some() && lru.get(&4).is_some());
+  }
+}
+
+#[cfg(all(test, feature = "viz"))]
 mod tests {
   use plotly::{common::Line, Plot, Scatter};
 
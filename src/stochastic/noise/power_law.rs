@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use ndarray::{Array1, ArrayView1};
+use ndrustfft::{ndfft, FftHandler};
+use num_complex::Complex;
+
+use crate::stochastic::Sampling;
+
+use super::spectral::SpectralNoise;
+
+/// Power-law (1/f^beta) noise generated by spectral synthesis.
+/// S(f) = scale / |f|^beta, beta = 0 is white, beta = 1 is pink and beta = 2 is brown noise.
+/// Unlike fGn only the spectral slope is matched, not the exact covariance structure.
+pub struct PowerLawNoise {
+  /// Spectral exponent
+  pub beta: f64,
+  /// Scale of the spectral density (default 1.0)
+  pub scale: Option<f64>,
+  /// Number of time steps
+  pub n: usize,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+  /// Spectral noise generator
+  pub spectral: SpectralNoise,
+}
+
+impl PowerLawNoise {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    Self::with_beta(params.beta, params.scale, params.n, params.t, params.m)
+  }
+
+  #[must_use]
+  pub fn with_beta(
+    beta: f64,
+    scale: Option<f64>,
+    n: usize,
+    t: Option<f64>,
+    m: Option<usize>,
+  ) -> Self {
+    let c = scale.unwrap_or(1.0);
+    let spectral = SpectralNoise::new(&SpectralNoise {
+      psd: Arc::new(move |f: f64| if f > 0.0 { c * f.powf(-beta) } else { 0.0 }),
+      n,
+      t,
+      m,
+    });
+
+    Self {
+      beta,
+      scale,
+      n,
+      t,
+      m,
+      spectral,
+    }
+  }
+}
+
+impl Default for PowerLawNoise {
+  fn default() -> Self {
+    Self::with_beta(1.0, None, 1024, None, None)
+  }
+}
+
+impl Sampling<f64> for PowerLawNoise {
+  fn sample(&self) -> Array1<f64> {
+    self.spectral.sample()
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+/// Estimate the spectral exponent beta of a signal by least squares
+/// regression of the log-periodogram on the log-frequency.
+pub fn spectral_exponent(x: ArrayView1<f64>) -> f64 {
+  let len = x.len();
+  let mean = x.mean().unwrap_or(0.0);
+  let data = x.mapv(|v| Complex::new(v - mean, 0.0));
+  let mut spectrum = Array1::<Complex<f64>>::zeros(len);
+  let handler = FftHandler::new(len);
+  ndfft(&data, &mut spectrum, &handler, 0);
+
+  let (log_f, log_p): (Vec<f64>, Vec<f64>) = (1..len / 2)
+    .filter(|&k| spectrum[k].norm_sqr() > 0.0)
+    .map(|k| ((k as f64).ln(), spectrum[k].norm_sqr().ln()))
+    .unzip();
+
+  let n = log_f.len() as f64;
+  let mean_f = log_f.iter().sum::<f64>() / n;
+  let mean_p = log_p.iter().sum::<f64>() / n;
+  let cov = log_f
+    .iter()
+    .zip(&log_p)
+    .map(|(f, p)| (f - mean_f) * (p - mean_p))
+    .sum::<f64>();
+  let var = log_f.iter().map(|f| (f - mean_f).powi(2)).sum::<f64>();
+
+  -cov / var
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn recovers_spectral_exponent() {
+    for beta in [0.0, 1.0, 2.0] {
+      let noise = PowerLawNoise::with_beta(beta, None, 4095, None, None);
+      let estimate = (0..20)
+        .map(|_| spectral_exponent(noise.sample().view()))
+        .sum::<f64>()
+        / 20.0;
+
+      assert!(
+        (estimate - beta).abs() < 0.15,
+        "beta = {beta}, estimate = {estimate}"
+      );
+    }
+  }
+}
@@ -0,0 +1,73 @@
+use std::f64::consts::PI;
+
+use ndarray::Array1;
+use ndarray_rand::RandomExt;
+use rand::Rng;
+use rand_distr::Normal;
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+/// Sine-Wiener bounded noise.
+/// xi(t) = a * sin(sqrt(2 / tau) W(t) + phi)
+/// where phi is uniform on [0, 2pi), which makes the noise stationary with
+/// autocorrelation E[xi(t)xi(s)] = a^2 / 2 * exp(-|t - s| / tau).
+#[derive(Default)]
+pub struct SineWienerNoise {
+  /// Amplitude
+  pub a: f64,
+  /// Correlation time
+  pub tau: f64,
+  /// Number of time steps
+  pub n: usize,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl SineWienerNoise {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.tau > 0.0, "Correlation time must be positive");
+
+    Self {
+      a: params.a,
+      tau: params.tau,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    }
+  }
+}
+
+impl Sampling<f64> for SineWienerNoise {
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
+    let scale = (2.0 / self.tau).sqrt();
+
+    let mut phase = thread_rng().gen_range(0.0..2.0 * PI);
+    let mut xi = Array1::<f64>::zeros(self.n + 1);
+    xi[0] = self.a * phase.sin();
+
+    for i in 1..=self.n {
+      phase += scale * gn[i - 1];
+      xi[i] = self.a * phase.sin();
+    }
+
+    xi
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use ndarray::{s, Array1};
+use ndarray_rand::RandomExt;
+use ndrustfft::{ndifft, FftHandler};
+use num_complex::{Complex, ComplexDistribution};
+use rand_distr::StandardNormal;
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+/// Stationary Gaussian noise with a prescribed power spectral density, generated
+/// by spectral synthesis. `psd` is the two-sided spectral density S(f) in cycles
+/// per unit time, normalized so that the integral of S over the real line is the variance.
+/// The noise is synthesized on a periodic grid of at least twice the length to
+/// reduce wrap-around correlations.
+pub struct SpectralNoise {
+  /// Two-sided power spectral density
+  pub psd: Arc<dyn Fn(f64) -> f64 + Send + Sync>,
+  /// Number of time steps
+  pub n: usize,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl SpectralNoise {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    Self {
+      psd: params.psd.clone(),
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    }
+  }
+
+  /// Standard deviation of the Fourier coefficients on a grid of size len
+  pub(crate) fn amplitudes(&self, len: usize, dt: f64) -> Array1<f64> {
+    let df = 1.0 / (len as f64 * dt);
+
+    Array1::from_shape_fn(len, |k| {
+      let f = if k <= len / 2 { k } else { len - k } as f64 * df;
+      ((self.psd)(f).max(0.0) * df).sqrt()
+    })
+  }
+}
+
+impl Sampling<f64> for SpectralNoise {
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let len = (2 * (self.n + 1)).next_power_of_two();
+    let amplitudes = self.amplitudes(len, dt);
+
+    let z = Array1::<Complex<f64>>::random_using(
+      len,
+      ComplexDistribution::new(StandardNormal, StandardNormal),
+      &mut thread_rng(),
+    );
+    let coefficients = z * amplitudes.mapv(|a| Complex::new(a, 0.0));
+
+    let mut x = Array1::<Complex<f64>>::zeros(len);
+    let handler = FftHandler::new(len);
+    ndifft(&coefficients, &mut x, &handler, 0);
+
+    // ndifft is normalized by 1 / len
+    x.slice(s![..=self.n]).mapv(|v| v.re * len as f64)
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
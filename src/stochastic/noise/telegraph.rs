@@ -0,0 +1,75 @@
+use ndarray::Array1;
+use rand::Rng;
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+/// Telegraph (dichotomous Markov) noise.
+/// The noise jumps between -a and a with switching rate lambda, its stationary
+/// autocorrelation is E[xi(t)xi(s)] = a^2 exp(-2 lambda |t - s|).
+#[derive(Default)]
+pub struct TelegraphNoise {
+  /// Amplitude
+  pub a: f64,
+  /// Switching rate
+  pub lambda: f64,
+  /// Number of time steps
+  pub n: usize,
+  /// Initial value, drawn from the stationary distribution if not set
+  pub x0: Option<f64>,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl TelegraphNoise {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.a > 0.0, "Amplitude must be positive");
+    assert!(params.lambda >= 0.0, "Switching rate must be non-negative");
+
+    Self {
+      a: params.a,
+      lambda: params.lambda,
+      n: params.n,
+      x0: params.x0,
+      t: params.t,
+      m: params.m,
+    }
+  }
+}
+
+impl Sampling<f64> for TelegraphNoise {
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    // probability of an odd number of switches during dt, so the sampling is exact on the grid
+    let flip = 0.5 * (1.0 - (-2.0 * self.lambda * dt).exp());
+    let mut rng = thread_rng();
+
+    let mut xi = Array1::<f64>::zeros(self.n + 1);
+    xi[0] = match self.x0 {
+      Some(x0) => self.a * x0.signum(),
+      None if rng.gen_bool(0.5) => self.a,
+      None => -self.a,
+    };
+
+    for i in 1..=self.n {
+      xi[i] = if rng.gen::<f64>() < flip {
+        -xi[i - 1]
+      } else {
+        xi[i - 1]
+      };
+    }
+
+    xi
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
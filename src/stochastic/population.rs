@@ -0,0 +1,2 @@
+pub mod logistic;
+pub mod sir;
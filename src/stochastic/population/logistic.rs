@@ -0,0 +1,173 @@
+use ndarray::Array1;
+use ndarray_rand::RandomExt;
+use rand::Rng;
+use rand_distr::{Distribution, Exp, Normal};
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+/// Logistic growth with environmental noise.
+/// dX(t) = r * X(t)(1 - X(t) / k)dt + sigma * X(t)dW(t)
+#[derive(Default)]
+pub struct Logistic {
+  /// Intrinsic growth rate
+  pub r: f64,
+  /// Carrying capacity
+  pub k: f64,
+  /// Environmental noise intensity
+  pub sigma: f64,
+  /// Number of time steps
+  pub n: usize,
+  /// Initial population
+  pub x0: Option<f64>,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl Logistic {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.k > 0.0, "Carrying capacity must be positive");
+
+    Self {
+      r: params.r,
+      k: params.k,
+      sigma: params.sigma,
+      n: params.n,
+      x0: params.x0,
+      t: params.t,
+      m: params.m,
+    }
+  }
+}
+
+impl Sampling<f64> for Logistic {
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
+
+    let mut x = Array1::<f64>::zeros(self.n + 1);
+    x[0] = self.x0.unwrap_or(1.0);
+
+    // log-Euler step keeps the population positive
+    for i in 1..=self.n {
+      x[i] = x[i - 1]
+        * ((self.r * (1.0 - x[i - 1] / self.k) - 0.5 * self.sigma.powi(2)) * dt
+          + self.sigma * gn[i - 1])
+          .exp();
+    }
+
+    x
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+/// Logistic growth with demographic noise (exact simulation by the Gillespie algorithm).
+/// X -> X + 1 with rate birth * X
+/// X -> X - 1 with rate death * X + (birth - death) * X^2 / k
+#[derive(Default)]
+pub struct DemographicLogistic {
+  /// Per capita birth rate
+  pub birth: f64,
+  /// Per capita death rate
+  pub death: f64,
+  /// Carrying capacity
+  pub k: f64,
+  /// Number of time steps
+  pub n: usize,
+  /// Initial population
+  pub x0: Option<u64>,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl DemographicLogistic {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.k > 0.0, "Carrying capacity must be positive");
+    assert!(
+      params.birth >= params.death,
+      "birth rate must be at least the death rate"
+    );
+
+    Self {
+      birth: params.birth,
+      death: params.death,
+      k: params.k,
+      n: params.n,
+      x0: params.x0,
+      t: params.t,
+      m: params.m,
+    }
+  }
+}
+
+impl Sampling<f64> for DemographicLogistic {
+  /// Population on the grid t_i = i * dt, i = 0..=n
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let mut rng = thread_rng();
+
+    let mut logistic = Array1::<f64>::zeros(self.n + 1);
+    let mut x = self.x0.unwrap_or(1) as f64;
+    let mut t = 0.0;
+    logistic[0] = x;
+
+    for i in 1..=self.n {
+      let t_next = i as f64 * dt;
+
+      loop {
+        let birth = self.birth * x;
+        let death = self.death * x + (self.birth - self.death) * x.powi(2) / self.k;
+        let rate = birth + death;
+
+        if rate <= 0.0 {
+          t = t_next;
+          break;
+        }
+
+        let tau = Exp::new(rate).unwrap().sample(&mut rng);
+
+        if t + tau > t_next {
+          // memoryless waiting times, so the clock restarts at the grid point
+          t = t_next;
+          break;
+        }
+
+        t += tau;
+        x += if rng.gen::<f64>() * rate < birth {
+          1.0
+        } else {
+          -1.0
+        };
+      }
+
+      logistic[i] = x;
+    }
+
+    logistic
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
@@ -0,0 +1,203 @@
+use ndarray::{Array1, Array2};
+use rand::Rng;
+use rand_distr::{Distribution, Exp};
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling3D;
+
+/// Stochastic SIR epidemic model (continuous-time Markov chain).
+/// S + I -> 2I with rate beta * S * I / N
+/// I -> R with rate gamma * I
+#[derive(Default)]
+pub struct SIR {
+  /// Transmission rate
+  pub beta: f64,
+  /// Recovery rate
+  pub gamma: f64,
+  /// Population size
+  pub population: u64,
+  /// Initial number of infected
+  pub i0: Option<u64>,
+  /// Number of time steps
+  pub n: usize,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl SIR {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.i0.unwrap_or(1) <= params.population,
+      "i0 must not exceed the population"
+    );
+
+    Self {
+      beta: params.beta,
+      gamma: params.gamma,
+      population: params.population,
+      i0: params.i0,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    }
+  }
+
+  /// Basic reproduction number
+  pub fn r0(&self) -> f64 {
+    self.beta / self.gamma
+  }
+}
+
+impl Sampling3D<f64> for SIR {
+  /// Susceptible, infected and recovered on the grid t_i = i * dt, i = 0..=n
+  fn sample(&self) -> [Array1<f64>; 3] {
+    let seir = SEIR {
+      beta: self.beta,
+      sigma: f64::INFINITY,
+      gamma: self.gamma,
+      population: self.population,
+      e0: Some(0),
+      i0: self.i0,
+      n: self.n,
+      t: self.t,
+      m: self.m,
+    };
+    let [s, _, i, r] = seir.sample();
+
+    [s, i, r]
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+/// Stochastic SEIR epidemic model (continuous-time Markov chain).
+/// S + I -> E + I with rate beta * S * I / N
+/// E -> I with rate sigma * E
+/// I -> R with rate gamma * I
+/// With sigma = infinity the exposed stage is skipped and the model reduces to SIR.
+#[derive(Default)]
+pub struct SEIR {
+  /// Transmission rate
+  pub beta: f64,
+  /// Incubation rate (1 / mean latent period)
+  pub sigma: f64,
+  /// Recovery rate
+  pub gamma: f64,
+  /// Population size
+  pub population: u64,
+  /// Initial number of exposed
+  pub e0: Option<u64>,
+  /// Initial number of infected
+  pub i0: Option<u64>,
+  /// Number of time steps
+  pub n: usize,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl SEIR {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.e0.unwrap_or(0) + params.i0.unwrap_or(1) <= params.population,
+      "e0 + i0 must not exceed the population"
+    );
+
+    Self {
+      beta: params.beta,
+      sigma: params.sigma,
+      gamma: params.gamma,
+      population: params.population,
+      e0: params.e0,
+      i0: params.i0,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    }
+  }
+
+  /// Susceptible, exposed, infected and recovered on the grid t_i = i * dt, i = 0..=n
+  pub fn sample(&self) -> [Array1<f64>; 4] {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let n_pop = self.population as f64;
+    let skip_latent = self.sigma.is_infinite();
+    let mut rng = thread_rng();
+
+    let e0 = self.e0.unwrap_or(0) as f64;
+    let i0 = self.i0.unwrap_or(1) as f64;
+    let mut x = [n_pop - e0 - i0, e0, i0, 0.0];
+    let mut seir = Array2::<f64>::zeros((4, self.n + 1));
+    seir.column_mut(0).assign(&Array1::from(x.to_vec()));
+    let mut t = 0.0;
+
+    for k in 1..=self.n {
+      let t_next = k as f64 * dt;
+
+      loop {
+        let [s, e, i, _] = x;
+        let infection = self.beta * s * i / n_pop;
+        let incubation = if skip_latent { 0.0 } else { self.sigma * e };
+        let recovery = self.gamma * i;
+        let rate = infection + incubation + recovery;
+
+        if rate <= 0.0 {
+          t = t_next;
+          break;
+        }
+
+        let tau = Exp::new(rate).unwrap().sample(&mut rng);
+
+        if t + tau > t_next {
+          t = t_next;
+          break;
+        }
+
+        t += tau;
+        let u = rng.gen::<f64>() * rate;
+
+        if u < infection {
+          x[0] -= 1.0;
+          if skip_latent {
+            x[2] += 1.0;
+          } else {
+            x[1] += 1.0;
+          }
+        } else if u < infection + incubation {
+          x[1] -= 1.0;
+          x[2] += 1.0;
+        } else {
+          x[2] -= 1.0;
+          x[3] += 1.0;
+        }
+      }
+
+      seir.column_mut(k).assign(&Array1::from(x.to_vec()));
+    }
+
+    [
+      seir.row(0).to_owned(),
+      seir.row(1).to_owned(),
+      seir.row(2).to_owned(),
+      seir.row(3).to_owned(),
+    ]
+  }
+
+  pub fn n(&self) -> usize {
+    self.n
+  }
+
+  pub fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
@@ -1,8 +1,21 @@
+pub mod birth_death;
 pub mod bm;
+pub mod carma;
 pub mod cbms;
 pub mod ccustom;
 pub mod cfbms;
 pub mod cpoisson;
+pub mod csbp;
+pub mod ctrw;
 pub mod customjt;
+pub mod extinction;
 pub mod fbm;
+pub mod gaussian_process;
+pub mod gillespie;
+pub mod hawkes;
+pub mod karhunen_loeve;
+pub mod markov_chain;
+pub mod mixed_fbm;
+pub mod path_construction;
 pub mod poisson;
+pub mod random_walk;
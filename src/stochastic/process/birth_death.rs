@@ -0,0 +1,120 @@
+use ndarray::Array1;
+use rand::Rng;
+use rand_distr::{Distribution, Exp};
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+use super::extinction::Extinction;
+
+/// Linear birth-death process with immigration.
+/// X -> X + 1 with rate lambda * X + nu
+/// X -> X - 1 with rate mu * X
+#[derive(Default)]
+pub struct BirthDeath {
+  /// Per capita birth rate
+  pub lambda: f64,
+  /// Per capita death rate
+  pub mu: f64,
+  /// Immigration rate
+  pub nu: Option<f64>,
+  /// Number of time steps
+  pub n: usize,
+  /// Initial population
+  pub x0: Option<u64>,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl BirthDeath {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    Self {
+      lambda: params.lambda,
+      mu: params.mu,
+      nu: params.nu,
+      n: params.n,
+      x0: params.x0,
+      t: params.t,
+      m: params.m,
+    }
+  }
+
+  /// Sample the event times and the population after each event (Gillespie algorithm).
+  /// The first element is always (0.0, x0).
+  pub fn sample_events(&self) -> (Array1<f64>, Array1<u64>) {
+    let t_max = self.t.unwrap_or(1.0);
+    let nu = self.nu.unwrap_or(0.0);
+    let mut rng = thread_rng();
+    let mut x = self.x0.unwrap_or(1);
+    let mut t = 0.0;
+    let mut times = vec![0.0];
+    let mut states = vec![x];
+
+    loop {
+      let birth = self.lambda * x as f64 + nu;
+      let death = self.mu * x as f64;
+      let rate = birth + death;
+
+      if rate <= 0.0 {
+        break;
+      }
+
+      t += Exp::new(rate).unwrap().sample(&mut rng);
+
+      if t > t_max {
+        break;
+      }
+
+      if rng.gen::<f64>() * rate < birth {
+        x += 1;
+      } else {
+        x -= 1;
+      }
+
+      times.push(t);
+      states.push(x);
+    }
+
+    (Array1::from(times), Array1::from(states))
+  }
+}
+
+impl Sampling<f64> for BirthDeath {
+  /// Population on the grid t_i = i * dt, i = 0..=n
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let (times, states) = self.sample_events();
+
+    let mut bd = Array1::<f64>::zeros(self.n + 1);
+    let mut k = 0;
+
+    for i in 0..=self.n {
+      while k + 1 < times.len() && times[k + 1] <= i as f64 * dt {
+        k += 1;
+      }
+
+      bd[i] = states[k] as f64;
+    }
+
+    bd
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl Extinction for BirthDeath {
+  /// First hitting time of zero (absorbing only without immigration)
+  fn sample_extinction_time(&self) -> Option<f64> {
+    let (times, states) = self.sample_events();
+    states.iter().position(|&x| x == 0).map(|i| times[i])
+  }
+}
@@ -1,31 +1,93 @@
-use ndarray::{s, Array1};
+use ndarray::{s, Array1, ArrayView1};
 use ndarray_rand::RandomExt;
-use rand_distr::Normal;
+use rand_distr::{Normal, StandardNormal};
 
+use crate::rng::thread_rng;
 use crate::stochastic::Sampling;
 
+use super::path_construction::{BrownianBridge, PathConstruction, PrincipalComponents};
+
 #[derive(Default)]
 pub struct BM {
   pub n: usize,
   pub t: Option<f64>,
   pub m: Option<usize>,
+  /// Path construction
+  pub construction: PathConstruction,
+  /// Brownian bridge of the time grid
+  pub bridge: BrownianBridge,
+  /// Principal components of the time grid
+  pub pca: PrincipalComponents,
 }
 
 impl BM {
   #[must_use]
   pub fn new(params: &Self) -> Self {
+    let dt = params.t.unwrap_or(1.0) / params.n as f64;
+    let times = Array1::from_shape_fn(params.n.saturating_sub(1), |i| (i + 1) as f64 * dt);
+    // a path of fewer than two points is pinned to zero and needs no construction
+    let (bridge, pca) = match params.construction {
+      _ if times.is_empty() => Default::default(),
+      PathConstruction::BrownianBridge => (BrownianBridge::new(times.view()), Default::default()),
+      PathConstruction::PCA => (
+        Default::default(),
+        PrincipalComponents::brownian(times.view()),
+      ),
+      PathConstruction::Incremental => Default::default(),
+    };
+
     Self {
       n: params.n,
       t: params.t,
       m: params.m,
+      construction: params.construction,
+      bridge,
+      pca,
+    }
+  }
+
+  /// Build the path from n - 1 given standard normals, e.g. from a
+  /// stratified or low-discrepancy sequence. With the PCA construction
+  /// fewer normals can be given to keep only the leading components.
+  pub fn sample_from_normals(&self, z: ArrayView1<f64>) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let mut bm = Array1::<f64>::zeros(self.n);
+    if self.n < 2 {
+      return bm;
+    }
+
+    match self.construction {
+      PathConstruction::Incremental => {
+        for i in 1..self.n {
+          bm[i] = bm[i - 1] + dt.sqrt() * z[i - 1];
+        }
+      }
+      PathConstruction::BrownianBridge => {
+        bm.slice_mut(s![1..]).assign(&self.bridge.build(z));
+      }
+      PathConstruction::PCA => {
+        bm.slice_mut(s![1..]).assign(&self.pca.build(z));
+      }
     }
+
+    bm
   }
 }
 
 impl Sampling<f64> for BM {
   fn sample(&self) -> Array1<f64> {
+    if self.construction != PathConstruction::Incremental {
+      return self.sample_from_normals(
+        Array1::random_using(self.n.saturating_sub(1), StandardNormal, &mut thread_rng()).view(),
+      );
+    }
+
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let gn = Array1::random_using(
+      self.n - 1,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
     let mut bm = Array1::<f64>::zeros(self.n);
     bm.slice_mut(s![1..]).assign(&gn);
 
@@ -44,3 +106,26 @@ impl Sampling<f64> for BM {
     self.m
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn short_grids_need_no_construction() {
+    for construction in [
+      PathConstruction::Incremental,
+      PathConstruction::BrownianBridge,
+      PathConstruction::PCA,
+    ] {
+      for n in [0, 1] {
+        let bm = BM::new(&BM {
+          n,
+          construction,
+          ..Default::default()
+        });
+        assert_eq!(bm.sample_from_normals(Array1::zeros(0).view()).len(), n);
+      }
+    }
+  }
+}
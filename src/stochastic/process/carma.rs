@@ -0,0 +1,257 @@
+use nalgebra::{DMatrix, DVector};
+use ndarray::{Array1, ArrayView1};
+use rand::Rng;
+use rand_distr::{Distribution, Poisson, StandardNormal};
+
+use crate::{
+  quant::calibration::{
+    global::DifferentialEvolution,
+    transform::{barrier, ParameterSpace, Transform},
+  },
+  rng::thread_rng,
+  stochastic::{ProcessDistribution, Sampling},
+};
+
+/// CARMA(p, q) process in state-space form
+/// Y(t) = mu + b' X(t), dX(t) = A X(t) dt + e_p dL(t)
+/// where A is the companion matrix with the last row (-alpha_p, ..., -alpha_1),
+/// b = (b_0, ..., b_q, 0, ..., 0) and the driving Lévy process L is a Brownian motion
+/// with volatility sigma plus compound Poisson jumps of intensity lambda.
+/// The transitions are exact, so the process can be sampled and estimated on irregular grids.
+/// https://doi.org/10.1016/S0169-7161(00)19011-5 (Brockwell)
+#[derive(Default, Clone)]
+pub struct Carma<D>
+where
+  D: ProcessDistribution,
+{
+  /// Autoregressive coefficients alpha_1, ..., alpha_p
+  pub alpha: Vec<f64>,
+  /// Moving average coefficients b_0, ..., b_q with q < p, usually b_q = 1
+  pub beta: Vec<f64>,
+  /// Volatility of the Brownian part of the driver
+  pub sigma: f64,
+  /// Mean of the process
+  pub mu: f64,
+  /// Jump intensity of the driver
+  pub lambda: Option<f64>,
+  pub jump_distribution: D,
+  /// Initial state, zero by default
+  pub x0: Option<Vec<f64>>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl<D: ProcessDistribution> Carma<D> {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    let p = params.alpha.len();
+    assert!(p > 0, "CARMA order p must be at least 1");
+    assert!(
+      !params.beta.is_empty() && params.beta.len() <= p,
+      "CARMA order q must be below p"
+    );
+    if let Some(x0) = &params.x0 {
+      assert_eq!(x0.len(), p, "x0 must have the order p");
+    }
+
+    params.clone()
+  }
+
+  /// Companion matrix A
+  pub fn companion(&self) -> DMatrix<f64> {
+    let p = self.alpha.len();
+    DMatrix::from_fn(p, p, |i, j| {
+      if i + 1 < p {
+        f64::from(j == i + 1)
+      } else {
+        -self.alpha[p - 1 - j]
+      }
+    })
+  }
+
+  fn b(&self) -> DVector<f64> {
+    let p = self.alpha.len();
+    DVector::from_fn(p, |i, _| self.beta.get(i).copied().unwrap_or(0.0))
+  }
+
+  /// Whether all eigenvalues of A have negative real parts
+  pub fn is_stationary(&self) -> bool {
+    self
+      .companion()
+      .complex_eigenvalues()
+      .iter()
+      .all(|l| l.re < 0.0)
+  }
+
+  /// Transition matrix exp(A dt) and the covariance of the Brownian part over dt,
+  /// by Van Loan's block matrix exponential
+  fn transition(&self, dt: f64) -> (DMatrix<f64>, DMatrix<f64>) {
+    let p = self.alpha.len();
+    let a = self.companion();
+    let mut block = DMatrix::<f64>::zeros(2 * p, 2 * p);
+    block.view_mut((0, 0), (p, p)).copy_from(&(-&a * dt));
+    block[(p - 1, 2 * p - 1)] = self.sigma.powi(2) * dt;
+    block
+      .view_mut((p, p), (p, p))
+      .copy_from(&(a.transpose() * dt));
+
+    let e = block.exp();
+    let f = e.view((p, p), (p, p)).transpose();
+    let q = &f * e.view((0, p), (p, p));
+
+    (f, 0.5 * (&q + q.transpose()))
+  }
+
+  /// Stationary covariance of the state, the solution of A P + P A' + sigma^2 e_p e_p' = 0
+  pub fn stationary_covariance(&self) -> DMatrix<f64> {
+    let p = self.alpha.len();
+    let a = self.companion();
+    let id = DMatrix::<f64>::identity(p, p);
+    let lyapunov = id.kronecker(&a) + a.kronecker(&id);
+    let mut rhs = DVector::<f64>::zeros(p * p);
+    rhs[p * p - 1] = -self.sigma.powi(2);
+
+    let vec_p = lyapunov
+      .lu()
+      .solve(&rhs)
+      .expect("CARMA process must be stationary");
+    DMatrix::from_column_slice(p, p, vec_p.as_slice())
+  }
+
+  /// Process observed at the given increasing times, starting from x0 at the first time
+  pub fn sample_at(&self, times: ArrayView1<f64>) -> Array1<f64> {
+    let p = self.alpha.len();
+    let b = self.b();
+    let a = self.companion();
+    let mut rng = thread_rng();
+
+    let mut x = self
+      .x0
+      .as_ref()
+      .map_or_else(|| DVector::zeros(p), |x0| DVector::from_column_slice(x0));
+    let mut y = Array1::<f64>::zeros(times.len());
+    y[0] = self.mu + b.dot(&x);
+
+    for i in 1..times.len() {
+      let dt = times[i] - times[i - 1];
+      assert!(dt > 0.0, "Observation times must be increasing");
+      let (f, q) = self.transition(dt);
+      let z = DVector::<f64>::from_fn(p, |_, _| rng.sample(StandardNormal));
+      let noise = q
+        .clone()
+        .cholesky()
+        .map_or_else(|| DVector::zeros(p), |c| c.l() * z);
+      x = &f * &x + noise;
+
+      if let Some(lambda) = self.lambda {
+        let count = Poisson::new(lambda * dt).unwrap().sample(&mut rng) as usize;
+        for _ in 0..count {
+          // a jump at a uniform time of the step, propagated to its end
+          let remaining = dt * rng.gen::<f64>();
+          let jump = self.jump_distribution.sample(&mut rng);
+          x += (&a * remaining).exp().column(p - 1) * jump;
+        }
+      }
+
+      y[i] = self.mu + b.dot(&x);
+    }
+
+    y
+  }
+
+  /// Negative Gaussian log-likelihood (up to a constant) of observations at irregular times,
+  /// from the Kalman filter started at the stationary distribution
+  pub fn negative_log_likelihood(&self, times: ArrayView1<f64>, y: ArrayView1<f64>) -> f64 {
+    assert_eq!(times.len(), y.len(), "One observation is needed per time");
+    let b = self.b();
+    let mut x = DVector::<f64>::zeros(self.alpha.len());
+    let mut cov = self.stationary_covariance();
+    let mut nll = 0.0;
+
+    for i in 0..y.len() {
+      if i > 0 {
+        let (f, q) = self.transition(times[i] - times[i - 1]);
+        x = &f * &x;
+        cov = &f * &cov * f.transpose() + q;
+      }
+
+      let innovation = y[i] - self.mu - b.dot(&x);
+      let pb = &cov * &b;
+      let s = b.dot(&pb).max(f64::MIN_POSITIVE);
+      nll += 0.5 * (s.ln() + innovation.powi(2) / s);
+
+      let gain = pb / s;
+      x += &gain * innovation;
+      cov -= &gain * (&b.transpose() * &cov);
+    }
+
+    nll
+  }
+
+  /// Gaussian maximum likelihood fit of a CARMA(p, q) with b_q = 1 to observations at
+  /// irregular times, the mean is the sample mean. The parameters of the search are
+  /// (alpha_1, ..., alpha_p, b_0, ..., b_(q-1), sigma), non-stationary candidates are rejected.
+  #[must_use]
+  pub fn fit(
+    times: ArrayView1<f64>,
+    y: ArrayView1<f64>,
+    p: usize,
+    q: usize,
+    de: &DifferentialEvolution,
+  ) -> Self
+  where
+    D: 'static,
+  {
+    assert!(q < p, "CARMA order q must be below p");
+    assert_eq!(
+      de.bounds.len(),
+      p + q + 1,
+      "CARMA fit has p + q + 1 parameters"
+    );
+    let mu = y.mean().expect("Observations must not be empty");
+
+    let with = move |x: &[f64]| {
+      let mut beta = x[p..p + q].to_vec();
+      beta.push(1.0);
+
+      Self {
+        alpha: x[..p].to_vec(),
+        beta,
+        sigma: x[p + q],
+        mu,
+        ..Default::default()
+      }
+    };
+
+    // sigma > 0 and a stationary autoregressive part
+    let space =
+      ParameterSpace::new(vec![Transform::Identity; p + q + 1]).with_penalty(barrier(move |x| {
+        let model = with(x);
+        model.sigma > 0.0 && model.is_stationary()
+      }));
+    let best = de.minimize(space.objective(|x| {
+      // the likelihood is not evaluated outside the valid region
+      if space.penalty(x) > 0.0 {
+        return f64::INFINITY;
+      }
+      with(x).negative_log_likelihood(times, y)
+    }));
+
+    with(&best.params)
+  }
+}
+
+impl<D: ProcessDistribution> Sampling<f64> for Carma<D> {
+  fn sample(&self) -> Array1<f64> {
+    self.sample_at(Array1::linspace(0.0, self.t.unwrap_or(1.0), self.n + 1).view())
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
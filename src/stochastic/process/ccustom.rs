@@ -1,6 +1,6 @@
 use ndarray::{Array1, Axis};
-use rand::thread_rng;
 
+use crate::rng::thread_rng;
 use crate::stochastic::{ProcessDistribution, Sampling, Sampling3D};
 
 use super::customjt::CustomJt;
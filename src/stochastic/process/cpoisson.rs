@@ -1,10 +1,43 @@
-use ndarray::{Array1, Axis};
-use rand::thread_rng;
+use ndarray::{Array1, ArrayView1, Axis};
 
-use crate::stochastic::{ProcessDistribution, Sampling, Sampling3D};
+use crate::rng::thread_rng;
+use crate::stochastic::{ProcessDistribution, Sampling};
 
 use super::poisson::Poisson;
 
+/// Path of a compound Poisson process, the first entry of every array is at the time 0
+#[derive(Debug, Clone)]
+pub struct CompoundPoissonSample {
+  /// Arrival times
+  pub times: Array1<f64>,
+  /// Jump sizes
+  pub marks: Array1<f64>,
+  /// Value of the process after every jump
+  pub cumulative: Array1<f64>,
+}
+
+impl CompoundPoissonSample {
+  /// Number of jumps
+  pub fn jumps(&self) -> usize {
+    self.times.len() - 1
+  }
+
+  /// Value of the process at the end of the path
+  pub fn total(&self) -> f64 {
+    self.cumulative[self.cumulative.len() - 1]
+  }
+
+  /// Value of the process at the given times, right-continuous between the jumps
+  pub fn on_grid(&self, grid: ArrayView1<f64>) -> Array1<f64> {
+    grid.mapv(|t| {
+      let k = self.times.iter().take_while(|&&s| s <= t).count();
+      self.cumulative[k.max(1) - 1]
+    })
+  }
+}
+
+/// Compound Poisson process with the jump sizes drawn from `distribution`, either over the
+/// first n jumps or over the interval [0, t_max]
 #[derive(Default)]
 pub struct CompoundPoisson<D>
 where
@@ -39,22 +72,49 @@ impl<D: ProcessDistribution> CompoundPoisson<D> {
   }
 }
 
-impl<D: ProcessDistribution> Sampling3D<f64> for CompoundPoisson<D> {
-  fn sample(&self) -> [Array1<f64>; 3] {
+impl<D: ProcessDistribution> CompoundPoisson<D> {
+  /// Arrival times, jump sizes and values of the process
+  pub fn sample_jumps(&self) -> CompoundPoissonSample {
     if self.n.is_none() && self.t_max.is_none() {
       panic!("n or t_max must be provided");
     }
 
-    let poisson = self.poisson.sample();
-    let mut jumps = Array1::<f64>::zeros(poisson.len());
-    for i in 1..poisson.len() {
-      jumps[i] = self.distribution.sample(&mut thread_rng());
+    let mut times = self.poisson.sample();
+    // the arrival times are drawn until they pass t_max, the last one is outside the interval
+    if let (None, Some(t_max)) = (self.n, self.t_max) {
+      let inside = times.iter().filter(|&&t| t <= t_max).count();
+      times = times.slice(ndarray::s![..inside]).to_owned();
     }
 
-    let mut cum_jupms = jumps.clone();
-    cum_jupms.accumulate_axis_inplace(Axis(0), |&prev, curr| *curr += prev);
+    self.with_marks(times)
+  }
+
+  /// Path on [0, t_max] conditioned on `count` jumps, see `Poisson::sample_given_count`
+  pub fn sample_given_count(&self, count: usize) -> CompoundPoissonSample {
+    self.with_marks(self.poisson.sample_given_count(count))
+  }
+
+  fn with_marks(&self, times: Array1<f64>) -> CompoundPoissonSample {
+    let mut marks = Array1::<f64>::zeros(times.len());
+    for i in 1..times.len() {
+      marks[i] = self.distribution.sample(&mut thread_rng());
+    }
+
+    let mut cumulative = marks.clone();
+    cumulative.accumulate_axis_inplace(Axis(0), |&prev, curr| *curr += prev);
+
+    CompoundPoissonSample {
+      times,
+      marks,
+      cumulative,
+    }
+  }
+}
 
-    [poisson, cum_jupms, jumps]
+/// Values of the process after every jump, n + 1 values for n jumps
+impl<D: ProcessDistribution> Sampling<f64> for CompoundPoisson<D> {
+  fn sample(&self) -> Array1<f64> {
+    self.sample_jumps().cumulative
   }
 
   fn n(&self) -> usize {
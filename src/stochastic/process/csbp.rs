@@ -0,0 +1,117 @@
+use ndarray::Array1;
+use rand_distr::{Distribution, Gamma, Poisson};
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+use super::extinction::Extinction;
+
+/// Continuous-state branching process with branching mechanism
+/// psi(u) = -b * u + sigma^2 / 2 * u^2 (Feller diffusion)
+/// dX(t) = b * X(t)dt + sigma * sqrt(X(t))dW(t)
+///
+/// The transition law is a Poisson mixture of exponentials, so the process is sampled exactly.
+#[derive(Default)]
+pub struct CSBP {
+  /// Malthusian growth rate
+  pub b: f64,
+  /// Branching volatility
+  pub sigma: f64,
+  /// Number of time steps
+  pub n: usize,
+  /// Initial population
+  pub x0: Option<f64>,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl CSBP {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.sigma > 0.0, "sigma must be positive");
+
+    Self {
+      b: params.b,
+      sigma: params.sigma,
+      n: params.n,
+      x0: params.x0,
+      t: params.t,
+      m: params.m,
+    }
+  }
+
+  /// Laplace exponent u_t(lambda) = a * lambda / (1 + c * lambda), returns (a, c)
+  fn coefficients(&self, t: f64) -> (f64, f64) {
+    let beta = 0.5 * self.sigma.powi(2);
+    let a = (self.b * t).exp();
+    let c = if self.b.abs() < 1e-12 {
+      beta * t
+    } else {
+      beta * (a - 1.0) / self.b
+    };
+
+    (a, c)
+  }
+
+  /// Probability that the population is extinct at time t
+  pub fn extinction_probability(&self, t: f64) -> f64 {
+    let (a, c) = self.coefficients(t);
+    (-self.x0.unwrap_or(1.0) * a / c).exp()
+  }
+
+  /// Probability of ultimate extinction
+  pub fn ultimate_extinction_probability(&self) -> f64 {
+    if self.b <= 0.0 {
+      1.0
+    } else {
+      (-2.0 * self.b * self.x0.unwrap_or(1.0) / self.sigma.powi(2)).exp()
+    }
+  }
+}
+
+impl Sampling<f64> for CSBP {
+  fn sample(&self) -> Array1<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let (a, c) = self.coefficients(dt);
+    let mut rng = thread_rng();
+
+    let mut csbp = Array1::<f64>::zeros(self.n + 1);
+    csbp[0] = self.x0.unwrap_or(1.0);
+
+    for i in 1..=self.n {
+      if csbp[i - 1] <= 0.0 {
+        break;
+      }
+
+      let k = Poisson::new(csbp[i - 1] * a / c).unwrap().sample(&mut rng);
+
+      csbp[i] = if k > 0.0 {
+        Gamma::new(k, c).unwrap().sample(&mut rng)
+      } else {
+        0.0
+      };
+    }
+
+    csbp
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl Extinction for CSBP {
+  /// Extinction time resolved on the time grid
+  fn sample_extinction_time(&self) -> Option<f64> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let path = self.sample();
+
+    path.iter().position(|&x| x <= 0.0).map(|i| i as f64 * dt)
+  }
+}
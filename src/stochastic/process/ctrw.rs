@@ -0,0 +1,120 @@
+use ndarray::{Array1, Axis};
+use rand::Rng;
+use rand_distr::Distribution;
+use statrs::function::gamma::gamma;
+
+use crate::distributions::mittag_leffler::MittagLeffler;
+use crate::rng::thread_rng;
+use crate::stochastic::{ProcessDistribution, Sampling};
+
+use super::cpoisson::CompoundPoissonSample;
+
+/// Law of the waiting times between the jumps of a CTRW
+#[derive(Debug, Clone, Copy)]
+pub enum WaitingTime {
+  /// Exponential with the rate, a compound Poisson process
+  Exponential { rate: f64 },
+  /// Pareto with the minimum and the tail index, subdiffusive for alpha < 1
+  Pareto { scale: f64, alpha: f64 },
+  /// Mittag-Leffler with the index beta in (0, 1] and the scale, whose CTRW limit is the
+  /// time-fractional diffusion of order beta
+  MittagLeffler { beta: f64, scale: f64 },
+}
+
+impl Default for WaitingTime {
+  fn default() -> Self {
+    Self::Exponential { rate: 1.0 }
+  }
+}
+
+impl WaitingTime {
+  pub fn sample(&self, rng: &mut impl Rng) -> f64 {
+    match *self {
+      Self::Exponential { rate } => -(1.0 - rng.gen::<f64>()).ln() / rate,
+      Self::Pareto { scale, alpha } => scale * (1.0 - rng.gen::<f64>()).powf(-1.0 / alpha),
+      Self::MittagLeffler { beta, scale } => MittagLeffler { beta, scale }.sample(rng),
+    }
+  }
+
+  /// Expected number of jumps up to t of the renewal process, when it is known in closed
+  /// form: lambda t for exponential and t^beta / (scale^beta Gamma(1 + beta)) for
+  /// Mittag-Leffler waiting times
+  pub fn expected_jumps(&self, t: f64) -> Option<f64> {
+    match *self {
+      Self::Exponential { rate } => Some(rate * t),
+      Self::MittagLeffler { beta, scale } => Some((t / scale).powf(beta) / gamma(1.0 + beta)),
+      Self::Pareto { .. } => None,
+    }
+  }
+}
+
+/// Continuous-time random walk, jumps drawn from `jumps` after i.i.d. waiting times.
+/// Heavy-tailed waiting times give subdiffusion and heavy-tailed jumps (Lévy flights)
+/// superdiffusion.
+/// https://doi.org/10.1063/1.1704269 (Montroll, Weiss 1965)
+#[derive(Default)]
+pub struct Ctrw<D>
+where
+  D: ProcessDistribution,
+{
+  pub waiting_time: WaitingTime,
+  pub jumps: D,
+  pub x0: Option<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl<D: ProcessDistribution> Ctrw<D> {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    Self {
+      waiting_time: params.waiting_time,
+      jumps: params.jumps,
+      x0: params.x0,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    }
+  }
+
+  /// Jump times, jump sizes and positions up to t
+  pub fn sample_jumps(&self) -> CompoundPoissonSample {
+    let t_max = self.t.unwrap_or(1.0);
+    let mut rng = thread_rng();
+    let mut times = vec![0.0];
+    let mut marks = vec![0.0];
+    let mut s = self.waiting_time.sample(&mut rng);
+    while s <= t_max {
+      times.push(s);
+      marks.push(self.jumps.sample(&mut rng));
+      s += self.waiting_time.sample(&mut rng);
+    }
+
+    let marks = Array1::from(marks);
+    let mut cumulative = marks.clone();
+    cumulative.accumulate_axis_inplace(Axis(0), |&prev, curr| *curr += prev);
+
+    CompoundPoissonSample {
+      times: Array1::from(times),
+      marks,
+      cumulative: cumulative + self.x0.unwrap_or(0.0),
+    }
+  }
+}
+
+impl<D: ProcessDistribution> Sampling<f64> for Ctrw<D> {
+  /// Position on the grid t_i = i t / n, i = 0..=n
+  fn sample(&self) -> Array1<f64> {
+    let grid = Array1::linspace(0.0, self.t.unwrap_or(1.0), self.n + 1);
+    self.sample_jumps().on_grid(grid.view())
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
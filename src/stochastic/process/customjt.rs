@@ -1,7 +1,7 @@
 use ndarray::{Array0, Array1, Axis, Dim};
 use ndarray_rand::RandomExt;
-use rand::thread_rng;
 
+use crate::rng::thread_rng;
 use crate::stochastic::{ProcessDistribution, Sampling};
 
 #[derive(Default)]
@@ -30,7 +30,7 @@ impl<D: ProcessDistribution> CustomJt<D> {
 impl<D: ProcessDistribution> Sampling<f64> for CustomJt<D> {
   fn sample(&self) -> Array1<f64> {
     if let Some(n) = self.n {
-      let random = Array1::random(n, self.distribution);
+      let random = Array1::random_using(n, self.distribution, &mut thread_rng());
       let mut x = Array1::<f64>::zeros(n + 1);
       for i in 1..n + 11 {
         x[i] = x[i - 1] + random[i - 1];
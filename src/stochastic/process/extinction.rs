@@ -0,0 +1,36 @@
+use rayon::prelude::*;
+
+/// Extinction-time statistics of a population process.
+#[derive(Debug, Clone)]
+pub struct ExtinctionStats {
+  /// Fraction of paths that went extinct before the horizon
+  pub probability: f64,
+  /// Mean extinction time of the extinct paths
+  pub mean_time: Option<f64>,
+  /// Extinction times of the extinct paths
+  pub times: Vec<f64>,
+}
+
+/// Population processes absorbed at zero.
+pub trait Extinction: Send + Sync {
+  /// Sample a single extinction time, `None` if the population survives the horizon
+  fn sample_extinction_time(&self) -> Option<f64>;
+
+  /// Monte Carlo extinction statistics over `paths` independent samples
+  fn extinction_stats(&self, paths: usize) -> ExtinctionStats {
+    let times = (0..paths)
+      .into_par_iter()
+      .filter_map(|_| self.sample_extinction_time())
+      .collect::<Vec<_>>();
+
+    ExtinctionStats {
+      probability: times.len() as f64 / paths as f64,
+      mean_time: if times.is_empty() {
+        None
+      } else {
+        Some(times.iter().sum::<f64>() / times.len() as f64)
+      },
+      times,
+    }
+  }
+}
@@ -1,6 +1,10 @@
-use ndarray::{s, Array1};
+use ndarray::{s, Array1, Array2, ArrayView1};
 
-use crate::stochastic::{noise::fgn::FGN, Sampling};
+use crate::stochastic::{
+  noise::fgn::FGN,
+  process::gaussian_process::{cholesky, conditional, covariance_matrix, fbm_kernel},
+  Sampling, TheoreticalMoments,
+};
 
 #[derive(Default)]
 pub struct Fbm {
@@ -51,7 +55,59 @@ impl Sampling<f64> for Fbm {
   }
 }
 
-#[cfg(test)]
+impl TheoreticalMoments for Fbm {
+  fn mean(&self, _t: f64) -> f64 {
+    0.0
+  }
+
+  fn variance(&self, t: f64) -> f64 {
+    t.powf(2.0 * self.hurst)
+  }
+
+  fn covariance(&self, s: f64, t: f64) -> Option<f64> {
+    Some(
+      0.5
+        * (s.powf(2.0 * self.hurst) + t.powf(2.0 * self.hurst)
+          - (t - s).abs().powf(2.0 * self.hurst)),
+    )
+  }
+}
+
+/// Exact covariance matrix of fBm at arbitrary times
+pub fn fbm_covariance(hurst: f64, times: ArrayView1<f64>) -> Array2<f64> {
+  covariance_matrix(&fbm_kernel(hurst), times, times)
+}
+
+/// Exact covariance matrix of the fGn increments between consecutive times of an
+/// arbitrary grid, Cov(B(t_i+1) - B(t_i), B(t_j+1) - B(t_j))
+pub fn fgn_covariance(hurst: f64, times: ArrayView1<f64>) -> Array2<f64> {
+  let n = times.len().saturating_sub(1);
+  let h2 = 2.0 * hurst;
+  let r = |s: f64, t: f64| (t - s).abs().powf(h2);
+
+  Array2::from_shape_fn((n, n), |(i, j)| {
+    let (a, b, c, d) = (times[i], times[i + 1], times[j], times[j + 1]);
+    0.5 * (r(a, d) + r(b, c) - r(a, c) - r(b, d))
+  })
+}
+
+/// Lower Cholesky factor of the fBm covariance at arbitrary (non-zero) times,
+/// multiplying standard normals by it samples fBm on the grid
+pub fn fbm_cholesky(hurst: f64, times: ArrayView1<f64>) -> Array2<f64> {
+  cholesky(&fbm_covariance(hurst, times))
+}
+
+/// Conditional mean and covariance of fBm at `times` given its values at `observed_times`
+pub fn fbm_conditional(
+  hurst: f64,
+  observed_times: ArrayView1<f64>,
+  observed: ArrayView1<f64>,
+  times: ArrayView1<f64>,
+) -> (Array1<f64>, Array2<f64>) {
+  conditional(&fbm_kernel(hurst), observed_times, observed, times)
+}
+
+#[cfg(all(test, feature = "viz"))]
 mod tests {
   use ndarray::Axis;
   use plotly::{common::Line, Plot, Scatter};
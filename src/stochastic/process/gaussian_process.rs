@@ -0,0 +1,274 @@
+use std::sync::Arc;
+
+use nalgebra::DMatrix;
+use ndarray::{s, Array1, Array2, ArrayView1};
+use ndarray_rand::RandomExt;
+use ndrustfft::{ndfft, FftHandler};
+use num_complex::{Complex, ComplexDistribution};
+use rand_distr::StandardNormal;
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+/// Covariance kernel k(s, t) of a Gaussian process
+pub type Kernel = Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>;
+
+/// Squared exponential (RBF) kernel
+pub fn rbf_kernel(variance: f64, length_scale: f64) -> Kernel {
+  Arc::new(move |s, t| variance * (-0.5 * ((s - t) / length_scale).powi(2)).exp())
+}
+
+/// Matérn kernel with smoothness nu in {0.5, 1.5, 2.5}
+pub fn matern_kernel(nu: f64, variance: f64, length_scale: f64) -> Kernel {
+  Arc::new(move |s, t| {
+    let r = (s - t).abs() / length_scale;
+
+    let sqrt3 = 3f64.sqrt();
+    let sqrt5 = 5f64.sqrt();
+
+    variance
+      * if nu == 0.5 {
+        (-r).exp()
+      } else if nu == 1.5 {
+        (1.0 + sqrt3 * r) * (-sqrt3 * r).exp()
+      } else if nu == 2.5 {
+        (1.0 + sqrt5 * r + 5.0 / 3.0 * r.powi(2)) * (-sqrt5 * r).exp()
+      } else {
+        panic!("Matérn kernel is only implemented for nu = 0.5, 1.5, 2.5")
+      }
+  })
+}
+
+/// Covariance kernel of fractional Brownian motion
+pub fn fbm_kernel(hurst: f64) -> Kernel {
+  Arc::new(move |s, t| {
+    0.5 * (s.abs().powf(2.0 * hurst) + t.abs().powf(2.0 * hurst) - (s - t).abs().powf(2.0 * hurst))
+  })
+}
+
+/// Covariance matrix k(s_i, t_j) of a kernel between two time grids
+pub fn covariance_matrix(kernel: &Kernel, s: ArrayView1<f64>, t: ArrayView1<f64>) -> Array2<f64> {
+  Array2::from_shape_fn((s.len(), t.len()), |(i, j)| kernel(s[i], t[j]))
+}
+
+/// Lower Cholesky factor of a covariance matrix, with a growing jitter on the diagonal
+/// if the matrix is only semi-definite numerically
+pub fn cholesky(cov: &Array2<f64>) -> Array2<f64> {
+  let n = cov.nrows();
+  let scale = cov
+    .diag()
+    .mean()
+    .unwrap_or(1.0)
+    .abs()
+    .max(f64::MIN_POSITIVE);
+  let mut jitter = 0.0;
+
+  for _ in 0..10 {
+    let matrix = DMatrix::from_fn(n, n, |i, j| cov[[i, j]] + if i == j { jitter } else { 0.0 });
+
+    if let Some(chol) = matrix.cholesky() {
+      let l = chol.l();
+      return Array2::from_shape_fn((n, n), |(i, j)| l[(i, j)]);
+    }
+
+    jitter = if jitter == 0.0 {
+      1e-12 * scale
+    } else {
+      jitter * 10.0
+    };
+  }
+
+  panic!("Covariance matrix is not positive definite");
+}
+
+/// Conditional mean and covariance of a centered Gaussian process at `times` given the
+/// values observed at `observed_times` (kriging)
+pub fn conditional(
+  kernel: &Kernel,
+  observed_times: ArrayView1<f64>,
+  observed: ArrayView1<f64>,
+  times: ArrayView1<f64>,
+) -> (Array1<f64>, Array2<f64>) {
+  assert_eq!(
+    observed_times.len(),
+    observed.len(),
+    "One observation is needed per time"
+  );
+  let (n, k) = (times.len(), observed_times.len());
+  let l = cholesky(&covariance_matrix(kernel, observed_times, observed_times));
+  let l = DMatrix::from_fn(k, k, |i, j| l[[i, j]]);
+  let cross = covariance_matrix(kernel, observed_times, times);
+  let cross = DMatrix::from_fn(k, n, |i, j| cross[[i, j]]);
+
+  // with K_oo = L L', v = L^-1 K_ox and w = L^-1 y
+  let v = l
+    .solve_lower_triangular(&cross)
+    .expect("Cholesky factor must be invertible");
+  let w = l
+    .solve_lower_triangular(&DMatrix::from_iterator(k, 1, observed.iter().copied()))
+    .expect("Cholesky factor must be invertible");
+
+  let mean = v.transpose() * w;
+  let reduction = v.transpose() * &v;
+  let prior = covariance_matrix(kernel, times, times);
+
+  (
+    Array1::from_shape_fn(n, |i| mean[(i, 0)]),
+    Array2::from_shape_fn((n, n), |(i, j)| prior[[i, j]] - reduction[(i, j)]),
+  )
+}
+
+/// Sample of a centered Gaussian process at `times` conditional on the observed values
+pub fn sample_conditional(
+  kernel: &Kernel,
+  observed_times: ArrayView1<f64>,
+  observed: ArrayView1<f64>,
+  times: ArrayView1<f64>,
+) -> Array1<f64> {
+  let (mean, cov) = conditional(kernel, observed_times, observed, times);
+  mean
+    + cholesky(&cov).dot(&Array1::<f64>::random_using(
+      times.len(),
+      StandardNormal,
+      &mut thread_rng(),
+    ))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GaussianProcessMethod {
+  /// Cholesky factorization of the covariance matrix, O(n^3) setup
+  #[default]
+  Cholesky,
+  /// Circulant embedding, O(n log n), requires a stationary kernel on a uniform grid.
+  /// Falls back to Cholesky if the embedding is not non-negative definite.
+  CirculantEmbedding,
+}
+
+/// Centered Gaussian process on a time grid with an arbitrary covariance kernel.
+/// The factorization of the covariance is computed once on construction.
+pub struct GaussianProcess {
+  pub kernel: Kernel,
+  pub times: Array1<f64>,
+  pub method: GaussianProcessMethod,
+  pub m: Option<usize>,
+  /// Lower Cholesky factor of the covariance matrix
+  pub cholesky: Option<Array2<f64>>,
+  /// Square root of the circulant eigenvalues, scaled by the embedding size
+  pub sqrt_eigenvalues: Option<Arc<Array1<Complex<f64>>>>,
+  pub fft_handler: Option<Arc<FftHandler<f64>>>,
+}
+
+impl GaussianProcess {
+  #[must_use]
+  pub fn new(
+    kernel: Kernel,
+    times: Array1<f64>,
+    method: GaussianProcessMethod,
+    m: Option<usize>,
+  ) -> Self {
+    assert!(!times.is_empty(), "Time grid must not be empty");
+
+    let mut gp = Self {
+      kernel,
+      times,
+      method,
+      m,
+      cholesky: None,
+      sqrt_eigenvalues: None,
+      fft_handler: None,
+    };
+
+    if method == GaussianProcessMethod::CirculantEmbedding {
+      gp.circulant_embedding();
+    }
+
+    if gp.sqrt_eigenvalues.is_none() {
+      gp.cholesky = Some(gp.cholesky_factor());
+    }
+
+    gp
+  }
+
+  /// Covariance matrix on the time grid
+  pub fn covariance(&self) -> Array2<f64> {
+    let n = self.times.len();
+    Array2::from_shape_fn((n, n), |(i, j)| (self.kernel)(self.times[i], self.times[j]))
+  }
+
+  fn cholesky_factor(&self) -> Array2<f64> {
+    cholesky(&self.covariance())
+  }
+
+  fn circulant_embedding(&mut self) {
+    let n = self.times.len();
+
+    if n < 2 {
+      return;
+    }
+
+    let dt = self.times[1] - self.times[0];
+    let uniform = self
+      .times
+      .windows(2)
+      .into_iter()
+      .all(|w| ((w[1] - w[0]) - dt).abs() <= 1e-10 * dt.abs());
+    assert!(uniform, "Circulant embedding requires a uniform time grid");
+
+    let t0 = self.times[0];
+    let len = 2 * (n - 1);
+    let c = Array1::from_shape_fn(len, |j| {
+      let lag = if j < n { j } else { len - j };
+      Complex::new((self.kernel)(t0, t0 + lag as f64 * dt), 0.0)
+    });
+
+    let handler = FftHandler::new(len);
+    let mut eigenvalues = Array1::<Complex<f64>>::zeros(len);
+    ndfft(&c, &mut eigenvalues, &handler, 0);
+
+    let max = eigenvalues.iter().map(|l| l.re).fold(0.0, f64::max);
+    if eigenvalues.iter().any(|l| l.re < -1e-10 * max) {
+      return;
+    }
+
+    self.sqrt_eigenvalues =
+      Some(Arc::new(eigenvalues.mapv(|l| {
+        Complex::new((l.re.max(0.0) / len as f64).sqrt(), 0.0)
+      })));
+    self.fft_handler = Some(Arc::new(handler));
+  }
+}
+
+impl Sampling<f64> for GaussianProcess {
+  fn sample(&self) -> Array1<f64> {
+    let n = self.times.len();
+
+    match (&self.sqrt_eigenvalues, &self.fft_handler, &self.cholesky) {
+      (Some(sqrt_eigenvalues), Some(fft_handler), _) => {
+        let len = sqrt_eigenvalues.len();
+        let z = Array1::<Complex<f64>>::random_using(
+          len,
+          ComplexDistribution::new(StandardNormal, StandardNormal),
+          &mut thread_rng(),
+        );
+        let w = &**sqrt_eigenvalues * &z;
+        let mut x = Array1::<Complex<f64>>::zeros(len);
+        ndfft(&w, &mut x, &**fft_handler, 0);
+        x.slice(s![..n]).mapv(|v| v.re)
+      }
+      (_, _, Some(l)) => l.dot(&Array1::<f64>::random_using(
+        n,
+        StandardNormal,
+        &mut thread_rng(),
+      )),
+      _ => unreachable!("Gaussian process is not initialized"),
+    }
+  }
+
+  fn n(&self) -> usize {
+    self.times.len()
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
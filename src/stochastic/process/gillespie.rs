@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use rand::Rng;
+use rand_distr::{Distribution, Exp, Poisson};
+
+use crate::rng::thread_rng;
+
+/// Propensity function of a reaction, evaluated on the current species counts.
+pub type Propensity = Arc<dyn Fn(&ArrayView1<f64>) -> f64 + Send + Sync>;
+
+/// Event-time trajectory of a reaction network.
+#[derive(Debug, Clone)]
+pub struct Trajectory {
+  /// Event (or leap) times, starting at 0.0
+  pub times: Array1<f64>,
+  /// Species counts after each event (times x species)
+  pub states: Array2<f64>,
+}
+
+impl Trajectory {
+  /// Piecewise-constant trajectory evaluated on the grid t_i = i * t / n, i = 0..=n
+  pub fn on_grid(&self, n: usize, t: f64) -> Array2<f64> {
+    let dt = t / n as f64;
+    let mut grid = Array2::<f64>::zeros((n + 1, self.states.ncols()));
+    let mut k = 0;
+
+    for i in 0..=n {
+      while k + 1 < self.times.len() && self.times[k + 1] <= i as f64 * dt {
+        k += 1;
+      }
+
+      grid.row_mut(i).assign(&self.states.row(k));
+    }
+
+    grid
+  }
+}
+
+/// Gillespie stochastic simulation algorithm for reaction networks.
+///
+/// Reaction j fires with rate `propensities[j](x)` and changes the state by
+/// `stoichiometry.row(j)`. Without `tau` the exact direct method is used,
+/// otherwise tau-leaping with the given step, which is much faster for large systems.
+#[derive(Default)]
+pub struct Gillespie {
+  /// Stoichiometry matrix (reactions x species)
+  pub stoichiometry: Array2<f64>,
+  /// Propensity function of each reaction
+  pub propensities: Vec<Propensity>,
+  /// Initial species counts
+  pub x0: Array1<f64>,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Tau-leaping step size
+  pub tau: Option<f64>,
+  /// Maximum number of events (or leaps)
+  pub max_events: Option<usize>,
+}
+
+impl Gillespie {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert_eq!(
+      params.stoichiometry.nrows(),
+      params.propensities.len(),
+      "Every reaction needs a propensity function"
+    );
+    assert_eq!(
+      params.stoichiometry.ncols(),
+      params.x0.len(),
+      "Stoichiometry must have a column for every species"
+    );
+
+    Self {
+      stoichiometry: params.stoichiometry.clone(),
+      propensities: params.propensities.clone(),
+      x0: params.x0.clone(),
+      t: params.t,
+      tau: params.tau,
+      max_events: params.max_events,
+    }
+  }
+
+  fn rates(&self, x: &Array1<f64>) -> Array1<f64> {
+    self
+      .propensities
+      .iter()
+      .map(|a| a(&x.view()).max(0.0))
+      .collect()
+  }
+
+  /// Sample an event-time trajectory
+  pub fn sample(&self) -> Trajectory {
+    match self.tau {
+      Some(tau) => self.sample_tau_leaping(tau),
+      None => self.sample_direct(),
+    }
+  }
+
+  /// Exact simulation by the direct method
+  pub fn sample_direct(&self) -> Trajectory {
+    let mut rng = thread_rng();
+    let t_max = self.t.unwrap_or(1.0);
+    let max_events = self.max_events.unwrap_or(usize::MAX);
+    let mut x = self.x0.clone();
+    let mut t = 0.0;
+    let mut times = vec![0.0];
+    let mut states = vec![x.clone()];
+
+    while times.len() <= max_events {
+      let rates = self.rates(&x);
+      let rate = rates.sum();
+
+      if rate <= 0.0 {
+        break;
+      }
+
+      t += Exp::new(rate).unwrap().sample(&mut rng);
+
+      if t > t_max {
+        break;
+      }
+
+      let u = rng.gen::<f64>() * rate;
+      let mut acc = 0.0;
+      let mut j = rates.len() - 1;
+
+      for (k, r) in rates.iter().enumerate() {
+        acc += r;
+
+        if u < acc {
+          j = k;
+          break;
+        }
+      }
+
+      x += &self.stoichiometry.row(j);
+      times.push(t);
+      states.push(x.clone());
+    }
+
+    Self::trajectory(times, states)
+  }
+
+  /// Approximate simulation by tau-leaping, the step is halved whenever
+  /// a leap would make a species count negative
+  pub fn sample_tau_leaping(&self, tau: f64) -> Trajectory {
+    let mut rng = thread_rng();
+    let t_max = self.t.unwrap_or(1.0);
+    let max_events = self.max_events.unwrap_or(usize::MAX);
+    let mut x = self.x0.clone();
+    let mut t = 0.0;
+    let mut times = vec![0.0];
+    let mut states = vec![x.clone()];
+
+    while t < t_max && times.len() <= max_events {
+      let rates = self.rates(&x);
+
+      if rates.sum() <= 0.0 {
+        break;
+      }
+
+      let mut h = tau.min(t_max - t);
+
+      let x_next = loop {
+        let mut x_next = x.clone();
+
+        for (j, &r) in rates.iter().enumerate() {
+          if r > 0.0 {
+            let k = Poisson::new(r * h).unwrap().sample(&mut rng);
+            x_next.scaled_add(k, &self.stoichiometry.row(j));
+          }
+        }
+
+        if x_next.iter().all(|&v| v >= 0.0) {
+          break x_next;
+        }
+
+        h /= 2.0;
+      };
+
+      t += h;
+      x = x_next;
+      times.push(t);
+      states.push(x.clone());
+    }
+
+    Self::trajectory(times, states)
+  }
+
+  fn trajectory(times: Vec<f64>, states: Vec<Array1<f64>>) -> Trajectory {
+    let views = states.iter().map(|s| s.view()).collect::<Vec<_>>();
+
+    Trajectory {
+      times: Array1::from(times),
+      states: ndarray::stack(Axis(0), &views).unwrap(),
+    }
+  }
+}
@@ -0,0 +1,139 @@
+use ndarray::{Array1, ArrayView1};
+use rand::Rng;
+use rand_distr::{Distribution, Exp};
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+/// Hawkes process with exponential kernel.
+/// lambda(t) = lambda0 + sum_{t_i < t} alpha * exp(-beta(t - t_i))
+/// The process is stationary if alpha < beta.
+#[derive(Default)]
+pub struct Hawkes {
+  /// Baseline intensity
+  pub lambda0: f64,
+  /// Jump of the intensity after an event
+  pub alpha: f64,
+  /// Decay rate of the intensity
+  pub beta: f64,
+  /// Number of events, if set the path stops after n events instead of at t_max
+  pub n: Option<usize>,
+  /// Time horizon
+  pub t_max: f64,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl Hawkes {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.lambda0 > 0.0, "lambda0 must be positive");
+    assert!(
+      params.alpha >= 0.0 && params.beta > 0.0,
+      "alpha must be non-negative and beta must be positive"
+    );
+
+    Self {
+      lambda0: params.lambda0,
+      alpha: params.alpha,
+      beta: params.beta,
+      n: params.n,
+      t_max: params.t_max,
+      m: params.m,
+    }
+  }
+
+  /// Intensity at time t given the event times
+  pub fn intensity(&self, events: ArrayView1<f64>, t: f64) -> f64 {
+    self.lambda0
+      + events
+        .iter()
+        .skip(1)
+        .take_while(|&&ti| ti < t)
+        .map(|ti| self.alpha * (-self.beta * (t - ti)).exp())
+        .sum::<f64>()
+  }
+
+  /// Intensity evaluated on a time grid, including the events up to (and at) each grid point
+  pub fn intensity_path(&self, events: ArrayView1<f64>, grid: ArrayView1<f64>) -> Array1<f64> {
+    let mut intensity = Array1::<f64>::zeros(grid.len());
+    let mut excitation = 0.0;
+    let mut last = 0.0;
+    let mut k = 1;
+
+    for (i, &t) in grid.iter().enumerate() {
+      while k < events.len() && events[k] <= t {
+        excitation = excitation * (-self.beta * (events[k] - last)).exp() + self.alpha;
+        last = events[k];
+        k += 1;
+      }
+
+      intensity[i] = self.lambda0 + excitation * (-self.beta * (t - last)).exp();
+    }
+
+    intensity
+  }
+}
+
+impl Sampling<f64> for Hawkes {
+  /// Event times by Ogata's thinning algorithm, the first element is 0.0. The path has n + 1
+  /// elements if n is set, otherwise it holds the events up to t_max.
+  fn sample(&self) -> Array1<f64> {
+    let mut rng = thread_rng();
+    let mut events = vec![0.0];
+    let mut t = 0.0;
+    let mut excitation = 0.0;
+
+    while self.n.is_none_or(|n| events.len() <= n) {
+      // the intensity is non-increasing between events, so the current value is an upper bound
+      let lambda_bar = self.lambda0 + excitation;
+      let w = Exp::new(lambda_bar).unwrap().sample(&mut rng);
+      excitation *= (-self.beta * w).exp();
+      t += w;
+
+      if self.n.is_none() && t > self.t_max {
+        break;
+      }
+
+      if rng.gen::<f64>() * lambda_bar <= self.lambda0 + excitation {
+        excitation += self.alpha;
+        events.push(t);
+      }
+    }
+
+    Array1::from(events)
+  }
+
+  /// Length of the path if the number of events is set, 0 otherwise
+  fn n(&self) -> usize {
+    self.n.map_or(0, |n| n + 1)
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn paths_of_n_events_have_the_length_of_n() {
+    let hawkes = Hawkes::new(&Hawkes {
+      lambda0: 1.0,
+      alpha: 0.5,
+      beta: 2.0,
+      n: Some(20),
+      t_max: 1.0,
+      m: Some(4),
+    });
+    let paths = hawkes.sample_par();
+    assert_eq!(paths.dim(), (4, hawkes.n()));
+    assert_eq!(hawkes.n(), 21);
+    assert!(paths
+      .rows()
+      .into_iter()
+      .all(|path| path.windows(2).into_iter().all(|w| w[0] < w[1])));
+  }
+}
@@ -0,0 +1,158 @@
+use std::f64::consts::PI;
+
+use ndarray::{s, Array1, ArrayView1};
+use ndarray_rand::RandomExt;
+use rand_distr::StandardNormal;
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+use super::path_construction::PrincipalComponents;
+
+/// Process expanded by the Karhunen–Loève series
+#[derive(Debug, Clone, Copy, Default)]
+pub enum KarhunenLoeveProcess {
+  /// Brownian motion, with the closed-form eigenfunctions
+  /// sqrt(2 / T) sin((k - 1/2) pi t / T) and eigenvalues T^2 / ((k - 1/2) pi)^2
+  #[default]
+  Brownian,
+  /// Fractional Brownian motion with the given Hurst parameter,
+  /// the eigenpairs are computed numerically on the time grid (Nyström method)
+  Fractional(f64),
+}
+
+/// Karhunen–Loève series truncation sampler.
+/// X(t) = sum_{k=1}^{K} sqrt(lambda_k) e_k(t) Z_k
+/// The truncation error is reported as the integrated mean square error
+/// E int_0^T (X(t) - X_K(t))^2 dt = sum_{k > K} lambda_k.
+#[derive(Default)]
+pub struct KarhunenLoeve {
+  /// Expanded process
+  pub process: KarhunenLoeveProcess,
+  /// Number of terms of the series
+  pub terms: usize,
+  /// Number of time steps
+  pub n: usize,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+  /// Eigenpairs of the fBm covariance on the time grid
+  pub pca: PrincipalComponents,
+}
+
+impl KarhunenLoeve {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.terms > 0, "Number of terms must be positive");
+
+    let pca = match params.process {
+      KarhunenLoeveProcess::Brownian => PrincipalComponents::default(),
+      KarhunenLoeveProcess::Fractional(hurst) => {
+        assert!(
+          hurst > 0.0 && hurst < 1.0,
+          "Hurst parameter must be in (0, 1)"
+        );
+        assert!(
+          params.terms <= params.n,
+          "Number of terms can not exceed the number of time steps"
+        );
+
+        let dt = params.t.unwrap_or(1.0) / params.n as f64;
+        let n = params.n;
+        PrincipalComponents::new(
+          ndarray::Array2::from_shape_fn((n, n), |(i, j)| {
+            let (s, t) = ((i + 1) as f64 * dt, (j + 1) as f64 * dt);
+            0.5 * (s.powf(2.0 * hurst) + t.powf(2.0 * hurst) - (s - t).abs().powf(2.0 * hurst))
+          })
+          .view(),
+        )
+      }
+    };
+
+    Self {
+      process: params.process,
+      terms: params.terms,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+      pca,
+    }
+  }
+
+  /// Eigenvalues of the covariance operator used by the truncated series
+  pub fn eigenvalues(&self) -> Array1<f64> {
+    let t = self.t.unwrap_or(1.0);
+
+    match self.process {
+      KarhunenLoeveProcess::Brownian => {
+        Array1::from_shape_fn(self.terms, |k| (t / ((k as f64 + 0.5) * PI)).powi(2))
+      }
+      KarhunenLoeveProcess::Fractional(_) => {
+        let dt = t / self.n as f64;
+        self
+          .pca
+          .eigenvalues
+          .slice(s![..self.terms])
+          .mapv(|l| l * dt)
+      }
+    }
+  }
+
+  /// Integrated mean square error of the truncation
+  pub fn truncation_error(&self) -> f64 {
+    let t = self.t.unwrap_or(1.0);
+    let total = match self.process {
+      KarhunenLoeveProcess::Brownian => 0.5 * t.powi(2),
+      KarhunenLoeveProcess::Fractional(_) => self.pca.eigenvalues.sum() * t / self.n as f64,
+    };
+
+    (total - self.eigenvalues().sum()).max(0.0)
+  }
+
+  /// Path from the given standard normals, one for each term of the series
+  pub fn sample_from_normals(&self, z: ArrayView1<f64>) -> Array1<f64> {
+    assert_eq!(
+      z.len(),
+      self.terms,
+      "Number of normals must match the terms"
+    );
+
+    let t = self.t.unwrap_or(1.0);
+    let dt = t / self.n as f64;
+    let mut x = Array1::<f64>::zeros(self.n + 1);
+
+    match self.process {
+      KarhunenLoeveProcess::Brownian => {
+        for (k, zk) in z.iter().enumerate() {
+          let w = (k as f64 + 0.5) * PI / t;
+
+          for i in 1..=self.n {
+            x[i] += (2.0 * t).sqrt() * (w * i as f64 * dt).sin() / (w * t) * zk;
+          }
+        }
+      }
+      KarhunenLoeveProcess::Fractional(_) => {
+        x.slice_mut(s![1..]).assign(&self.pca.build(z));
+      }
+    }
+
+    x
+  }
+}
+
+impl Sampling<f64> for KarhunenLoeve {
+  fn sample(&self) -> Array1<f64> {
+    self.sample_from_normals(
+      Array1::random_using(self.terms, StandardNormal, &mut thread_rng()).view(),
+    )
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
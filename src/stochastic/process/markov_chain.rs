@@ -0,0 +1,132 @@
+use ndarray::{Array1, Array2};
+use rand::Rng;
+use rand_distr::{Distribution, Exp};
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+/// Continuous-time Markov chain on the states 0..d
+/// defined by the generator (Q) matrix, where q_ij >= 0 is the jump
+/// intensity from state i to state j and every row sums up to zero.
+#[derive(Default)]
+pub struct MarkovChain {
+  /// Generator matrix
+  pub generator: Array2<f64>,
+  /// Initial state
+  pub x0: Option<usize>,
+  /// Number of time steps
+  pub n: usize,
+  /// Time horizon
+  pub t: Option<f64>,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl MarkovChain {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    let d = params.generator.nrows();
+    assert_eq!(
+      d,
+      params.generator.ncols(),
+      "Generator must be a square matrix"
+    );
+
+    for row in params.generator.rows() {
+      assert!(
+        row.sum().abs() < 1e-10,
+        "Rows of the generator must sum up to zero"
+      );
+    }
+
+    Self {
+      generator: params.generator.clone(),
+      x0: params.x0,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+    }
+  }
+
+  /// Number of states
+  pub fn states(&self) -> usize {
+    self.generator.nrows()
+  }
+
+  /// Sample the jump times and the visited states in continuous time.
+  /// The first element is always (0.0, x0).
+  pub fn sample_jumps(&self) -> (Array1<f64>, Array1<usize>) {
+    let t_max = self.t.unwrap_or(1.0);
+    let mut rng = thread_rng();
+    let mut state = self.x0.unwrap_or(0);
+    let mut t = 0.0;
+    let mut times = vec![0.0];
+    let mut states = vec![state];
+
+    loop {
+      let rate = -self.generator[[state, state]];
+
+      if rate <= 0.0 {
+        break;
+      }
+
+      t += Exp::new(rate).unwrap().sample(&mut rng);
+
+      if t > t_max {
+        break;
+      }
+
+      let u = rng.gen::<f64>() * rate;
+      let mut acc = 0.0;
+
+      for j in 0..self.states() {
+        if j == state {
+          continue;
+        }
+
+        acc += self.generator[[state, j]];
+
+        if u < acc {
+          state = j;
+          break;
+        }
+      }
+
+      times.push(t);
+      states.push(state);
+    }
+
+    (Array1::from(times), Array1::from(states))
+  }
+}
+
+impl Sampling<usize> for MarkovChain {
+  /// States of the chain on the grid t_i = i * dt, i = 0..=n
+  fn sample(&self) -> Array1<usize> {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let (times, states) = self.sample_jumps();
+
+    let mut chain = Array1::<usize>::zeros(self.n + 1);
+    let mut k = 0;
+
+    for i in 0..=self.n {
+      let t = i as f64 * dt;
+
+      while k + 1 < times.len() && times[k + 1] <= t {
+        k += 1;
+      }
+
+      chain[i] = states[k];
+    }
+
+    chain
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
@@ -0,0 +1,99 @@
+use ndarray::{s, Array1};
+use ndarray_rand::RandomExt;
+use rand_distr::Normal;
+
+use crate::rng::thread_rng;
+use crate::stochastic::{noise::fgn::FGN, Sampling, TheoreticalMoments};
+
+/// Mixed fractional Brownian motion a B(t) + b B_H(t) of an independent Brownian motion B
+/// and fractional Brownian motion B_H. It is equivalent to a Brownian motion for H > 3/4,
+/// so the mixed fractional Black-Scholes model is arbitrage-free in that range.
+/// https://doi.org/10.2307/3318689 (Cheridito)
+#[derive(Default, Clone)]
+pub struct MixedFbm {
+  /// Weight of the Brownian motion
+  pub a: f64,
+  /// Weight of the fractional Brownian motion
+  pub b: f64,
+  pub hurst: f64,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+  pub fgn: FGN,
+}
+
+impl MixedFbm {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(
+      params.hurst > 0.0 && params.hurst < 1.0,
+      "Hurst parameter must be in (0, 1)"
+    );
+
+    Self {
+      a: params.a,
+      b: params.b,
+      hurst: params.hurst,
+      n: params.n,
+      t: params.t,
+      m: params.m,
+      fgn: FGN::new(params.hurst, params.n, params.t, None),
+    }
+  }
+
+  /// Brownian and fractional Brownian components [B, B_H] of one path, without the weights
+  pub fn components(&self) -> [Array1<f64>; 2] {
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
+    let fgn = self.fgn.sample();
+
+    let mut bm = Array1::<f64>::zeros(self.n + 1);
+    let mut fbm = Array1::<f64>::zeros(self.n + 1);
+    for i in 1..=self.n {
+      bm[i] = bm[i - 1] + gn[i - 1];
+      fbm[i] = fbm[i - 1] + fgn[i - 1];
+    }
+
+    [
+      bm.slice(s![..self.n()]).to_owned(),
+      fbm.slice(s![..self.n()]).to_owned(),
+    ]
+  }
+}
+
+impl Sampling<f64> for MixedFbm {
+  fn sample(&self) -> Array1<f64> {
+    let [bm, fbm] = self.components();
+    self.a * bm + self.b * fbm
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+impl TheoreticalMoments for MixedFbm {
+  fn mean(&self, _t: f64) -> f64 {
+    0.0
+  }
+
+  fn variance(&self, t: f64) -> f64 {
+    self.a.powi(2) * t + self.b.powi(2) * t.powf(2.0 * self.hurst)
+  }
+
+  fn covariance(&self, s: f64, t: f64) -> Option<f64> {
+    let h2 = 2.0 * self.hurst;
+    Some(
+      self.a.powi(2) * s.min(t)
+        + self.b.powi(2) * 0.5 * (s.powf(h2) + t.powf(h2) - (t - s).abs().powf(h2)),
+    )
+  }
+}
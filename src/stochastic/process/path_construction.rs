@@ -0,0 +1,185 @@
+use nalgebra::{DMatrix, SymmetricEigen};
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2};
+
+/// Construction of Brownian paths from independent standard normals.
+/// The constructions differ in how the variance is distributed over the normals,
+/// which matters for stratified and quasi-Monte Carlo sampling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathConstruction {
+  /// Forward summation of the increments
+  #[default]
+  Incremental,
+  /// Terminal value first, then the midpoints of the intervals recursively
+  BrownianBridge,
+  /// Principal components of the covariance matrix in decreasing order of variance
+  PCA,
+}
+
+/// Precomputed Brownian bridge construction on a time grid.
+/// The first normal sets the terminal value and every further normal
+/// fills the midpoint (in index) of an interval with known endpoints.
+#[derive(Debug, Clone, Default)]
+pub struct BrownianBridge {
+  /// Time grid, excluding the origin
+  pub times: Array1<f64>,
+  /// Grid index set by the k-th normal (1-based, 0 is the origin)
+  bridge_index: Vec<usize>,
+  left_index: Vec<usize>,
+  right_index: Vec<usize>,
+  left_weight: Vec<f64>,
+  right_weight: Vec<f64>,
+  std_dev: Vec<f64>,
+}
+
+impl BrownianBridge {
+  #[must_use]
+  pub fn new(times: ArrayView1<f64>) -> Self {
+    let n = times.len();
+    assert!(n > 0, "Time grid must not be empty");
+    assert!(
+      times.windows(2).into_iter().all(|w| w[1] > w[0]) && times[0] > 0.0,
+      "Time grid must be positive and strictly increasing"
+    );
+
+    // t[0] = 0 is the origin where the path is pinned to zero
+    let t = |i: usize| if i == 0 { 0.0 } else { times[i - 1] };
+
+    let mut bridge = Self {
+      times: times.to_owned(),
+      bridge_index: vec![n],
+      left_index: vec![0],
+      right_index: vec![0],
+      left_weight: vec![0.0],
+      right_weight: vec![0.0],
+      std_dev: vec![times[n - 1].sqrt()],
+    };
+
+    let mut intervals = std::collections::VecDeque::from([(0, n)]);
+
+    while let Some((l, r)) = intervals.pop_front() {
+      if r - l < 2 {
+        continue;
+      }
+
+      let i = (l + r) / 2;
+      let (tl, ti, tr) = (t(l), t(i), t(r));
+
+      bridge.bridge_index.push(i);
+      bridge.left_index.push(l);
+      bridge.right_index.push(r);
+      bridge.left_weight.push((tr - ti) / (tr - tl));
+      bridge.right_weight.push((ti - tl) / (tr - tl));
+      bridge
+        .std_dev
+        .push(((ti - tl) * (tr - ti) / (tr - tl)).sqrt());
+
+      intervals.push_back((l, i));
+      intervals.push_back((i, r));
+    }
+
+    bridge
+  }
+
+  /// Brownian path on the grid (excluding the origin) from standard normals
+  pub fn build(&self, z: ArrayView1<f64>) -> Array1<f64> {
+    let n = self.times.len();
+    assert_eq!(z.len(), n, "Number of normals must match the time grid");
+
+    // w[0] is the origin
+    let mut w = vec![0.0; n + 1];
+
+    for k in 0..n {
+      w[self.bridge_index[k]] = self.left_weight[k] * w[self.left_index[k]]
+        + self.right_weight[k] * w[self.right_index[k]]
+        + self.std_dev[k] * z[k];
+    }
+
+    Array1::from(w[1..].to_vec())
+  }
+}
+
+/// Probability that a Brownian bridge from x0 to x1 with total variance
+/// sigma^2 * dt crosses the barrier, used to correct discretely monitored barriers.
+pub fn barrier_crossing_probability(x0: f64, x1: f64, barrier: f64, variance: f64) -> f64 {
+  if (x0 - barrier) * (x1 - barrier) <= 0.0 {
+    return 1.0;
+  }
+
+  (-2.0 * (barrier - x0) * (barrier - x1) / variance).exp()
+}
+
+/// Principal component construction of a centered Gaussian vector.
+/// The covariance matrix C = V diag(lambda) V^T is decomposed once and the
+/// k-th normal drives the k-th largest eigenvalue, so the leading normals
+/// carry most of the variance, which is what QMC sequences need.
+#[derive(Debug, Clone, Default)]
+pub struct PrincipalComponents {
+  /// Eigenvalues in decreasing order
+  pub eigenvalues: Array1<f64>,
+  /// Eigenvectors scaled by the square root of the eigenvalues (columns)
+  pub loadings: Array2<f64>,
+}
+
+impl PrincipalComponents {
+  #[must_use]
+  pub fn new(covariance: ArrayView2<f64>) -> Self {
+    let n = covariance.nrows();
+    assert_eq!(n, covariance.ncols(), "Covariance must be a square matrix");
+
+    let eigen = SymmetricEigen::new(DMatrix::from_fn(n, n, |i, j| covariance[[i, j]]));
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+
+    let eigenvalues = order
+      .iter()
+      .map(|&k| eigen.eigenvalues[k].max(0.0))
+      .collect::<Array1<f64>>();
+    let loadings = Array2::from_shape_fn((n, n), |(i, k)| {
+      eigen.eigenvectors[(i, order[k])] * eigenvalues[k].sqrt()
+    });
+
+    Self {
+      eigenvalues,
+      loadings,
+    }
+  }
+
+  /// Principal components of Brownian motion on the time grid, C_ij = min(t_i, t_j)
+  #[must_use]
+  pub fn brownian(times: ArrayView1<f64>) -> Self {
+    let n = times.len();
+    Self::new(Array2::from_shape_fn((n, n), |(i, j)| times[i].min(times[j])).view())
+  }
+
+  /// Gaussian vector from standard normals. If fewer normals than the dimension
+  /// are given, only the leading components are used (dimension reduction).
+  pub fn build(&self, z: ArrayView1<f64>) -> Array1<f64> {
+    let k = z.len();
+    assert!(
+      k <= self.eigenvalues.len(),
+      "Too many normals for the dimension"
+    );
+
+    self.loadings.slice(ndarray::s![.., ..k]).dot(&z)
+  }
+
+  /// Fraction of the total variance explained by each component
+  pub fn explained_variance_ratio(&self) -> Array1<f64> {
+    &self.eigenvalues / self.eigenvalues.sum()
+  }
+
+  /// Smallest number of components explaining at least the given fraction of the variance
+  pub fn components_for(&self, ratio: f64) -> usize {
+    let mut acc = 0.0;
+
+    for (k, r) in self.explained_variance_ratio().iter().enumerate() {
+      acc += r;
+
+      if acc >= ratio {
+        return k + 1;
+      }
+    }
+
+    self.eigenvalues.len()
+  }
+}
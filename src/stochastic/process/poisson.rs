@@ -1,9 +1,11 @@
-use ndarray::{Array0, Array1, Axis, Dim};
-use ndarray_rand::rand_distr::{Distribution, Exp};
+use ndarray::{Array0, Array1, ArrayView1, Axis, Dim};
+use ndarray_rand::rand_distr::{Binomial, Distribution, Exp, Uniform};
 use ndarray_rand::RandomExt;
-use rand::thread_rng;
+use rayon::prelude::*;
 
-use crate::stochastic::Sampling;
+use crate::quant::greeks::Estimate;
+use crate::rng::{path_seed, thread_rng, with_seed};
+use crate::stochastic::{Sampling, TheoreticalMoments};
 
 #[derive(Default)]
 pub struct Poisson {
@@ -25,10 +27,97 @@ impl Poisson {
   }
 }
 
+impl Poisson {
+  /// Arrival times on [0, t_max] conditioned on `count` arrivals, the sorted uniform
+  /// order statistics, starting at 0 as the sampler
+  pub fn sample_given_count(&self, count: usize) -> Array1<f64> {
+    let t_max = self.t_max.expect("t_max must be provided");
+    let mut times =
+      Array1::random_using(count, Uniform::new(0.0, t_max), &mut thread_rng()).to_vec();
+    times.sort_by(|a, b| a.total_cmp(b));
+    times.insert(0, 0.0);
+    Array1::from(times)
+  }
+
+  /// Poisson bridge, the counting process on an increasing grid of [0, t_max] conditioned on
+  /// N(t_max) = count. Given the count left at t_i, the count of (t_i, t_(i+1)] is binomial
+  /// with the probability (t_(i+1) - t_i) / (t_max - t_i).
+  pub fn bridge(&self, grid: ArrayView1<f64>, count: usize) -> Array1<f64> {
+    let t_max = self.t_max.expect("t_max must be provided");
+    let mut rng = thread_rng();
+    let mut left = count as u64;
+    let mut previous = 0.0;
+    let mut counts = Array1::<f64>::zeros(grid.len());
+
+    for (i, &t) in grid.iter().enumerate() {
+      assert!(
+        t >= previous && t <= t_max,
+        "Grid must be increasing within [0, t_max]"
+      );
+      if left > 0 && t > previous {
+        let p = ((t - previous) / (t_max - previous)).min(1.0);
+        left -= Binomial::new(left, p).unwrap().sample(&mut rng);
+      }
+      counts[i] = (count as u64 - left) as f64;
+      previous = t;
+    }
+
+    counts
+  }
+}
+
+/// Poisson probabilities of the counts 0..=K for the mean lambda t, where K is the
+/// first count with a tail probability P(N > K) below `tolerance`
+pub fn count_probabilities(mean: f64, tolerance: f64) -> Vec<f64> {
+  assert!(mean >= 0.0, "Mean must be non-negative");
+  let mut p = (-mean).exp();
+  let mut probabilities = vec![p];
+  let mut cumulative = p;
+  while 1.0 - cumulative > tolerance && p.is_finite() {
+    p *= mean / probabilities.len() as f64;
+    cumulative += p;
+    probabilities.push(p);
+  }
+  probabilities
+}
+
+/// Monte Carlo estimate stratified over the number of jumps of a Poisson process with the
+/// mean lambda t. `f(k)` simulates one outcome conditioned on k jumps (e.g. with
+/// `sample_given_count`), the paths are allocated proportionally to the count probabilities
+/// (at least two per stratum) and the counts with a tail probability below `tolerance` are
+/// dropped.
+pub fn stratified_by_count<F>(mean: f64, paths: usize, tolerance: f64, seed: u64, f: F) -> Estimate
+where
+  F: Fn(usize) -> f64 + Sync,
+{
+  let probabilities = count_probabilities(mean, tolerance);
+  let mut offset = 0u64;
+  let mut value = 0.0;
+  let mut variance = 0.0;
+
+  for (k, &p) in probabilities.iter().enumerate() {
+    let size = ((p * paths as f64).round() as usize).max(2);
+    let samples = (0..size)
+      .into_par_iter()
+      .map(|i| with_seed(path_seed(seed, offset + i as u64), || f(k)))
+      .collect::<Vec<_>>();
+    offset += size as u64;
+
+    let stratum = Estimate::from_samples(Array1::from(samples).view());
+    value += p * stratum.value;
+    variance += (p * stratum.std_error).powi(2);
+  }
+
+  Estimate {
+    value,
+    std_error: variance.sqrt(),
+  }
+}
+
 impl Sampling<f64> for Poisson {
   fn sample(&self) -> Array1<f64> {
     if let Some(n) = self.n {
-      let exponentials = Array1::random(n, Exp::new(1.0 / self.lambda).unwrap());
+      let exponentials = Array1::random_using(n, Exp::new(self.lambda).unwrap(), &mut thread_rng());
       let mut poisson = Array1::<f64>::zeros(n + 1);
       for i in 1..(n + 1) {
         poisson[i] = poisson[i - 1] + exponentials[i - 1];
@@ -40,9 +129,7 @@ impl Sampling<f64> for Poisson {
       let mut t = 0.0;
 
       while t < t_max {
-        t += Exp::new(1.0 / self.lambda)
-          .unwrap()
-          .sample(&mut thread_rng());
+        t += Exp::new(self.lambda).unwrap().sample(&mut thread_rng());
         poisson
           .push(Axis(0), Array0::from_elem(Dim(()), t).view())
           .unwrap();
@@ -62,3 +149,18 @@ impl Sampling<f64> for Poisson {
     self.m
   }
 }
+
+/// Moments of the counting process N(t) (the sampler returns the arrival times).
+impl TheoreticalMoments for Poisson {
+  fn mean(&self, t: f64) -> f64 {
+    self.lambda * t
+  }
+
+  fn variance(&self, t: f64) -> f64 {
+    self.lambda * t
+  }
+
+  fn covariance(&self, s: f64, t: f64) -> Option<f64> {
+    Some(self.lambda * s.min(t))
+  }
+}
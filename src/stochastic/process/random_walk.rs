@@ -0,0 +1,170 @@
+use ndarray::{s, Array1, Array2};
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+use crate::rng::thread_rng;
+use crate::stochastic::Sampling;
+
+/// Random walk on the integer lattice Z^d.
+/// At every step one of the d axes is chosen uniformly and the walker moves
+/// one unit along it, forward with probability p. With persistence q the
+/// previous step is repeated with probability q (correlated random walk).
+#[derive(Default)]
+pub struct RandomWalk {
+  /// Dimension of the lattice (default 1)
+  pub dim: Option<usize>,
+  /// Probability of a positive step (default 0.5)
+  pub p: Option<f64>,
+  /// Probability of repeating the previous step (default 0.0)
+  pub persistence: Option<f64>,
+  /// Number of steps
+  pub n: usize,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl RandomWalk {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.dim.unwrap_or(1) >= 1, "dim must be at least 1");
+    assert!(
+      (0.0..=1.0).contains(&params.p.unwrap_or(0.5)),
+      "p must be in [0, 1]"
+    );
+    assert!(
+      (0.0..=1.0).contains(&params.persistence.unwrap_or(0.0)),
+      "persistence must be in [0, 1]"
+    );
+
+    Self {
+      dim: params.dim,
+      p: params.p,
+      persistence: params.persistence,
+      n: params.n,
+      m: params.m,
+    }
+  }
+
+  /// Sample the walk, the rows are the positions at steps 0..=n
+  pub fn sample_path(&self) -> Array2<i64> {
+    let dim = self.dim.unwrap_or(1);
+    let p = self.p.unwrap_or(0.5);
+    let persistence = self.persistence.unwrap_or(0.0);
+    let mut rng = thread_rng();
+
+    let mut x = Array2::<i64>::zeros((self.n + 1, dim));
+    let mut step: Option<(usize, i64)> = None;
+
+    for i in 1..=self.n {
+      let (axis, sign) = match step {
+        Some(prev) if rng.gen::<f64>() < persistence => prev,
+        _ => (
+          rng.gen_range(0..dim),
+          if rng.gen::<f64>() < p { 1 } else { -1 },
+        ),
+      };
+
+      let prev = x.row(i - 1).to_owned();
+      x.row_mut(i).assign(&prev);
+      x[[i, axis]] += sign;
+      step = Some((axis, sign));
+    }
+
+    x
+  }
+}
+
+impl Sampling<i64> for RandomWalk {
+  /// First coordinate of the walk, which is the walk itself in 1D
+  fn sample(&self) -> Array1<i64> {
+    self.sample_path().slice(s![.., 0]).to_owned()
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
+
+/// Lévy flight in R^d.
+/// The step lengths are Pareto distributed, P(L > l) = (l / l0)^(-alpha) for l >= l0,
+/// and the directions are uniform on the unit sphere. For alpha < 2 the step
+/// variance is infinite and the walk is superdiffusive.
+#[derive(Default)]
+pub struct LevyFlight {
+  /// Tail index of the step lengths
+  pub alpha: f64,
+  /// Minimal step length (default 1.0)
+  pub l0: Option<f64>,
+  /// Dimension of the space (default 1)
+  pub dim: Option<usize>,
+  /// Number of steps
+  pub n: usize,
+  /// Number of paths for multithreading
+  pub m: Option<usize>,
+}
+
+impl LevyFlight {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(params.alpha > 0.0, "alpha must be positive");
+    assert!(params.l0.unwrap_or(1.0) > 0.0, "l0 must be positive");
+    assert!(params.dim.unwrap_or(1) >= 1, "dim must be at least 1");
+
+    Self {
+      alpha: params.alpha,
+      l0: params.l0,
+      dim: params.dim,
+      n: params.n,
+      m: params.m,
+    }
+  }
+
+  /// Sample the flight, the rows are the positions at steps 0..=n
+  pub fn sample_path(&self) -> Array2<f64> {
+    let dim = self.dim.unwrap_or(1);
+    let l0 = self.l0.unwrap_or(1.0);
+    let mut rng = thread_rng();
+
+    let mut x = Array2::<f64>::zeros((self.n + 1, dim));
+
+    for i in 1..=self.n {
+      let u = 1.0 - rng.gen::<f64>();
+      let length = l0 * u.powf(-1.0 / self.alpha);
+
+      let direction = loop {
+        let d = (0..dim)
+          .map(|_| rng.sample::<f64, _>(StandardNormal))
+          .collect::<Array1<f64>>();
+        let norm = d.dot(&d).sqrt();
+
+        if norm > 0.0 {
+          break d / norm;
+        }
+      };
+
+      let next = &x.row(i - 1) + &(direction * length);
+      x.row_mut(i).assign(&next);
+    }
+
+    x
+  }
+}
+
+impl Sampling<f64> for LevyFlight {
+  /// First coordinate of the flight, which is the flight itself in 1D
+  fn sample(&self) -> Array1<f64> {
+    self.sample_path().slice(s![.., 0]).to_owned()
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}
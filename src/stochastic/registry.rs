@@ -0,0 +1,389 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use ndarray::Array1;
+use rand::Rng;
+use rand_distr::{Distribution, StandardNormal};
+
+use super::{
+  diffusion::{cir::CIR, fou::FOU, gbm::GBM, ou::OU},
+  interest::vasicek::Vasicek,
+  jump::{merton::Merton, nig::NIG, vg::VG},
+  process::{bm::BM, fbm::Fbm},
+  volatility::{heston::Heston, sabr::Sabr},
+  ProcessDistribution, Sampling, Sampling2D,
+};
+
+/// Parameters of a model by name
+pub type Params = HashMap<String, f64>;
+
+/// Constructor of a model from its parameters
+pub type Factory = Arc<dyn Fn(&Params) -> Result<Box<dyn Sampling<f64>>> + Send + Sync>;
+
+/// One component of a two-dimensional model as a one-dimensional sampler, the price (0) or
+/// the volatility (1) of a stochastic volatility model
+pub struct Component<S> {
+  pub model: S,
+  pub index: usize,
+}
+
+impl<S: Sampling2D<f64>> Sampling<f64> for Component<S> {
+  fn sample(&self) -> Array1<f64> {
+    let [first, second] = self.model.sample();
+    if self.index == 0 {
+      first
+    } else {
+      second
+    }
+  }
+
+  fn n(&self) -> usize {
+    self.model.n()
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.model.m()
+  }
+}
+
+/// Normal jump sizes of the Merton model
+#[derive(Debug, Clone, Copy, Default)]
+struct NormalJumps {
+  mean: f64,
+  std: f64,
+}
+
+impl Distribution<f64> for NormalJumps {
+  fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+    self.mean + self.std * rng.sample::<f64, _>(StandardNormal)
+  }
+}
+
+impl ProcessDistribution for NormalJumps {}
+
+/// Reader of the parameters of one model, it rejects the names the model does not read
+struct Reader<'a> {
+  model: &'a str,
+  params: &'a Params,
+  read: RefCell<HashSet<&'static str>>,
+}
+
+impl<'a> Reader<'a> {
+  fn new(model: &'a str, params: &'a Params) -> Self {
+    Self {
+      model,
+      params,
+      read: RefCell::new(HashSet::new()),
+    }
+  }
+
+  fn optional(&self, name: &'static str) -> Option<f64> {
+    self.read.borrow_mut().insert(name);
+    self.params.get(name).copied()
+  }
+
+  fn required(&self, name: &'static str) -> Result<f64> {
+    match self.optional(name) {
+      Some(value) => Ok(value),
+      None => bail!("Model {} needs the parameter {}", self.model, name),
+    }
+  }
+
+  fn or(&self, name: &'static str, default: f64) -> f64 {
+    self.optional(name).unwrap_or(default)
+  }
+
+  fn count(&self, name: &'static str) -> Result<Option<usize>> {
+    match self.optional(name) {
+      Some(value) if value >= 0.0 && value.fract() == 0.0 => Ok(Some(value as usize)),
+      Some(value) => bail!(
+        "The parameter {} of model {} must be a non-negative integer, got {}",
+        name,
+        self.model,
+        value
+      ),
+      None => Ok(None),
+    }
+  }
+
+  /// Component of a two-dimensional model, the price by default
+  fn component(&self) -> Result<usize> {
+    match self.count("component")? {
+      None => Ok(0),
+      Some(index) if index < 2 => Ok(index),
+      Some(index) => bail!(
+        "Model {} has the components 0 and 1, got {}",
+        self.model,
+        index
+      ),
+    }
+  }
+
+  /// Number of steps, horizon and number of paths
+  fn grid(&self) -> Result<(usize, Option<f64>, Option<usize>)> {
+    let n = match self.count("n")? {
+      Some(n) if n > 0 => n,
+      _ => bail!("Model {} needs a positive number of steps n", self.model),
+    };
+    Ok((n, self.optional("t"), self.count("m")?))
+  }
+
+  fn finish(&self, model: Box<dyn Sampling<f64>>) -> Result<Box<dyn Sampling<f64>>> {
+    let read = self.read.borrow();
+    let mut unknown: Vec<&str> = self
+      .params
+      .keys()
+      .map(String::as_str)
+      .filter(|name| !read.contains(name))
+      .collect();
+    if !unknown.is_empty() {
+      unknown.sort_unstable();
+      bail!(
+        "Model {} has no parameters {}",
+        self.model,
+        unknown.join(", ")
+      );
+    }
+    Ok(model)
+  }
+}
+
+/// Models by name, each built from a parameter map into a boxed [`Sampling`], so a
+/// configuration file, a command line or a foreign caller can instantiate any model
+/// without compile-time wiring.
+///
+/// Every model reads the grid parameters `n` (required), `t` and `m`. The two-dimensional
+/// models (`heston`, `sabr`) sample the price, or the volatility with `component = 1`.
+/// Unknown parameter names are an error, so typos in a configuration do not silently
+/// fall back to defaults.
+#[derive(Clone)]
+pub struct ModelRegistry {
+  factories: BTreeMap<String, Factory>,
+}
+
+impl Default for ModelRegistry {
+  fn default() -> Self {
+    Self::builtin()
+  }
+}
+
+impl ModelRegistry {
+  /// Registry without models
+  pub fn empty() -> Self {
+    Self {
+      factories: BTreeMap::new(),
+    }
+  }
+
+  /// Registry of the built-in models
+  pub fn builtin() -> Self {
+    let mut registry = Self::empty();
+
+    registry.register("bm", |params| {
+      let p = Reader::new("bm", params);
+      let (n, t, m) = p.grid()?;
+      p.finish(Box::new(BM::new(&BM {
+        n,
+        t,
+        m,
+        ..Default::default()
+      })))
+    });
+    registry.register("cir", |params| {
+      let p = Reader::new("cir", params);
+      let (n, t, m) = p.grid()?;
+      p.finish(Box::new(CIR::new(&CIR {
+        theta: p.required("theta")?,
+        mu: p.required("mu")?,
+        sigma: p.required("sigma")?,
+        x0: p.optional("x0"),
+        use_sym: p.optional("use_sym").map(|flag| flag != 0.0),
+        n,
+        t,
+        m,
+      })))
+    });
+    registry.register("fbm", |params| {
+      let p = Reader::new("fbm", params);
+      let (n, t, m) = p.grid()?;
+      p.finish(Box::new(Fbm::new(&Fbm {
+        hurst: p.required("hurst")?,
+        n,
+        t,
+        m,
+        ..Default::default()
+      })))
+    });
+    registry.register("fou", |params| {
+      let p = Reader::new("fou", params);
+      let (n, t, m) = p.grid()?;
+      p.finish(Box::new(FOU::new(&FOU {
+        hurst: p.required("hurst")?,
+        mu: p.required("mu")?,
+        sigma: p.required("sigma")?,
+        theta: p.required("theta")?,
+        x0: p.optional("x0"),
+        n,
+        t,
+        m,
+        ..Default::default()
+      })))
+    });
+    registry.register("gbm", |params| {
+      let p = Reader::new("gbm", params);
+      let (n, t, m) = p.grid()?;
+      p.finish(Box::new(GBM::new(&GBM {
+        mu: p.required("mu")?,
+        sigma: p.required("sigma")?,
+        x0: p.optional("x0"),
+        n,
+        t,
+        m,
+        ..Default::default()
+      })))
+    });
+    registry.register("heston", |params| {
+      let p = Reader::new("heston", params);
+      let (n, t, m) = p.grid()?;
+      let model = Heston::new(&Heston {
+        s0: p.optional("s0"),
+        v0: p.optional("v0"),
+        kappa: p.required("kappa")?,
+        theta: p.required("theta")?,
+        sigma: p.required("sigma")?,
+        rho: p.required("rho")?,
+        mu: p.or("mu", 0.0),
+        use_sym: p.optional("use_sym").map(|flag| flag != 0.0),
+        n,
+        t,
+        m,
+        ..Default::default()
+      });
+      let index = p.component()?;
+      p.finish(Box::new(Component { model, index }))
+    });
+    registry.register("merton", |params| {
+      let p = Reader::new("merton", params);
+      let (n, t, m) = p.grid()?;
+      p.finish(Box::new(Merton::new(&Merton {
+        alpha: p.required("alpha")?,
+        sigma: p.required("sigma")?,
+        lambda: p.required("lambda")?,
+        theta: p.or("theta", 0.0),
+        x0: p.optional("x0"),
+        jump_distribution: NormalJumps {
+          mean: p.or("jump_mean", 0.0),
+          std: p.required("jump_std")?,
+        },
+        n,
+        t,
+        m,
+        ..Default::default()
+      })))
+    });
+    registry.register("nig", |params| {
+      let p = Reader::new("nig", params);
+      let (n, t, m) = p.grid()?;
+      p.finish(Box::new(NIG::new(&NIG {
+        theta: p.required("theta")?,
+        sigma: p.required("sigma")?,
+        kappa: p.required("kappa")?,
+        x0: p.optional("x0"),
+        n,
+        t,
+        m,
+      })))
+    });
+    registry.register("ou", |params| {
+      let p = Reader::new("ou", params);
+      let (n, t, m) = p.grid()?;
+      p.finish(Box::new(OU::new(&OU {
+        mu: p.required("mu")?,
+        sigma: p.required("sigma")?,
+        theta: p.required("theta")?,
+        x0: p.optional("x0"),
+        n,
+        t,
+        m,
+        ..Default::default()
+      })))
+    });
+    registry.register("sabr", |params| {
+      let p = Reader::new("sabr", params);
+      let (n, t, m) = p.grid()?;
+      let model = Sabr::new(&Sabr {
+        alpha: p.required("alpha")?,
+        beta: p.required("beta")?,
+        rho: p.required("rho")?,
+        f0: p.optional("f0"),
+        v0: p.optional("v0"),
+        n,
+        t,
+        m,
+        ..Default::default()
+      });
+      let index = p.component()?;
+      p.finish(Box::new(Component { model, index }))
+    });
+    registry.register("vasicek", |params| {
+      let p = Reader::new("vasicek", params);
+      let (n, t, m) = p.grid()?;
+      p.finish(Box::new(Vasicek::new(&Vasicek {
+        mu: p.required("mu")?,
+        sigma: p.required("sigma")?,
+        theta: p.optional("theta"),
+        x0: p.optional("x0"),
+        n,
+        t,
+        m,
+        ..Default::default()
+      })))
+    });
+    registry.register("vg", |params| {
+      let p = Reader::new("vg", params);
+      let (n, t, m) = p.grid()?;
+      p.finish(Box::new(VG::new(&VG {
+        mu: p.required("mu")?,
+        sigma: p.required("sigma")?,
+        nu: p.required("nu")?,
+        x0: p.optional("x0"),
+        n,
+        t,
+        m,
+      })))
+    });
+
+    registry
+  }
+
+  /// Register a model under the name, replacing the model of the same name
+  pub fn register<F>(&mut self, name: &str, factory: F)
+  where
+    F: Fn(&Params) -> Result<Box<dyn Sampling<f64>>> + Send + Sync + 'static,
+  {
+    self.factories.insert(name.to_string(), Arc::new(factory));
+  }
+
+  /// Model of the name built from the parameters
+  pub fn create(&self, name: &str, params: &Params) -> Result<Box<dyn Sampling<f64>>> {
+    match self.factories.get(name) {
+      Some(factory) => factory(params),
+      None => bail!(
+        "Unknown model {}, the registered models are {}",
+        name,
+        self.names().collect::<Vec<_>>().join(", ")
+      ),
+    }
+  }
+
+  pub fn contains(&self, name: &str) -> bool {
+    self.factories.contains_key(name)
+  }
+
+  /// Names of the registered models in alphabetical order
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.factories.keys().map(String::as_str)
+  }
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use ndarray::{Array1, ArrayView1};
+
+/// Time-dependent parameter of a simulator, evaluated at the left end of every time step
+#[derive(Clone)]
+pub enum Schedule {
+  /// One value per time step, aligned to the time grid (length n)
+  Steps(Array1<f64>),
+  /// Piecewise constant: `values[k]` until `times[k]`, the last value after the last time
+  Piecewise { times: Vec<f64>, values: Vec<f64> },
+  /// Curve t -> value
+  Curve(Arc<dyn Fn(f64) -> f64 + Send + Sync>),
+}
+
+impl Schedule {
+  #[must_use]
+  pub fn piecewise(times: ArrayView1<f64>, values: ArrayView1<f64>) -> Self {
+    assert_eq!(
+      times.len(),
+      values.len(),
+      "Piecewise schedule needs one value per time"
+    );
+    assert!(!values.is_empty(), "Piecewise schedule needs a value");
+    assert!(
+      times.windows(2).into_iter().all(|w| w[0] < w[1]),
+      "Piecewise schedule times must be increasing"
+    );
+
+    Self::Piecewise {
+      times: times.to_vec(),
+      values: values.to_vec(),
+    }
+  }
+
+  #[must_use]
+  pub fn curve(f: impl Fn(f64) -> f64 + Send + Sync + 'static) -> Self {
+    Self::Curve(Arc::new(f))
+  }
+
+  /// Seasonal curve a + b t + sum_k (c_k cos(2 pi k t / period) + d_k sin(2 pi k t / period))
+  /// with `harmonics[k - 1] = (c_k, d_k)`
+  #[must_use]
+  pub fn harmonic(level: f64, trend: f64, harmonics: Vec<(f64, f64)>, period: f64) -> Self {
+    assert!(period > 0.0, "Period must be positive");
+
+    Self::curve(move |t| {
+      let w = 2.0 * std::f64::consts::PI * t / period;
+      level
+        + trend * t
+        + harmonics
+          .iter()
+          .enumerate()
+          .map(|(k, (c, d))| {
+            let kw = (k + 1) as f64 * w;
+            c * kw.cos() + d * kw.sin()
+          })
+          .sum::<f64>()
+    })
+  }
+
+  /// Value at time t. Step schedules are only defined on a grid, see [`Self::value_on`]
+  /// and [`Self::at`].
+  pub fn value(&self, t: f64) -> f64 {
+    match self {
+      Self::Steps(_) => panic!("Step schedules are only defined on the time grid"),
+      Self::Piecewise { times, values } => {
+        let k = times.partition_point(|&ti| ti <= t);
+        values[k.min(values.len() - 1)]
+      }
+      Self::Curve(f) => f(t),
+    }
+  }
+
+  /// Value on the i-th step of a grid with step dt, a step schedule keeps its last value
+  /// after the grid
+  pub fn at(&self, i: usize, dt: f64) -> f64 {
+    match self {
+      Self::Steps(values) => values[i.min(values.len() - 1)],
+      _ => self.value(i as f64 * dt),
+    }
+  }
+
+  /// Value at time t of a grid with step dt, a step schedule holds the value of the step
+  /// that contains t
+  pub fn value_on(&self, t: f64, dt: f64) -> f64 {
+    match self {
+      // the tolerance keeps grid times on their own step
+      Self::Steps(_) => self.at((t / dt + 1e-9).floor().max(0.0) as usize, dt),
+      _ => self.value(t),
+    }
+  }
+
+  /// Values on the first n steps of a grid with step dt
+  pub fn on_grid(&self, n: usize, dt: f64) -> Array1<f64> {
+    if let Self::Steps(values) = self {
+      assert!(
+        values.len() >= n,
+        "Step schedule must have a value for every time step"
+      );
+    }
+
+    Array1::from_shape_fn(n, |i| self.at(i, dt))
+  }
+}
+
+/// Values of an optional schedule on the grid, the constant if there is none
+pub(crate) fn on_grid(
+  schedule: &Option<Schedule>,
+  constant: f64,
+  n: usize,
+  dt: f64,
+) -> Array1<f64> {
+  match schedule {
+    Some(schedule) => schedule.on_grid(n, dt),
+    None => Array1::from_elem(n, constant),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::array;
+
+  use super::*;
+
+  #[test]
+  fn step_schedule_is_read_on_its_grid() {
+    let steps = Schedule::Steps(array![1.0, 2.0, 3.0]);
+    assert_eq!(steps.at(1, 0.5), 2.0);
+    // the end of the grid keeps the last value
+    assert_eq!(steps.at(3, 0.5), 3.0);
+    assert_eq!(steps.value_on(0.5, 0.5), 2.0);
+    assert_eq!(steps.value_on(0.9, 0.5), 2.0);
+    assert_eq!(steps.value_on(10.0, 0.5), 3.0);
+  }
+
+  #[test]
+  fn piecewise_holds_values_until_the_times() {
+    let schedule = Schedule::piecewise(array![1.0, 2.0].view(), array![0.1, 0.2].view());
+    assert_eq!(schedule.value(0.5), 0.1);
+    assert_eq!(schedule.value(1.5), 0.2);
+    assert_eq!(schedule.value(3.0), 0.2);
+    assert_eq!(schedule.value_on(1.5, 0.25), 0.2);
+  }
+}
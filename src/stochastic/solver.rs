@@ -0,0 +1,4 @@
+pub mod fokker_planck;
+pub mod levy_area;
+pub mod milstein;
+pub mod tangent;
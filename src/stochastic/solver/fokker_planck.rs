@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use ndarray::{Array1, Array2, ArrayView1};
+use rand_distr::{Distribution, StandardNormal};
+use rayon::prelude::*;
+
+use crate::rng::{path_seed, thread_rng, with_seed};
+
+/// Coefficient mu(t, x) or sigma(t, x) of a one-dimensional diffusion
+pub type Coefficient = Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>;
+
+/// Fokker-Planck (Kolmogorov forward) equation of the transition density of the diffusion
+/// dX = mu(t, X) dt + sigma(t, X) dW
+/// dp/dt = -d(mu p)/dx + 1/2 d^2(sigma^2 p)/dx^2,
+/// solved on `nx` cells of [x_min, x_max] by conservative central finite volumes in space
+/// and Crank-Nicolson in time. The boundaries are reflecting (zero flux), so the mass is
+/// conserved; the interval must be wide enough for the density to vanish at its ends.
+#[derive(Clone)]
+pub struct FokkerPlanck {
+  pub drift: Coefficient,
+  pub diffusion: Coefficient,
+  pub x_min: f64,
+  pub x_max: f64,
+  /// Number of cells
+  pub nx: usize,
+}
+
+/// Density on the cell centers at every time step, one row per time
+#[derive(Debug, Clone)]
+pub struct DensityEvolution {
+  pub grid: Array1<f64>,
+  pub times: Array1<f64>,
+  pub density: Array2<f64>,
+}
+
+/// Solution of a tridiagonal system by the Thomas algorithm
+fn thomas(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64]) -> Vec<f64> {
+  let n = diag.len();
+  let mut c = vec![0.0; n];
+  let mut d = vec![0.0; n];
+  c[0] = upper[0] / diag[0];
+  d[0] = rhs[0] / diag[0];
+  for i in 1..n {
+    let m = diag[i] - lower[i] * c[i - 1];
+    c[i] = upper[i] / m;
+    d[i] = (rhs[i] - lower[i] * d[i - 1]) / m;
+  }
+
+  let mut x = vec![0.0; n];
+  x[n - 1] = d[n - 1];
+  for i in (0..n - 1).rev() {
+    x[i] = d[i] - c[i] * x[i + 1];
+  }
+  x
+}
+
+impl FokkerPlanck {
+  #[must_use]
+  pub fn new(
+    drift: impl Fn(f64, f64) -> f64 + Send + Sync + 'static,
+    diffusion: impl Fn(f64, f64) -> f64 + Send + Sync + 'static,
+    x_min: f64,
+    x_max: f64,
+    nx: usize,
+  ) -> Self {
+    assert!(x_max > x_min, "Interval must not be empty");
+    assert!(nx >= 3, "At least three cells are needed");
+
+    Self {
+      drift: Arc::new(drift),
+      diffusion: Arc::new(diffusion),
+      x_min,
+      x_max,
+      nx,
+    }
+  }
+
+  fn dx(&self) -> f64 {
+    (self.x_max - self.x_min) / self.nx as f64
+  }
+
+  /// Centers of the cells
+  pub fn grid(&self) -> Array1<f64> {
+    let dx = self.dx();
+    Array1::from_shape_fn(self.nx, |i| self.x_min + (i as f64 + 0.5) * dx)
+  }
+
+  /// Density of a point mass at x0, split linearly between the two nearest cells
+  pub fn point_mass(&self, x0: f64) -> Array1<f64> {
+    let dx = self.dx();
+    let position = ((x0 - self.x_min) / dx - 0.5).clamp(0.0, (self.nx - 1) as f64);
+    let i = (position.floor() as usize).min(self.nx - 2);
+    let w = position - i as f64;
+    let mut p = Array1::zeros(self.nx);
+    p[i] = (1.0 - w) / dx;
+    p[i + 1] = w / dx;
+    p
+  }
+
+  /// Tridiagonal generator L(t) of the semi-discrete equation dp/dt = L p
+  fn operator(&self, t: f64) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let dx = self.dx();
+    let grid = self.grid();
+    let d = grid.mapv(|x| (self.diffusion)(t, x).powi(2));
+    // the flux through the right face of the cell i is a_i p_i + b_i p_(i+1)
+    let (a, b): (Vec<f64>, Vec<f64>) = (0..self.nx - 1)
+      .map(|i| {
+        let mu = (self.drift)(t, self.x_min + (i + 1) as f64 * dx);
+        (0.5 * mu + 0.5 * d[i] / dx, 0.5 * mu - 0.5 * d[i + 1] / dx)
+      })
+      .unzip();
+
+    let mut lower = vec![0.0; self.nx];
+    let mut diag = vec![0.0; self.nx];
+    let mut upper = vec![0.0; self.nx];
+    for i in 0..self.nx {
+      if i + 1 < self.nx {
+        diag[i] -= a[i] / dx;
+        upper[i] = -b[i] / dx;
+      }
+      if i > 0 {
+        lower[i] = a[i - 1] / dx;
+        diag[i] += b[i - 1] / dx;
+      }
+    }
+
+    (lower, diag, upper)
+  }
+
+  /// Evolve the initial density over `nt` steps up to t
+  pub fn solve(&self, initial: ArrayView1<f64>, t: f64, nt: usize) -> DensityEvolution {
+    assert_eq!(
+      initial.len(),
+      self.nx,
+      "Initial density must be given on the grid"
+    );
+    assert!(nt > 0, "At least one time step is needed");
+    let dt = t / nt as f64;
+    let mut density = Array2::zeros((nt + 1, self.nx));
+    density.row_mut(0).assign(&initial);
+    let mut p = initial.to_vec();
+
+    for k in 0..nt {
+      let (l0, d0, u0) = self.operator(k as f64 * dt);
+      let rhs = (0..self.nx)
+        .map(|i| {
+          let mut v = p[i] + 0.5 * dt * d0[i] * p[i];
+          if i > 0 {
+            v += 0.5 * dt * l0[i] * p[i - 1];
+          }
+          if i + 1 < self.nx {
+            v += 0.5 * dt * u0[i] * p[i + 1];
+          }
+          v
+        })
+        .collect::<Vec<_>>();
+
+      let (l1, d1, u1) = self.operator((k + 1) as f64 * dt);
+      let lower = l1.iter().map(|v| -0.5 * dt * v).collect::<Vec<_>>();
+      let diag = d1.iter().map(|v| 1.0 - 0.5 * dt * v).collect::<Vec<_>>();
+      let upper = u1.iter().map(|v| -0.5 * dt * v).collect::<Vec<_>>();
+      p = thomas(&lower, &diag, &upper, &rhs);
+      density.row_mut(k + 1).assign(&Array1::from(p.clone()));
+    }
+
+    DensityEvolution {
+      grid: self.grid(),
+      times: Array1::linspace(0.0, t, nt + 1),
+      density,
+    }
+  }
+
+  /// Terminal values of `paths` Euler paths from x0 over `nt` steps up to t,
+  /// for the comparison with the density
+  pub fn monte_carlo(&self, x0: f64, t: f64, nt: usize, paths: usize, seed: u64) -> Array1<f64> {
+    let dt = t / nt as f64;
+
+    Array1::from(
+      (0..paths)
+        .into_par_iter()
+        .map(|i| {
+          with_seed(path_seed(seed, i as u64), || {
+            let mut rng = thread_rng();
+            let mut x = x0;
+            for k in 0..nt {
+              let s = k as f64 * dt;
+              let z: f64 = StandardNormal.sample(&mut rng);
+              x += (self.drift)(s, x) * dt + (self.diffusion)(s, x) * dt.sqrt() * z;
+            }
+            x
+          })
+        })
+        .collect::<Vec<_>>(),
+    )
+  }
+}
+
+impl DensityEvolution {
+  fn dx(&self) -> f64 {
+    self.grid[1] - self.grid[0]
+  }
+
+  /// Total mass of the density at the step k
+  pub fn mass(&self, k: usize) -> f64 {
+    self.density.row(k).sum() * self.dx()
+  }
+
+  pub fn mean(&self, k: usize) -> f64 {
+    (&self.density.row(k) * &self.grid).sum() * self.dx()
+  }
+
+  pub fn variance(&self, k: usize) -> f64 {
+    let mean = self.mean(k);
+    (&self.density.row(k) * &self.grid.mapv(|x| (x - mean).powi(2))).sum() * self.dx()
+  }
+
+  /// Histogram density of samples on the cells of the grid, samples outside are dropped
+  pub fn histogram(&self, samples: ArrayView1<f64>) -> Array1<f64> {
+    let dx = self.dx();
+    let start = self.grid[0] - 0.5 * dx;
+    let mut counts = Array1::<f64>::zeros(self.grid.len());
+    for &x in samples {
+      let i = ((x - start) / dx).floor();
+      if i >= 0.0 && (i as usize) < self.grid.len() {
+        counts[i as usize] += 1.0;
+      }
+    }
+    counts / (samples.len() as f64 * dx)
+  }
+
+  /// L1 distance between the density at the last step and the histogram of samples
+  pub fn l1_error(&self, samples: ArrayView1<f64>) -> f64 {
+    let last = self.density.row(self.density.nrows() - 1);
+    (&last - &self.histogram(samples)).mapv(f64::abs).sum() * self.dx()
+  }
+}
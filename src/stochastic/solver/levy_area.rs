@@ -0,0 +1,51 @@
+use std::f64::consts::PI;
+
+use ndarray::{Array1, Array2, ArrayView1};
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+/// Iterated Itô integrals I_(j1, j2) = int_0^h int_0^s dW_j1(u) dW_j2(s) of an m-dimensional
+/// Brownian motion over a step h given its increments dw, from the Kloeden-Platen-Wright
+/// truncated Fourier expansion of the Lévy areas with `terms` terms and the tail correction.
+/// The truncation error is of the order h / terms, so terms ~ 1 / h keeps the Milstein order.
+/// https://doi.org/10.1080/07362999208809288 (Kloeden, Platen, Wright)
+pub fn iterated_integrals<R: Rng + ?Sized>(
+  dw: ArrayView1<f64>,
+  h: f64,
+  terms: usize,
+  rng: &mut R,
+) -> Array2<f64> {
+  let m = dw.len();
+  let xi = dw.mapv(|w| w / h.sqrt());
+  let mut zeta = Array2::<f64>::zeros((m, terms));
+  let mut eta = Array2::<f64>::zeros((m, terms));
+  zeta.mapv_inplace(|_| rng.sample(StandardNormal));
+  eta.mapv_inplace(|_| rng.sample(StandardNormal));
+  let mu = Array1::<f64>::from_shape_fn(m, |_| rng.sample(StandardNormal));
+
+  let rho =
+    1.0 / 12.0 - (1..=terms).map(|r| 1.0 / (r as f64).powi(2)).sum::<f64>() / (2.0 * PI.powi(2));
+  let tail = rho.max(0.0).sqrt();
+
+  Array2::from_shape_fn((m, m), |(j1, j2)| {
+    if j1 == j2 {
+      return 0.5 * (dw[j1].powi(2) - h);
+    }
+
+    let series = (0..terms)
+      .map(|r| {
+        (zeta[[j1, r]] * (2f64.sqrt() * xi[j2] + eta[[j2, r]])
+          - zeta[[j2, r]] * (2f64.sqrt() * xi[j1] + eta[[j1, r]]))
+          / (r + 1) as f64
+      })
+      .sum::<f64>();
+
+    h * (0.5 * xi[j1] * xi[j2] + tail * (mu[j1] * xi[j2] - mu[j2] * xi[j1]))
+      + h / (2.0 * PI) * series
+  })
+}
+
+/// Lévy areas A_(j1, j2) = (I_(j1, j2) - I_(j2, j1)) / 2 of the iterated integrals
+pub fn levy_area(integrals: &Array2<f64>) -> Array2<f64> {
+  0.5 * (integrals - &integrals.t())
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use ndarray::{array, Array1, Array2, ArrayView1};
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+use crate::rng::thread_rng;
+
+use super::levy_area::iterated_integrals;
+
+/// Drift a(t, x) of an SDE
+pub type Drift = Arc<dyn Fn(f64, ArrayView1<f64>) -> Array1<f64> + Send + Sync>;
+/// Diffusion matrix b(t, x) of an SDE, one row per state and one column per Brownian motion
+pub type Diffusion = Arc<dyn Fn(f64, ArrayView1<f64>) -> Array2<f64> + Send + Sync>;
+
+/// Multi-dimensional Itô SDE dX(t) = a(t, X(t)) dt + b(t, X(t)) dW(t)
+/// driven by independent Brownian motions
+#[derive(Clone)]
+pub struct Sde {
+  pub drift: Drift,
+  pub diffusion: Diffusion,
+}
+
+impl Sde {
+  #[must_use]
+  pub fn new(
+    drift: impl Fn(f64, ArrayView1<f64>) -> Array1<f64> + Send + Sync + 'static,
+    diffusion: impl Fn(f64, ArrayView1<f64>) -> Array2<f64> + Send + Sync + 'static,
+  ) -> Self {
+    Self {
+      drift: Arc::new(drift),
+      diffusion: Arc::new(diffusion),
+    }
+  }
+
+  /// Heston model of (S, v), the variance is truncated at zero in the coefficients
+  #[must_use]
+  pub fn heston(mu: f64, kappa: f64, theta: f64, sigma: f64, rho: f64) -> Self {
+    Self::new(
+      move |_, x| array![mu * x[0], kappa * (theta - x[1])],
+      move |_, x| {
+        let vol = x[1].max(0.0).sqrt();
+        array![
+          [vol * x[0], 0.0],
+          [sigma * vol * rho, sigma * vol * (1.0 - rho.powi(2)).sqrt()]
+        ]
+      },
+    )
+  }
+
+  /// SABR model of (F, alpha) with the volatility of volatility nu
+  #[must_use]
+  pub fn sabr(beta: f64, nu: f64, rho: f64) -> Self {
+    Self::new(
+      |_, _| Array1::zeros(2),
+      move |_, x| {
+        let level = x[1] * x[0].max(0.0).powf(beta);
+        array![
+          [level, 0.0],
+          [nu * x[1] * rho, nu * x[1] * (1.0 - rho.powi(2)).sqrt()]
+        ]
+      },
+    )
+  }
+}
+
+/// Milstein scheme for multi-dimensional SDEs, strong order 1.
+/// The derivative terms L^j1 b^k,j2 = sum_l b^l,j1 d_l b^k,j2 are central differences
+/// of the diffusion along its own columns, and the iterated integrals of distinct
+/// Brownian motions come from the Lévy area expansion unless the noise is commutative.
+#[derive(Clone)]
+pub struct Milstein {
+  pub sde: Sde,
+  pub x0: Array1<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+  /// Terms of the Lévy area expansion (default ceil(1 / dt))
+  pub area_terms: Option<usize>,
+  /// Commutative noise (L^j1 b^j2 = L^j2 b^j1), the Lévy areas cancel and are not simulated
+  pub commutative: bool,
+}
+
+impl Milstein {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(!params.x0.is_empty(), "x0 must not be empty");
+
+    params.clone()
+  }
+
+  /// Path of the state, one row per component
+  pub fn sample(&self) -> Array2<f64> {
+    let d = self.x0.len();
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let terms = self
+      .area_terms
+      .unwrap_or_else(|| (1.0 / dt).ceil() as usize)
+      .max(1);
+    let mut rng = thread_rng();
+
+    let mut x = Array2::<f64>::zeros((d, self.n + 1));
+    x.column_mut(0).assign(&self.x0);
+
+    for i in 1..=self.n {
+      let s = (i - 1) as f64 * dt;
+      let xi = x.column(i - 1).to_owned();
+      let b = (self.sde.diffusion)(s, xi.view());
+      let noise = b.ncols();
+      let dw =
+        Array1::<f64>::from_shape_fn(noise, |_| rng.sample::<f64, _>(StandardNormal)) * dt.sqrt();
+
+      let integrals = if self.commutative {
+        // the symmetric part is all that remains when the areas cancel
+        Array2::from_shape_fn((noise, noise), |(j1, j2)| {
+          0.5 * (dw[j1] * dw[j2] - if j1 == j2 { dt } else { 0.0 })
+        })
+      } else {
+        iterated_integrals(dw.view(), dt, terms, &mut rng)
+      };
+
+      let mut next = &xi + &((self.sde.drift)(s, xi.view()) * dt) + b.dot(&dw);
+
+      for j1 in 0..noise {
+        let direction = b.column(j1);
+        let scale = 1e-6 * (1.0 + xi.iter().fold(0.0f64, |a, v| a.max(v.abs())));
+        let up = (self.sde.diffusion)(s, (&xi + &(&direction * scale)).view());
+        let down = (self.sde.diffusion)(s, (&xi - &(&direction * scale)).view());
+        let derivative = (up - down) / (2.0 * scale);
+
+        for j2 in 0..noise {
+          next.scaled_add(integrals[[j1, j2]], &derivative.column(j2));
+        }
+      }
+
+      x.column_mut(i).assign(&next);
+    }
+
+    x
+  }
+}
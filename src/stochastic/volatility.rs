@@ -1,8 +1,11 @@
 pub mod bergomi;
+pub mod diagnostics;
+pub mod double_heston;
 pub mod fheston;
 pub mod heston;
 pub mod rbergomi;
 pub mod sabr;
+pub mod wishart;
 
 #[derive(Debug, Clone, Copy, Default)]
 pub enum HestonPow {
@@ -10,3 +13,14 @@ pub enum HestonPow {
   Sqrt,
   ThreeHalves,
 }
+
+/// Discretization scheme of the Heston simulator
+#[derive(Debug, Clone, Copy, Default)]
+pub enum HestonScheme {
+  /// Euler-Maruyama scheme
+  #[default]
+  Euler,
+  /// Broadie-Kaya exact scheme with the Glasserman-Kim gamma expansion
+  /// of the integrated variance, it is only available for the square root variance
+  BroadieKaya,
+}
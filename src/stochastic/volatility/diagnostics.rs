@@ -0,0 +1,244 @@
+/// Parameters of a Heston-type variance process
+/// dv(t) = kappa(theta - v(t))dt + sigma * sqrt(v(t))dW(t)
+/// with correlation rho to the price driver.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct HestonParams {
+  /// Initial variance
+  pub v0: f64,
+  /// Mean reversion rate
+  pub kappa: f64,
+  /// Long-run variance
+  pub theta: f64,
+  /// Volatility of variance
+  pub sigma: f64,
+  /// Correlation between the price and the variance
+  pub rho: f64,
+  /// Time horizon (maturity)
+  pub t: f64,
+}
+
+impl HestonParams {
+  /// Feller condition: 2 * kappa * theta >= sigma^2
+  pub fn feller_condition(&self) -> bool {
+    self.feller_ratio() >= 1.0
+  }
+
+  /// Feller ratio: 2 * kappa * theta / sigma^2
+  pub fn feller_ratio(&self) -> f64 {
+    2.0 * self.kappa * self.theta / self.sigma.powi(2)
+  }
+
+  /// Explosion time of the moment E[S(t)^omega]
+  /// https://doi.org/10.1007/s00780-006-0011-7 (Andersen-Piterbarg)
+  ///
+  /// Returns `f64::INFINITY` if the moment is finite for every maturity.
+  pub fn explosion_time(&self, omega: f64) -> f64 {
+    // The moments of order 0 <= omega <= 1 of the martingale S are finite
+    if omega * (omega - 1.0) <= 0.0 {
+      return f64::INFINITY;
+    }
+
+    // b = rho sigma omega - kappa of Andersen-Piterbarg
+    let b = self.rho * self.sigma * omega - self.kappa;
+    let d = b.powi(2) - self.sigma.powi(2) * omega * (omega - 1.0);
+
+    if d >= 0.0 {
+      if b <= 0.0 {
+        return f64::INFINITY;
+      }
+
+      let d = d.sqrt();
+      ((b + d) / (b - d)).ln() / d
+    } else {
+      let gamma = (-d).sqrt();
+      2.0 / gamma * gamma.atan2(b)
+    }
+  }
+
+  /// Critical (upper) moment: the largest omega > 1 for which E[S(t)^omega] is finite
+  /// at the horizon `t`. Found by bisection on the explosion time.
+  ///
+  /// Returns `f64::INFINITY` if no moment explodes before `t`.
+  pub fn critical_moment(&self) -> f64 {
+    let mut lo = 1.0;
+    let mut hi = 2.0;
+
+    while self.explosion_time(hi) > self.t {
+      lo = hi;
+      hi *= 2.0;
+
+      if hi > 1e6 {
+        return f64::INFINITY;
+      }
+    }
+
+    for _ in 0..100 {
+      let mid = 0.5 * (lo + hi);
+
+      if self.explosion_time(mid) > self.t {
+        lo = mid;
+      } else {
+        hi = mid;
+      }
+
+      if hi - lo < 1e-10 {
+        break;
+      }
+    }
+
+    lo
+  }
+
+  /// Asymptotic decay rate of the characteristic function |phi(u)| ~ exp(-c * u)
+  /// https://doi.org/10.21314/JCF.2007.163 (Lord-Kahl)
+  pub fn cf_decay_rate(&self) -> f64 {
+    (1.0 - self.rho.powi(2)).sqrt() / self.sigma * (self.v0 + self.kappa * self.theta * self.t)
+  }
+}
+
+/// Diagnostics of a Heston-type model.
+#[derive(Debug, Clone, Copy)]
+pub struct Diagnostics {
+  /// Whether the Feller condition holds (variance stays strictly positive)
+  pub feller_condition: bool,
+  /// Feller ratio: 2 * kappa * theta / sigma^2
+  pub feller_ratio: f64,
+  /// Critical (upper) moment at the horizon
+  pub critical_moment: f64,
+  /// Explosion time of the second moment E[S(t)^2]
+  pub explosion_time: f64,
+  /// Suggested damping factor for Carr-Madan style Fourier pricing
+  pub damping: f64,
+  /// Suggested upper bound of the Fourier integration
+  pub integration_upper_bound: f64,
+}
+
+/// Diagnostics for Heston-type models (Heston, Bates, rough Heston).
+pub trait HestonDiagnostics {
+  /// Variance process parameters of the model
+  fn heston_params(&self) -> HestonParams;
+
+  /// Report Feller condition, moment explosion and suggested integration settings
+  fn diagnostics(&self) -> Diagnostics {
+    self.diagnostics_with_tolerance(1e-8)
+  }
+
+  /// Same as `diagnostics`, the integration bound is chosen so that the
+  /// characteristic function decays below `tol`
+  fn diagnostics_with_tolerance(&self, tol: f64) -> Diagnostics {
+    let params = self.heston_params();
+    let critical_moment = params.critical_moment();
+    let decay = params.cf_decay_rate();
+
+    Diagnostics {
+      feller_condition: params.feller_condition(),
+      feller_ratio: params.feller_ratio(),
+      critical_moment,
+      explosion_time: params.explosion_time(2.0),
+      damping: ((critical_moment - 1.0) / 2.0).min(0.75),
+      integration_upper_bound: if decay > 0.0 {
+        -tol.ln() / decay
+      } else {
+        f64::INFINITY
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use approx::assert_relative_eq;
+
+  use super::*;
+
+  fn params(t: f64) -> HestonParams {
+    HestonParams {
+      v0: 0.04,
+      kappa: 1.5,
+      theta: 0.04,
+      sigma: 0.8,
+      rho: -0.7,
+      t,
+    }
+  }
+
+  #[test]
+  fn feller() {
+    let p = params(1.0);
+    assert!(!p.feller_condition());
+    assert_relative_eq!(p.feller_ratio(), 0.1875, epsilon = 1e-12);
+  }
+
+  /// Blow-up time of the Riccati equation of the moment E[S(t)^omega],
+  /// B' = sigma^2 B^2 / 2 + (rho sigma omega - kappa) B + omega (omega - 1) / 2, B(0) = 0,
+  /// integrated by RK4
+  fn riccati_blow_up(p: &HestonParams, omega: f64) -> f64 {
+    let f = |b: f64| {
+      0.5 * p.sigma.powi(2) * b * b
+        + (p.rho * p.sigma * omega - p.kappa) * b
+        + 0.5 * omega * (omega - 1.0)
+    };
+    let h = 1e-5;
+    let (mut t, mut b) = (0.0, 0.0f64);
+    while b.is_finite() && b < 1e9 && t < 50.0 {
+      let k1 = f(b);
+      let k2 = f(b + 0.5 * h * k1);
+      let k3 = f(b + 0.5 * h * k2);
+      let k4 = f(b + h * k3);
+      b += h / 6.0 * (k1 + 2.0 * k2 + 2.0 * k3 + k4);
+      t += h;
+    }
+    t
+  }
+
+  #[test]
+  fn explosion_time_matches_riccati_blow_up() {
+    let cases = [
+      // D < 0
+      (params(1.0), 10.0),
+      (params(1.0), 8.0),
+      // D >= 0 with b > 0
+      (
+        HestonParams {
+          kappa: 0.1,
+          sigma: 1.0,
+          rho: 0.95,
+          ..params(1.0)
+        },
+        2.0,
+      ),
+    ];
+    for (p, omega) in cases {
+      assert_relative_eq!(
+        p.explosion_time(omega),
+        riccati_blow_up(&p, omega),
+        max_relative = 1e-3
+      );
+    }
+    assert_relative_eq!(params(1.0).explosion_time(10.0), 2.074, epsilon = 1e-3);
+
+    // D >= 0 with b < 0, the moment stays finite
+    assert!(params(1.0).explosion_time(3.0).is_infinite());
+    assert!(riccati_blow_up(&params(1.0), 3.0) >= 50.0);
+  }
+
+  #[test]
+  fn critical_moment_explodes_at_the_horizon() {
+    for t in [0.5, 1.0, 5.0] {
+      let p = params(t);
+      let omega = p.critical_moment();
+      assert!(omega > 1.0);
+      assert_relative_eq!(riccati_blow_up(&p, omega), t, max_relative = 1e-3);
+    }
+  }
+
+  #[test]
+  fn no_explosion_without_vol_of_vol_feedback() {
+    let p = HestonParams {
+      sigma: 1e-3,
+      rho: 0.0,
+      ..params(1.0)
+    };
+    assert_eq!(p.explosion_time(1.5), f64::INFINITY);
+  }
+}
@@ -3,8 +3,11 @@ use ndarray_rand::RandomExt;
 use rand_distr::Normal;
 use statrs::function::gamma::gamma;
 
+use crate::rng::thread_rng;
 use crate::stochastic::Sampling;
 
+use super::diagnostics::{HestonDiagnostics, HestonParams};
+
 #[derive(Default)]
 pub struct RoughHeston {
   pub v0: Option<f64>,
@@ -40,7 +43,11 @@ impl RoughHeston {
 impl Sampling<f64> for RoughHeston {
   fn sample(&self) -> ndarray::Array1<f64> {
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
-    let gn = Array1::random(self.n, Normal::new(0.0, dt.sqrt()).unwrap());
+    let gn = Array1::random_using(
+      self.n,
+      Normal::new(0.0, dt.sqrt()).unwrap(),
+      &mut thread_rng(),
+    );
     let mut yt = Array1::<f64>::zeros(self.n + 1);
     let mut zt = Array1::<f64>::zeros(self.n + 1);
     let mut v2 = Array1::zeros(self.n + 1);
@@ -78,7 +85,22 @@ impl Sampling<f64> for RoughHeston {
   }
 }
 
-#[cfg(test)]
+impl HestonDiagnostics for RoughHeston {
+  /// Classical Heston counterpart of the rough model (uncorrelated, v0 is a volatility).
+  /// The moment explosion of the rough model happens no later than this bound.
+  fn heston_params(&self) -> HestonParams {
+    HestonParams {
+      v0: self.v0.unwrap_or(1.0).powi(2),
+      kappa: self.kappa,
+      theta: self.theta,
+      sigma: self.nu,
+      rho: 0.0,
+      t: self.t.unwrap_or(1.0),
+    }
+  }
+}
+
+#[cfg(all(test, feature = "viz"))]
 mod tests {
   use plotly::{common::Line, Plot, Scatter};
 
@@ -1,8 +1,26 @@
+use std::f64::consts::PI;
+
 use ndarray::Array1;
+use rand::Rng;
+use rand_distr::{Distribution, Exp1, Gamma, Poisson, StandardNormal};
+use statrs::function::gamma::ln_gamma;
+
+use crate::quant::curve::YieldCurve;
+use crate::rng::thread_rng;
+use crate::stochastic::ensemble::InitialLaw;
+use crate::stochastic::{
+  noise::cgns::CGNS,
+  schedule::{on_grid, Schedule},
+  Sampling2D,
+};
 
-use crate::stochastic::{noise::cgns::CGNS, Sampling2D};
+use super::{
+  diagnostics::{HestonDiagnostics, HestonParams},
+  HestonPow, HestonScheme,
+};
 
-use super::HestonPow;
+/// Number of explicit terms in the gamma expansion of the integrated variance
+const GAMMA_EXPANSION_TERMS: usize = 10;
 
 #[derive(Default)]
 
@@ -31,10 +49,18 @@ pub struct Heston {
   pub pow: HestonPow,
   /// Use the symmetric method for the variance to avoid negative values
   pub use_sym: Option<bool>,
+  /// Discretization scheme
+  pub scheme: HestonScheme,
   /// Number of paths for multithreading
   pub m: Option<usize>,
   /// Noise generator
   pub cgns: CGNS,
+  /// Time-dependent drift, overrides mu
+  pub mu_t: Option<Schedule>,
+  /// Time-dependent long-run variance, overrides theta
+  pub theta_t: Option<Schedule>,
+  /// Time-dependent volatility of volatility, overrides sigma
+  pub sigma_t: Option<Schedule>,
 }
 
 impl Heston {
@@ -59,28 +85,65 @@ impl Heston {
       t: params.t,
       pow: params.pow,
       use_sym: params.use_sym,
+      scheme: params.scheme,
       m: params.m,
       cgns,
+      mu_t: params.mu_t.clone(),
+      theta_t: params.theta_t.clone(),
+      sigma_t: params.sigma_t.clone(),
+    }
+  }
+
+  /// Heston model under a discount curve and an optional dividend yield curve instead
+  /// of a flat drift, the expected price at every grid point is the forward of the curves.
+  /// Only the Euler scheme supports it.
+  #[must_use]
+  pub fn with_curves(params: &Self, rates: &YieldCurve, dividends: Option<&YieldCurve>) -> Self {
+    Self {
+      mu_t: Some(rates.drift_schedule(dividends, params.n, params.t.unwrap_or(1.0))),
+      ..Self::new(params)
     }
   }
+
+  /// Copy of the model started from the variance v0, for ensembles of initial variances
+  #[must_use]
+  pub fn with_initial_variance(&self, v0: f64) -> Self {
+    Self::new(&Self {
+      v0: Some(v0),
+      ..Self::new(self)
+    })
+  }
+
+  /// Stationary gamma law of the variance, to draw an ensemble of initial variances
+  pub fn stationary_variance_law(&self) -> InitialLaw {
+    assert!(
+      matches!(self.pow, HestonPow::Sqrt),
+      "The stationary law is known for the square root variance"
+    );
+    InitialLaw::cir_stationary(self.kappa, self.theta, self.sigma)
+  }
 }
 
-impl Sampling2D<f64> for Heston {
-  fn sample(&self) -> [Array1<f64>; 2] {
+impl Heston {
+  fn sample_euler(&self) -> [Array1<f64>; 2] {
     let [cgn1, cgn2] = self.cgns.sample();
     let dt = self.t.unwrap_or(1.0) / self.n as f64;
 
     let mut s = Array1::<f64>::zeros(self.n + 1);
     let mut v = Array1::<f64>::zeros(self.n + 1);
 
+    let mu = on_grid(&self.mu_t, self.mu, self.n, dt);
+    let theta = on_grid(&self.theta_t, self.theta, self.n, dt);
+    let sigma = on_grid(&self.sigma_t, self.sigma, self.n, dt);
+
     s[0] = self.s0.unwrap_or(0.0);
     v[0] = self.v0.unwrap_or(0.0);
 
     for i in 1..=self.n {
-      s[i] = s[i - 1] + self.mu * s[i - 1] * dt + s[i - 1] * v[i - 1].sqrt() * cgn1[i - 1];
+      s[i] = s[i - 1] + mu[i - 1] * s[i - 1] * dt + s[i - 1] * v[i - 1].sqrt() * cgn1[i - 1];
 
-      let dv = self.kappa * (self.theta - v[i - 1]) * dt
-        + self.sigma
+      let dv = self.kappa * (theta[i - 1] - v[i - 1]) * dt
+        + sigma[i - 1]
           * v[i - 1].powf(match self.pow {
             HestonPow::Sqrt => 0.5,
             HestonPow::ThreeHalves => 1.5,
@@ -96,6 +159,71 @@ impl Sampling2D<f64> for Heston {
     [s, v]
   }
 
+  /// Exact simulation on the time grid.
+  /// The variance is sampled from its noncentral chi-squared transition and the
+  /// integrated variance conditional on the endpoints by the gamma expansion of
+  /// Glasserman and Kim, with the tail of the series matched by a single gamma variable.
+  /// https://doi.org/10.1287/opre.1050.0247
+  /// https://doi.org/10.1007/s00780-009-0115-y
+  fn sample_broadie_kaya(&self) -> [Array1<f64>; 2] {
+    assert!(
+      matches!(self.pow, HestonPow::Sqrt),
+      "Broadie-Kaya scheme requires the square root variance"
+    );
+    assert!(
+      self.mu_t.is_none() && self.theta_t.is_none() && self.sigma_t.is_none(),
+      "Broadie-Kaya scheme requires constant parameters"
+    );
+
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let expansion = GammaExpansion::new(self.kappa, self.theta, self.sigma, dt);
+    let mut rng = thread_rng();
+
+    let mut s = Array1::<f64>::zeros(self.n + 1);
+    let mut v = Array1::<f64>::zeros(self.n + 1);
+
+    s[0] = self.s0.unwrap_or(0.0);
+    v[0] = self.v0.unwrap_or(0.0);
+
+    let decay = (-self.kappa * dt).exp();
+    let c = self.sigma.powi(2) * (1.0 - decay) / (4.0 * self.kappa);
+
+    for i in 1..=self.n {
+      let lambda = v[i - 1] * decay / c;
+      let k = if lambda > 0.0 {
+        Poisson::new(0.5 * lambda).unwrap().sample(&mut rng)
+      } else {
+        0.0
+      };
+      v[i] = c
+        * 2.0
+        * Gamma::new(0.5 * expansion.delta + k, 1.0)
+          .unwrap()
+          .sample(&mut rng);
+
+      let iv = expansion.sample(v[i - 1], v[i], &mut rng);
+      let z: f64 = rng.sample(StandardNormal);
+
+      s[i] = s[i - 1]
+        * (self.mu * dt - 0.5 * iv
+          + self.rho / self.sigma
+            * (v[i] - v[i - 1] - self.kappa * self.theta * dt + self.kappa * iv)
+          + (1.0 - self.rho.powi(2)).sqrt() * iv.sqrt() * z)
+          .exp();
+    }
+
+    [s, v]
+  }
+}
+
+impl Sampling2D<f64> for Heston {
+  fn sample(&self) -> [Array1<f64>; 2] {
+    match self.scheme {
+      HestonScheme::Euler => self.sample_euler(),
+      HestonScheme::BroadieKaya => self.sample_broadie_kaya(),
+    }
+  }
+
   fn n(&self) -> usize {
     self.n
   }
@@ -105,13 +233,220 @@ impl Sampling2D<f64> for Heston {
   }
 }
 
+/// Gamma expansion of the integrated variance over a step of length dt
+/// conditional on the variance at both ends of the step
+struct GammaExpansion {
+  kappa: f64,
+  sigma: f64,
+  dt: f64,
+  delta: f64,
+  /// 1 / gamma_n for the explicit terms
+  inv_gamma: Vec<f64>,
+  /// lambda_n for the explicit terms
+  lambda: Vec<f64>,
+  /// Tail sums of 1 / gamma_n, 1 / gamma_n^2, lambda_n / gamma_n and lambda_n / gamma_n^2
+  tail: [f64; 4],
+}
+
+impl GammaExpansion {
+  fn new(kappa: f64, theta: f64, sigma: f64, dt: f64) -> Self {
+    let gamma_n = |n: f64| {
+      (kappa.powi(2) * dt.powi(2) + 4.0 * PI.powi(2) * n.powi(2))
+        / (2.0 * sigma.powi(2) * dt.powi(2))
+    };
+    let lambda_n = |n: f64| {
+      16.0 * PI.powi(2) * n.powi(2)
+        / (sigma.powi(2) * dt * (kappa.powi(2) * dt.powi(2) + 4.0 * PI.powi(2) * n.powi(2)))
+    };
+
+    let terms = 1..=GAMMA_EXPANSION_TERMS;
+    let inv_gamma = terms.clone().map(|n| 1.0 / gamma_n(n as f64)).collect();
+    let lambda = terms.map(|n| lambda_n(n as f64)).collect();
+
+    let mut tail = [0.0; 4];
+    for n in GAMMA_EXPANSION_TERMS + 1..=GAMMA_EXPANSION_TERMS + 10_000 {
+      let (g, l) = (gamma_n(n as f64), lambda_n(n as f64));
+      tail[0] += 1.0 / g;
+      tail[1] += 1.0 / g.powi(2);
+      tail[2] += l / g;
+      tail[3] += l / g.powi(2);
+    }
+
+    Self {
+      kappa,
+      sigma,
+      dt,
+      delta: 4.0 * kappa * theta / sigma.powi(2),
+      inv_gamma,
+      lambda,
+      tail,
+    }
+  }
+
+  /// Gamma variable with the given mean and variance
+  fn moment_matched<R: Rng>(mean: f64, var: f64, rng: &mut R) -> f64 {
+    if mean <= 0.0 || var <= 0.0 {
+      return mean.max(0.0);
+    }
+
+    Gamma::new(mean.powi(2) / var, var / mean)
+      .unwrap()
+      .sample(rng)
+  }
+
+  /// Sample of the integrated variance given v0 and v1
+  fn sample<R: Rng>(&self, v0: f64, v1: f64, rng: &mut R) -> f64 {
+    let [t1, t2, t3, t4] = self.tail;
+
+    // X1: compound Poisson sum of exponentials
+    let mut x1 = 0.0;
+    for (inv_gamma, lambda) in self.inv_gamma.iter().zip(&self.lambda) {
+      let rate = (v0 + v1) * lambda;
+      if rate > 0.0 {
+        let k = Poisson::new(rate).unwrap().sample(rng) as usize;
+        x1 += inv_gamma * (0..k).map(|_| rng.sample::<f64, _>(Exp1)).sum::<f64>();
+      }
+    }
+    x1 += Self::moment_matched((v0 + v1) * t3, 2.0 * (v0 + v1) * t4, rng);
+
+    // X2: sum of Gamma(delta / 2) variables
+    let shape = Gamma::new(0.5 * self.delta, 1.0).unwrap();
+    let mut x2 = self
+      .inv_gamma
+      .iter()
+      .map(|inv_gamma| inv_gamma * shape.sample(rng))
+      .sum::<f64>();
+    x2 += Self::moment_matched(0.5 * self.delta * t1, 0.5 * self.delta * t2, rng);
+
+    // X3: Bessel distributed number of Z variables, each a sum of Gamma(2) variables
+    let eta = self.sample_bessel(v0, v1, rng);
+    let gamma2 = Gamma::new(2.0, 1.0).unwrap();
+    let mut x3 = 0.0;
+    for _ in 0..eta {
+      x3 += self
+        .inv_gamma
+        .iter()
+        .map(|inv_gamma| inv_gamma * gamma2.sample(rng))
+        .sum::<f64>();
+      x3 += Self::moment_matched(2.0 * t1, 2.0 * t2, rng);
+    }
+
+    x1 + x2 + x3
+  }
+
+  /// Bessel distributed variable with index delta / 2 - 1 and argument
+  /// z = 2 kappa / sigma^2 * sqrt(v0 v1) / sinh(kappa dt / 2), sampled by inversion
+  fn sample_bessel<R: Rng>(&self, v0: f64, v1: f64, rng: &mut R) -> usize {
+    let z = 2.0 * self.kappa / self.sigma.powi(2) * (v0 * v1).sqrt()
+      / (0.5 * self.kappa * self.dt).sinh();
+
+    if z <= 0.0 {
+      return 0;
+    }
+
+    let nu = 0.5 * self.delta - 1.0;
+    let log_w = |n: usize| {
+      2.0 * n as f64 * (0.5 * z).ln() - ln_gamma(n as f64 + 1.0) - ln_gamma(n as f64 + nu + 1.0)
+    };
+
+    let mode = (0.5 * (-nu + (nu.powi(2) + z.powi(2)).sqrt())).floor() as usize;
+    let log_max = log_w(mode);
+    let weights = (0..)
+      .map(|n| (log_w(n) - log_max).exp())
+      .take_while({
+        let mut n = 0;
+        move |w| {
+          n += 1;
+          n <= mode + 1 || *w > 1e-16
+        }
+      })
+      .collect::<Vec<_>>();
+
+    let u = rng.gen::<f64>() * weights.iter().sum::<f64>();
+    let mut acc = 0.0;
+
+    for (n, w) in weights.iter().enumerate() {
+      acc += w;
+
+      if u < acc {
+        return n;
+      }
+    }
+
+    weights.len() - 1
+  }
+}
+
+impl HestonDiagnostics for Heston {
+  fn heston_params(&self) -> HestonParams {
+    HestonParams {
+      v0: self.v0.unwrap_or(0.0),
+      kappa: self.kappa,
+      theta: self.theta,
+      sigma: self.sigma,
+      rho: self.rho,
+      t: self.t.unwrap_or(1.0),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
+  #[cfg(feature = "viz")]
   use plotly::{common::Line, Plot, Scatter};
 
   use super::*;
+  use crate::rng::{path_seed, with_seed};
+
+  /// Mean and standard error of the mean of the samples
+  fn mean(x: ndarray::ArrayView1<f64>) -> (f64, f64) {
+    (x.mean().unwrap(), (x.var(1.0) / x.len() as f64).sqrt())
+  }
+
+  #[test]
+  fn broadie_kaya_matches_the_moments() {
+    let (kappa, theta, sigma, v0, mu, t) = (1.5, 0.04, 0.5, 0.09, 0.03, 1.0);
+    let heston = Heston::new(&Heston {
+      s0: Some(100.0),
+      v0: Some(v0),
+      kappa,
+      theta,
+      sigma,
+      rho: -0.7,
+      mu,
+      n: 4,
+      t: Some(t),
+      scheme: HestonScheme::BroadieKaya,
+      ..Default::default()
+    });
+    let paths = (0..20_000)
+      .map(|i| with_seed(path_seed(5, i), || heston.sample()))
+      .collect::<Vec<_>>();
+    let s = Array1::from_iter(paths.iter().map(|[s, _]| s[4]));
+    let v = Array1::from_iter(paths.iter().map(|[_, v]| v[4]));
+
+    let decay = (-kappa * t).exp();
+    let v_mean = theta + (v0 - theta) * decay;
+    let v_var = v0 * sigma.powi(2) * decay * (1.0 - decay) / kappa
+      + theta * sigma.powi(2) * (1.0 - decay).powi(2) / (2.0 * kappa);
+    let integrated_variance = theta * t + (v0 - theta) * (1.0 - decay) / kappa;
+
+    let (v_t, v_se) = mean(v.view());
+    assert!((v_t - v_mean).abs() < 4.0 * v_se, "{v_t} {v_mean}");
+    let squares = v.mapv(|x| (x - v_mean).powi(2));
+    let (var_t, var_se) = mean(squares.view());
+    assert!((var_t - v_var).abs() < 4.0 * var_se, "{var_t} {v_var}");
+
+    let (s_t, s_se) = mean(s.view());
+    assert!((s_t - 100.0 * (mu * t).exp()).abs() < 4.0 * s_se, "{s_t}");
+    let log_s = s.mapv(f64::ln);
+    let (ln_s, ln_se) = mean(log_s.view());
+    let expected = 100f64.ln() + mu * t - 0.5 * integrated_variance;
+    assert!((ln_s - expected).abs() < 4.0 * ln_se, "{ln_s} {expected}");
+  }
 
   #[test]
+  #[cfg(feature = "viz")]
   fn plot() {
     let heston = Heston::new(&Heston {
       s0: Some(0.05),
@@ -125,8 +460,10 @@ mod tests {
       t: Some(1.0),
       pow: HestonPow::default(),
       use_sym: Some(true),
+      scheme: HestonScheme::default(),
       m: Some(1),
       cgns: CGNS::default(),
+      ..Default::default()
     });
     let mut plot = Plot::new();
     let [s, v] = heston.sample();
@@ -0,0 +1,184 @@
+use nalgebra::DMatrix;
+use ndarray::{Array2, Array3};
+use rand_distr::{Distribution, StandardNormal};
+
+use crate::rng::thread_rng;
+
+/// Discretization of the Wishart process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WishartScheme {
+  /// Exact for an integer beta: X is the sum of the outer products of beta independent
+  /// Gaussian OU vectors dx = M x dt + Q^T dW, each stepped exactly
+  #[default]
+  Factor,
+  /// Euler steps projected onto the positive semidefinite matrices by clipping the
+  /// negative eigenvalues, for any beta >= d - 1. The projection biases the mean upwards
+  /// when the process is often near the boundary (beta close to d - 1), less so on finer grids.
+  ProjectedEuler,
+}
+
+/// Wishart process of d x d positive semidefinite matrices, the matrix analogue of CIR
+/// dX = (beta Q^T Q + M X + X M^T) dt + sqrt(X) dW Q + Q^T dW^T sqrt(X),
+/// with a d x d Brownian motion W.
+/// https://doi.org/10.1007/BF01169219 (Bru 1991)
+#[derive(Debug, Clone, Default)]
+pub struct Wishart {
+  pub x0: Array2<f64>,
+  /// Mean reversion matrix M, usually negative definite
+  pub drift: Array2<f64>,
+  /// Volatility matrix Q
+  pub q: Array2<f64>,
+  /// Degrees of freedom, at least d - 1
+  pub beta: f64,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub scheme: WishartScheme,
+}
+
+fn to_nalgebra(a: &Array2<f64>) -> DMatrix<f64> {
+  DMatrix::from_fn(a.nrows(), a.ncols(), |i, j| a[[i, j]])
+}
+
+fn to_ndarray(a: &DMatrix<f64>) -> Array2<f64> {
+  Array2::from_shape_fn((a.nrows(), a.ncols()), |(i, j)| a[(i, j)])
+}
+
+/// Symmetric matrix with its eigenvalues mapped by f
+fn spectral_map(a: &DMatrix<f64>, f: impl Fn(f64) -> f64) -> DMatrix<f64> {
+  let symmetric = (a + a.transpose()) * 0.5;
+  let eigen = symmetric.symmetric_eigen();
+  let values = eigen.eigenvalues.map(f);
+  &eigen.eigenvectors * DMatrix::from_diagonal(&values) * eigen.eigenvectors.transpose()
+}
+
+impl Wishart {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    let d = params.x0.nrows();
+    assert!(
+      params.x0.dim() == (d, d) && params.drift.dim() == (d, d) && params.q.dim() == (d, d),
+      "x0, drift and q must be d x d matrices"
+    );
+    assert!(
+      params.beta >= d as f64 - 1.0,
+      "Degrees of freedom must be at least d - 1"
+    );
+    if params.scheme == WishartScheme::Factor {
+      assert!(
+        params.beta.fract() == 0.0,
+        "The factor scheme needs an integer beta"
+      );
+    }
+
+    params.clone()
+  }
+
+  pub fn dim(&self) -> usize {
+    self.x0.nrows()
+  }
+
+  /// Transition e^(M t) and covariance int_0^t e^(M s) Q^T Q e^(M^T s) ds of the OU factors,
+  /// both from one matrix exponential (Van Loan)
+  fn factor_transition(&self, t: f64) -> (DMatrix<f64>, DMatrix<f64>) {
+    let d = self.dim();
+    let m = to_nalgebra(&self.drift);
+    let q = to_nalgebra(&self.q);
+    let mut block = DMatrix::<f64>::zeros(2 * d, 2 * d);
+    block.view_mut((0, 0), (d, d)).copy_from(&(-&m * t));
+    block
+      .view_mut((0, d), (d, d))
+      .copy_from(&(q.transpose() * &q * t));
+    block
+      .view_mut((d, d), (d, d))
+      .copy_from(&(m.transpose() * t));
+    let e = block.exp();
+
+    let transition = e.view((d, d), (d, d)).transpose();
+    let covariance = &transition * e.view((0, d), (d, d));
+    (transition, (&covariance + covariance.transpose()) * 0.5)
+  }
+
+  /// E[X(t)] = e^(M t) x0 e^(M^T t) + beta int_0^t e^(M s) Q^T Q e^(M^T s) ds
+  pub fn mean(&self, t: f64) -> Array2<f64> {
+    let (transition, covariance) = self.factor_transition(t);
+    to_ndarray(
+      &(&transition * to_nalgebra(&self.x0) * transition.transpose() + covariance * self.beta),
+    )
+  }
+
+  /// Path of the matrices, time x d x d
+  pub fn sample(&self) -> Array3<f64> {
+    match self.scheme {
+      WishartScheme::Factor => self.sample_factor(),
+      WishartScheme::ProjectedEuler => self.sample_projected_euler(),
+    }
+  }
+
+  fn sample_factor(&self) -> Array3<f64> {
+    let d = self.dim();
+    let k = self.beta as usize;
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let (transition, covariance) = self.factor_transition(dt);
+    let noise = spectral_map(&covariance, |l| l.max(0.0).sqrt());
+    let mut rng = thread_rng();
+
+    // x0 = sum of x_k x_k^T from the scaled eigenvectors
+    let eigen = to_nalgebra(&self.x0).symmetric_eigen();
+    let mut order = (0..d).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+    assert!(
+      order
+        .iter()
+        .skip(k)
+        .all(|&i| eigen.eigenvalues[i] < 1e-12 * eigen.eigenvalues.amax().max(1e-300)),
+      "x0 must have a rank of at most beta"
+    );
+    let mut factors = DMatrix::<f64>::zeros(d, k);
+    for (column, &i) in order.iter().take(k).enumerate() {
+      factors.set_column(
+        column,
+        &(eigen.eigenvectors.column(i) * eigen.eigenvalues[i].max(0.0).sqrt()),
+      );
+    }
+
+    let mut path = Array3::<f64>::zeros((self.n + 1, d, d));
+    path.index_axis_mut(ndarray::Axis(0), 0).assign(&self.x0);
+    for i in 1..=self.n {
+      let z = DMatrix::from_fn(d, k, |_, _| StandardNormal.sample(&mut rng));
+      factors = &transition * &factors + &noise * z;
+      path
+        .index_axis_mut(ndarray::Axis(0), i)
+        .assign(&to_ndarray(&(&factors * factors.transpose())));
+    }
+
+    path
+  }
+
+  fn sample_projected_euler(&self) -> Array3<f64> {
+    let d = self.dim();
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let m = to_nalgebra(&self.drift);
+    let q = to_nalgebra(&self.q);
+    let constant = q.transpose() * &q * self.beta;
+    let mut rng = thread_rng();
+
+    let mut x = to_nalgebra(&self.x0);
+    let mut path = Array3::<f64>::zeros((self.n + 1, d, d));
+    path.index_axis_mut(ndarray::Axis(0), 0).assign(&self.x0);
+    for i in 1..=self.n {
+      let root = spectral_map(&x, |l| l.max(0.0).sqrt());
+      let dw = DMatrix::from_fn(d, d, |_, _| {
+        dt.sqrt() * <StandardNormal as Distribution<f64>>::sample(&StandardNormal, &mut rng)
+      });
+      let diffusion = &root * &dw * &q;
+      let next =
+        &x + (&constant + &m * &x + &x * m.transpose()) * dt + &diffusion + diffusion.transpose();
+      x = spectral_map(&next, |l| l.max(0.0));
+      path
+        .index_axis_mut(ndarray::Axis(0), i)
+        .assign(&to_ndarray(&x));
+    }
+
+    path
+  }
+}
@@ -0,0 +1,2 @@
+pub mod degree_days;
+pub mod temperature;
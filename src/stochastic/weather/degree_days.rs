@@ -0,0 +1,93 @@
+use ndarray::{s, Array1, ArrayView1};
+
+use crate::{
+  quant::{greeks::Estimate, OptionType},
+  rng::with_seed,
+  stochastic::Sampling,
+};
+
+use super::temperature::CarTemperature;
+
+/// Degree day index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegreeDays {
+  /// Heating degree days, sum of max(base - T, 0)
+  Heating,
+  /// Cooling degree days, sum of max(T - base, 0)
+  Cooling,
+}
+
+impl DegreeDays {
+  /// Index of the daily temperatures over a period
+  pub fn index(&self, temperatures: ArrayView1<f64>, base: f64) -> f64 {
+    temperatures
+      .iter()
+      .map(|&t| match self {
+        Self::Heating => (base - t).max(0.0),
+        Self::Cooling => (t - base).max(0.0),
+      })
+      .sum()
+  }
+}
+
+/// HDD/CDD futures and options on the daily temperatures of a CAR model,
+/// the index covers the simulated days 1..=n (the day 0 is the start of the model)
+pub struct DegreeDayContract {
+  pub model: CarTemperature,
+  pub kind: DegreeDays,
+  /// Base temperature, usually 18 C or 65 F
+  pub base: f64,
+  /// Money per index point
+  pub tick: f64,
+  /// Discount factor to the settlement date
+  pub discount: f64,
+}
+
+impl DegreeDayContract {
+  #[must_use]
+  pub fn new(
+    model: &CarTemperature,
+    kind: DegreeDays,
+    base: f64,
+    tick: f64,
+    discount: f64,
+  ) -> Self {
+    Self {
+      model: CarTemperature::new(model),
+      kind,
+      base,
+      tick,
+      discount,
+    }
+  }
+
+  /// Simulated index values of the period
+  pub fn simulate(&self, paths: usize, seed: u64) -> Array1<f64> {
+    with_seed(seed, || {
+      Array1::from_shape_fn(paths, |_| {
+        let temperature = self.model.sample();
+        self.kind.index(temperature.slice(s![1..]), self.base)
+      })
+    })
+  }
+
+  /// Futures price in index points, the risk-neutral expectation of the index
+  /// without a market price of risk
+  pub fn futures(&self, paths: usize, seed: u64) -> Estimate {
+    Estimate::from_samples(self.simulate(paths, seed).view())
+  }
+
+  /// Price of the option on the index struck at k
+  pub fn option(&self, k: f64, option_type: OptionType, paths: usize, seed: u64) -> Estimate {
+    let payoffs = self.simulate(paths, seed).mapv(|index| {
+      self.discount
+        * self.tick
+        * match option_type {
+          OptionType::Call => (index - k).max(0.0),
+          OptionType::Put => (k - index).max(0.0),
+        }
+    });
+
+    Estimate::from_samples(payoffs.view())
+  }
+}
@@ -0,0 +1,121 @@
+use ndarray::{Array1, Array2};
+use ndarray_rand::RandomExt;
+use rand_distr::StandardNormal;
+
+use crate::{
+  rng::thread_rng,
+  stochastic::{schedule::Schedule, Sampling},
+};
+
+/// Temperature as a seasonal mean plus a continuous-time autoregressive CAR(p) process
+/// T(t) = mu(t) + X_1(t), dX(t) = A X(t) dt + e_p sigma(t) dW(t)
+/// where A is the companion matrix with the last row (-alpha_p, ..., -alpha_1).
+/// CAR(3) fits daily temperatures well, time is measured in days.
+/// https://doi.org/10.1111/j.1467-9469.2007.00576.x (Benth, Saltyte Benth, Koekebakker)
+#[derive(Default, Clone)]
+pub struct CarTemperature {
+  /// Autoregressive coefficients alpha_1, ..., alpha_p
+  pub alpha: Vec<f64>,
+  /// Seasonal mean mu(t)
+  pub mean: Option<Schedule>,
+  /// Constant mean if there is no seasonal one
+  pub mu: f64,
+  /// Seasonal volatility sigma(t)
+  pub sigma_t: Option<Schedule>,
+  /// Constant volatility if there is no seasonal one
+  pub sigma: f64,
+  /// Initial state of the CAR process, zero by default
+  pub x0: Option<Vec<f64>>,
+  /// Start of the simulation on the time axis of the schedules
+  pub t0: Option<f64>,
+  pub n: usize,
+  pub t: Option<f64>,
+  pub m: Option<usize>,
+}
+
+impl CarTemperature {
+  #[must_use]
+  pub fn new(params: &Self) -> Self {
+    assert!(!params.alpha.is_empty(), "CAR order must be at least 1");
+    if let Some(x0) = &params.x0 {
+      assert_eq!(x0.len(), params.alpha.len(), "x0 must have the CAR order");
+    }
+
+    params.clone()
+  }
+
+  /// Order p
+  pub fn order(&self) -> usize {
+    self.alpha.len()
+  }
+
+  /// Companion matrix A
+  pub fn companion(&self) -> Array2<f64> {
+    let p = self.order();
+    Array2::from_shape_fn((p, p), |(i, j)| {
+      if i + 1 < p {
+        f64::from(j == i + 1)
+      } else {
+        -self.alpha[p - 1 - j]
+      }
+    })
+  }
+
+  /// Mean at the i-th point of the grid with step dt
+  fn mean_at(&self, i: usize, dt: f64) -> f64 {
+    self
+      .mean
+      .as_ref()
+      .map_or(self.mu, |s| self.schedule_at(s, i, dt))
+  }
+
+  /// Volatility on the i-th step of the grid with step dt
+  fn sigma_at(&self, i: usize, dt: f64) -> f64 {
+    self
+      .sigma_t
+      .as_ref()
+      .map_or(self.sigma, |s| self.schedule_at(s, i, dt))
+  }
+
+  /// Step schedules are aligned to the grid, the others are read at t0 + i dt
+  fn schedule_at(&self, schedule: &Schedule, i: usize, dt: f64) -> f64 {
+    match schedule {
+      Schedule::Steps(_) => schedule.at(i, dt),
+      _ => schedule.value(self.t0.unwrap_or(0.0) + i as f64 * dt),
+    }
+  }
+}
+
+impl Sampling<f64> for CarTemperature {
+  /// Temperature path, Euler scheme for the state
+  fn sample(&self) -> Array1<f64> {
+    let p = self.order();
+    let dt = self.t.unwrap_or(1.0) / self.n as f64;
+    let a = self.companion();
+    let gn = Array1::<f64>::random_using(self.n, StandardNormal, &mut thread_rng()) * dt.sqrt();
+
+    let mut x = self
+      .x0
+      .as_ref()
+      .map_or_else(|| Array1::zeros(p), |x0| Array1::from_vec(x0.clone()));
+    let mut temperature = Array1::<f64>::zeros(self.n + 1);
+    temperature[0] = self.mean_at(0, dt) + x[0];
+
+    for i in 1..=self.n {
+      let mut next = &x + &(a.dot(&x) * dt);
+      next[p - 1] += self.sigma_at(i - 1, dt) * gn[i - 1];
+      x = next;
+      temperature[i] = self.mean_at(i, dt) + x[0];
+    }
+
+    temperature
+  }
+
+  fn n(&self) -> usize {
+    self.n
+  }
+
+  fn m(&self) -> Option<usize> {
+    self.m
+  }
+}